@@ -0,0 +1,97 @@
+//! Compares the lookups of the registries with the [`PrototypeIdHasher`] to the default hasher:
+//! `cargo bench --bench hasher`.
+
+use core::any::TypeId;
+use std::hint::black_box;
+
+use bevy::platform::collections::HashMap;
+use bevy_histrion_proto::{ErasedPrototypeId, PrototypeIdMap};
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+
+const SIZES: [usize; 3] = [64, 1_024, 16_384];
+
+fn ids(size: usize) -> Vec<ErasedPrototypeId> {
+    (0..size)
+        .map(|i| ErasedPrototypeId::from_name(&format!("item_{i}")))
+        .collect()
+}
+
+/// The slots of a registry, keyed by id.
+fn slot_lookups(c: &mut Criterion) {
+    let mut group = c.benchmark_group("slot_lookups");
+
+    for size in SIZES {
+        let ids = ids(size);
+        let default = ids
+            .iter()
+            .enumerate()
+            .map(|(slot, id)| (*id, slot))
+            .collect::<HashMap<_, _>>();
+        let prototype_id = ids
+            .iter()
+            .enumerate()
+            .map(|(slot, id)| (*id, slot))
+            .collect::<PrototypeIdMap<_, _>>();
+
+        group.bench_with_input(BenchmarkId::new("default", size), &ids, |b, ids| {
+            b.iter(|| {
+                ids.iter()
+                    .filter_map(|id| default.get(black_box(id)))
+                    .sum::<usize>()
+            });
+        });
+        group.bench_with_input(BenchmarkId::new("prototype_id", size), &ids, |b, ids| {
+            b.iter(|| {
+                ids.iter()
+                    .filter_map(|id| prototype_id.get(black_box(id)))
+                    .sum::<usize>()
+            });
+        });
+    }
+
+    group.finish();
+}
+
+/// The prototypes of all the registries, keyed by type and id.
+fn typed_lookups(c: &mut Criterion) {
+    let mut group = c.benchmark_group("typed_lookups");
+    let types = [TypeId::of::<u8>(), TypeId::of::<u16>(), TypeId::of::<u32>()];
+
+    for size in SIZES {
+        let keys = ids(size)
+            .into_iter()
+            .enumerate()
+            .map(|(i, id)| (types[i % types.len()], id))
+            .collect::<Vec<_>>();
+        let default = keys
+            .iter()
+            .enumerate()
+            .map(|(slot, key)| (*key, slot))
+            .collect::<HashMap<_, _>>();
+        let prototype_id = keys
+            .iter()
+            .enumerate()
+            .map(|(slot, key)| (*key, slot))
+            .collect::<PrototypeIdMap<_, _>>();
+
+        group.bench_with_input(BenchmarkId::new("default", size), &keys, |b, keys| {
+            b.iter(|| {
+                keys.iter()
+                    .filter_map(|key| default.get(black_box(key)))
+                    .sum::<usize>()
+            });
+        });
+        group.bench_with_input(BenchmarkId::new("prototype_id", size), &keys, |b, keys| {
+            b.iter(|| {
+                keys.iter()
+                    .filter_map(|key| prototype_id.get(black_box(key)))
+                    .sum::<usize>()
+            });
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, slot_lookups, typed_lookups);
+criterion_main!(benches);
@@ -0,0 +1,718 @@
+//! Build scripts helpers validating prototypes files against the schema generated by
+//! `bevy_histrion_proto`, so malformed data fails the build instead of the game.
+//!
+//! The files are read like the prototypes asset loader reads them: JSON, newline-delimited JSON,
+//! CSV and TSV tables, and KDL with the `kdl` feature, KDL files failing the validation without
+//! it.
+//!
+//! ```no_run
+//! // build.rs
+//! fn main() {
+//!     bevy_histrion_proto_build::check_prototypes(".vscode/prototypes.schema.json", "assets");
+//! }
+//! ```
+
+// The example is the `main` of a build script.
+#![allow(clippy::needless_doctest_main)]
+
+use std::path::{Path, PathBuf};
+
+use bevy_histrion_proto_format::{
+    PROTOTYPE_ASSET_EXTENSIONS, PROTOTYPE_KDL_EXTENSIONS, PROTOTYPE_TABLE_EXTENSIONS, TableFormat,
+    expand_fragments, has_extension, is_kdl_file, is_ndjson_file, is_prototype_table_file,
+    read_ndjson, table_to_entries, table_type, untyped_cell_value,
+};
+use jsonschema::{error::ValidationErrorKind, paths::LocationSegment};
+use percent_encoding::{AsciiSet, CONTROLS, utf8_percent_encode};
+use serde_json::Value as JsonValue;
+
+/// A prototypes file not matching the schema.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    pub file: PathBuf,
+    /// Location of the invalid value in the file, using the reflection path syntax
+    /// (`[1].effects[0]`), empty when the whole file is invalid.
+    pub path: String,
+    pub message: String,
+}
+
+impl core::fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "{}: {}", self.file.display(), self.message)
+        } else {
+            write!(
+                f,
+                "{}: {}: {}",
+                self.file.display(),
+                self.path,
+                self.message
+            )
+        }
+    }
+}
+
+/// Validates the prototypes files of the `prototypes` folder, searched recursively, against the
+/// prototypes `schema` file.
+///
+/// Fails only when the schema or the folder can't be read, invalid files are returned as
+/// [`ValidationError`]s.
+pub fn validate_prototypes(
+    schema: impl AsRef<Path>,
+    prototypes: impl AsRef<Path>,
+) -> std::io::Result<Vec<ValidationError>> {
+    let schema: JsonValue = serde_json::from_slice(&std::fs::read(schema)?)
+        .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))?;
+
+    let mut files = Vec::new();
+    collect_prototype_files(prototypes.as_ref(), &mut files)?;
+    files.sort();
+
+    // Each entry is validated on its own rather than through the root `oneOf` of the file, it
+    // reports the errors of the entries instead of a single mismatch for the whole file.
+    let mut entry_schema = schema.clone();
+    if let JsonValue::Object(fields) = &mut entry_schema {
+        fields.retain(|keyword, _| keyword == "$schema" || keyword == "definitions");
+        fields.insert("$ref".to_string(), "#/definitions/PrototypeAny".into());
+    }
+    encode_references(&mut entry_schema);
+
+    let validator = jsonschema::draft7::options()
+        .should_validate_formats(true)
+        // Part of the later drafts, the generated schemas use it for the prototype uuids.
+        .with_format("uuid", |uuid| uuid::Uuid::try_parse(uuid).is_ok())
+        .build(&entry_schema)
+        .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err.to_string()))?;
+    let mut errors = Vec::new();
+
+    for file in files {
+        let mut error = |path: String, message: String| {
+            errors.push(ValidationError {
+                file: file.clone(),
+                path,
+                message,
+            });
+        };
+
+        let value = match std::fs::read(&file)
+            .map_err(|err| err.to_string())
+            .and_then(|bytes| read_prototypes_file(&file, &bytes, &schema))
+        {
+            Ok(value) => value,
+            Err(err) => {
+                error(String::new(), err);
+                continue;
+            }
+        };
+
+        let (entries, is_list) = match value {
+            JsonValue::Array(entries) => (entries, true),
+            entry => (vec![entry], false),
+        };
+        let entry_path = |index: usize| {
+            if is_list {
+                format!("[{index}]")
+            } else {
+                String::new()
+            }
+        };
+
+        // The fragments are expanded like the loader does, a file it rejects fails here too.
+        let entries = match expand_fragments(entries) {
+            Ok(entries) => entries,
+            Err((index, err)) => {
+                error(entry_path(index), err);
+                continue;
+            }
+        };
+
+        // Entries only declaring the fragments of the file have no prototype.
+        for (index, entry) in entries.iter().enumerate() {
+            if let Some(entry) = entry {
+                for err in validator.iter_errors(entry) {
+                    report_error(&err, &entry_path(index), &mut error);
+                }
+            }
+        }
+    }
+
+    // The same requirement can come from several sub-schemas, e.g. the `name` of `PrototypeAny`
+    // and of each prototype type.
+    errors.dedup();
+
+    Ok(errors)
+}
+
+/// Validates the prototypes like [`validate_prototypes`] and fails the build on invalid files.
+///
+/// Meant to be called from the `build.rs` of the game, it tells cargo to rerun the build script
+/// when the schema or the prototypes change.
+pub fn check_prototypes(schema: impl AsRef<Path>, prototypes: impl AsRef<Path>) {
+    let (schema, prototypes) = (schema.as_ref(), prototypes.as_ref());
+
+    println!("cargo:rerun-if-changed={}", schema.display());
+    println!("cargo:rerun-if-changed={}", prototypes.display());
+
+    let errors = match validate_prototypes(schema, prototypes) {
+        Ok(errors) => errors,
+        Err(err) => panic!(
+            "failed to validate the prototypes of {} against {}: {err}",
+            prototypes.display(),
+            schema.display()
+        ),
+    };
+
+    if errors.is_empty() {
+        return;
+    }
+
+    for error in &errors {
+        println!("cargo:warning={error}");
+    }
+
+    panic!("{} invalid prototypes values", errors.len());
+}
+
+fn collect_prototype_files(path: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(path)? {
+        let path = entry?.path();
+
+        if path.is_dir() {
+            collect_prototype_files(&path, files)?;
+            continue;
+        }
+
+        let is_prototype_file = has_extension(&path, PROTOTYPE_ASSET_EXTENSIONS)
+            || has_extension(&path, PROTOTYPE_TABLE_EXTENSIONS)
+            || has_extension(&path, PROTOTYPE_KDL_EXTENSIONS);
+
+        if is_prototype_file {
+            files.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+/// Reads the entries of a prototypes file like the prototypes asset loader, any of the JSON,
+/// newline-delimited JSON, CSV, TSV and, with the `kdl` feature, KDL files.
+fn read_prototypes_file(
+    path: &Path,
+    bytes: &[u8],
+    schema: &JsonValue,
+) -> Result<JsonValue, String> {
+    if is_prototype_table_file(path) {
+        let text = std::str::from_utf8(bytes).map_err(|err| err.to_string())?;
+
+        // The loader converts the cells with the type of their field, the schema of the field
+        // tells the string fields apart.
+        let entries = table_to_entries(
+            text,
+            TableFormat::from_path(path),
+            table_type(path),
+            |ty, field, cell| {
+                if is_string_field(schema, ty, field) {
+                    JsonValue::String(cell.to_string())
+                } else {
+                    untyped_cell_value(cell)
+                }
+            },
+        )?;
+
+        return Ok(entries.into_iter().map(|(_, entry)| entry).collect());
+    }
+
+    if is_kdl_file(path) {
+        return read_kdl(path, bytes);
+    }
+
+    if is_ndjson_file(path) {
+        return read_ndjson(bytes)
+            .map(JsonValue::Array)
+            .map_err(|err| err.to_string());
+    }
+
+    serde_json::from_slice(bytes).map_err(|err| err.to_string())
+}
+
+#[cfg(feature = "kdl")]
+fn read_kdl(path: &Path, bytes: &[u8]) -> Result<JsonValue, String> {
+    let entries = bevy_histrion_proto_format::read_kdl_file(path, bytes)
+        .map_err(|err| err.to_string())?
+        .unwrap_or_default();
+
+    Ok(entries.into_iter().map(|(_, entry)| entry).collect())
+}
+
+#[cfg(not(feature = "kdl"))]
+fn read_kdl(_path: &Path, _bytes: &[u8]) -> Result<JsonValue, String> {
+    Err("KDL prototypes files need the `kdl` feature of bevy_histrion_proto_build".to_string())
+}
+
+/// Returns `true` if the field at the dot separated `path` of the `ty` prototypes is a string,
+/// or an optional string, in the prototypes `schema`.
+fn is_string_field(schema: &JsonValue, ty: &str, path: &str) -> bool {
+    // The `then` of the `PrototypeAny` condition on the `type` of the prototype.
+    let prototype = schema
+        .pointer("/definitions/PrototypeAny/allOf")
+        .and_then(JsonValue::as_array)
+        .into_iter()
+        .flatten()
+        .find(|case| case.pointer("/if/properties/type/const") == Some(&JsonValue::from(ty)))
+        .and_then(|case| case.get("then"));
+
+    let Some(field) = path
+        .split('.')
+        .try_fold(prototype, |value, name| {
+            value.map(|value| schema_property(schema, value, name))
+        })
+        .flatten()
+    else {
+        return false;
+    };
+
+    let is_string =
+        |value| resolve_schema(schema, value).get("type") == Some(&JsonValue::from("string"));
+    let field = resolve_schema(schema, field);
+
+    is_string(field)
+        || field
+            .get("anyOf")
+            .and_then(JsonValue::as_array)
+            .is_some_and(|cases| cases.iter().any(is_string))
+}
+
+/// Returns the schema of the `name` property of the objects of `value`, looking into its
+/// `allOf` sub-schemas.
+fn schema_property<'a>(
+    schema: &'a JsonValue,
+    value: &'a JsonValue,
+    name: &str,
+) -> Option<&'a JsonValue> {
+    let value = resolve_schema(schema, value);
+
+    value
+        .get("properties")
+        .and_then(|properties| properties.get(name))
+        .or_else(|| {
+            value
+                .get("allOf")
+                .and_then(JsonValue::as_array)
+                .into_iter()
+                .flatten()
+                .find_map(|value| schema_property(schema, value, name))
+        })
+}
+
+/// Follows the local `$ref` of `value` to the schema it references.
+fn resolve_schema<'a>(schema: &'a JsonValue, mut value: &'a JsonValue) -> &'a JsonValue {
+    while let Some(target) = value
+        .get("$ref")
+        .and_then(JsonValue::as_str)
+        .and_then(|reference| reference.strip_prefix('#'))
+        .and_then(|pointer| schema.pointer(pointer))
+    {
+        value = target;
+    }
+
+    value
+}
+
+/// Characters of the definition names, e.g. `Vec<alloc::string::String>`, to percent-encode in
+/// the `$ref` URI fragments.
+const FRAGMENT: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'<')
+    .add(b'>')
+    .add(b'[')
+    .add(b']')
+    .add(b'\\')
+    .add(b'^')
+    .add(b'`')
+    .add(b'{')
+    .add(b'|')
+    .add(b'}');
+
+/// Percent-encodes the local `$ref`s of `schema`, the names of the generated definitions are
+/// type paths that aren't valid in a URI.
+fn encode_references(schema: &mut JsonValue) {
+    match schema {
+        JsonValue::Object(fields) => {
+            for (keyword, value) in fields.iter_mut() {
+                if keyword == "$ref"
+                    && let JsonValue::String(reference) = value
+                    && let Some(pointer) = reference.strip_prefix('#')
+                {
+                    *reference = format!("#{}", utf8_percent_encode(pointer, FRAGMENT));
+                } else {
+                    encode_references(value);
+                }
+            }
+        }
+        JsonValue::Array(values) => values.iter_mut().for_each(encode_references),
+        _ => {}
+    }
+}
+
+/// Reports a schema error with the reflection path of the invalid value, the errors of the
+/// closest schema for the values matching none of the schemas of an `anyOf` or a `oneOf`.
+fn report_error(
+    err: &jsonschema::ValidationError,
+    entry_path: &str,
+    error: &mut dyn FnMut(String, String),
+) {
+    if let ValidationErrorKind::AnyOf { context } | ValidationErrorKind::OneOfNotValid { context } =
+        err.kind()
+        && let Some(closest) = context.iter().rev().min_by_key(|errors| {
+            // The schemas rejecting the value itself rather than one of its fields are the
+            // farthest, e.g. the `null` of an `Option`.
+            let rejecting = errors
+                .iter()
+                .filter(|error| error.instance_path() == err.instance_path())
+                .count();
+
+            (rejecting, errors.len())
+        })
+        && !closest.is_empty()
+    {
+        for err in closest {
+            report_error(err, entry_path, error);
+        }
+        return;
+    }
+
+    let path = err
+        .instance_path()
+        .segments()
+        .fold(entry_path.to_string(), |path, segment| match segment {
+            LocationSegment::Property(field) => join_field_path(&path, &field),
+            LocationSegment::Index(index) => format!("{path}[{index}]"),
+        });
+
+    error(path, err.to_string());
+}
+
+fn join_field_path(path: &str, field: &str) -> String {
+    if path.is_empty() {
+        field.to_string()
+    } else {
+        format!("{path}.{field}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn schema() -> JsonValue {
+        json!({
+            "definitions": {
+                "PrototypeAny": {
+                    "type": "object",
+                    "required": ["type", "name"],
+                    "allOf": [{
+                        "if": { "required": ["type"], "properties": { "type": { "const": "sword" } } },
+                        "then": { "$ref": "#/definitions/Sword" }
+                    }]
+                },
+                "Sword": {
+                    "type": "object",
+                    "properties": { "name": { "type": "string" } },
+                    "allOf": [{ "$ref": "#/definitions/Data<schema::Sword>" }]
+                },
+                "Data<schema::Sword>": {
+                    "type": "object",
+                    "properties": {
+                        "damage": { "type": "number" },
+                        "title": { "anyOf": [{ "type": "null" }, { "$ref": "#/definitions/alloc::string::String" }] }
+                    }
+                },
+                "alloc::string::String": { "type": "string" }
+            }
+        })
+    }
+
+    #[test]
+    fn tables_keep_the_cells_of_string_fields() {
+        let table = "name,damage,title\nsword_1,4,12\n";
+        let entries =
+            read_prototypes_file(Path::new("sword.proto.csv"), table.as_bytes(), &schema())
+                .unwrap();
+
+        assert_eq!(
+            entries,
+            json!([{ "type": "sword", "name": "sword_1", "damage": 4, "title": "12" }])
+        );
+    }
+
+    #[test]
+    fn ndjson_files_are_read_per_line() {
+        let file =
+            "{ \"type\": \"sword\", \"name\": \"a\" }\n{ \"type\": \"sword\", \"name\": \"b\" }\n";
+        let entries =
+            read_prototypes_file(Path::new("swords.proto.ndjson"), file.as_bytes(), &schema())
+                .unwrap();
+
+        assert_eq!(entries.as_array().map(Vec::len), Some(2));
+    }
+
+    #[cfg(feature = "kdl")]
+    #[test]
+    fn kdl_files_are_read_with_the_kdl_feature() {
+        let file = "sword \"a\" damage=2.0\n";
+        let entries =
+            read_prototypes_file(Path::new("swords.proto.kdl"), file.as_bytes(), &schema())
+                .unwrap();
+
+        assert_eq!(
+            entries,
+            json!([{ "type": "sword", "name": "a", "damage": 2.0 }])
+        );
+    }
+
+    #[cfg(not(feature = "kdl"))]
+    #[test]
+    fn kdl_files_are_rejected_without_the_kdl_feature() {
+        assert!(
+            read_prototypes_file(Path::new("swords.proto.kdl"), b"sword \"a\"", &schema()).is_err()
+        );
+    }
+
+    fn validate(name: &str, file: &str) -> Vec<ValidationError> {
+        validate_with(&schema(), name, file)
+    }
+
+    fn validate_with(schema: &JsonValue, name: &str, file: &str) -> Vec<ValidationError> {
+        let folder = std::env::temp_dir().join(format!("bhp_build_{name}_{}", std::process::id()));
+        let prototypes = folder.join("prototypes");
+        std::fs::create_dir_all(&prototypes).unwrap();
+        std::fs::write(folder.join("schema.json"), schema.to_string()).unwrap();
+        std::fs::write(prototypes.join(name), file).unwrap();
+
+        let errors = validate_prototypes(folder.join("schema.json"), &prototypes).unwrap();
+        std::fs::remove_dir_all(&folder).unwrap();
+        errors
+    }
+
+    /// A schema with every keyword of the generated schemas.
+    fn gear_schema() -> JsonValue {
+        json!({
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "oneOf": [{ "$ref": "#/definitions/PrototypeAny" }],
+            "definitions": {
+                "PrototypeAny": {
+                    "type": "object",
+                    "required": ["type", "name"],
+                    "properties": { "type": { "enum": ["gear"] } },
+                    "allOf": [{
+                        "if": { "required": ["type"], "properties": { "type": { "const": "gear" } } },
+                        "then": { "$ref": "#/definitions/Gear" }
+                    }]
+                },
+                "Gear": {
+                    "type": "object",
+                    "required": ["name", "level"],
+                    "properties": {
+                        "type": {},
+                        "name": { "type": "string", "minLength": 1, "maxLength": 8 },
+                        "level": { "type": "integer", "minimum": 1, "maximum": 10 },
+                        "slots": {
+                            "type": "array",
+                            "items": { "$ref": "#/definitions/Slot" },
+                            "minItems": 1,
+                            "maxItems": 2,
+                            "uniqueItems": true
+                        },
+                        "position": {
+                            "type": "array",
+                            "items": [{ "type": "number" }, { "type": "number" }],
+                            "minItems": 2,
+                            "maxItems": 2
+                        },
+                        "stats": {
+                            "type": "object",
+                            "propertyNames": { "pattern": "^[a-z]+$" },
+                            "additionalProperties": { "type": "number" }
+                        },
+                        "kind": {
+                            "oneOf": [
+                                { "const": "plain" },
+                                {
+                                    "type": "object",
+                                    "required": ["rune"],
+                                    "properties": { "rune": { "type": "string" } },
+                                    "additionalProperties": false
+                                }
+                            ]
+                        },
+                        "seed": { "type": "string", "pattern": "^-?[0-9]+$" },
+                        "title": { "anyOf": [{ "type": "null" }, { "type": "string" }] },
+                        "uuid": { "type": "string", "format": "uuid" }
+                    },
+                    "additionalProperties": false
+                },
+                "Slot": { "enum": ["head", "hand"] }
+            }
+        })
+    }
+
+    #[test]
+    fn every_keyword_of_the_generated_schemas_is_enforced() {
+        let valid = json!({
+            "type": "gear",
+            "name": "helm",
+            "level": 2,
+            "slots": ["head"],
+            "position": [1.0, 2.0],
+            "stats": { "armor": 3 },
+            "kind": { "rune": "fire" },
+            "seed": "-12",
+            "title": null,
+            "uuid": "67e55044-10b1-426f-9247-bb680e5fe0c8"
+        });
+
+        assert_eq!(
+            validate_with(&gear_schema(), "valid.proto.json", &valid.to_string()),
+            []
+        );
+
+        let invalid = [
+            ("type", json!("hat"), "type"),
+            ("name", json!(""), "name"),
+            ("name", json!("very_long_name"), "name"),
+            ("level", json!(1.5), "level"),
+            ("level", json!(0), "level"),
+            ("level", json!(11), "level"),
+            ("slots", json!([]), "slots"),
+            ("slots", json!(["head", "hand", "head"]), "slots"),
+            ("slots", json!(["hand", "hand"]), "slots"),
+            ("slots", json!(["feet"]), "slots[0]"),
+            ("position", json!([1.0, "2"]), "position[1]"),
+            ("position", json!([1.0]), "position"),
+            ("stats", json!({ "Armor": 3 }), "stats"),
+            ("stats", json!({ "armor": "3" }), "stats.armor"),
+            ("kind", json!("fancy"), "kind"),
+            ("kind", json!({ "rune": 1 }), "kind.rune"),
+            ("kind", json!({ "rune": "fire", "color": "red" }), "kind"),
+            ("seed", json!("1.5"), "seed"),
+            ("title", json!(3), "title"),
+            ("uuid", json!("not a uuid"), "uuid"),
+            ("unknown", json!(1), ""),
+        ];
+
+        for (field, value, path) in invalid {
+            let mut entry = valid.clone();
+            entry[field] = value.clone();
+
+            let errors = validate_with(&gear_schema(), "invalid.proto.json", &entry.to_string());
+
+            assert!(
+                errors.iter().any(|error| error.path == path),
+                "{field} = {value} isn't reported at `{path}`: {errors:?}"
+            );
+        }
+
+        let mut entry = valid.clone();
+        entry.as_object_mut().unwrap().remove("level");
+        let errors = validate_with(&gear_schema(), "missing.proto.json", &entry.to_string());
+        assert_eq!(errors.len(), 1, "{errors:?}");
+    }
+
+    #[test]
+    fn errors_have_the_path_of_their_entry() {
+        let file = r#"[
+            { "type": "sword", "name": "a", "damage": 1 },
+            { "type": "sword", "name": "b", "damage": "high" }
+        ]"#;
+
+        let errors = validate("paths.proto.json", file);
+
+        assert_eq!(errors.len(), 1, "{errors:?}");
+        assert_eq!(errors[0].path, "[1].damage");
+        assert!(errors[0].file.ends_with("paths.proto.json"));
+    }
+
+    #[test]
+    fn fragments_are_expanded_like_the_loader() {
+        let file = r#"[
+            { "$fragments": { "damage": 3 } },
+            { "type": "sword", "name": "a", "damage": "$fragments.damage" }
+        ]"#;
+
+        assert_eq!(validate("expanded.proto.json", file), []);
+    }
+
+    #[test]
+    fn fragments_rejected_by_the_loader_fail() {
+        let duplicated = r#"[
+            { "$fragments": { "damage": 3 } },
+            { "$fragments": { "damage": 4 } }
+        ]"#;
+        let cyclic = r#"[
+            { "$fragments": { "damage": "$fragments.damage" } },
+            { "type": "sword", "name": "a", "damage": "$fragments.damage" }
+        ]"#;
+
+        let errors = validate("duplicated.proto.json", duplicated);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].path, "[1]");
+        assert_eq!(errors[0].message, "fragment damage is declared twice");
+
+        let errors = validate("cyclic.proto.json", cyclic);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].message, "fragment damage references itself");
+
+        let errors = validate(
+            "unknown.proto.json",
+            r#"{ "type": "sword", "name": "a", "damage": "$fragments.x" }"#,
+        );
+        assert_eq!(errors[0].path, "");
+        assert_eq!(errors[0].message, "unknown fragment x");
+    }
+
+    #[test]
+    fn every_prototypes_file_is_collected() {
+        let folder = std::env::temp_dir().join(format!("bhp_build_collect_{}", std::process::id()));
+        std::fs::create_dir_all(folder.join("nested")).unwrap();
+
+        for file in [
+            "a.proto",
+            "b.proto.json",
+            "c.proto.ndjson",
+            "nested/d.proto.csv",
+            "e.proto.tsv",
+            "f.proto.kdl",
+            "g.json",
+            "h.profile.json",
+        ] {
+            std::fs::write(folder.join(file), "").unwrap();
+        }
+
+        let mut files = Vec::new();
+        collect_prototype_files(&folder, &mut files).unwrap();
+        std::fs::remove_dir_all(&folder).unwrap();
+
+        let mut names = files
+            .iter()
+            .filter_map(|file| file.file_name()?.to_str())
+            .collect::<Vec<_>>();
+        names.sort_unstable();
+
+        assert_eq!(
+            names,
+            [
+                "a.proto",
+                "b.proto.json",
+                "c.proto.ndjson",
+                "d.proto.csv",
+                "e.proto.tsv",
+                "f.proto.kdl"
+            ]
+        );
+    }
+}
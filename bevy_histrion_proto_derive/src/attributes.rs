@@ -1,6 +1,8 @@
 use proc_macro2::TokenStream;
-use quote::ToTokens;
-use syn::{Attribute, Expr, Lit, Meta, Token, punctuated::Punctuated};
+use quote::{ToTokens, quote};
+use syn::{
+    Attribute, Expr, ExprLit, Lit, Meta, Token, Type, WherePredicate, punctuated::Punctuated,
+};
 
 #[derive(Default, Clone)]
 pub(crate) struct SerdeAttributes {
@@ -13,6 +15,7 @@ pub(crate) struct SerdeAttributes {
     pub rename_all: Option<SerdeRenameAll>,
     pub rename_all_fields: Option<SerdeRenameAll>,
     pub default: bool,
+    pub default_fn: Option<syn::ExprPath>,
 }
 
 impl SerdeAttributes {
@@ -24,6 +27,9 @@ impl SerdeAttributes {
         let mut serde_attributes = SerdeAttributes::default();
 
         if !do_reflect_deserialize {
+            // Deserialized through reflection, which leaves out the ignored fields, e.g. the
+            // `PhantomData` of the type parameters of generic prototypes.
+            serde_attributes.skip = is_field && attrs.iter().any(is_reflect_ignore);
             return Ok(serde_attributes);
         }
 
@@ -100,6 +106,20 @@ impl SerdeAttributes {
                         serde_attributes.rename.replace(lit_str.value());
                     } else if meta.path().is_ident("default") {
                         serde_attributes.default = true;
+
+                        let Meta::NameValue(name_value) = meta else {
+                            continue;
+                        };
+
+                        let Expr::Lit(lit) = &name_value.value else {
+                            continue;
+                        };
+
+                        let Lit::Str(lit_str) = &lit.lit else {
+                            continue;
+                        };
+
+                        serde_attributes.default_fn.replace(lit_str.parse()?);
                     }
                 }
             }
@@ -109,6 +129,106 @@ impl SerdeAttributes {
     }
 }
 
+fn is_reflect_ignore(attr: &Attribute) -> bool {
+    attr.path().is_ident("reflect")
+        && attr
+            .parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
+            .is_ok_and(|metas| metas.iter().any(|meta| meta.path().is_ident("ignore")))
+}
+
+#[derive(Default, Clone)]
+pub(crate) struct SchemaAttributes {
+    /// `#[schema(reflect)]`, the schema of the field is synthesized from its reflected type
+    /// info instead of its `JsonSchema` impl.
+    pub reflect: bool,
+    /// `#[schema(remote = "path")]`, on a field the shim whose schema is used for the foreign
+    /// type of the field, on a type the foreign type it is a shim of.
+    pub remote: Option<Type>,
+    /// `#[schema(bound = "...")]`, predicates added to the where clause of the impl.
+    pub bound: Option<Punctuated<WherePredicate, Token![,]>>,
+}
+
+impl SchemaAttributes {
+    pub fn try_from_attributes(attrs: &[Attribute], is_field: bool) -> Result<Self, syn::Error> {
+        let mut schema_attributes = SchemaAttributes::default();
+
+        for attr in attrs {
+            if !attr.path().is_ident("schema") {
+                continue;
+            }
+
+            let meta_list = attr
+                .meta
+                .require_list()?
+                .parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+
+            for meta in &meta_list {
+                if meta.path().is_ident("remote") {
+                    if schema_attributes.remote.is_some() {
+                        return Err(syn::Error::new_spanned(meta, "Duplicate remote attribute"));
+                    }
+
+                    let name_value = meta.require_name_value()?;
+
+                    let Expr::Lit(ExprLit {
+                        lit: Lit::Str(lit_str),
+                        ..
+                    }) = &name_value.value
+                    else {
+                        return Err(syn::Error::new_spanned(
+                            name_value,
+                            "Remote must be a string literal",
+                        ));
+                    };
+
+                    schema_attributes.remote = Some(lit_str.parse()?);
+                } else if is_field && meta.path().is_ident("reflect") {
+                    meta.require_path_only()?;
+                    schema_attributes.reflect = true;
+                } else if !is_field && meta.path().is_ident("bound") {
+                    if schema_attributes.bound.is_some() {
+                        return Err(syn::Error::new_spanned(meta, "Duplicate bound attribute"));
+                    }
+
+                    schema_attributes.bound = Some(crate::parse_bound(meta)?);
+                } else {
+                    return Err(syn::Error::new_spanned(meta, "Unknown schema attribute"));
+                }
+            }
+        }
+
+        if schema_attributes.reflect && schema_attributes.remote.is_some() {
+            return Err(syn::Error::new(
+                proc_macro2::Span::call_site(),
+                "A field can't have both the reflect and remote schema attributes",
+            ));
+        }
+
+        Ok(schema_attributes)
+    }
+
+    /// Returns the statement registering the schema of a field of type `ty` and the expression
+    /// of its reference.
+    pub fn field_schema(&self, ty: &Type) -> (TokenStream, TokenStream) {
+        if let Some(remote) = &self.remote {
+            (
+                quote!(<#remote as JsonSchema>::register_schema(refs);),
+                quote!(<#remote as JsonSchema>::schema_ref()),
+            )
+        } else if self.reflect {
+            (
+                quote!(::bevy_histrion_proto::_private::register_reflect_schema::<#ty>(refs);),
+                quote!(::bevy_histrion_proto::_private::reflect_schema_ref::<#ty>()),
+            )
+        } else {
+            (
+                quote!(<#ty as JsonSchema>::register_schema(refs);),
+                quote!(<#ty as JsonSchema>::schema_ref()),
+            )
+        }
+    }
+}
+
 #[derive(Clone, Copy, PartialEq, Eq)]
 #[allow(clippy::enum_variant_names)]
 pub(crate) enum SerdeRenameAll {
@@ -2,12 +2,13 @@ mod attributes;
 
 use std::collections::HashSet;
 
-use attributes::SerdeAttributes;
+use attributes::{SchemaAttributes, SerdeAttributes};
 use proc_macro::TokenStream;
 use quote::quote;
 use syn::{
-    Attribute, Data, DataEnum, DataStruct, DeriveInput, Expr, Fields, Lit, Meta, Token, Type,
-    parse_macro_input, punctuated::Punctuated, spanned::Spanned,
+    Attribute, Data, DataEnum, DataStruct, DeriveInput, Expr, ExprLit, Fields, GenericParam,
+    Generics, Lit, LitStr, Meta, Token, Type, WherePredicate, parse_macro_input,
+    punctuated::Punctuated, spanned::Spanned,
 };
 
 #[proc_macro_derive(Prototype, attributes(proto))]
@@ -29,6 +30,10 @@ pub fn prototype_derive(input: TokenStream) -> TokenStream {
         .into();
     }
 
+    let mut allowed_tags = None;
+    let mut bound = None;
+    let mut ids = false;
+
     let prototype_name = {
         let mut name = None;
 
@@ -79,6 +84,51 @@ pub fn prototype_derive(input: TokenStream) -> TokenStream {
                     };
 
                     name = Some(lit_str.value());
+                } else if meta.path().is_ident("allowed_tags") {
+                    if allowed_tags.is_some() {
+                        return syn::Error::new(meta.span(), "Duplicate allowed_tags attribute")
+                            .into_compile_error()
+                            .into();
+                    }
+
+                    let tags = match meta.require_list().and_then(|list| {
+                        list.parse_args_with(Punctuated::<LitStr, Token![,]>::parse_terminated)
+                    }) {
+                        Ok(tags) => tags,
+                        Err(err) => {
+                            return err.into_compile_error().into();
+                        }
+                    };
+
+                    allowed_tags = Some(tags.into_iter().collect::<Vec<_>>());
+                } else if meta.path().is_ident("bound") {
+                    if bound.is_some() {
+                        return syn::Error::new(meta.span(), "Duplicate bound attribute")
+                            .into_compile_error()
+                            .into();
+                    }
+
+                    bound = match parse_bound(meta) {
+                        Ok(predicates) => Some(predicates),
+                        Err(err) => {
+                            return err.into_compile_error().into();
+                        }
+                    };
+                } else if meta.path().is_ident("ids") {
+                    if let Err(err) = meta.require_path_only() {
+                        return err.into_compile_error().into();
+                    }
+
+                    if !input.generics.params.is_empty() {
+                        return syn::Error::new(
+                            meta.span(),
+                            "ids can only be generated for prototypes without generics",
+                        )
+                        .into_compile_error()
+                        .into();
+                    }
+
+                    ids = true;
                 }
             }
         }
@@ -93,20 +143,108 @@ pub fn prototype_derive(input: TokenStream) -> TokenStream {
     };
 
     let ident = &input.ident;
-    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let mut generics = with_bound(&input.generics, bound);
+
+    // The names of the type parameters are part of the prototype name.
+    for param in input.generics.type_params() {
+        let param = &param.ident;
+
+        generics
+            .make_where_clause()
+            .predicates
+            .push(syn::parse_quote! { #param: ::bevy_histrion_proto::_private::TypePath });
+    }
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let prototype_name = match compose_prototype_name(&prototype_name, &input.generics) {
+        Ok(prototype_name) => prototype_name,
+        Err(err) => {
+            return err.into_compile_error().into();
+        }
+    };
+
+    let allowed_tags = allowed_tags.map(|tags| {
+        quote! {
+            fn allowed_tags() -> Option<&'static [&'static str]> {
+                Some(&[#(#tags),*])
+            }
+        }
+    });
+
+    // `#[proto(ids)]` adds `SwordId` and `SwordName` aliases and a `Sword::id` helper.
+    let ids = ids.then(|| {
+        let vis = &input.vis;
+        let id_alias = quote::format_ident!("{ident}Id");
+        let name_alias = quote::format_ident!("{ident}Name");
+        let id_doc =
+            format!("The [`PrototypeId`](::bevy_histrion_proto::PrototypeId) of a [`{ident}`].");
+        let name_doc = format!(
+            "The [`PrototypeName`](::bevy_histrion_proto::PrototypeName) of a [`{ident}`]."
+        );
+
+        quote! {
+            #[doc = #id_doc]
+            #vis type #id_alias = ::bevy_histrion_proto::PrototypeId<#ident>;
+
+            #[doc = #name_doc]
+            #vis type #name_alias = ::bevy_histrion_proto::PrototypeName<#ident>;
+
+            impl #ident {
+                /// Returns the id of the prototype named `name`.
+                #[must_use]
+                #vis const fn id(name: &str) -> #id_alias {
+                    ::bevy_histrion_proto::PrototypeId::from_name(name)
+                }
+            }
+        }
+    });
 
     quote! {
         impl #impl_generics ::bevy_histrion_proto::PrototypeData for #ident #ty_generics #where_clause {
             fn prototype_name() -> &'static str {
                 #prototype_name
             }
+
+            #allowed_tags
         }
+
+        #ids
     }
     .into()
 }
 
-#[proc_macro_derive(JsonSchema, attributes(reflect, serde))]
+/// Implements `PrototypeComponent` for a prototype that is also a component, its data is cloned
+/// onto the entities spawned from it.
+#[proc_macro_derive(PrototypeComponent)]
+pub fn prototype_component_derive(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics ::bevy_histrion_proto::SpawnablePrototype for #ident #ty_generics #where_clause {
+            fn apply_to(
+                prototype: &::bevy_histrion_proto::Prototype<Self>,
+                entity: &mut ::bevy_histrion_proto::_private::EntityWorldMut,
+            ) {
+                entity.insert(::core::clone::Clone::clone(prototype.data()));
+            }
+        }
+
+        impl #impl_generics ::bevy_histrion_proto::PrototypeComponent for #ident #ty_generics #where_clause {}
+    }
+    .into()
+}
+
+#[proc_macro_derive(JsonSchema, attributes(reflect, serde, schema))]
 pub fn json_schema_derive(item: TokenStream) -> TokenStream {
+    // Schema generation is compiled out, the derive only keeps accepting its attributes.
+    if !cfg!(feature = "schema") {
+        return TokenStream::new();
+    }
+
     let item = parse_macro_input!(item as DeriveInput);
 
     let do_reflect_deserialize = do_reflect_deserialize(&item.attrs);
@@ -142,8 +280,27 @@ pub fn json_schema_derive(item: TokenStream) -> TokenStream {
         }
     };
 
+    let SchemaAttributes { bound, remote, .. } =
+        match SchemaAttributes::try_from_attributes(&item.attrs, false) {
+            Ok(schema_attributes) => schema_attributes,
+            Err(err) => {
+                return err.into_compile_error().into();
+            }
+        };
+
+    // A shim of a foreign type is titled after the foreign type, its schema is the one of the
+    // foreign type in the data files.
+    let schema_title = remote.map(|remote| {
+        quote! {
+            fn schema_title() -> String {
+                ::core::any::type_name::<#remote>().to_string()
+            }
+        }
+    });
+
     let ident = &item.ident;
-    let (impl_generics, ty_generics, where_clause) = item.generics.split_for_impl();
+    let generics = with_bound(&item.generics, bound);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
     quote! {
         #[doc(hidden)]
@@ -162,6 +319,8 @@ pub fn json_schema_derive(item: TokenStream) -> TokenStream {
                 fn json_schema(refs: &mut serde_json::Map<String, serde_json::Value>) -> serde_json::Value {
                     #body
                 }
+
+                #schema_title
             }
         };
     }
@@ -180,6 +339,7 @@ fn json_schema_struct(
             let mut all_of = None;
             let mut properties = None;
             let mut required = quote!();
+            let mut defaults = quote!();
 
             for field in &fields_named.named {
                 let serde_attributes = SerdeAttributes::try_from_attributes(
@@ -193,22 +353,18 @@ fn json_schema_struct(
                 }
 
                 let ty = &field.ty;
+                let (register_schema, schema_ref) =
+                    SchemaAttributes::try_from_attributes(&field.attrs, true)?.field_schema(ty);
 
                 if !types.contains(ty) {
                     types.insert(ty);
-                    register_exp.extend(quote! {
-                    let ty_title = <#ty as JsonSchema>::schema_title();
-                    if !refs.contains_key(&ty_title) {
-                        let ty_schema = <#ty as JsonSchema>::json_schema(refs);
-                        refs.insert(ty_title, ty_schema);
-                        }
-                    });
+                    register_exp.extend(register_schema);
                 }
 
                 if serde_attributes.flatten {
                     all_of.replace(quote! {
                         #all_of
-                        { "$ref": <#ty as JsonSchema>::schema_ref() }
+                        { "$ref": #schema_ref }
                     });
                     continue;
                 }
@@ -225,9 +381,17 @@ fn json_schema_struct(
                     required.extend(quote!(#ident_str,));
                 }
 
+                if let Some(default_fn) = &serde_attributes.default_fn {
+                    defaults.extend(quote! {
+                        if let Some(default) = ::bevy_histrion_proto::_private::schema_default_value::<#ty>(#default_fn()) {
+                            schema["properties"][#ident_str]["default"] = default;
+                        }
+                    });
+                }
+
                 properties.replace(quote! {
                     #properties
-                    #ident_str: { "$ref": <#ty as JsonSchema>::schema_ref() },
+                    #ident_str: { "$ref": #schema_ref },
                 });
             }
 
@@ -236,12 +400,13 @@ fn json_schema_struct(
                 properties.map_or(quote!(), |properties| quote!("properties": {#properties},));
             Ok(quote! {
                 #register_exp
-                let schema = serde_json::json!({
+                let mut schema = serde_json::json!({
                     "type": "object",
                     "required": [#required],
                     #properties
                     #all_of
                 });
+                #defaults
 
                 schema
             })
@@ -265,20 +430,16 @@ fn json_schema_struct(
 
                 num_fields += 1;
                 let ty = &field.ty;
+                let (register_schema, schema_ref) =
+                    SchemaAttributes::try_from_attributes(&field.attrs, true)?.field_schema(ty);
 
                 refs.extend(quote! {
-                    { "$refs": <#ty as JsonSchema>::schema_ref() },
+                    { "$refs": #schema_ref },
                 });
 
                 if !types.contains(ty) {
                     types.insert(ty);
-                    register_exp.extend(quote! {
-                        let ty_title = <#ty as JsonSchema>::schema_title();
-                        if !refs.contains_key(&ty_title) {
-                            let ty_schema = <#ty as JsonSchema>::json_schema(refs);
-                            refs.insert(ty_title, ty_schema);
-                        }
-                    });
+                    register_exp.extend(register_schema);
                 }
             }
 
@@ -340,22 +501,18 @@ fn json_schema_enum(
                     }
 
                     let ty = &field.ty;
+                    let (register_schema, schema_ref) =
+                        SchemaAttributes::try_from_attributes(&field.attrs, true)?.field_schema(ty);
 
                     if !types.contains(ty) {
                         types.insert(ty);
-                        register_exp.extend(quote! {
-                        let ty_title = <#ty as JsonSchema>::schema_title();
-                        if !refs.contains_key(&ty_title) {
-                            let ty_schema = <#ty as JsonSchema>::json_schema(refs);
-                            refs.insert(ty_title, ty_schema);
-                            }
-                        });
+                        register_exp.extend(register_schema);
                     }
 
                     if serde_attributes.flatten {
                         all_of.replace(quote! {
                             #all_of
-                            { "$ref": <#ty as JsonSchema>::schema_ref() }
+                            { "$ref": #schema_ref }
                         });
                         continue;
                     }
@@ -374,7 +531,7 @@ fn json_schema_enum(
 
                     properties.replace(quote! {
                         #properties
-                        #field_name: { "$ref": <#ty as JsonSchema>::schema_ref() },
+                        #field_name: { "$ref": #schema_ref },
                     });
                 }
 
@@ -412,20 +569,16 @@ fn json_schema_enum(
 
                     num_fields += 1;
                     let ty = &field.ty;
+                    let (register_schema, schema_ref) =
+                        SchemaAttributes::try_from_attributes(&field.attrs, true)?.field_schema(ty);
 
                     if !types.contains(ty) {
                         types.insert(ty);
-                        register_exp.extend(quote! {
-                        let ty_title = <#ty as JsonSchema>::schema_title();
-                            if !refs.contains_key(&ty_title) {
-                                let ty_schema = <#ty as JsonSchema>::json_schema(refs);
-                                refs.insert(ty_title, ty_schema);
-                            }
-                        });
+                        register_exp.extend(register_schema);
                     }
 
                     refs.extend(quote! {
-                        { "$refs": <#ty as JsonSchema>::schema_ref() },
+                        { "$refs": #schema_ref },
                     });
                 }
 
@@ -463,14 +616,129 @@ fn json_schema_enum(
 }
 
 fn is_option(ty: &Type) -> bool {
-    if let Type::Path(type_path) = ty {
-        if let Some(segment) = type_path.path.segments.last() {
-            return segment.ident == "Option";
-        }
+    if let Type::Path(type_path) = ty
+        && let Some(segment) = type_path.path.segments.last()
+    {
+        return segment.ident == "Option";
     }
     false
 }
 
+/// Parses the where predicates of a `bound = "..."` attribute.
+fn parse_bound(meta: &Meta) -> Result<Punctuated<WherePredicate, Token![,]>, syn::Error> {
+    let name_value = meta.require_name_value()?;
+
+    let Expr::Lit(ExprLit {
+        lit: Lit::Str(lit_str),
+        ..
+    }) = &name_value.value
+    else {
+        return Err(syn::Error::new(
+            name_value.span(),
+            "Bound must be a string literal",
+        ));
+    };
+
+    lit_str.parse_with(Punctuated::<WherePredicate, Token![,]>::parse_terminated)
+}
+
+/// Adds the predicates of a `bound = "..."` attribute to the where clause of the generated impl.
+/// Builds the body of `prototype_name`, the name of generic prototypes includes the names of
+/// their parameters, e.g. `stat<strength>` for `Stat<Strength>` named `stat`.
+///
+/// The name can place the parameters itself with `{T}` placeholders, e.g. `{T}_stat`, type
+/// parameters are named after their type in `snake_case` and const parameters by their value.
+fn compose_prototype_name(
+    name: &str,
+    generics: &Generics,
+) -> Result<proc_macro2::TokenStream, syn::Error> {
+    let params = generics
+        .params
+        .iter()
+        .filter_map(|param| match param {
+            GenericParam::Type(param) => Some((param.ident.clone(), false)),
+            GenericParam::Const(param) => Some((param.ident.clone(), true)),
+            GenericParam::Lifetime(_) => None,
+        })
+        .collect::<Vec<_>>();
+
+    if params.is_empty() {
+        return Ok(quote! { #name });
+    }
+
+    let template = if name.contains('{') {
+        name.to_string()
+    } else {
+        let params = params
+            .iter()
+            .map(|(param, _)| format!("{{{param}}}"))
+            .collect::<Vec<_>>();
+
+        format!("{name}<{}>", params.join(","))
+    };
+
+    let mut parts = Vec::new();
+    let mut rest = template.as_str();
+
+    while let Some(start) = rest.find('{') {
+        let Some(end) = rest[start..].find('}').map(|end| start + end) else {
+            return Err(syn::Error::new(
+                proc_macro2::Span::call_site(),
+                format!("Unclosed placeholder in prototype name {name}"),
+            ));
+        };
+
+        let literal = &rest[..start];
+        let placeholder = &rest[start + 1..end];
+        rest = &rest[end + 1..];
+
+        if !literal.is_empty() {
+            parts.push(quote! { name.push_str(#literal); });
+        }
+
+        let Some((param, is_const)) = params.iter().find(|(param, _)| param == placeholder) else {
+            return Err(syn::Error::new(
+                proc_macro2::Span::call_site(),
+                format!("Unknown generic parameter {{{placeholder}}} in prototype name {name}"),
+            ));
+        };
+
+        parts.push(if *is_const {
+            quote! { name.push_str(&::std::string::ToString::to_string(&#param)); }
+        } else {
+            quote! { name.push_str(&::bevy_histrion_proto::_private::prototype_param_name::<#param>()); }
+        });
+    }
+
+    if !rest.is_empty() {
+        parts.push(quote! { name.push_str(#rest); });
+    }
+
+    Ok(quote! {
+        static NAME: ::bevy_histrion_proto::_private::GenericTypePathCell =
+            ::bevy_histrion_proto::_private::GenericTypePathCell::new();
+
+        NAME.get_or_insert::<Self, _>(|| {
+            let mut name = ::std::string::String::new();
+            #(#parts)*
+            name
+        })
+    })
+}
+
+fn with_bound(
+    generics: &Generics,
+    bound: Option<Punctuated<WherePredicate, Token![,]>>,
+) -> Generics {
+    let mut generics = generics.clone();
+
+    if let Some(bound) = bound {
+        generics.make_where_clause().predicates.extend(bound);
+    }
+
+    generics
+}
+
 fn do_reflect_deserialize(attrs: &[Attribute]) -> bool {
     for attr in attrs {
         if !attr.path().is_ident("reflect") {
@@ -0,0 +1,212 @@
+use serde_json::{Map as JsonMap, Value as JsonValue};
+
+/// Prefix of the strings referencing a fragment, e.g. `"$fragments.fire_dot"`.
+const FRAGMENT_PREFIX: &str = "$fragments.";
+
+/// Expands the fragments of a file, values declared once in the `$fragments` object of any of
+/// its entries and referenced by name from the fields of the entries:
+///
+/// ```json
+/// [
+///     { "$fragments": { "fire_dot": { "damage": 2.0, "duration": 3.0 } } },
+///     { "type": "sword", "name": "flame_blade", "effects": ["$fragments.fire_dot"] },
+///     { "type": "arrow", "name": "fire_arrow", "on_hit": "$fragments.fire_dot" }
+/// ]
+/// ```
+///
+/// Each `"$fragments.<name>"` string is replaced by the value of the fragment, fragments can
+/// reference other fragments. Entries only declaring fragments are returned as `None`, keeping
+/// the index of the other entries. Errors are reported with the index of their entry.
+pub fn expand_fragments(
+    entries: Vec<JsonValue>,
+) -> Result<Vec<Option<JsonValue>>, (usize, String)> {
+    let mut fragments = JsonMap::new();
+    let mut entries = entries
+        .into_iter()
+        .enumerate()
+        .map(|(index, mut entry)| {
+            let Some(declared) = entry
+                .as_object_mut()
+                .and_then(|fields| fields.remove("$fragments"))
+            else {
+                return Ok(Some(entry));
+            };
+
+            let JsonValue::Object(declared) = declared else {
+                return Err((index, "`$fragments` isn't an object".to_string()));
+            };
+
+            for (name, fragment) in declared {
+                if fragments.contains_key(&name) {
+                    return Err((index, format!("fragment {name} is declared twice")));
+                }
+
+                fragments.insert(name, fragment);
+            }
+
+            Ok(entry
+                .as_object()
+                .is_some_and(|fields| !fields.is_empty())
+                .then_some(entry))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for (index, entry) in entries.iter_mut().enumerate() {
+        if let Some(entry) = entry {
+            substitute(entry, &fragments, &mut Vec::new()).map_err(|err| (index, err))?;
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Returns the fragment referenced by `value`, if it's a `"$fragments.<name>"` reference.
+pub fn fragment_reference<'a>(
+    value: &JsonValue,
+    fragments: &'a JsonMap<String, JsonValue>,
+) -> Option<&'a JsonValue> {
+    value
+        .as_str()
+        .and_then(|value| value.strip_prefix(FRAGMENT_PREFIX))
+        .and_then(|name| fragments.get(name))
+}
+
+/// Returns the fragments declared by the entries of a file, see [`expand_fragments`].
+pub fn declared_fragments(file: &JsonValue) -> JsonMap<String, JsonValue> {
+    let entries = match file {
+        JsonValue::Array(entries) => entries.as_slice(),
+        entry => core::slice::from_ref(entry),
+    };
+
+    entries
+        .iter()
+        .filter_map(|entry| entry.get("$fragments").and_then(JsonValue::as_object))
+        .flatten()
+        .map(|(name, fragment)| (name.clone(), fragment.clone()))
+        .collect()
+}
+
+/// Replaces the fragment references of `value`, `stack` holds the fragments being expanded.
+fn substitute(
+    value: &mut JsonValue,
+    fragments: &JsonMap<String, JsonValue>,
+    stack: &mut Vec<String>,
+) -> Result<(), String> {
+    match value {
+        JsonValue::String(string) => {
+            let Some(name) = string.strip_prefix(FRAGMENT_PREFIX).map(str::to_string) else {
+                return Ok(());
+            };
+
+            if stack.contains(&name) {
+                return Err(format!("fragment {name} references itself"));
+            }
+
+            let Some(fragment) = fragments.get(&name) else {
+                return Err(format!("unknown fragment {name}"));
+            };
+
+            let mut fragment = fragment.clone();
+
+            stack.push(name);
+            substitute(&mut fragment, fragments, stack)?;
+            stack.pop();
+
+            *value = fragment;
+        }
+        JsonValue::Array(values) => {
+            for value in values {
+                substitute(value, fragments, stack)?;
+            }
+        }
+        JsonValue::Object(fields) => {
+            for value in fields.values_mut() {
+                substitute(value, fragments, stack)?;
+            }
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn expand(file: JsonValue) -> Result<Vec<Option<JsonValue>>, (usize, String)> {
+        let JsonValue::Array(entries) = file else {
+            unreachable!()
+        };
+
+        expand_fragments(entries)
+    }
+
+    #[test]
+    fn references_are_replaced_by_their_fragment() {
+        let entries = expand(json!([
+            { "$fragments": { "burn": { "damage": 2 }, "burns": ["$fragments.burn", "$fragments.burn"] } },
+            { "name": "a", "effects": "$fragments.burns", "on_hit": "$fragments.burn" }
+        ]))
+        .unwrap();
+
+        assert_eq!(
+            entries,
+            [
+                None,
+                Some(
+                    json!({ "name": "a", "effects": [{ "damage": 2 }, { "damage": 2 }], "on_hit": { "damage": 2 } })
+                )
+            ]
+        );
+    }
+
+    #[test]
+    fn entries_declaring_fragments_keep_their_other_fields() {
+        let entries =
+            expand(json!([{ "$fragments": { "x": 1 }, "name": "a", "value": "$fragments.x" }]))
+                .unwrap();
+
+        assert_eq!(entries, [Some(json!({ "name": "a", "value": 1 }))]);
+    }
+
+    #[test]
+    fn invalid_fragments_are_errors() {
+        assert_eq!(
+            expand(json!([{ "$fragments": [1] }])),
+            Err((0, "`$fragments` isn't an object".to_string()))
+        );
+        assert_eq!(
+            expand(json!([{ "$fragments": { "x": 1 } }, { "$fragments": { "x": 2 } }])),
+            Err((1, "fragment x is declared twice".to_string()))
+        );
+        assert_eq!(
+            expand(json!([{ "name": "a", "value": "$fragments.x" }])),
+            Err((0, "unknown fragment x".to_string()))
+        );
+        assert_eq!(
+            expand(json!([
+                { "$fragments": { "x": ["$fragments.y"], "y": { "z": "$fragments.x" } } },
+                { "name": "a", "value": "$fragments.x" }
+            ])),
+            Err((1, "fragment x references itself".to_string()))
+        );
+    }
+
+    #[test]
+    fn declared_fragments_are_found_in_any_entry() {
+        let file = json!([{ "name": "a" }, { "$fragments": { "x": 1 } }]);
+
+        assert_eq!(declared_fragments(&file).get("x"), Some(&json!(1)));
+        assert_eq!(
+            fragment_reference(&json!("$fragments.x"), &declared_fragments(&file)),
+            Some(&json!(1))
+        );
+        assert_eq!(
+            fragment_reference(&json!("x"), &declared_fragments(&file)),
+            None
+        );
+    }
+}
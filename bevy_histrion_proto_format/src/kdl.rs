@@ -0,0 +1,274 @@
+use std::path::Path;
+
+use ::kdl::{KdlDocument, KdlEntry, KdlNode, KdlValue};
+use serde_json::{Map as JsonMap, Number as JsonNumber, Value as JsonValue};
+
+use crate::{is_kdl_file, line_column};
+
+/// Reads the entries of a KDL prototypes file with their byte offset, `None` for the other files.
+///
+/// Each top-level node is a prototype, named after its type with its name as argument, the
+/// properties and children of a node are the fields of its object:
+///
+/// ```kdl
+/// sword "wooden_stick" tags="weapon" {
+///     damage 1.0
+///     stats speed=1.5
+///     (list)effects "bleeding"
+///     description """
+///         A stick.
+///         Better than nothing.
+///         """
+/// }
+/// ```
+///
+/// A node with a single argument is that value and a node with several arguments a list. A
+/// node annotated with `(list)` is always a list, of its arguments or of its `-` children, which
+/// are also how lists of objects are written. The other type annotations are ignored. Both KDL
+/// v2 and v1 documents are read.
+pub fn read_kdl_file(
+    path: &Path,
+    bytes: &[u8],
+) -> std::io::Result<Option<Vec<(usize, JsonValue)>>> {
+    use std::io::{Error, ErrorKind};
+
+    if !is_kdl_file(path) {
+        return Ok(None);
+    }
+
+    let text = std::str::from_utf8(bytes).map_err(|err| Error::new(ErrorKind::InvalidData, err))?;
+
+    let document = KdlDocument::parse(text).map_err(|err| {
+        let message = match err.diagnostics.first() {
+            Some(diagnostic) => {
+                let (line, column) = line_column(bytes, diagnostic.span.offset());
+                format!("{line}:{column}: {diagnostic}")
+            }
+            None => err.to_string(),
+        };
+
+        Error::new(ErrorKind::InvalidData, message)
+    })?;
+
+    document
+        .nodes()
+        .iter()
+        .map(|node| Ok((node.span().offset(), node_to_entry(text, node)?)))
+        .collect::<Result<_, String>>()
+        .map(Some)
+        .map_err(|err| Error::new(ErrorKind::InvalidData, err))
+}
+
+/// Formats an error at the position of `node`.
+fn node_error(text: &str, node: &KdlNode, message: impl core::fmt::Display) -> String {
+    let (line, column) = line_column(text.as_bytes(), node.span().offset());
+    format!("{line}:{column}: {message}")
+}
+
+/// Converts a top-level node into a prototype entry.
+fn node_to_entry(text: &str, node: &KdlNode) -> Result<JsonValue, String> {
+    let mut args = node.entries().iter().filter(|entry| entry.name().is_none());
+    let (Some(KdlValue::String(name)), None) = (args.next().map(KdlEntry::value), args.next())
+    else {
+        return Err(node_error(
+            text,
+            node,
+            format!(
+                "prototype node {} must have its name as only argument",
+                node.name().value()
+            ),
+        ));
+    };
+
+    let mut entry = JsonMap::new();
+    entry.insert("type".to_string(), node.name().value().into());
+    entry.insert("name".to_string(), name.as_str().into());
+
+    node_fields(text, node, &mut entry)?;
+
+    // A single tag is written as a single argument.
+    if let Some(tag @ JsonValue::String(_)) = entry.get_mut("tags") {
+        *tag = JsonValue::Array(vec![tag.take()]);
+    }
+
+    Ok(JsonValue::Object(entry))
+}
+
+fn node_value(text: &str, node: &KdlNode) -> Result<JsonValue, String> {
+    let children = node.children().map_or(&[][..], KdlDocument::nodes);
+    let is_list = node.ty().is_some_and(|ty| ty.value() == "list")
+        || (!children.is_empty() && children.iter().all(|child| child.name().value() == "-"));
+
+    let mut args = Vec::new();
+    let mut has_props = false;
+    for entry in node.entries() {
+        match entry.name() {
+            Some(_) => has_props = true,
+            None => args.push(kdl_value(text, node, entry.value())?),
+        }
+    }
+
+    if is_list {
+        if has_props || (!args.is_empty() && !children.is_empty()) {
+            return Err(node_error(
+                text,
+                node,
+                format!(
+                    "list node {} must only have arguments or `-` children",
+                    node.name().value()
+                ),
+            ));
+        }
+
+        for child in children {
+            if child.name().value() != "-" {
+                return Err(node_error(
+                    text,
+                    child,
+                    format!("items of list {} must be `-` nodes", node.name().value()),
+                ));
+            }
+
+            args.push(node_value(text, child)?);
+        }
+
+        return Ok(JsonValue::Array(args));
+    }
+
+    if !has_props && node.children().is_none() {
+        return Ok(match args.len() {
+            0 => JsonValue::Null,
+            1 => args.remove(0),
+            _ => JsonValue::Array(args),
+        });
+    }
+
+    if !args.is_empty() {
+        return Err(node_error(
+            text,
+            node,
+            format!(
+                "node {} can't have both arguments and properties or children",
+                node.name().value()
+            ),
+        ));
+    }
+
+    let mut fields = JsonMap::new();
+    node_fields(text, node, &mut fields)?;
+
+    Ok(JsonValue::Object(fields))
+}
+
+fn node_fields(
+    text: &str,
+    node: &KdlNode,
+    fields: &mut JsonMap<String, JsonValue>,
+) -> Result<(), String> {
+    for entry in node.entries() {
+        if let Some(key) = entry.name() {
+            fields.insert(
+                key.value().to_string(),
+                kdl_value(text, node, entry.value())?,
+            );
+        }
+    }
+
+    for child in node.children().map_or(&[][..], KdlDocument::nodes) {
+        let name = child.name().value();
+        let value = node_value(text, child)?;
+
+        if fields.insert(name.to_string(), value).is_some() {
+            return Err(node_error(
+                text,
+                child,
+                format!("field {name} is set twice"),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+fn kdl_value(text: &str, node: &KdlNode, value: &KdlValue) -> Result<JsonValue, String> {
+    Ok(match value {
+        KdlValue::String(string) => JsonValue::String(string.clone()),
+        KdlValue::Integer(integer) => i64::try_from(*integer)
+            .map(JsonNumber::from)
+            .or_else(|_| u64::try_from(*integer).map(JsonNumber::from))
+            .map(JsonValue::Number)
+            .map_err(|_| node_error(text, node, format!("integer {integer} is out of range")))?,
+        KdlValue::Float(float) => JsonNumber::from_f64(*float)
+            .map(JsonValue::Number)
+            .ok_or_else(|| node_error(text, node, format!("float {float} isn't finite")))?,
+        KdlValue::Bool(bool) => JsonValue::Bool(*bool),
+        KdlValue::Null => JsonValue::Null,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn read(text: &str) -> std::io::Result<Option<Vec<(usize, JsonValue)>>> {
+        read_kdl_file(Path::new("items.proto.kdl"), text.as_bytes())
+    }
+
+    #[test]
+    fn nodes_are_prototype_entries() {
+        let text = r#"
+sword "wooden_stick" tags="weapon" {
+    damage 1.0
+    stats speed=1.5
+    (list)effects "bleeding"
+    upgrades {
+        - level=1
+        - level=2
+    }
+    pair 1 2
+}
+"#;
+
+        let entries = read(text).unwrap().unwrap();
+
+        assert_eq!(entries[0].0, text.find("sword").unwrap());
+        assert_eq!(
+            entries[0].1,
+            json!({
+                "type": "sword",
+                "name": "wooden_stick",
+                "tags": ["weapon"],
+                "damage": 1.0,
+                "stats": { "speed": 1.5 },
+                "effects": ["bleeding"],
+                "upgrades": [{ "level": 1 }, { "level": 2 }],
+                "pair": [1, 2],
+            })
+        );
+    }
+
+    #[test]
+    fn invalid_nodes_are_reported_at_their_position() {
+        let err = read("sword \"a\"\nsword 1\n").unwrap_err();
+        assert!(err.to_string().starts_with("2:1: "), "{err}");
+
+        let err = read("sword \"a\" {\n    damage 1\n    damage 2\n}\n").unwrap_err();
+        assert!(
+            err.to_string().contains("field damage is set twice"),
+            "{err}"
+        );
+
+        assert!(read("sword \"a\" {\n").is_err());
+    }
+
+    #[test]
+    fn other_files_are_not_read() {
+        assert!(
+            read_kdl_file(Path::new("items.proto.json"), b"{}")
+                .unwrap()
+                .is_none()
+        );
+    }
+}
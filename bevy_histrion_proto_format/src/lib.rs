@@ -0,0 +1,132 @@
+//! Readers of the prototypes files shared by `bevy_histrion_proto` and
+//! `bevy_histrion_proto_build`, so the build checks read the files the way the loader does.
+//!
+//! This crate is an implementation detail of `bevy_histrion_proto`, its API isn't stable.
+
+use std::path::Path;
+
+use serde_json::Value as JsonValue;
+
+mod fragment;
+#[cfg(feature = "kdl")]
+mod kdl;
+mod table;
+
+pub use fragment::*;
+#[cfg(feature = "kdl")]
+pub use kdl::*;
+pub use table::*;
+
+/// Extensions of the JSON prototypes files.
+pub const PROTOTYPE_ASSET_EXTENSIONS: &[&str] = &["proto", "proto.json", "proto.ndjson"];
+
+/// Extension of the newline-delimited prototypes files, one entry per line.
+pub const PROTOTYPE_NDJSON_EXTENSION: &str = "proto.ndjson";
+
+/// Extensions of the prototype tables.
+pub const PROTOTYPE_TABLE_EXTENSIONS: &[&str] = &["proto.csv", "proto.tsv"];
+
+/// Extensions of the KDL prototypes files, read with the `kdl` feature.
+pub const PROTOTYPE_KDL_EXTENSIONS: &[&str] = &["proto.kdl"];
+
+/// Returns `true` if the file name of `path` ends with one of the `extensions`.
+pub fn has_extension(path: &Path, extensions: &[&str]) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| {
+            extensions
+                .iter()
+                .any(|extension| name.ends_with(&format!(".{extension}")))
+        })
+}
+
+/// Returns `true` for the newline-delimited prototypes files.
+pub fn is_ndjson_file(path: &Path) -> bool {
+    has_extension(path, &[PROTOTYPE_NDJSON_EXTENSION])
+}
+
+/// Returns `true` for the prototype tables.
+pub fn is_prototype_table_file(path: &Path) -> bool {
+    has_extension(path, PROTOTYPE_TABLE_EXTENSIONS)
+}
+
+/// Returns `true` for the KDL prototypes files.
+pub fn is_kdl_file(path: &Path) -> bool {
+    has_extension(path, PROTOTYPE_KDL_EXTENSIONS)
+}
+
+/// Parses the entries of a newline-delimited prototypes file, any whitespace between the entries
+/// is accepted so tools can append to the file without caring about its last line.
+pub fn read_ndjson(bytes: &[u8]) -> serde_json::Result<Vec<JsonValue>> {
+    serde_json::Deserializer::from_slice(bytes)
+        .into_iter()
+        .collect()
+}
+
+/// Returns the line and column, starting at 1, of the byte `offset` of a file.
+pub fn line_column(bytes: &[u8], offset: usize) -> (usize, usize) {
+    let before = &bytes[..offset.min(bytes.len())];
+    let line = before.iter().filter(|byte| **byte == b'\n').count() + 1;
+    let line_start = before
+        .iter()
+        .rposition(|byte| *byte == b'\n')
+        .map_or(0, |position| position + 1);
+    let column = String::from_utf8_lossy(&before[line_start..])
+        .chars()
+        .count()
+        + 1;
+
+    (line, column)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn files_are_told_apart_by_their_extension() {
+        assert!(is_ndjson_file(Path::new("items.proto.ndjson")));
+        assert!(is_prototype_table_file(Path::new("assets/sword.proto.tsv")));
+        assert!(!is_prototype_table_file(Path::new("sword.csv")));
+        assert!(is_kdl_file(Path::new("items.proto.kdl")));
+        assert!(has_extension(
+            Path::new("items.proto"),
+            PROTOTYPE_ASSET_EXTENSIONS
+        ));
+        assert!(!has_extension(
+            Path::new("items.proto.mobile.json"),
+            PROTOTYPE_ASSET_EXTENSIONS
+        ));
+    }
+
+    #[test]
+    fn ndjson_entries_may_be_separated_by_any_whitespace() {
+        let entries =
+            read_ndjson(b"{\"name\": \"a\"}\n\n{\"name\": \"b\"} {\"name\": \"c\"}\n").unwrap();
+
+        assert_eq!(
+            entries,
+            [
+                json!({ "name": "a" }),
+                json!({ "name": "b" }),
+                json!({ "name": "c" })
+            ]
+        );
+        assert!(read_ndjson(b"{\"name\": \"a\"}\n{").is_err());
+    }
+
+    #[test]
+    fn columns_count_characters() {
+        let text = "first\nsé ab\n";
+
+        assert_eq!(line_column(text.as_bytes(), 0), (1, 1));
+        assert_eq!(line_column(text.as_bytes(), 6), (2, 1));
+        assert_eq!(
+            line_column(text.as_bytes(), text.find('a').unwrap()),
+            (2, 4)
+        );
+        assert_eq!(line_column(text.as_bytes(), 100), (3, 1));
+    }
+}
@@ -0,0 +1,299 @@
+use std::path::Path;
+
+use serde_json::{Map as JsonMap, Value as JsonValue};
+
+use crate::PROTOTYPE_TABLE_EXTENSIONS;
+
+/// The delimited text formats of the prototype tables.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TableFormat {
+    /// Comma-separated values.
+    #[default]
+    Csv,
+    /// Tab-separated values.
+    Tsv,
+}
+
+impl TableFormat {
+    /// Returns the character separating the cells of a row.
+    pub fn delimiter(&self) -> char {
+        match self {
+            Self::Csv => ',',
+            Self::Tsv => '\t',
+        }
+    }
+
+    /// Returns the usual extension of the files of this format.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Tsv => "tsv",
+        }
+    }
+
+    /// Returns the format of a table file from its extension, CSV by default.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("tsv") => Self::Tsv,
+            _ => Self::Csv,
+        }
+    }
+}
+
+/// Reads the rows of a CSV or TSV table, with the line each row starts at, starting at 1.
+///
+/// Quoted cells may contain the delimiter, line breaks and doubled quotes, empty lines are
+/// skipped.
+fn read_rows(text: &str, delimiter: char) -> Result<Vec<(usize, Vec<String>)>, String> {
+    let mut rows = Vec::new();
+    let (mut row, mut cell) = (Vec::new(), String::new());
+    let (mut line, mut row_line) = (1, 1);
+    let mut chars = text.chars().peekable();
+    let mut in_quotes = false;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                chars.next();
+                cell.push('"');
+            }
+            '"' if in_quotes => in_quotes = false,
+            '"' if cell.is_empty() => in_quotes = true,
+            '\n' if in_quotes => {
+                line += 1;
+                cell.push(c);
+            }
+            '\r' if !in_quotes => {}
+            '\n' => {
+                row.push(core::mem::take(&mut cell));
+                let cells = core::mem::take(&mut row);
+
+                if cells.len() > 1 || !cells[0].is_empty() {
+                    rows.push((row_line, cells));
+                }
+
+                line += 1;
+                row_line = line;
+            }
+            c if c == delimiter && !in_quotes => row.push(core::mem::take(&mut cell)),
+            c => cell.push(c),
+        }
+    }
+
+    if in_quotes {
+        return Err(format!("unclosed quote in the row at line {row_line}"));
+    }
+
+    if !row.is_empty() || !cell.is_empty() {
+        row.push(cell);
+        rows.push((row_line, row));
+    }
+
+    Ok(rows)
+}
+
+/// Converts the rows of a table into prototype entries with the line
+/// of their row.
+///
+/// Prototypes without a `type` cell have the `default_ty` type, `cell_value` converts the
+/// non-empty cells of the fields, given the type and the reflect path of the field, empty cells
+/// are left to the default value of their field.
+pub fn table_to_entries(
+    text: &str,
+    format: TableFormat,
+    default_ty: Option<&str>,
+    mut cell_value: impl FnMut(&str, &str, &str) -> JsonValue,
+) -> Result<Vec<(usize, JsonValue)>, String> {
+    let mut rows = read_rows(text, format.delimiter())?.into_iter();
+
+    let Some((_, header)) = rows.next() else {
+        return Ok(Vec::new());
+    };
+
+    if !header.iter().any(|column| column == "name") {
+        return Err("the table has no name column".to_string());
+    }
+
+    rows.map(|(line, cells)| {
+        let mut entry = JsonMap::new();
+        let ty = header
+            .iter()
+            .zip(&cells)
+            .find(|(column, _)| *column == "type")
+            .map(|(_, ty)| ty.as_str())
+            .filter(|ty| !ty.is_empty())
+            .or(default_ty)
+            .ok_or_else(|| format!("the row at line {line} has no type"))?
+            .to_string();
+
+        for (column, cell) in header.iter().zip(cells) {
+            match column.as_str() {
+                "type" => {}
+                "name" => {
+                    entry.insert(column.clone(), JsonValue::String(cell));
+                }
+                "tags" => {
+                    let tags = cell
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|tag| !tag.is_empty())
+                        .map(|tag| JsonValue::String(tag.to_string()))
+                        .collect();
+
+                    entry.insert(column.clone(), JsonValue::Array(tags));
+                }
+                _ if cell.is_empty() => {}
+                path => {
+                    let value = cell_value(&ty, path, &cell);
+                    insert_path(&mut entry, path, value)
+                        .map_err(|err| format!("invalid column {path} at line {line}: {err}"))?;
+                }
+            }
+        }
+
+        entry.insert("type".to_string(), JsonValue::String(ty));
+
+        Ok((line, JsonValue::Object(entry)))
+    })
+    .collect()
+}
+
+/// Converts a cell into JSON without knowing its type, see `typed_cell_value`.
+pub fn untyped_cell_value(cell: &str) -> JsonValue {
+    serde_json::from_str(cell).unwrap_or_else(|_| JsonValue::String(cell.to_string()))
+}
+
+/// Inserts `value` at the dot separated `path` of `entry`, creating the nested objects.
+fn insert_path(
+    entry: &mut JsonMap<String, JsonValue>,
+    path: &str,
+    value: JsonValue,
+) -> Result<(), String> {
+    let (object, field) = match path.rsplit_once('.') {
+        Some((parents, field)) => {
+            let mut object = &mut *entry;
+
+            for parent in parents.split('.') {
+                object = match object
+                    .entry(parent)
+                    .or_insert_with(|| JsonValue::Object(JsonMap::new()))
+                {
+                    JsonValue::Object(object) => object,
+                    _ => return Err(format!("{parent} is also set as a whole")),
+                };
+            }
+
+            (object, field)
+        }
+        None => (entry, path),
+    };
+
+    object.insert(field.to_string(), value);
+
+    Ok(())
+}
+
+/// Returns the prototype type a table file is named after, e.g. `sword` for `sword.proto.csv`.
+pub fn table_type(path: &Path) -> Option<&str> {
+    let file_name = path.file_name()?.to_str()?;
+
+    PROTOTYPE_TABLE_EXTENSIONS
+        .iter()
+        .find_map(|extension| file_name.strip_suffix(&format!(".{extension}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn quoted_cells_keep_their_delimiters_and_line_breaks() {
+        let rows = read_rows("a,\"b,c\",\"d\"\"e\"\r\n\n\"f\ng\",h\n", ',').unwrap();
+
+        assert_eq!(
+            rows,
+            [
+                (
+                    1,
+                    vec!["a".to_string(), "b,c".to_string(), "d\"e".to_string()]
+                ),
+                (3, vec!["f\ng".to_string(), "h".to_string()]),
+            ]
+        );
+        assert!(read_rows("a,\"b\n", ',').is_err());
+    }
+
+    #[test]
+    fn rows_are_entries_of_the_table_type() {
+        let text = "name\ttags\tstats.damage\tstats.range\ttype\n\
+                    stick\tweapon, melee\t1\t\t\n\
+                    bow\t\t2\t10\tranged_weapon\n";
+
+        let entries = table_to_entries(text, TableFormat::Tsv, Some("sword"), |_, _, cell| {
+            untyped_cell_value(cell)
+        })
+        .unwrap();
+
+        assert_eq!(
+            entries,
+            [
+                (
+                    2,
+                    json!({
+                        "type": "sword",
+                        "name": "stick",
+                        "tags": ["weapon", "melee"],
+                        "stats": { "damage": 1 },
+                    })
+                ),
+                (
+                    3,
+                    json!({
+                        "type": "ranged_weapon",
+                        "name": "bow",
+                        "tags": [],
+                        "stats": { "damage": 2, "range": 10 },
+                    })
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn tables_need_names_and_types() {
+        let cell = |_: &str, _: &str, cell: &str| untyped_cell_value(cell);
+
+        assert!(table_to_entries("damage\n1\n", TableFormat::Csv, Some("sword"), cell).is_err());
+        assert!(table_to_entries("name\nstick\n", TableFormat::Csv, None, cell).is_err());
+        assert!(
+            table_to_entries(
+                "name,stats,stats.damage\nstick,1,2\n",
+                TableFormat::Csv,
+                Some("sword"),
+                cell
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn tables_are_named_after_their_type() {
+        assert_eq!(
+            table_type(Path::new("assets/sword.proto.csv")),
+            Some("sword")
+        );
+        assert_eq!(table_type(Path::new("sword.csv")), None);
+        assert_eq!(
+            TableFormat::from_path(Path::new("a.proto.tsv")),
+            TableFormat::Tsv
+        );
+        assert_eq!(
+            TableFormat::from_path(Path::new("a.proto.csv")),
+            TableFormat::Csv
+        );
+        assert_eq!(untyped_cell_value("stick"), json!("stick"));
+        assert_eq!(untyped_cell_value("[1, 2]"), json!([1, 2]));
+    }
+}
@@ -7,6 +7,8 @@ use prototypes::*;
 #[derive(Debug, Clone, Reflect, Default, Resource, Deref)]
 struct HaveDlc(bool);
 
+const WOODEN_STICK: SwordId = Sword::id("wooden_stick");
+
 fn main() {
     let mut app = App::new();
 
@@ -18,7 +20,7 @@ fn main() {
     .add_plugins(PrototypesPlugin)
     .insert_resource(HaveDlc(true))
     .add_systems(Startup, load_prototypes)
-    .add_systems(Update, on_new_sword);
+    .add_systems(Update, (on_new_sword, on_new_effect));
 
     app.run();
 }
@@ -32,16 +34,16 @@ fn load_prototypes(mut prototype_server: PrototypeServer, have_dlc: Res<HaveDlc>
 }
 
 fn on_new_sword(swords: Reg<Sword>, icons: Res<Assets<Icon>>) {
-    if let Some(sword) = swords.get("wooden_stick") {
+    if let Some(sword) = swords.get(WOODEN_STICK) {
         info!(
             r#"New sword:
-            name: {}
+            id: {}
             damage: {}
             level: {}
             effects: {:?}
             icon: {}
         "#,
-            sword.name(),
+            sword.id(),
             sword.damage,
             sword.level,
             sword.effects,
@@ -50,27 +52,27 @@ fn on_new_sword(swords: Reg<Sword>, icons: Res<Assets<Icon>>) {
     }
 }
 
-// fn on_new_effect(
-//     mut events: EventReader<RegistryEvent<Effect>>,
-//     effects: Reg<Effect>,
-//     icons: Res<Assets<Icon>>,
-// ) {
-//     for event in events.read() {
-//         if let RegistryEvent::Added(id) = event {
-//             let effect = effects.get(id).unwrap();
-//             info!(
-//                 r#"New effect:
-//         id: {}
-//         damage_multiplier: x{}
-//         slow_factor: {}%
-//         slow_duration: {}s
-//         icon: {}"#,
-//                 effect.id,
-//                 effect.damage_multiplier.unwrap_or(1.0),
-//                 effect.slow_factor.unwrap_or(0.0) * 100.0,
-//                 effect.slow_duration.unwrap_or(0.0),
-//                 icons.get(&effect.icon).unwrap()
-//             );
-//         }
-//     }
-// }
+fn on_new_effect(
+    mut events: EventReader<RegistryEvent<Effect>>,
+    effects: Reg<Effect>,
+    icons: Res<Assets<Icon>>,
+) {
+    for event in events.read() {
+        if let RegistryEvent::Added(id) = event {
+            let effect = effects.get(*id).unwrap();
+            info!(
+                r#"New effect:
+        id: {}
+        damage_multiplier: x{}
+        slow_factor: {}%
+        slow_duration: {}s
+        icon: {}"#,
+                effect.id(),
+                effect.damage_multiplier.unwrap_or(1.0),
+                effect.slow_factor.unwrap_or(0.0) * 100.0,
+                effect.slow_duration.unwrap_or(0.0),
+                icons.get(&effect.icon).unwrap()
+            );
+        }
+    }
+}
@@ -6,8 +6,9 @@ use bevy::{
 };
 use bevy_histrion_proto::prelude::*;
 
-#[derive(Debug, Default, Clone, Reflect, JsonSchema, Prototype)]
-#[proto(name = "sword")]
+#[derive(Debug, Clone, Reflect, JsonSchema, Prototype)]
+#[reflect(Default)]
+#[proto(name = "sword", ids)]
 pub struct Sword {
     pub damage: f32,
     pub level: u32,
@@ -15,7 +16,19 @@ pub struct Sword {
     pub icon: Handle<Icon>,
 }
 
+impl Default for Sword {
+    fn default() -> Self {
+        Self {
+            damage: 1.0,
+            level: 1,
+            effects: Vec::new(),
+            icon: Handle::default(),
+        }
+    }
+}
+
 #[derive(Debug, Default, Clone, Reflect, JsonSchema, Prototype)]
+#[reflect(Default)]
 #[proto(name = "effect")]
 pub struct Effect {
     pub damage_multiplier: Option<f32>,
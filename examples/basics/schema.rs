@@ -12,7 +12,10 @@ fn main() {
         .add_plugins(bevy_histrion_proto::PrototypesPlugin)
         .add_plugins(PrototypesPlugin);
 
-    let schema = app.get_prototypes_schemas();
-    println!("{}", &schema);
-    std::fs::write("./.vscode/prototypes.schema.json", &schema).unwrap();
+    // Includes the prototypes registered while finishing the plugins.
+    app.finish();
+    app.cleanup();
+
+    println!("{}", app.get_prototypes_schemas());
+    app.write_vscode_schema_settings(".vscode").unwrap();
 }
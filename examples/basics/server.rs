@@ -0,0 +1,39 @@
+use core::time::Duration;
+
+use bevy::{ecs::system::RunSystemOnce, log::LogPlugin, prelude::*};
+use bevy_histrion_proto::prelude::*;
+
+mod prototypes;
+use prototypes::*;
+
+fn main() {
+    let mut app = App::new();
+
+    // No rendering plugins, the icons of the prototypes aren't loaded by the server.
+    app.add_plugins((MinimalPlugins, AssetPlugin::default(), LogPlugin::default()))
+        .add_plugins(bevy_histrion_proto::ServerPrototypesPlugin)
+        .register_prototype::<Sword>()
+        .register_prototype::<Effect>();
+
+    if let Err(err) = app.load_prototypes_blocking(
+        ["prototypes", "mega_dlc.proto.json"],
+        Duration::from_secs(30),
+    ) {
+        error!("{err}");
+        return;
+    }
+
+    app.world_mut()
+        .run_system_once(|swords: Reg<Sword>| {
+            for sword in swords.iter() {
+                info!(
+                    "Sword {}: damage {}, level {}, effects {:?}",
+                    sword.id(),
+                    sword.damage,
+                    sword.level,
+                    sword.effects
+                );
+            }
+        })
+        .unwrap();
+}
@@ -0,0 +1,171 @@
+use std::io::BufRead;
+
+use bevy_histrion_proto::{
+    LintConfig, LintRule, LintSeverity, MergeStrategy, assign_prototype_uuids, diff_packs,
+    find_unknown_proto_ids, import_table, lint_pack, merge_packs, merge_packs_with,
+};
+
+const USAGE: &str = r#"usage:
+    pack diff <a> <b>
+    pack merge [--on-conflict last-wins|error|ask] <output> <inputs>...
+    pack manifest [--key <hex key>] <dir>
+    pack check-ids <src> <pack>
+    pack import <table> <output>
+    pack assign-uuids <pack>
+    pack validate [--asset-root <dir>] [--entry-type <type>]... [<rule>=allow|warn|deny]... <pack>
+
+lint rules (warn by default): snake_case_names, unused_tags, unreferenced_prototypes, missing_icons"#;
+
+fn main() {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let args = args.iter().map(String::as_str).collect::<Vec<_>>();
+
+    match args.as_slice() {
+        ["diff", a, b] => match diff_packs(a, b) {
+            Ok(diff) => print!("{diff}"),
+            Err(err) => exit_with_error(err),
+        },
+        ["merge", "--on-conflict", on_conflict, output, inputs @ ..] if !inputs.is_empty() => {
+            merge(on_conflict, output, inputs);
+        }
+        ["merge", output, inputs @ ..] if !inputs.is_empty() => merge("last-wins", output, inputs),
+        ["manifest", "--key", key, dir] => manifest(Some(key), dir),
+        ["manifest", dir] => manifest(None, dir),
+        ["check-ids", src, pack] => match find_unknown_proto_ids(src, pack) {
+            Ok(unknown) if unknown.is_empty() => {}
+            Ok(unknown) => {
+                for reference in unknown {
+                    eprintln!("{reference}: no prototype with this name");
+                }
+
+                std::process::exit(1);
+            }
+            Err(err) => exit_with_error(err),
+        },
+        ["import", table, output] => match import_table(table, output) {
+            Ok(written) => println!("{written} prototypes written to {output}"),
+            Err(err) => exit_with_error(err),
+        },
+        ["assign-uuids", pack] => match assign_prototype_uuids(pack) {
+            Ok(written) => println!("{written} uuids written"),
+            Err(err) => exit_with_error(err),
+        },
+        ["validate", options @ .., pack] => validate(options, pack),
+        _ => exit_with_error(USAGE),
+    }
+}
+
+fn validate(options: &[&str], pack: &str) {
+    let mut config = LintRule::ALL
+        .into_iter()
+        .fold(LintConfig::default(), |config, rule| {
+            config.with(rule, LintSeverity::Warn)
+        });
+    let mut asset_root = "assets";
+    let mut options = options.iter();
+
+    while let Some(option) = options.next() {
+        match *option {
+            "--asset-root" => asset_root = options.next().unwrap_or_else(|| exit_with_error(USAGE)),
+            "--entry-type" => {
+                let ty = options.next().unwrap_or_else(|| exit_with_error(USAGE));
+                config.entry_types.push(ty.to_string());
+            }
+            lint => {
+                let Some((rule, severity)) = lint.split_once('=') else {
+                    exit_with_error(USAGE);
+                };
+
+                let (Some(rule), Some(severity)) =
+                    (LintRule::from_name(rule), LintSeverity::from_name(severity))
+                else {
+                    exit_with_error(format!("invalid lint {lint}\n{USAGE}"));
+                };
+
+                config.set(rule, severity);
+            }
+        }
+    }
+
+    let issues = lint_pack(pack, asset_root, &config).unwrap_or_else(|err| exit_with_error(err));
+
+    for issue in &issues {
+        eprintln!("{issue}");
+    }
+
+    if issues
+        .iter()
+        .any(|issue| issue.severity == LintSeverity::Deny)
+    {
+        std::process::exit(1);
+    }
+}
+
+fn merge(on_conflict: &str, output: &str, inputs: &[&str]) {
+    let report = match on_conflict {
+        "last-wins" => merge_packs(inputs, output, MergeStrategy::LastWins),
+        "error" => merge_packs(inputs, output, MergeStrategy::Error),
+        "ask" => merge_packs_with(inputs, output, |conflict| {
+            println!("{} is defined in:", conflict.key);
+
+            for (i, entry) in conflict.entries.iter().enumerate() {
+                println!("    [{i}] {}", entry.file.display());
+            }
+
+            let mut line = String::new();
+            std::io::stdin()
+                .lock()
+                .read_line(&mut line)
+                .map_err(|source| bevy_histrion_proto::PackError::Io {
+                    path: "<stdin>".into(),
+                    source,
+                })?;
+
+            Ok(line.trim().parse().unwrap_or(conflict.entries.len() - 1))
+        }),
+        _ => exit_with_error(USAGE),
+    };
+
+    match report {
+        Ok(report) => {
+            for (key, file) in &report.resolved {
+                println!("{key}: kept {}", file.display());
+            }
+
+            println!("{} prototypes written to {output}", report.written);
+        }
+        Err(err) => exit_with_error(err),
+    }
+}
+
+fn manifest(key: Option<&str>, dir: &str) {
+    use bevy_histrion_proto::PackManifest;
+
+    // Without the integrity feature the manifest only lists the files, e.g. for web builds.
+    #[cfg(feature = "integrity")]
+    let mut manifest = PackManifest::generate(dir).unwrap_or_else(|err| exit_with_error(err));
+    #[cfg(not(feature = "integrity"))]
+    let manifest = PackManifest::list(dir).unwrap_or_else(|err| exit_with_error(err));
+
+    if let Some(key) = key {
+        #[cfg(feature = "integrity")]
+        {
+            let key = bevy_histrion_proto::PackSigningKey::from_hex(key)
+                .unwrap_or_else(|| exit_with_error("the key must be 64 hexadecimal characters"));
+            manifest.sign(&key);
+        }
+
+        #[cfg(not(feature = "integrity"))]
+        exit_with_error(format!("signing with {key} requires the integrity feature"));
+    }
+
+    match manifest.write(dir) {
+        Ok(()) => println!("{} files listed in {dir}", manifest.files.len()),
+        Err(err) => exit_with_error(err),
+    }
+}
+
+fn exit_with_error(err: impl std::fmt::Display) -> ! {
+    eprintln!("{err}");
+    std::process::exit(1);
+}
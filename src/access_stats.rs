@@ -0,0 +1,86 @@
+use core::any::TypeId;
+use std::sync::Mutex;
+
+use bevy::prelude::*;
+
+use crate::{ErasedPrototypeId, PrototypeData, PrototypeId, PrototypeIdMap, PrototypeName, Reg};
+
+/// Number of lookups of each prototype through [`Reg::get`], to find the content never used and
+/// the prototypes worth caching.
+#[derive(Resource, Default)]
+pub struct PrototypeAccessStats {
+    counts: Mutex<PrototypeIdMap<(TypeId, ErasedPrototypeId), u64>>,
+}
+
+impl PrototypeAccessStats {
+    pub(crate) fn record(&self, type_id: TypeId, id: ErasedPrototypeId) {
+        *self
+            .counts
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .entry((type_id, id))
+            .or_default() += 1;
+    }
+
+    /// Returns the number of lookups of the `id` prototype.
+    pub fn count<P: PrototypeData>(&self, id: impl Into<PrototypeId<P>>) -> u64 {
+        self.counts
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .get(&(TypeId::of::<P>(), id.into().into()))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Forgets the lookups counted so far, e.g. to only count them during a play session.
+    pub fn reset(&self) {
+        self.counts
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .clear();
+    }
+}
+
+/// The lookups of the prototypes of a registry, see [`Reg::access_report`].
+#[derive(Debug, Clone)]
+pub struct PrototypeAccessReport<P: PrototypeData> {
+    /// The most looked up prototypes with their number of lookups, most looked up first.
+    pub hottest: Vec<(PrototypeName<P>, u64)>,
+    /// The prototypes never looked up, in their storage order.
+    pub never_accessed: Vec<PrototypeName<P>>,
+}
+
+impl<P: PrototypeData> Reg<'_, P> {
+    /// Returns the `top` most looked up prototypes of the registry and the ones never looked up,
+    /// as counted in [`PrototypeAccessStats`].
+    pub fn access_report(&self, top: usize) -> PrototypeAccessReport<P> {
+        let counts = self.access_stats.as_ref().map(|stats| {
+            stats
+                .counts
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner)
+                .clone()
+        });
+        let count = |id: &PrototypeId<P>| {
+            counts
+                .as_ref()
+                .and_then(|counts| counts.get(&(TypeId::of::<P>(), (*id).into())))
+                .copied()
+                .unwrap_or_default()
+        };
+
+        let (mut hottest, never_accessed): (Vec<_>, Vec<_>) = self
+            .iter()
+            .map(|prototype| (prototype.name.clone(), count(prototype.id())))
+            .partition(|(_, count)| *count > 0);
+
+        // Stable, prototypes looked up as many times keep their storage order.
+        hottest.sort_by_key(|(_, count)| core::cmp::Reverse(*count));
+        hottest.truncate(top);
+
+        PrototypeAccessReport {
+            hottest,
+            never_accessed: never_accessed.into_iter().map(|(name, _)| name).collect(),
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use bevy::{ecs::component::Tick, prelude::*};
+
+use crate::{Prototype, PrototypeData, PrototypeId, Reg, TagQuery};
+
+/// The memoized result of a filtered query over the `P` prototypes, e.g. the items of a shop or
+/// the entries of a codex listed each frame by a UI.
+///
+/// The prototypes matching the filter, and the [`TagQuery`] if any, are only looked up again
+/// once the registries changed since the last lookup, e.g. after a reload or a
+/// [`RegMut::get_mut`](crate::RegMut::get_mut).
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_histrion_proto::{CachedQuery, prelude::*};
+/// # #[derive(Debug, Clone, Default, Reflect, JsonSchema, Prototype)]
+/// # #[proto(name = "item")]
+/// # struct Item { price: u32 }
+/// # fn f(app: &mut App) {
+/// #[derive(Resource)]
+/// struct ShopItems(CachedQuery<Item>);
+///
+/// fn show_shop(mut shop: ResMut<ShopItems>, items: Reg<Item>) {
+///     for item in shop.0.iter(&items) {
+///         // ...
+///     }
+/// }
+///
+/// app.insert_resource(ShopItems(
+///     CachedQuery::<Item>::new(|item| item.price > 0).with_tags(TagQuery::all(["shop"])),
+/// ));
+/// # }
+/// ```
+pub struct CachedQuery<P: PrototypeData, F = fn(&Prototype<P>) -> bool> {
+    filter: F,
+    tags: Option<TagQuery>,
+    /// The matching prototypes in their storage order, and the change tick of the registries
+    /// they were looked up at.
+    cached: Option<(Tick, Vec<PrototypeId<P>>)>,
+}
+
+impl<P: PrototypeData> CachedQuery<P> {
+    /// Caches the prototypes whose tags match `query`.
+    pub fn from_tags(query: TagQuery) -> Self {
+        Self::new(|_: &Prototype<P>| true).with_tags(query)
+    }
+}
+
+impl<P, F> CachedQuery<P, F>
+where
+    P: PrototypeData,
+    F: Fn(&Prototype<P>) -> bool,
+{
+    /// Caches the prototypes for which `filter` returns `true`.
+    pub fn new(filter: F) -> Self {
+        Self {
+            filter,
+            tags: None,
+            cached: None,
+        }
+    }
+
+    /// Only caches the prototypes whose tags also match `query`.
+    pub fn with_tags(mut self, query: TagQuery) -> Self {
+        self.tags = Some(query);
+        self.cached = None;
+        self
+    }
+
+    /// Returns the ids of the matching prototypes in their storage order, looked up again if
+    /// the registries changed since the last call.
+    pub fn get(&mut self, reg: &Reg<P>) -> &[PrototypeId<P>] {
+        let tick = reg.last_changed();
+
+        if self
+            .cached
+            .as_ref()
+            .is_none_or(|(cached, _)| *cached != tick)
+        {
+            let ids = match &self.tags {
+                Some(query) => reg
+                    .iter_with_tags(query)
+                    .filter(|proto| (self.filter)(proto))
+                    .map(|proto| *proto.id())
+                    .collect(),
+                None => reg
+                    .iter()
+                    .filter(|proto| (self.filter)(proto))
+                    .map(|proto| *proto.id())
+                    .collect(),
+            };
+
+            self.cached = Some((tick, ids));
+        }
+
+        self.cached.as_ref().map_or(&[], |(_, ids)| ids)
+    }
+
+    /// Iterates over the matching prototypes in their storage order, see
+    /// [`get`](Self::get).
+    pub fn iter<'a>(&'a mut self, reg: &'a Reg<P>) -> impl Iterator<Item = &'a Prototype<P>> {
+        self.get(reg).iter().filter_map(|id| reg.get(*id))
+    }
+
+    /// Forgets the cached result, the prototypes are looked up again by the next call.
+    pub fn invalidate(&mut self) {
+        self.cached = None;
+    }
+}
@@ -0,0 +1,210 @@
+use core::any::TypeId;
+
+use bevy::{
+    asset::{AssetLoader, LoadContext, io::Reader as AssetReader},
+    ecs::system::SystemParam,
+    platform::collections::HashMap,
+    prelude::*,
+};
+use serde::Deserialize;
+
+use crate::{AppPrototypeTypeRegistry, ErasedPrototypeId, PrototypeData, PrototypeId};
+
+/// A named and ordered list of prototypes of any types, e.g. the loot of a starting chest.
+///
+/// Collections are added with [`PrototypeAppExt::add_prototype_collection`](crate::PrototypeAppExt::add_prototype_collection)
+/// or loaded from `.collection.json` files with [`PrototypeServer::load_collections`](crate::PrototypeServer::load_collections):
+///
+/// ```json
+/// {
+///     "name": "starter_loot",
+///     "prototypes": [
+///         { "type": "sword", "name": "wooden_stick" },
+///         { "type": "effect", "name": "bleeding" }
+///     ]
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrototypeCollection {
+    name: String,
+    prototypes: Vec<(TypeId, ErasedPrototypeId)>,
+}
+
+impl PrototypeCollection {
+    /// Creates an empty collection.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            prototypes: Vec::new(),
+        }
+    }
+
+    /// Appends a prototype to the collection.
+    #[must_use]
+    pub fn with<P: PrototypeData>(mut self, id: impl Into<PrototypeId<P>>) -> Self {
+        self.push(id);
+        self
+    }
+
+    /// Appends a prototype to the collection.
+    pub fn push<P: PrototypeData>(&mut self, id: impl Into<PrototypeId<P>>) {
+        self.prototypes
+            .push((TypeId::of::<P>(), ErasedPrototypeId::from(id.into())));
+    }
+
+    /// Returns the name of the collection.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the ids of the `P` prototypes of the collection, in order.
+    pub fn ids<P: PrototypeData>(&self) -> impl Iterator<Item = PrototypeId<P>> + '_ {
+        self.prototypes
+            .iter()
+            .filter(|(type_id, _)| *type_id == TypeId::of::<P>())
+            .map(|(_, id)| PrototypeId::from(*id))
+    }
+
+    /// Returns the prototypes of the collection with the [`TypeId`] of their [`PrototypeData`],
+    /// in order.
+    pub fn iter(&self) -> impl Iterator<Item = (TypeId, ErasedPrototypeId)> + '_ {
+        self.prototypes.iter().copied()
+    }
+
+    /// Returns the number of prototypes of the collection.
+    pub fn len(&self) -> usize {
+        self.prototypes.len()
+    }
+
+    /// Returns `true` if the collection has no prototypes.
+    pub fn is_empty(&self) -> bool {
+        self.prototypes.is_empty()
+    }
+}
+
+#[derive(Default, Resource, Deref, DerefMut)]
+pub(crate) struct PrototypeCollections(HashMap<String, PrototypeCollection>);
+
+/// Read access to the [`PrototypeCollection`]s.
+#[derive(SystemParam)]
+pub struct Collections<'w> {
+    collections: Res<'w, PrototypeCollections>,
+}
+
+impl Collections<'_> {
+    /// Get a collection with its name.
+    pub fn get(&self, name: &str) -> Option<&PrototypeCollection> {
+        self.collections.get(name)
+    }
+
+    /// Iterates over every collection, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = &PrototypeCollection> {
+        self.collections.values()
+    }
+}
+
+impl core::fmt::Debug for Collections<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Collections").finish()
+    }
+}
+
+#[derive(Deserialize)]
+struct OnDiskCollection {
+    name: String,
+    #[serde(default)]
+    prototypes: Vec<OnDiskCollectionEntry>,
+}
+
+#[derive(Deserialize)]
+struct OnDiskCollectionEntry {
+    #[serde(rename = "type")]
+    ty: Box<str>,
+    name: ErasedPrototypeId,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum OnDiskCollections {
+    Many(Vec<OnDiskCollection>),
+    One(OnDiskCollection),
+}
+
+/// The collections of a `.collection.json` file.
+#[derive(Asset, TypePath)]
+pub(crate) struct PrototypeCollectionsAsset(Vec<OnDiskCollection>);
+
+#[derive(Default)]
+pub(crate) struct PrototypeCollectionsLoader;
+
+impl AssetLoader for PrototypeCollectionsLoader {
+    type Asset = PrototypeCollectionsAsset;
+    type Settings = ();
+    type Error = std::io::Error;
+
+    async fn load(
+        &self,
+        reader: &mut dyn AssetReader,
+        _settings: &Self::Settings,
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        let collections = match serde_json::from_slice(&bytes)? {
+            OnDiskCollections::Many(collections) => collections,
+            OnDiskCollections::One(collection) => vec![collection],
+        };
+
+        Ok(PrototypeCollectionsAsset(collections))
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["collection.json"]
+    }
+}
+
+#[derive(Default, Resource, Deref, DerefMut)]
+pub(crate) struct LoadingCollections(
+    HashMap<AssetId<PrototypeCollectionsAsset>, Handle<PrototypeCollectionsAsset>>,
+);
+
+pub(crate) fn on_collections_loaded(
+    mut events_rx: EventReader<AssetEvent<PrototypeCollectionsAsset>>,
+    mut assets: ResMut<Assets<PrototypeCollectionsAsset>>,
+    mut loading_collections: ResMut<LoadingCollections>,
+    mut collections: ResMut<PrototypeCollections>,
+    prototype_types: Res<AppPrototypeTypeRegistry>,
+) {
+    let prototype_types = prototype_types.0.read();
+
+    for event in events_rx.read() {
+        let AssetEvent::LoadedWithDependencies { id } = event else {
+            continue;
+        };
+
+        if loading_collections.remove(id).is_none() {
+            continue;
+        }
+
+        let Some(PrototypeCollectionsAsset(loaded)) = assets.remove(*id) else {
+            warn!("Collections asset {id} not found");
+            continue;
+        };
+
+        for OnDiskCollection { name, prototypes } in loaded {
+            let prototypes = prototypes
+                .into_iter()
+                .filter_map(|entry| match prototype_types.get(&entry.ty) {
+                    Some(type_id) => Some((*type_id, entry.name)),
+                    None => {
+                        error!("Unknown prototype type {} in collection {name}", entry.ty);
+                        None
+                    }
+                })
+                .collect();
+
+            collections.insert(name.clone(), PrototypeCollection { name, prototypes });
+        }
+    }
+}
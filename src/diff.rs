@@ -0,0 +1,405 @@
+use bevy::reflect::{PartialReflect, Reflect, ReflectRef, VariantType};
+use serde_json::{Map as JsonMap, Number as JsonNumber, Value as JsonValue};
+
+use crate::{FieldChange, pack::join_field_path};
+
+/// Compares two prototypes, or their data, field by field.
+///
+/// Structs, tuples, lists of the same length, maps and enums of the same variant are compared
+/// recursively, any other difference is reported with the values of both sides converted to JSON,
+/// e.g. to show how a reloaded prototype changed in an editor or to assert it in a test.
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_histrion_proto::{diff_prototypes, prelude::*};
+/// # #[derive(Debug, Clone, Default, Reflect, JsonSchema)]
+/// # struct Stats { damage: f32 }
+/// # #[derive(Debug, Clone, Default, Reflect, JsonSchema, Prototype)]
+/// # #[proto(name = "sword")]
+/// # struct Sword { stats: Stats }
+/// # fn f(old: &Prototype<Sword>, new: &Prototype<Sword>) {
+/// let changes = diff_prototypes(old.data(), new.data());
+/// assert_eq!(changes[0].path, "stats.damage");
+/// # }
+/// ```
+pub fn diff_prototypes(a: &dyn Reflect, b: &dyn Reflect) -> Vec<FieldChange> {
+    let mut changes = Vec::new();
+    diff_reflect(
+        String::new(),
+        a.as_partial_reflect(),
+        b.as_partial_reflect(),
+        &mut changes,
+    );
+    changes
+}
+
+fn diff_reflect(
+    path: String,
+    old: &dyn PartialReflect,
+    new: &dyn PartialReflect,
+    changes: &mut Vec<FieldChange>,
+) {
+    match (old.reflect_ref(), new.reflect_ref()) {
+        (ReflectRef::Struct(old), ReflectRef::Struct(new)) => {
+            for (i, old_value) in old.iter_fields().enumerate() {
+                let Some(field) = old.name_at(i) else {
+                    continue;
+                };
+
+                diff_field(
+                    join_field_path(&path, field),
+                    Some(old_value),
+                    new.field(field),
+                    changes,
+                );
+            }
+
+            for (i, new_value) in new.iter_fields().enumerate() {
+                if let Some(field) = new.name_at(i)
+                    && old.field(field).is_none()
+                {
+                    diff_field(
+                        join_field_path(&path, field),
+                        None,
+                        Some(new_value),
+                        changes,
+                    );
+                }
+            }
+        }
+        (ReflectRef::TupleStruct(old), ReflectRef::TupleStruct(new))
+            if old.field_len() == new.field_len() =>
+        {
+            for (i, (old, new)) in old.iter_fields().zip(new.iter_fields()).enumerate() {
+                diff_reflect(join_field_path(&path, &i.to_string()), old, new, changes);
+            }
+        }
+        (ReflectRef::Tuple(old), ReflectRef::Tuple(new)) if old.field_len() == new.field_len() => {
+            for (i, (old, new)) in old.iter_fields().zip(new.iter_fields()).enumerate() {
+                diff_reflect(join_field_path(&path, &i.to_string()), old, new, changes);
+            }
+        }
+        (ReflectRef::List(old), ReflectRef::List(new)) if old.len() == new.len() => {
+            for (i, (old, new)) in old.iter().zip(new.iter()).enumerate() {
+                diff_reflect(format!("{path}[{i}]"), old, new, changes);
+            }
+        }
+        (ReflectRef::Array(old), ReflectRef::Array(new)) if old.len() == new.len() => {
+            for (i, (old, new)) in old.iter().zip(new.iter()).enumerate() {
+                diff_reflect(format!("{path}[{i}]"), old, new, changes);
+            }
+        }
+        (ReflectRef::Map(old), ReflectRef::Map(new)) => {
+            for (key, old_value) in old.iter() {
+                let path = format!("{path}[{}]", key_name(key));
+                diff_field(path, Some(old_value), new.get(key), changes);
+            }
+
+            for (key, new_value) in new.iter() {
+                if old.get(key).is_none() {
+                    let path = format!("{path}[{}]", key_name(key));
+                    diff_field(path, None, Some(new_value), changes);
+                }
+            }
+        }
+        (ReflectRef::Enum(old), ReflectRef::Enum(new))
+            if old.variant_name() == new.variant_name() && old.field_len() == new.field_len() =>
+        {
+            for (i, (old_field, new_field)) in old.iter_fields().zip(new.iter_fields()).enumerate()
+            {
+                let field = match old.variant_type() {
+                    VariantType::Struct => old_field.name().unwrap_or_default().to_string(),
+                    _ => i.to_string(),
+                };
+
+                // `Some` is transparent in the data files.
+                let path = if is_option(old) {
+                    path.clone()
+                } else {
+                    join_field_path(&path, &field)
+                };
+
+                diff_reflect(path, old_field.value(), new_field.value(), changes);
+            }
+        }
+        _ => {
+            if !values_eq(old, new) {
+                changes.push(FieldChange {
+                    path,
+                    old: Some(reflect_to_json(old)),
+                    new: Some(reflect_to_json(new)),
+                });
+            }
+        }
+    }
+}
+
+fn diff_field(
+    path: String,
+    old: Option<&dyn PartialReflect>,
+    new: Option<&dyn PartialReflect>,
+    changes: &mut Vec<FieldChange>,
+) {
+    match (old, new) {
+        (Some(old), Some(new)) => diff_reflect(path, old, new, changes),
+        (old, new) => changes.push(FieldChange {
+            path,
+            old: old.map(reflect_to_json),
+            new: new.map(reflect_to_json),
+        }),
+    }
+}
+
+/// Compares two values without a structure to walk through, by their debug output when they
+/// don't support reflected comparison.
+fn values_eq(old: &dyn PartialReflect, new: &dyn PartialReflect) -> bool {
+    old.reflect_partial_eq(new)
+        .unwrap_or_else(|| format!("{old:?}") == format!("{new:?}"))
+}
+
+pub(crate) fn is_option(value: &dyn bevy::reflect::Enum) -> bool {
+    value
+        .get_represented_type_info()
+        .is_some_and(|info| info.type_path().starts_with("core::option::Option<"))
+}
+
+fn key_name(key: &dyn PartialReflect) -> String {
+    match reflect_to_json(key) {
+        JsonValue::String(key) => key,
+        key => key.to_string(),
+    }
+}
+
+/// Returns the hash of a reflected [`PrototypeId`](crate::PrototypeId) or
+/// [`ErasedPrototypeId`](crate::ErasedPrototypeId).
+fn prototype_id_hash(value: &dyn PartialReflect) -> Option<u64> {
+    let type_path = value.get_represented_type_info()?.type_path();
+
+    if !type_path.starts_with("bevy_histrion_proto::identifier::PrototypeId<")
+        && type_path != "bevy_histrion_proto::identifier::ErasedPrototypeId"
+    {
+        return None;
+    }
+
+    let ReflectRef::Struct(value) = value.reflect_ref() else {
+        return None;
+    };
+
+    value.field("hash")?.try_downcast_ref::<u64>().copied()
+}
+
+/// Converts a reflected value to JSON, as close as possible to its representation in the data
+/// files, values that can't be converted are written as their debug output.
+pub(crate) fn reflect_to_json(value: &dyn PartialReflect) -> JsonValue {
+    // Written like their `Display` implementation.
+    if let Some(hash) = prototype_id_hash(value) {
+        return JsonValue::String(format!("{hash:X}"));
+    }
+
+    // The path of a handle isn't reflected, its debug output has it.
+    if value
+        .get_represented_type_info()
+        .is_some_and(|info| info.type_path().starts_with("bevy_asset::handle::Handle<"))
+    {
+        return JsonValue::String(format!("{value:?}"));
+    }
+
+    macro_rules! primitive {
+        ($($ty:ty),*) => {
+            $(
+                if let Some(value) = value.try_downcast_ref::<$ty>() {
+                    return JsonValue::from(*value);
+                }
+            )*
+        };
+    }
+
+    primitive!(bool, u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+    if let Some(value) = value.try_downcast_ref::<f32>() {
+        return JsonNumber::from_f64(f64::from(*value)).map_or(JsonValue::Null, JsonValue::Number);
+    }
+
+    if let Some(value) = value.try_downcast_ref::<f64>() {
+        return JsonNumber::from_f64(*value).map_or(JsonValue::Null, JsonValue::Number);
+    }
+
+    if let Some(value) = value.try_downcast_ref::<String>() {
+        return JsonValue::String(value.clone());
+    }
+
+    // The interned names of the prototypes.
+    if let Some(value) = value.try_downcast_ref::<&'static str>() {
+        return JsonValue::from(*value);
+    }
+
+    match value.reflect_ref() {
+        ReflectRef::Struct(value) => JsonValue::Object(
+            value
+                .iter_fields()
+                .enumerate()
+                .filter_map(|(i, field)| {
+                    Some((value.name_at(i)?.to_string(), reflect_to_json(field)))
+                })
+                .collect(),
+        ),
+        ReflectRef::TupleStruct(value) if value.field_len() == 1 => {
+            value.field(0).map_or(JsonValue::Null, reflect_to_json)
+        }
+        ReflectRef::TupleStruct(value) => {
+            JsonValue::Array(value.iter_fields().map(reflect_to_json).collect())
+        }
+        ReflectRef::Tuple(value) => {
+            JsonValue::Array(value.iter_fields().map(reflect_to_json).collect())
+        }
+        ReflectRef::List(value) => JsonValue::Array(value.iter().map(reflect_to_json).collect()),
+        ReflectRef::Array(value) => JsonValue::Array(value.iter().map(reflect_to_json).collect()),
+        ReflectRef::Set(value) => JsonValue::Array(value.iter().map(reflect_to_json).collect()),
+        ReflectRef::Map(value) => JsonValue::Object(
+            value
+                .iter()
+                .map(|(key, value)| (key_name(key), reflect_to_json(value)))
+                .collect::<JsonMap<_, _>>(),
+        ),
+        ReflectRef::Enum(value) if is_option(value) => {
+            value.field_at(0).map_or(JsonValue::Null, reflect_to_json)
+        }
+        ReflectRef::Enum(value) => {
+            let fields = match value.variant_type() {
+                VariantType::Unit => return JsonValue::String(value.variant_name().to_string()),
+                VariantType::Tuple if value.field_len() == 1 => {
+                    value.field_at(0).map_or(JsonValue::Null, reflect_to_json)
+                }
+                VariantType::Tuple => JsonValue::Array(
+                    value
+                        .iter_fields()
+                        .map(|field| reflect_to_json(field.value()))
+                        .collect(),
+                ),
+                VariantType::Struct => JsonValue::Object(
+                    value
+                        .iter_fields()
+                        .filter_map(|field| {
+                            Some((field.name()?.to_string(), reflect_to_json(field.value())))
+                        })
+                        .collect(),
+                ),
+            };
+
+            JsonValue::Object(JsonMap::from_iter([(
+                value.variant_name().to_string(),
+                fields,
+            )]))
+        }
+        _ => JsonValue::String(format!("{value:?}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::platform::collections::HashMap;
+    use serde_json::json;
+
+    use super::*;
+    use crate::{ErasedPrototypeId, PrototypeId};
+
+    #[derive(Debug, Clone, Default, Reflect)]
+    struct Stats {
+        damage: f32,
+        range: Option<u32>,
+    }
+
+    #[derive(Debug, Clone, Reflect)]
+    enum Element {
+        Fire { power: u32 },
+        Ice,
+    }
+
+    #[derive(Debug, Clone, Reflect)]
+    struct Sword {
+        stats: Stats,
+        element: Element,
+        tags: Vec<String>,
+        effects: HashMap<String, u32>,
+        upgrade: Option<PrototypeId<Sword>>,
+    }
+
+    fn sword() -> Sword {
+        Sword {
+            stats: Stats {
+                damage: 1.5,
+                range: Some(1),
+            },
+            element: Element::Fire { power: 2 },
+            tags: vec!["melee".to_string()],
+            effects: HashMap::from_iter([("bleed".to_string(), 1)]),
+            upgrade: None,
+        }
+    }
+
+    fn changes(old: &Sword, new: &Sword) -> Vec<(String, Option<JsonValue>, Option<JsonValue>)> {
+        let mut changes = diff_prototypes(old, new)
+            .into_iter()
+            .map(|change| (change.path, change.old, change.new))
+            .collect::<Vec<_>>();
+        changes.sort_by(|a, b| a.0.cmp(&b.0));
+        changes
+    }
+
+    #[test]
+    fn equal_prototypes_have_no_changes() {
+        assert!(diff_prototypes(&sword(), &sword()).is_empty());
+    }
+
+    #[test]
+    fn changes_are_reported_by_field_path() {
+        let mut new = sword();
+        new.stats.damage = 2.0;
+        new.stats.range = None;
+        new.element = Element::Fire { power: 3 };
+        new.tags.push("heavy".to_string());
+        new.effects.insert("burn".to_string(), 2);
+        new.upgrade = Some(PrototypeId::from_name("long_sword"));
+
+        let upgrade = ErasedPrototypeId::from_name("long_sword").to_string();
+
+        assert_eq!(
+            changes(&sword(), &new),
+            [
+                ("effects[burn]".to_string(), None, Some(json!(2))),
+                ("element.power".to_string(), Some(json!(2)), Some(json!(3))),
+                (
+                    "stats.damage".to_string(),
+                    Some(json!(1.5)),
+                    Some(json!(2.0))
+                ),
+                ("stats.range".to_string(), Some(json!(1)), Some(json!(null))),
+                (
+                    "tags".to_string(),
+                    Some(json!(["melee"])),
+                    Some(json!(["melee", "heavy"]))
+                ),
+                (
+                    "upgrade".to_string(),
+                    Some(json!(null)),
+                    Some(json!(upgrade))
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn other_variants_are_reported_whole() {
+        let mut new = sword();
+        new.element = Element::Ice;
+
+        assert_eq!(
+            changes(&sword(), &new),
+            [(
+                "element".to_string(),
+                Some(json!({ "Fire": { "power": 2 } })),
+                Some(json!("Ice"))
+            )]
+        );
+    }
+}
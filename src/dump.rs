@@ -0,0 +1,310 @@
+use core::fmt::Write;
+
+use bevy::{
+    ecs::system::SystemParam,
+    prelude::*,
+    reflect::{ReflectRef, VariantType},
+};
+use serde_json::Value as JsonValue;
+
+use crate::{
+    AppPrototypeTypeRegistry, ErasedPrototypeId, Prototype, PrototypeData, PrototypeSource,
+    diff::{is_option, reflect_to_json},
+    registry::PrototypeRegistries,
+};
+
+impl<P: PrototypeData> Prototype<P> {
+    /// Returns a multi-line description of this prototype instance and of its data, for logs and
+    /// console commands.
+    ///
+    /// ```text
+    /// sword wooden_stick (8CB9A4ED3F1D33A6)
+    ///   tags: ["weapon"]
+    ///   source: prototypes/swords.proto.json:2:5 (/0)
+    ///   damage: 1.0
+    ///   stats:
+    ///     speed: 1.5
+    ///   effects: ["C5A0DD3AAF1CBF82"]
+    /// ```
+    pub fn dump_pretty(&self) -> String {
+        dump_prototype(P::prototype_name(), self.as_partial_reflect())
+    }
+}
+
+/// Type erased read access to the registries, e.g. for console commands taking the type of the
+/// prototype as an argument.
+#[derive(SystemParam)]
+pub struct Registries<'w> {
+    registries: Res<'w, PrototypeRegistries>,
+    prototype_types: Res<'w, AppPrototypeTypeRegistry>,
+    #[cfg(feature = "documentation")]
+    type_registry: Res<'w, AppTypeRegistry>,
+}
+
+/// The doc comments of a prototype type and of the fields of its data, see [`Registries::docs`].
+#[cfg(feature = "documentation")]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PrototypeDocs {
+    /// The doc comment of the data type.
+    pub docs: Option<String>,
+    /// The reflect path of each documented field of the data, e.g. `stats.speed`, with its doc
+    /// comment, in their declaration order.
+    pub fields: Vec<(String, String)>,
+}
+
+#[cfg(feature = "documentation")]
+impl PrototypeDocs {
+    /// Returns the doc comment of the field at the reflect `path` of the data, e.g. `stats.speed`.
+    pub fn field(&self, path: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find_map(|(field, docs)| (field == path).then_some(docs.as_str()))
+    }
+}
+
+impl Registries<'_> {
+    /// Returns the names of the registered prototype types.
+    pub fn type_names(&self) -> Vec<String> {
+        self.prototype_types.prototype_type_names()
+    }
+
+    /// Returns the reflected [`Prototype`] named `name` of the `type_name` registry, e.g.
+    /// `get_erased("sword", "wooden_stick")` for consoles, scripts or network messages only
+    /// holding strings, `None` if the type isn't registered or the prototype doesn't exist.
+    pub fn get_erased(&self, type_name: &str, name: &str) -> Option<&dyn Reflect> {
+        self.get_dyn(type_name, ErasedPrototypeId::from_name(name))
+    }
+
+    /// Iterates over the reflected [`Prototype`]s of all the types tagged with `tag`, with the
+    /// name of their type, e.g. for a console listing everything tagged `"debug"`.
+    pub fn iter_tagged<'a>(
+        &'a self,
+        tag: &'a str,
+    ) -> impl Iterator<Item = (&'static str, &'a dyn Reflect)> {
+        self.registries.iter_tagged(tag)
+    }
+
+    /// Returns the [`Prototype::dump_pretty`] description of the `id` prototype of the
+    /// `type_name` registry, `None` if the type isn't registered or the prototype doesn't exist.
+    pub fn dump(&self, type_name: &str, id: impl Into<ErasedPrototypeId>) -> Option<String> {
+        let proto = self.get_dyn(type_name, id.into())?;
+
+        Some(dump_prototype(type_name, proto.as_partial_reflect()))
+    }
+
+    /// Returns the doc comments of the data of the `type_name` prototypes and of its fields, for
+    /// inspectors and remote editors to show them next to the values, `None` if the type isn't
+    /// registered.
+    #[cfg(feature = "documentation")]
+    pub fn docs(&self, type_name: &str) -> Option<PrototypeDocs> {
+        let type_id = self.prototype_types.type_id_of(type_name)?;
+        let type_registry = self.type_registry.read();
+        let type_info = type_registry.get_type_info(type_id)?;
+
+        let mut docs = PrototypeDocs {
+            docs: type_info.docs().map(trim_docs),
+            fields: Vec::new(),
+        };
+        collect_field_docs(type_info, "", &mut docs.fields);
+
+        Some(docs)
+    }
+
+    fn get_dyn(&self, type_name: &str, id: ErasedPrototypeId) -> Option<&dyn Reflect> {
+        let type_id = self.prototype_types.type_id_of(type_name)?;
+
+        self.registries.get_dyn(&type_id, &id)
+    }
+}
+
+/// Collects the doc comments of the fields of the `type_info` structure and of their own fields.
+#[cfg(feature = "documentation")]
+fn collect_field_docs(
+    type_info: &bevy::reflect::TypeInfo,
+    path: &str,
+    fields: &mut Vec<(String, String)>,
+) {
+    let bevy::reflect::TypeInfo::Struct(info) = type_info else {
+        return;
+    };
+
+    for field in info.iter() {
+        let path = crate::pack::join_field_path(path, field.name());
+
+        if let Some(docs) = field.docs() {
+            fields.push((path.clone(), trim_docs(docs)));
+        }
+
+        if let Some(type_info) = field.type_info() {
+            collect_field_docs(type_info, &path, fields);
+        }
+    }
+}
+
+/// Removes the space after the `///` of each line of a doc comment.
+#[cfg(feature = "documentation")]
+fn trim_docs(docs: &str) -> String {
+    docs.lines()
+        .map(|line| line.strip_prefix(' ').unwrap_or(line))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Describes a reflected [`Prototype`], see [`Prototype::dump_pretty`].
+fn dump_prototype(type_name: &str, proto: &dyn PartialReflect) -> String {
+    let mut out = String::new();
+
+    let ReflectRef::Struct(proto) = proto.reflect_ref() else {
+        return out;
+    };
+
+    let _ = write!(out, "{type_name}");
+
+    // Stripped names only have their id.
+    if let Some(JsonValue::Object(name)) = proto.field("name").map(reflect_to_json) {
+        if let Some(JsonValue::String(name)) = name.get("name") {
+            let _ = write!(out, " {name}");
+        }
+
+        if let Some(JsonValue::String(id)) = name.get("id") {
+            let _ = write!(out, " ({id})");
+        }
+    }
+
+    out.push('\n');
+
+    if let Some(tags) = proto.field("tags").map(reflect_to_json)
+        && tags.as_array().is_some_and(|tags| !tags.is_empty())
+    {
+        let _ = writeln!(out, "  tags: {tags}");
+    }
+
+    if let Some(Some(source)) = proto
+        .field("source")
+        .and_then(|source| source.try_downcast_ref::<Option<PrototypeSource>>())
+    {
+        let _ = writeln!(out, "  source: {source}");
+    }
+
+    if let Some(data) = proto.field("data") {
+        match data.reflect_ref() {
+            ReflectRef::Struct(data) => {
+                for (i, field) in data.iter_fields().enumerate() {
+                    if let Some(name) = data.name_at(i) {
+                        write_field(&mut out, 1, name, field);
+                    }
+                }
+            }
+            _ => write_field(&mut out, 1, "data", data),
+        }
+    }
+
+    out
+}
+
+/// Writes `name: value` at `depth`, nested structures on the next lines.
+fn write_field(out: &mut String, depth: usize, name: &str, value: &dyn PartialReflect) {
+    let value = transparent(value);
+    let indent = "  ".repeat(depth);
+
+    if let Some(leaf) = leaf(value) {
+        let _ = writeln!(out, "{indent}{name}: {leaf}");
+        return;
+    }
+
+    if let ReflectRef::Enum(value) = value.reflect_ref() {
+        if value.variant_type() == VariantType::Tuple
+            && value.field_len() == 1
+            && let Some(inner) = value.field_at(0).and_then(leaf)
+        {
+            let _ = writeln!(out, "{indent}{name}: {}({inner})", value.variant_name());
+            return;
+        }
+
+        let _ = writeln!(out, "{indent}{name}: {}", value.variant_name());
+
+        for (i, field) in value.iter_fields().enumerate() {
+            let name = match value.variant_type() {
+                VariantType::Struct => field.name().unwrap_or_default().to_string(),
+                _ => i.to_string(),
+            };
+
+            write_field(out, depth + 1, &name, field.value());
+        }
+
+        return;
+    }
+
+    let _ = writeln!(out, "{indent}{name}:");
+
+    match value.reflect_ref() {
+        ReflectRef::Struct(value) => {
+            for (i, field) in value.iter_fields().enumerate() {
+                if let Some(name) = value.name_at(i) {
+                    write_field(out, depth + 1, name, field);
+                }
+            }
+        }
+        ReflectRef::TupleStruct(value) => {
+            for (i, field) in value.iter_fields().enumerate() {
+                write_field(out, depth + 1, &i.to_string(), field);
+            }
+        }
+        ReflectRef::Tuple(value) => {
+            for (i, field) in value.iter_fields().enumerate() {
+                write_field(out, depth + 1, &i.to_string(), field);
+            }
+        }
+        ReflectRef::List(value) => {
+            for item in value.iter() {
+                write_field(out, depth + 1, "-", item);
+            }
+        }
+        ReflectRef::Array(value) => {
+            for item in value.iter() {
+                write_field(out, depth + 1, "-", item);
+            }
+        }
+        ReflectRef::Set(value) => {
+            for item in value.iter() {
+                write_field(out, depth + 1, "-", item);
+            }
+        }
+        ReflectRef::Map(value) => {
+            for (key, value) in value.iter() {
+                let key = match reflect_to_json(key) {
+                    JsonValue::String(key) => key,
+                    key => key.to_string(),
+                };
+
+                write_field(out, depth + 1, &key, value);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Skips the `Some` and newtype wrappers, written as their inner value in the data files.
+fn transparent(value: &dyn PartialReflect) -> &dyn PartialReflect {
+    match value.reflect_ref() {
+        ReflectRef::Enum(inner) if is_option(inner) => inner.field_at(0).map_or(value, transparent),
+        ReflectRef::TupleStruct(inner) if inner.field_len() == 1 => {
+            inner.field(0).map_or(value, transparent)
+        }
+        _ => value,
+    }
+}
+
+/// Returns the JSON of `value` when it fits on the line of its field: scalars, empty structures
+/// and lists of scalars.
+fn leaf(value: &dyn PartialReflect) -> Option<String> {
+    let json = reflect_to_json(value);
+
+    let is_scalar = |value: &JsonValue| !value.is_object() && !value.is_array();
+
+    match &json {
+        JsonValue::Object(fields) if !fields.is_empty() => None,
+        JsonValue::Array(items) if !items.iter().all(is_scalar) => None,
+        _ => Some(json.to_string()),
+    }
+}
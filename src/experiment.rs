@@ -0,0 +1,29 @@
+use bevy::{platform::collections::HashMap, prelude::*};
+use serde::Deserialize;
+
+/// The `experiment` field of a prototype entry, the entry is only applied when the player is
+/// assigned to `group` of the `name` experiment, see [`PrototypeExperiments`].
+///
+/// ```json
+/// { "type": "shop", "name": "potions", "experiment": { "name": "econ_test", "group": "B" }, "price": 80 }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize)]
+pub struct PrototypeExperiment {
+    pub name: String,
+    pub group: String,
+}
+
+/// The group of each experiment the player is assigned to, e.g. `B` for `econ_test`.
+///
+/// Entries of an experiment are only applied for the players assigned to their group, and then
+/// replace the entry of the same name without experiment of their file. Assign the groups before
+/// loading the prototypes, or reload the prototypes files once assigned.
+#[derive(Resource, Debug, Clone, Default, PartialEq, Eq, Deref, DerefMut)]
+pub struct PrototypeExperiments(pub HashMap<String, String>);
+
+impl PrototypeExperiments {
+    /// Returns `true` if the player is assigned to the group of `experiment`.
+    pub fn is_assigned(&self, experiment: &PrototypeExperiment) -> bool {
+        self.get(&experiment.name) == Some(&experiment.group)
+    }
+}
@@ -0,0 +1,417 @@
+use serde_json::{Map as JsonMap, Value as JsonValue};
+
+use crate::profile::merge_json;
+
+/// Expands a generator entry into one entry per value of its range, e.g. for tiered content:
+///
+/// ```json
+/// {
+///     "type": "arrow",
+///     "generate": { "name": "arrow_tier_{i}", "range": [1, 5], "data": { "damage": "= 5 * i" } },
+///     "speed": 10.0
+/// }
+/// ```
+///
+/// The range is inclusive, `{i}` is replaced by the value in the strings of `name` and `data`,
+/// and the strings of `data` starting with `=` are formulas of `i` replaced by their result.
+/// `data` overrides the fields of the entry, objects being merged field by field. Entries without
+/// `generate` are returned as they are.
+pub(crate) fn expand_generator(entry: JsonValue) -> Result<Vec<JsonValue>, String> {
+    let JsonValue::Object(mut fields) = entry else {
+        return Ok(vec![entry]);
+    };
+
+    let Some(generate) = fields.remove("generate") else {
+        return Ok(vec![JsonValue::Object(fields)]);
+    };
+
+    if fields.contains_key("name") || fields.contains_key("names") {
+        return Err("entry has both `generate` and `name` or `names`".to_string());
+    }
+
+    let JsonValue::Object(mut generate) = generate else {
+        return Err("`generate` isn't an object".to_string());
+    };
+
+    let Some(JsonValue::String(name)) = generate.remove("name") else {
+        return Err("`generate` has no string `name`".to_string());
+    };
+
+    let (start, end) = generator_range(generate.get("range"))?;
+
+    let data = match generate.remove("data") {
+        None => JsonValue::Object(JsonMap::new()),
+        Some(data @ JsonValue::Object(_)) => data,
+        Some(_) => return Err("`data` of `generate` isn't an object".to_string()),
+    };
+
+    (start..=end)
+        .map(|i| {
+            let mut entry = JsonValue::Object(fields.clone());
+            let mut data = data.clone();
+
+            substitute(&mut data, i)
+                .map_err(|err| format!("invalid `data` of `generate` for i = {i}: {err}"))?;
+            merge_json(&mut entry, data);
+
+            if let JsonValue::Object(entry) = &mut entry {
+                entry.insert(
+                    "name".to_string(),
+                    name.replace("{i}", &i.to_string()).into(),
+                );
+            }
+
+            Ok(entry)
+        })
+        .collect()
+}
+
+/// Most prototypes a generator can expand into, a larger `range` is rejected rather than
+/// cloning the entry until the loader runs out of memory.
+pub(crate) const MAX_GENERATED_ENTRIES: u64 = 10_000;
+
+/// Returns the inclusive `range` of a generator, `[1, 5]` for 1 to 5.
+pub(crate) fn generator_range(range: Option<&JsonValue>) -> Result<(i64, i64), String> {
+    let range = range
+        .and_then(JsonValue::as_array)
+        .map(|range| range.iter().map(JsonValue::as_i64).collect::<Vec<_>>());
+
+    match range.as_deref() {
+        Some([Some(start), Some(end)]) if start <= end => {
+            if end.abs_diff(*start) >= MAX_GENERATED_ENTRIES {
+                return Err(format!(
+                    "`range` of `generate` expands into more than {MAX_GENERATED_ENTRIES} prototypes"
+                ));
+            }
+
+            Ok((*start, *end))
+        }
+        _ => Err("`range` of `generate` isn't a `[start, end]` list of integers".to_string()),
+    }
+}
+
+/// Replaces `{i}` in the strings of `value` and the formulas by their result.
+fn substitute(value: &mut JsonValue, i: i64) -> Result<(), String> {
+    match value {
+        JsonValue::String(string) => {
+            if let Some(formula) = string.strip_prefix('=').map(str::trim) {
+                *value = match evaluate(formula, i)
+                    .map_err(|err| format!("{err} in formula {formula}"))?
+                {
+                    Number::Integer(result) => result.into(),
+                    Number::Float(result)
+                        if result.fract() == 0.0 && result.abs() < 2f64.powi(63) =>
+                    {
+                        (result as i64).into()
+                    }
+                    Number::Float(result) => serde_json::Number::from_f64(result)
+                        .map(JsonValue::Number)
+                        .ok_or_else(|| format!("formula {formula} doesn't give a number"))?,
+                };
+            } else if string.contains("{i}") {
+                *string = string.replace("{i}", &i.to_string());
+            }
+        }
+        JsonValue::Array(values) => {
+            for value in values {
+                substitute(value, i)?;
+            }
+        }
+        JsonValue::Object(fields) => {
+            for value in fields.values_mut() {
+                substitute(value, i)?;
+            }
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+/// A value of a formula, integers are computed exactly and become floats with fractional
+/// operands or a division that isn't exact.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Number {
+    Integer(i64),
+    Float(f64),
+}
+
+impl Number {
+    fn float(self) -> f64 {
+        match self {
+            Self::Integer(value) => value as f64,
+            Self::Float(value) => value,
+        }
+    }
+
+    fn is_zero(self) -> bool {
+        self.float() == 0.0
+    }
+
+    /// Applies `integer` to two integers, `float` otherwise, the integer overflows are errors.
+    fn apply(
+        self,
+        other: Self,
+        integer: impl FnOnce(i64, i64) -> Option<i64>,
+        float: impl FnOnce(f64, f64) -> f64,
+    ) -> Result<Self, String> {
+        match (self, other) {
+            (Self::Integer(a), Self::Integer(b)) => integer(a, b)
+                .map(Self::Integer)
+                .ok_or_else(|| "integer overflow".to_string()),
+            (a, b) => Ok(Self::Float(float(a.float(), b.float()))),
+        }
+    }
+}
+
+/// Evaluates an arithmetic formula of `i`, with `+`, `-`, `*`, `/`, `%`, `^` and parentheses.
+fn evaluate(formula: &str, i: i64) -> Result<Number, String> {
+    let mut parser = FormulaParser {
+        chars: formula
+            .chars()
+            .filter(|char| !char.is_whitespace())
+            .collect(),
+        position: 0,
+        i,
+    };
+
+    let result = parser.sum()?;
+
+    match parser.peek() {
+        None => Ok(result),
+        Some(char) => Err(format!("unexpected {char}")),
+    }
+}
+
+struct FormulaParser {
+    chars: Vec<char>,
+    position: usize,
+    i: i64,
+}
+
+impl FormulaParser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn eat(&mut self, char: char) -> bool {
+        let eaten = self.peek() == Some(char);
+        self.position += usize::from(eaten);
+        eaten
+    }
+
+    fn sum(&mut self) -> Result<Number, String> {
+        let mut value = self.product()?;
+
+        loop {
+            if self.eat('+') {
+                value = value.apply(self.product()?, i64::checked_add, |a, b| a + b)?;
+            } else if self.eat('-') {
+                value = value.apply(self.product()?, i64::checked_sub, |a, b| a - b)?;
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    fn product(&mut self) -> Result<Number, String> {
+        let mut value = self.power()?;
+
+        loop {
+            if self.eat('*') {
+                value = value.apply(self.power()?, i64::checked_mul, |a, b| a * b)?;
+            } else if self.eat('/') {
+                let divisor = self.power()?;
+
+                if divisor.is_zero() {
+                    return Err("division by zero".to_string());
+                }
+
+                value = match (value, divisor) {
+                    (Number::Integer(a), Number::Integer(b)) if a % b == 0 => {
+                        value.apply(divisor, i64::checked_div, |a, b| a / b)?
+                    }
+                    (a, b) => Number::Float(a.float() / b.float()),
+                };
+            } else if self.eat('%') {
+                let divisor = self.power()?;
+
+                if divisor.is_zero() {
+                    return Err("division by zero".to_string());
+                }
+
+                value = value.apply(divisor, i64::checked_rem, |a, b| a % b)?;
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    fn power(&mut self) -> Result<Number, String> {
+        let base = self.unary()?;
+
+        // Right associative, `2 ^ 3 ^ 2` is `2 ^ 9`.
+        if !self.eat('^') {
+            return Ok(base);
+        }
+
+        match (base, self.power()?) {
+            (Number::Integer(base), Number::Integer(exponent)) if exponent >= 0 => {
+                u32::try_from(exponent)
+                    .ok()
+                    .and_then(|exponent| base.checked_pow(exponent))
+                    .map(Number::Integer)
+                    .ok_or_else(|| "integer overflow".to_string())
+            }
+            (base, exponent) => Ok(Number::Float(base.float().powf(exponent.float()))),
+        }
+    }
+
+    fn unary(&mut self) -> Result<Number, String> {
+        // `-i ^ 2` is `-(i ^ 2)`.
+        if self.eat('-') {
+            return match self.power()? {
+                Number::Integer(value) => value
+                    .checked_neg()
+                    .map(Number::Integer)
+                    .ok_or_else(|| "integer overflow".to_string()),
+                Number::Float(value) => Ok(Number::Float(-value)),
+            };
+        }
+
+        if self.eat('(') {
+            let value = self.sum()?;
+
+            return if self.eat(')') {
+                Ok(value)
+            } else {
+                Err("missing )".to_string())
+            };
+        }
+
+        if self.eat('i') {
+            return Ok(Number::Integer(self.i));
+        }
+
+        let start = self.position;
+
+        while self
+            .peek()
+            .is_some_and(|char| char.is_ascii_digit() || char == '.')
+        {
+            self.position += 1;
+        }
+
+        let number = self.chars[start..self.position].iter().collect::<String>();
+
+        let parsed = if number.contains('.') {
+            number.parse().map(Number::Float).ok()
+        } else {
+            number.parse().map(Number::Integer).ok()
+        };
+
+        parsed.ok_or_else(|| match self.peek() {
+            Some(char) if number.is_empty() => format!("unexpected {char}"),
+            None if number.is_empty() => "unexpected end".to_string(),
+            _ => format!("invalid number {number}"),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn eval(formula: &str, i: i64) -> Result<Number, String> {
+        evaluate(formula, i)
+    }
+
+    #[test]
+    fn formulas_follow_the_precedence() {
+        assert_eq!(eval("1 + 2 * 3", 0), Ok(Number::Integer(7)));
+        assert_eq!(eval("(1 + 2) * 3", 0), Ok(Number::Integer(9)));
+        assert_eq!(eval("10 - 4 - 3", 0), Ok(Number::Integer(3)));
+        assert_eq!(eval("2 ^ 3 ^ 2", 0), Ok(Number::Integer(512)));
+        assert_eq!(eval("2 * i ^ 2", 3), Ok(Number::Integer(18)));
+        assert_eq!(eval("7 % 4 + 1", 0), Ok(Number::Integer(4)));
+    }
+
+    #[test]
+    fn unary_minus_applies_after_the_power() {
+        assert_eq!(eval("-i ^ 2", 3), Ok(Number::Integer(-9)));
+        assert_eq!(eval("(-i) ^ 2", 3), Ok(Number::Integer(9)));
+        assert_eq!(eval("2 - -i", 3), Ok(Number::Integer(5)));
+        assert_eq!(eval("-1.5", 0), Ok(Number::Float(-1.5)));
+    }
+
+    #[test]
+    fn divisions_stay_integers_when_exact() {
+        assert_eq!(eval("i / 2", 8), Ok(Number::Integer(4)));
+        assert_eq!(eval("i / 2", 3), Ok(Number::Float(1.5)));
+        assert_eq!(eval("i * 0.5", 3), Ok(Number::Float(1.5)));
+    }
+
+    #[test]
+    fn division_by_zero_is_an_error() {
+        assert_eq!(eval("1 / 0", 0), Err("division by zero".to_string()));
+        assert_eq!(eval("1 / (i - 2)", 2), Err("division by zero".to_string()));
+        assert_eq!(eval("1 % 0.0", 0), Err("division by zero".to_string()));
+    }
+
+    #[test]
+    fn malformed_formulas_are_errors() {
+        assert_eq!(eval("", 0), Err("unexpected end".to_string()));
+        assert_eq!(eval("1 +", 0), Err("unexpected end".to_string()));
+        assert_eq!(eval("(1 + 2", 0), Err("missing )".to_string()));
+        assert_eq!(eval("1 + 2)", 0), Err("unexpected )".to_string()));
+        assert_eq!(eval("1.2.3", 0), Err("invalid number 1.2.3".to_string()));
+        assert_eq!(eval("x", 0), Err("unexpected x".to_string()));
+        assert_eq!(eval("i * i", i64::MAX), Err("integer overflow".to_string()));
+    }
+
+    #[test]
+    fn large_values_of_i_keep_their_precision() {
+        let i = (1 << 53) + 1;
+
+        assert_eq!(eval("i + 1", i), Ok(Number::Integer(i + 1)));
+    }
+
+    #[test]
+    fn generators_expand_into_one_entry_per_value() {
+        let entry = json!({
+            "type": "arrow",
+            "generate": { "name": "arrow_{i}", "range": [1, 3], "data": { "damage": "= 5 * i", "label": "tier {i}" } },
+            "speed": 10.0
+        });
+
+        let entries = expand_generator(entry).unwrap();
+
+        assert_eq!(entries.len(), 3);
+        assert_eq!(
+            entries[2],
+            json!({ "type": "arrow", "speed": 10.0, "damage": 15, "label": "tier 3", "name": "arrow_3" })
+        );
+    }
+
+    #[test]
+    fn generator_ranges_are_capped() {
+        let range = json!([0, i64::MAX]);
+
+        assert!(generator_range(Some(&range)).is_err());
+        assert_eq!(
+            generator_range(Some(&json!([1, MAX_GENERATED_ENTRIES as i64]))),
+            Ok((1, MAX_GENERATED_ENTRIES as i64))
+        );
+        assert!(generator_range(Some(&json!([0, MAX_GENERATED_ENTRIES as i64]))).is_err());
+        assert!(generator_range(Some(&json!([5, 1]))).is_err());
+    }
+
+    #[test]
+    fn entries_with_a_name_and_a_generator_are_errors() {
+        let entry = json!({ "type": "arrow", "name": "a", "generate": { "name": "a{i}", "range": [1, 2] } });
+
+        assert!(expand_generator(entry).is_err());
+    }
+}
@@ -0,0 +1,170 @@
+use bevy::{
+    platform::collections::{HashMap, HashSet},
+    prelude::*,
+};
+
+use crate::{
+    LoadingPackManifests, LoadingPrototypesHandles, PACK_MANIFEST_FILE, PackManifest,
+    PrototypeServer, PrototypesAsset, REGISTRY_SCENE_EXTENSION, is_prototype_file,
+    is_prototype_table_file, pack_info::is_pack_info_file, profile::is_override_file,
+};
+
+/// The prototypes files and pack manifests requested by a load.
+#[derive(Debug, Default)]
+pub(crate) struct LoadRequests {
+    pub files: Vec<AssetId<PrototypesAsset>>,
+    pub manifests: Vec<AssetId<PackManifest>>,
+}
+
+impl LoadRequests {
+    pub(crate) fn extend(&mut self, other: LoadRequests) {
+        self.files.extend(other.files);
+        self.manifests.extend(other.manifests);
+    }
+}
+
+#[derive(Debug, Default)]
+struct LoadGroup {
+    files: HashSet<AssetId<PrototypesAsset>>,
+    manifests: HashSet<AssetId<PackManifest>>,
+    loaded: bool,
+}
+
+/// The load groups requested with [`PrototypeServer::load_group`].
+#[derive(Resource, Debug, Default)]
+pub struct PrototypeLoadGroups {
+    groups: HashMap<String, LoadGroup>,
+}
+
+impl PrototypeLoadGroups {
+    /// Returns `true` once every file of the `name` group is applied.
+    pub fn is_loaded(&self, name: &str) -> bool {
+        self.groups.get(name).is_some_and(|group| group.loaded)
+    }
+
+    /// Returns `true` if the `name` group was requested.
+    pub fn contains(&self, name: &str) -> bool {
+        self.groups.contains_key(name)
+    }
+
+    /// Returns the names of the requested groups.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.groups.keys().map(String::as_str)
+    }
+
+    /// Adds the files listed by a loaded manifest to the groups it belongs to.
+    pub(crate) fn add_manifest_files(
+        &mut self,
+        manifest: AssetId<PackManifest>,
+        files: &[AssetId<PrototypesAsset>],
+    ) {
+        for group in self.groups.values_mut() {
+            if group.manifests.contains(&manifest) {
+                group.files.extend(files.iter().copied());
+            }
+        }
+    }
+}
+
+/// Sent once every file of a load group is applied, see [`PrototypeServer::load_group`].
+#[derive(Event, Debug, Clone, PartialEq, Eq)]
+pub struct PrototypeLoadGroupLoaded {
+    pub name: String,
+}
+
+/// Run condition returning `true` once every file of the `name` load group is applied.
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_histrion_proto::load_group_loaded;
+/// # fn start_level() {}
+/// # fn f(app: &mut App) {
+/// app.add_systems(Update, start_level.run_if(load_group_loaded("level1")));
+/// # }
+/// ```
+pub fn load_group_loaded(
+    name: impl Into<String>,
+) -> impl FnMut(Res<PrototypeLoadGroups>) -> bool + Clone {
+    let name = name.into();
+
+    move |groups: Res<PrototypeLoadGroups>| groups.is_loaded(&name)
+}
+
+impl PrototypeServer<'_> {
+    /// Loads the prototypes files, folders and pack manifests of `paths` as the `name` group,
+    /// e.g. `boot` for the startup content and `level1` for the content of a level.
+    ///
+    /// A [`PrototypeLoadGroupLoaded`] event is sent once every file of the group is applied, see
+    /// also [`load_group_loaded`]. Requesting a group again replaces its paths.
+    pub fn load_group(
+        &mut self,
+        name: impl Into<String>,
+        paths: impl IntoIterator<Item = impl AsRef<str>>,
+    ) {
+        let requests = self.request_paths(paths);
+
+        self.load_groups.groups.insert(
+            name.into(),
+            LoadGroup {
+                files: requests.files.into_iter().collect(),
+                manifests: requests.manifests.into_iter().collect(),
+                loaded: false,
+            },
+        );
+    }
+
+    /// Requests the prototypes files, folders and pack manifests of `paths`, telling them apart
+    /// by their extension.
+    pub(crate) fn request_paths(
+        &mut self,
+        paths: impl IntoIterator<Item = impl AsRef<str>>,
+    ) -> LoadRequests {
+        let mut requests = LoadRequests::default();
+
+        for path in paths {
+            let path = path.as_ref();
+
+            let request = if path.ends_with(PACK_MANIFEST_FILE) {
+                self.request_manifest(path, default())
+            } else if is_prototype_file(path.as_ref())
+                || is_prototype_table_file(path.as_ref())
+                || is_override_file(path.as_ref())
+                || is_pack_info_file(path.as_ref())
+                || path.ends_with(&format!(".{REGISTRY_SCENE_EXTENSION}"))
+            {
+                self.request_prototypes(path, default())
+            } else {
+                self.request_folder(path, default())
+            };
+
+            requests.extend(request);
+        }
+
+        requests
+    }
+}
+
+pub(crate) fn complete_load_groups(
+    mut groups: ResMut<PrototypeLoadGroups>,
+    loading_prototypes_handles: Res<LoadingPrototypesHandles>,
+    loading_pack_manifests: Res<LoadingPackManifests>,
+    mut loaded_tx: EventWriter<PrototypeLoadGroupLoaded>,
+) {
+    for (name, group) in &mut groups.groups {
+        if group.loaded
+            || group
+                .files
+                .iter()
+                .any(|id| loading_prototypes_handles.contains_key(id))
+            || group
+                .manifests
+                .iter()
+                .any(|id| loading_pack_manifests.contains_key(id))
+        {
+            continue;
+        }
+
+        group.loaded = true;
+        loaded_tx.write(PrototypeLoadGroupLoaded { name: name.clone() });
+    }
+}
@@ -9,7 +9,7 @@ use serde::{Deserialize, Serialize};
 ///
 /// e.g. a recipe may reference an item prototype as an ingredient.
 #[derive(Component, Reflect)]
-#[reflect(Clone, Serialize, Deserialize)]
+#[reflect(Clone, Hash, PartialEq, Serialize, Deserialize)]
 pub struct PrototypeId<T> {
     hash: u64,
     #[reflect(ignore)]
@@ -34,6 +34,96 @@ impl<T> PrototypeId<T> {
             _marker: core::marker::PhantomData,
         }
     }
+
+    /// Returns the raw hash of this prototype id.
+    #[must_use]
+    pub const fn to_raw(&self) -> u64 {
+        self.hash
+    }
+}
+
+/// Creates a [`PrototypeId`] from a prototype name at compile time.
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_histrion_proto::{proto_id, prelude::*};
+/// # #[derive(Debug, Clone, Default, Reflect, JsonSchema, Prototype)]
+/// # #[proto(name = "sword")]
+/// # struct Sword { damage: f32 }
+/// const WOODEN_STICK: PrototypeId<Sword> = proto_id!(Sword, "wooden_stick");
+/// ```
+///
+/// `cargo run --example pack -- check-ids <src> <pack>` reports the names used with this macro
+/// that aren't defined in a pack.
+#[macro_export]
+macro_rules! proto_id {
+    ($ty:ty, $name:expr $(,)?) => {
+        const { $crate::PrototypeId::<$ty>::from_name($name) }
+    };
+}
+
+/// Declares [`PrototypeId`] constants, two different names with the same hash fail to compile.
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_histrion_proto::{proto_ids, prelude::*};
+/// # #[derive(Debug, Clone, Default, Reflect, JsonSchema, Prototype)]
+/// # #[proto(name = "sword")]
+/// # struct Sword { damage: f32 }
+/// # #[derive(Debug, Clone, Default, Reflect, JsonSchema, Prototype)]
+/// # #[proto(name = "effect")]
+/// # struct Effect { duration: f32 }
+/// proto_ids! {
+///     pub WOODEN_STICK: Sword = "wooden_stick";
+///     pub BLEEDING: Effect = "bleeding";
+/// }
+/// ```
+#[macro_export]
+macro_rules! proto_ids {
+    ($($vis:vis $ident:ident: $ty:ty = $name:literal;)*) => {
+        $($vis const $ident: $crate::PrototypeId<$ty> = $crate::proto_id!($ty, $name);)*
+
+        const _: () = assert!(
+            !$crate::has_hash_collision(&[$(($ident.to_raw(), $name)),*]),
+            "two prototype names have the same hash",
+        );
+    };
+}
+
+/// Returns `true` if two different names have the same hash, used by [`proto_ids!`].
+#[doc(hidden)]
+pub const fn has_hash_collision(ids: &[(u64, &str)]) -> bool {
+    const fn str_eq(a: &str, b: &str) -> bool {
+        let (a, b) = (a.as_bytes(), b.as_bytes());
+
+        if a.len() != b.len() {
+            return false;
+        }
+
+        let mut i = 0;
+        while i < a.len() {
+            if a[i] != b[i] {
+                return false;
+            }
+            i += 1;
+        }
+
+        true
+    }
+
+    let mut i = 0;
+    while i < ids.len() {
+        let mut j = i + 1;
+        while j < ids.len() {
+            if ids[i].0 == ids[j].0 && !str_eq(ids[i].1, ids[j].1) {
+                return true;
+            }
+            j += 1;
+        }
+        i += 1;
+    }
+
+    false
 }
 
 impl<T> PartialEq for PrototypeId<T> {
@@ -136,11 +226,17 @@ impl<'de, T> Deserialize<'de> for PrototypeId<T> {
 
 /// A prototype name.
 /// This is a wrapper around a `PrototypeId` that also stores the name as a string.
+///
+/// The names are interned, each distinct name is allocated once for the session and shared by
+/// the names of the same prototype, cloning a name doesn't allocate.
+///
+/// With the `strip_names` feature the name is dropped once hashed and only the id is kept.
 #[derive(Component, Reflect)]
 #[reflect(Clone, Serialize, Deserialize)]
 pub struct PrototypeName<T> {
     id: PrototypeId<T>,
-    name: String,
+    #[cfg(not(feature = "strip_names"))]
+    name: &'static str,
 }
 
 impl<T> PrototypeName<T> {
@@ -149,7 +245,8 @@ impl<T> PrototypeName<T> {
     pub fn from_name(name: &str) -> Self {
         Self {
             id: PrototypeId::from_name(name),
-            name: name.to_string(),
+            #[cfg(not(feature = "strip_names"))]
+            name: intern_name(name),
         }
     }
 
@@ -157,8 +254,9 @@ impl<T> PrototypeName<T> {
         &self.id
     }
 
-    pub fn name(&self) -> &str {
-        &self.name
+    #[cfg(not(feature = "strip_names"))]
+    pub fn name(&self) -> &'static str {
+        self.name
     }
 }
 
@@ -174,23 +272,30 @@ impl<T> Clone for PrototypeName<T> {
     fn clone(&self) -> Self {
         Self {
             id: self.id,
-            name: self.name.clone(),
+            #[cfg(not(feature = "strip_names"))]
+            name: self.name,
         }
     }
 }
 
 impl<T> core::fmt::Debug for PrototypeName<T> {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        f.debug_struct("PrototypeName")
-            .field("id", &self.id)
-            .field("name", &self.name)
-            .finish()
+        let mut debug = f.debug_struct("PrototypeName");
+        debug.field("id", &self.id);
+        #[cfg(not(feature = "strip_names"))]
+        debug.field("name", &self.name);
+        debug.finish()
     }
 }
 
 impl<T> core::fmt::Display for PrototypeName<T> {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        write!(f, "{}", self.name)
+        #[cfg(feature = "strip_names")]
+        let name = &self.id;
+        #[cfg(not(feature = "strip_names"))]
+        let name = &self.name;
+
+        write!(f, "{name}")
     }
 }
 
@@ -221,12 +326,23 @@ impl<T> From<String> for PrototypeName<T> {
     }
 }
 
+#[cfg(not(feature = "strip_names"))]
+impl<T> Serialize for PrototypeName<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.name)
+    }
+}
+
+#[cfg(feature = "strip_names")]
 impl<T> Serialize for PrototypeName<T> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
     {
-        serializer.serialize_str(&self.name)
+        self.id.serialize(serializer)
     }
 }
 
@@ -235,14 +351,21 @@ impl<'de, T> Deserialize<'de> for PrototypeName<T> {
     where
         D: serde::Deserializer<'de>,
     {
-        let name = String::deserialize(deserializer)?;
-        Ok(Self::from_name(&name))
+        // Stripped names are serialized as their id.
+        #[cfg(feature = "strip_names")]
+        let name = Self {
+            id: PrototypeId::deserialize(deserializer)?,
+        };
+        #[cfg(not(feature = "strip_names"))]
+        let name = Self::from_name(&String::deserialize(deserializer)?);
+
+        Ok(name)
     }
 }
 
 /// A type erased version of [`PrototypeId`].
 #[derive(Component, Reflect)]
-#[reflect(Serialize, Deserialize)]
+#[reflect(Clone, Hash, PartialEq, Serialize, Deserialize)]
 pub struct ErasedPrototypeId {
     hash: u64,
 }
@@ -261,6 +384,12 @@ impl ErasedPrototypeId {
     pub const fn from_raw(hash: u64) -> Self {
         Self { hash }
     }
+
+    /// Returns the raw hash of this prototype id.
+    #[must_use]
+    pub const fn to_raw(&self) -> u64 {
+        self.hash
+    }
 }
 
 impl PartialEq for ErasedPrototypeId {
@@ -390,12 +519,46 @@ impl<'de> Deserialize<'de> for ErasedPrototypeId {
     }
 }
 
+/// Returns the interned copy of `name`, allocated on its first use.
+///
+/// The interned names are leaked for the life of the process, the table grows with each new
+/// name, e.g. each prototype renamed between two hot reloads, and never shrinks.
+#[cfg(not(feature = "strip_names"))]
+fn intern_name(name: &str) -> &'static str {
+    use std::sync::{LazyLock, PoisonError, RwLock};
+
+    use bevy::platform::collections::HashSet;
+
+    static NAMES: LazyLock<RwLock<HashSet<&'static str>>> = LazyLock::new(RwLock::default);
+
+    if let Some(interned) = NAMES
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(name)
+    {
+        return interned;
+    }
+
+    let mut names = NAMES.write().unwrap_or_else(PoisonError::into_inner);
+
+    // Interned by another thread between the two locks.
+    if let Some(interned) = names.get(name) {
+        return interned;
+    }
+
+    let interned: &'static str = Box::leak(name.into());
+    names.insert(interned);
+
+    interned
+}
+
 /// A type erased version of [`PrototypeName`].
 #[derive(Component, Reflect)]
 #[reflect(Clone, Serialize, Deserialize)]
 pub struct ErasedPrototypeName {
     id: ErasedPrototypeId,
-    name: String,
+    #[cfg(not(feature = "strip_names"))]
+    name: &'static str,
 }
 
 impl ErasedPrototypeName {
@@ -404,7 +567,8 @@ impl ErasedPrototypeName {
     pub fn from_name(name: &str) -> Self {
         Self {
             id: ErasedPrototypeId::from_name(name),
-            name: String::from(name),
+            #[cfg(not(feature = "strip_names"))]
+            name: intern_name(name),
         }
     }
 
@@ -412,8 +576,9 @@ impl ErasedPrototypeName {
         self.id
     }
 
-    pub fn name(&self) -> &str {
-        &self.name
+    #[cfg(not(feature = "strip_names"))]
+    pub fn name(&self) -> &'static str {
+        self.name
     }
 }
 
@@ -429,23 +594,30 @@ impl Clone for ErasedPrototypeName {
     fn clone(&self) -> Self {
         Self {
             id: self.id,
-            name: self.name.clone(),
+            #[cfg(not(feature = "strip_names"))]
+            name: self.name,
         }
     }
 }
 
 impl core::fmt::Debug for ErasedPrototypeName {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        f.debug_struct("ErasedPrototypeName")
-            .field("id", &self.id)
-            .field("name", &self.name)
-            .finish()
+        let mut debug = f.debug_struct("ErasedPrototypeName");
+        debug.field("id", &self.id);
+        #[cfg(not(feature = "strip_names"))]
+        debug.field("name", &self.name);
+        debug.finish()
     }
 }
 
 impl core::fmt::Display for ErasedPrototypeName {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        write!(f, "{}", self.name)
+        #[cfg(feature = "strip_names")]
+        let name = &self.id;
+        #[cfg(not(feature = "strip_names"))]
+        let name = &self.name;
+
+        write!(f, "{name}")
     }
 }
 
@@ -477,6 +649,7 @@ impl<T> From<PrototypeName<T>> for ErasedPrototypeName {
     fn from(name: PrototypeName<T>) -> Self {
         Self {
             id: name.id.into(),
+            #[cfg(not(feature = "strip_names"))]
             name: name.name,
         }
     }
@@ -486,17 +659,29 @@ impl<T> From<ErasedPrototypeName> for PrototypeName<T> {
     fn from(name: ErasedPrototypeName) -> Self {
         Self {
             id: name.id.into(),
+            #[cfg(not(feature = "strip_names"))]
             name: name.name,
         }
     }
 }
 
+#[cfg(not(feature = "strip_names"))]
 impl Serialize for ErasedPrototypeName {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
     {
-        serializer.serialize_str(&self.name)
+        serializer.serialize_str(self.name)
+    }
+}
+
+#[cfg(feature = "strip_names")]
+impl Serialize for ErasedPrototypeName {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.id.serialize(serializer)
     }
 }
 
@@ -505,7 +690,115 @@ impl<'de> Deserialize<'de> for ErasedPrototypeName {
     where
         D: serde::Deserializer<'de>,
     {
-        let name = String::deserialize(deserializer)?;
-        Ok(Self::from_name(&name))
+        // Stripped names are serialized as their id.
+        #[cfg(feature = "strip_names")]
+        let name = Self {
+            id: ErasedPrototypeId::deserialize(deserializer)?,
+        };
+        #[cfg(not(feature = "strip_names"))]
+        let name = {
+            struct ErasedPrototypeNameVisitor;
+
+            impl serde::de::Visitor<'_> for ErasedPrototypeNameVisitor {
+                type Value = ErasedPrototypeName;
+
+                fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                    write!(formatter, "a string")
+                }
+
+                fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+                where
+                    E: serde::de::Error,
+                {
+                    Ok(ErasedPrototypeName::from_name(v))
+                }
+            }
+
+            // Interned from the input of the deserializer, without copying it into a string.
+            deserializer.deserialize_str(ErasedPrototypeNameVisitor)?
+        };
+
+        Ok(name)
+    }
+}
+
+/// A [`Hasher`](core::hash::Hasher) for the maps keyed by [`PrototypeId`]s and
+/// [`ErasedPrototypeId`]s, the ids already being FNV-1a hashes of the names they're used as they
+/// are instead of being hashed again.
+///
+/// The values of keys combining an id with other values, e.g. `(TypeId, ErasedPrototypeId)`,
+/// are mixed together.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PrototypeIdHasher(u64);
+
+impl core::hash::Hasher for PrototypeIdHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        // A single id is kept as it is, the previous values are folded with a multiplication
+        // first, spreading them over the low bits the tables pick their buckets with.
+        let folded = u128::from(self.0) * 0x9e37_79b9_7f4a_7c15;
+        self.0 = (folded as u64 ^ (folded >> 64) as u64) ^ value;
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        // The other values are hashed with FNV-1a, like the names of the ids.
+        for byte in bytes {
+            self.0 = (self.0 ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
+
+/// Builds the [`PrototypeIdHasher`] of a [`PrototypeIdMap`].
+pub type PrototypeIdBuildHasher = core::hash::BuildHasherDefault<PrototypeIdHasher>;
+
+/// A [`HashMap`](bevy::platform::collections::HashMap) keyed by prototype ids, using the ids as
+/// their own hash, e.g. for the [`PrototypeIndex`](crate::PrototypeIndex)es of the hot paths.
+pub type PrototypeIdMap<K, V> = bevy::platform::collections::HashMap<K, V, PrototypeIdBuildHasher>;
+
+#[cfg(test)]
+mod tests {
+    use core::hash::BuildHasher;
+
+    use super::*;
+
+    /// Counts the keys in each of the 256 buckets of a table, picked from the low bits of the
+    /// hashes like `hashbrown`, and the distinct control bytes, picked from their high bits.
+    fn distribution<K: core::hash::Hash>(keys: impl Iterator<Item = K>) -> (Vec<usize>, usize) {
+        let mut buckets = vec![0; 256];
+        let mut control = [false; 128];
+
+        for key in keys {
+            let hash = PrototypeIdBuildHasher::default().hash_one(key);
+            buckets[(hash & 255) as usize] += 1;
+            control[(hash >> 57) as usize] = true;
+        }
+
+        (buckets, control.iter().filter(|used| **used).count())
+    }
+
+    #[test]
+    fn single_ids_are_their_own_hash() {
+        let id = ErasedPrototypeId::from_name("goblin");
+
+        assert_eq!(PrototypeIdBuildHasher::default().hash_one(id), id.hash);
+    }
+
+    #[test]
+    fn hashed_bytes_spread_over_the_buckets() {
+        for (buckets, control) in [
+            distribution(0..4096u32),
+            distribution((0..4096).map(|i| format!("item_{i}"))),
+            distribution((0..4096u32).map(|i| (i, ErasedPrototypeId::from_name("goblin")))),
+        ] {
+            // 16 keys by bucket on average.
+            assert!(
+                buckets.iter().all(|keys| (1..48).contains(keys)),
+                "{buckets:?}"
+            );
+            assert!(control > 120, "{control} control bytes");
+        }
     }
 }
@@ -0,0 +1,104 @@
+use bevy::prelude::*;
+
+use crate::{Prototype, PrototypeData, PrototypeId, RegistryEvent, registry::PrototypeRegistries};
+
+/// A custom lookup structure over the `P` prototypes, e.g. the monsters by level or the spells
+/// by element, kept in sync with the registry once added with
+/// [`PrototypeAppExt::add_prototype_index`](crate::PrototypeAppExt::add_prototype_index).
+///
+/// The index is a resource updated each frame, after the prototypes are loaded, reloaded and
+/// patched, read it like any other resource:
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_histrion_proto::{PrototypeIdMap, PrototypeIndex, prelude::*};
+/// # #[derive(Debug, Clone, Copy, Default, PartialEq, Reflect, JsonSchema)]
+/// # enum Element { #[default] Fire, Frost }
+/// # #[derive(Debug, Clone, Default, Reflect, JsonSchema, Prototype)]
+/// # #[proto(name = "spell")]
+/// # struct Spell { element: Element }
+/// #[derive(Resource, Default)]
+/// struct SpellsByElement(PrototypeIdMap<PrototypeId<Spell>, Element>);
+///
+/// impl SpellsByElement {
+///     fn spells(&self, element: Element) -> impl Iterator<Item = &PrototypeId<Spell>> {
+///         self.0.iter().filter_map(move |(id, e)| (*e == element).then_some(id))
+///     }
+/// }
+///
+/// impl PrototypeIndex<Spell> for SpellsByElement {
+///     fn on_insert(&mut self, proto: &Prototype<Spell>) {
+///         self.0.insert(*proto.id(), proto.element);
+///     }
+///
+///     fn on_remove(&mut self, id: &PrototypeId<Spell>) {
+///         self.0.remove(id);
+///     }
+///
+///     fn on_modify(&mut self, proto: &Prototype<Spell>) {
+///         self.0.insert(*proto.id(), proto.element);
+///     }
+/// }
+/// ```
+pub trait PrototypeIndex<P: PrototypeData>: Resource {
+    /// Called for each prototype inserted in the registry.
+    fn on_insert(&mut self, proto: &Prototype<P>);
+
+    /// Called for each prototype removed from the registry, with its id as the prototype itself
+    /// is returned to the caller of the removal, e.g. [`RegMut::remove`](crate::RegMut::remove).
+    fn on_remove(&mut self, id: &PrototypeId<P>);
+
+    /// Called for each prototype modified or replaced, e.g. by a reload, with its new value.
+    ///
+    /// The previous value isn't known anymore, indices grouping prototypes by a field should
+    /// keep the group of each prototype to move it.
+    fn on_modify(&mut self, proto: &Prototype<P>);
+}
+
+/// The prototypes inserted or removed since the last frame, sent for the indices.
+#[derive(Event, Debug)]
+pub(crate) enum PrototypeChange<P: PrototypeData> {
+    Inserted(PrototypeId<P>),
+    Removed(PrototypeId<P>),
+}
+
+/// Calls the callbacks of the `I` index for the `P` prototypes inserted, removed and modified
+/// since its last run, the prototypes already in the registry are inserted on the first run.
+pub(crate) fn update_prototype_index<P: PrototypeData, I: PrototypeIndex<P>>(
+    registries: Res<PrototypeRegistries>,
+    mut index: ResMut<I>,
+    mut changes: EventReader<PrototypeChange<P>>,
+    mut events: EventReader<RegistryEvent<P>>,
+    mut populated: Local<bool>,
+) {
+    if !*populated {
+        *populated = true;
+
+        for proto in registries.iter::<P>() {
+            index.on_insert(proto);
+        }
+
+        changes.clear();
+        events.clear();
+        return;
+    }
+
+    for change in changes.read() {
+        match change {
+            PrototypeChange::Inserted(id) => {
+                if let Some(proto) = registries.get(id) {
+                    index.on_insert(proto);
+                }
+            }
+            PrototypeChange::Removed(id) => index.on_remove(id),
+        }
+    }
+
+    for event in events.read() {
+        if let RegistryEvent::Modified(id) = event
+            && let Some(proto) = registries.get(id)
+        {
+            index.on_modify(proto);
+        }
+    }
+}
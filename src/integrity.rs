@@ -0,0 +1,199 @@
+use std::path::Path;
+
+use bevy::{
+    asset::AssetPath, ecs::system::SystemParam, platform::collections::HashMap, prelude::*,
+};
+
+use crate::{PACK_MANIFEST_FILE, PackError, PackManifest, PrototypesAsset};
+
+impl PackManifest {
+    /// Hashes the prototypes files of the `dir` folder, see [`PackManifest::list`] for a manifest
+    /// without hashes.
+    ///
+    /// Like [`PrototypeServer::load_prototypes_folder`](crate::PrototypeServer::load_prototypes_folder),
+    /// sub-folders are not included.
+    pub fn generate(dir: impl AsRef<Path>) -> Result<Self, PackError> {
+        let dir = dir.as_ref();
+        let mut manifest = Self::list(dir)?;
+
+        for (name, hash) in &mut manifest.files {
+            let path = dir.join(name);
+            let bytes = std::fs::read(&path).map_err(|source| PackError::Io { path, source })?;
+            *hash = blake3::hash(&bytes).to_hex().to_string();
+        }
+
+        Ok(manifest)
+    }
+
+    /// Signs the manifest with `key`, so that the listed hashes can't be updated without it.
+    pub fn sign(&mut self, key: &PackSigningKey) {
+        self.signature = Some(self.signature_with(key));
+    }
+
+    /// Returns `true` if the manifest was signed with `key` and wasn't modified since.
+    pub fn verify_signature(&self, key: &PackSigningKey) -> bool {
+        self.signature
+            .as_deref()
+            .is_some_and(|signature| signature == self.signature_with(key))
+    }
+
+    fn signature_with(&self, key: &PackSigningKey) -> String {
+        let mut hasher = blake3::Hasher::new_keyed(&key.0);
+
+        for (file, hash) in &self.files {
+            hasher.update(file.as_bytes());
+            hasher.update(b"\0");
+            hasher.update(hash.as_bytes());
+            hasher.update(b"\n");
+        }
+
+        hasher.finalize().to_hex().to_string()
+    }
+}
+
+/// Key of the pack manifests signatures.
+///
+/// When this resource exists, prototypes folders must have a manifest signed with this key.
+#[derive(Resource, Clone)]
+pub struct PackSigningKey(pub [u8; 32]);
+
+impl PackSigningKey {
+    /// Parses a key from its 64 characters hexadecimal representation.
+    pub fn from_hex(hex: &str) -> Option<Self> {
+        blake3::Hash::from_hex(hex)
+            .ok()
+            .map(|hash| Self(*hash.as_bytes()))
+    }
+}
+
+/// Sent when a prototypes file doesn't match the manifest of its folder, such files are not
+/// loaded.
+#[derive(Event, Debug, Clone)]
+pub struct TamperedPackEvent {
+    pub path: AssetPath<'static>,
+    pub reason: TamperReason,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TamperReason {
+    /// The file content doesn't match the hash of the manifest.
+    HashMismatch { expected: String, actual: String },
+    /// The file isn't listed in the manifest.
+    Unlisted,
+    /// The file is listed in the manifest but doesn't exist, or the manifest itself is missing
+    /// while a [`PackSigningKey`] is set.
+    Missing,
+    /// The manifest signature doesn't match the [`PackSigningKey`].
+    InvalidSignature,
+}
+
+#[derive(Default, Resource, Deref, DerefMut)]
+pub(crate) struct ExpectedPackHashes(HashMap<AssetPath<'static>, String>);
+
+#[derive(SystemParam)]
+pub(crate) struct PackIntegrity<'w> {
+    expected_hashes: ResMut<'w, ExpectedPackHashes>,
+    signing_key: Option<Res<'w, PackSigningKey>>,
+    events: EventWriter<'w, TamperedPackEvent>,
+}
+
+impl PackIntegrity<'_> {
+    /// Checks the `files` of a prototypes folder against its manifest and returns the ones that
+    /// can be loaded.
+    pub fn verify_folder(
+        &mut self,
+        folder: &AssetPath,
+        manifest: Option<PackManifest>,
+        files: Vec<String>,
+    ) -> Vec<String> {
+        let Some(manifest) = manifest else {
+            if self.signing_key.is_some() {
+                self.tampered(
+                    resolve_in(folder, PACK_MANIFEST_FILE),
+                    TamperReason::Missing,
+                );
+                return Vec::new();
+            }
+
+            return files;
+        };
+
+        if let Some(key) = &self.signing_key
+            && !manifest.verify_signature(key)
+        {
+            self.tampered(
+                resolve_in(folder, PACK_MANIFEST_FILE),
+                TamperReason::InvalidSignature,
+            );
+            return Vec::new();
+        }
+
+        let mut verified = Vec::with_capacity(files.len());
+
+        for file in files {
+            let path = AssetPath::from(file.clone()).into_owned();
+            let name = path
+                .path()
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+
+            match manifest.files.get(&name) {
+                Some(hash) => {
+                    // Manifests generated with `PackManifest::list` only list files.
+                    if !hash.is_empty() {
+                        self.expected_hashes.insert(path, hash.clone());
+                    }
+
+                    verified.push(file);
+                }
+                None => self.tampered(path, TamperReason::Unlisted),
+            }
+        }
+
+        for name in manifest.files.keys() {
+            let is_listed = verified
+                .iter()
+                .any(|file| Path::new(file).file_name() == Some(name.as_ref()));
+
+            if !is_listed {
+                self.tampered(resolve_in(folder, name), TamperReason::Missing);
+            }
+        }
+
+        verified
+    }
+
+    /// Returns `false` and sends a [`TamperedPackEvent`] if the loaded `asset` doesn't match the
+    /// hash of its folder manifest.
+    pub fn verify_asset(&mut self, asset: &PrototypesAsset) -> bool {
+        let Some(expected) = self.expected_hashes.get(&asset.path) else {
+            return true;
+        };
+
+        let actual = asset.hash.to_hex().to_string();
+
+        if *expected == actual {
+            return true;
+        }
+
+        let reason = TamperReason::HashMismatch {
+            expected: expected.clone(),
+            actual,
+        };
+        self.tampered(asset.path.clone(), reason);
+
+        false
+    }
+
+    fn tampered(&mut self, path: AssetPath<'static>, reason: TamperReason) {
+        warn!("Tampered prototypes file {path}: {reason:?}");
+        self.events.write(TamperedPackEvent { path, reason });
+    }
+}
+
+fn resolve_in(folder: &AssetPath, file: &str) -> AssetPath<'static> {
+    folder
+        .resolve(file)
+        .unwrap_or_else(|_| folder.clone_owned())
+}
@@ -0,0 +1,341 @@
+use core::any::TypeId;
+use std::{path::Path, sync::Mutex};
+
+use bevy::{
+    asset::{AssetLoader, AssetPath, LoadContext, io::Reader as AssetReader},
+    platform::collections::{HashMap, HashSet},
+    prelude::*,
+};
+use serde::Deserialize;
+use serde_json::{Map as JsonMap, Value as JsonValue};
+
+use crate::{
+    AppPrototypeTypeRegistry, ErasedPrototypeId, PrototypeIdMap, PrototypeLoadPriority,
+    PrototypeServer, PrototypesAsset, generate::generator_range, is_ndjson_file, named_from_file,
+};
+
+/// Extensions of the prototypes files that can be indexed, tables are always loaded eagerly.
+const PROTOTYPE_INDEX_EXTENSIONS: &[&str] = &["proto", "proto.json", "proto.ndjson"];
+
+/// Whether a prototype is available in its registry, see [`Reg::state`](crate::Reg::state).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PrototypeState {
+    /// The prototype is in its registry.
+    Loaded,
+    /// The prototype is indexed and its file is being loaded, or will be on its first access.
+    Pending,
+    /// The prototype is neither in its registry nor in a file indexed with
+    /// [`PrototypeServer::index_prototypes`].
+    Missing,
+}
+
+/// The prototypes of the files indexed with [`PrototypeServer::index_prototypes`], by type and id.
+#[derive(Resource, Default)]
+pub(crate) struct LazyPrototypes {
+    files: PrototypeIdMap<(TypeId, ErasedPrototypeId), AssetPath<'static>>,
+    /// Files whose load was requested, they aren't requested again.
+    loads: HashSet<AssetPath<'static>>,
+    /// Files being loaded on demand.
+    loading: Vec<(AssetId<PrototypesAsset>, AssetPath<'static>)>,
+    /// Files loaded on demand and applied into the registries.
+    applied: HashSet<AssetPath<'static>>,
+    /// Files holding a prototype missed by [`Reg::get`](crate::Reg::get) since the last
+    /// [`load_requested_prototypes`].
+    requested: Mutex<HashSet<AssetPath<'static>>>,
+}
+
+impl LazyPrototypes {
+    /// Requests the load of the file of the `id` prototype, if it's indexed and not loaded yet.
+    pub(crate) fn request(&self, type_id: TypeId, id: ErasedPrototypeId) {
+        let Some(file) = self.files.get(&(type_id, id)) else {
+            return;
+        };
+
+        if self.loads.contains(file) {
+            return;
+        }
+
+        self.requested
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .insert(file.clone());
+    }
+
+    /// Returns `true` if the `id` prototype is indexed in a file not applied yet.
+    pub(crate) fn is_pending(&self, type_id: TypeId, id: ErasedPrototypeId) -> bool {
+        self.files
+            .get(&(type_id, id))
+            .is_some_and(|file| !self.applied.contains(file))
+    }
+}
+
+/// The prototypes files being indexed, see [`PrototypeServer::index_prototypes`].
+#[derive(Resource, Default, Deref, DerefMut)]
+pub(crate) struct IndexingPrototypesFiles(
+    HashMap<AssetId<PrototypesIndex>, Handle<PrototypesIndex>>,
+);
+
+/// The type and name of the entries of a prototypes file, read without deserializing their data.
+#[derive(Asset, TypePath, Debug)]
+pub(crate) struct PrototypesIndex {
+    entries: Vec<(String, String)>,
+}
+
+#[derive(Default, Deserialize)]
+struct IndexedEntry {
+    #[serde(rename = "type")]
+    ty: Option<String>,
+    name: Option<String>,
+    /// The names of an entry naming a family of prototypes, see `expand_names`.
+    #[serde(default)]
+    names: Vec<IndexedName>,
+    generate: Option<IndexedGenerator>,
+    /// The fragments declared by the entry, see `expand_fragments`.
+    #[serde(rename = "$fragments")]
+    fragments: Option<serde::de::IgnoredAny>,
+}
+
+impl IndexedEntry {
+    /// Returns the type and the names of the prototypes of the entry.
+    fn prototypes(self) -> Option<(String, Vec<String>)> {
+        let ty = self.ty?;
+
+        let names = match (self.name, self.names, self.generate) {
+            (Some(name), names, None) if names.is_empty() => vec![name],
+            (None, names, None) if !names.is_empty() => names
+                .into_iter()
+                .map(|name| match name {
+                    IndexedName::Name(name) | IndexedName::Overrides { name } => name,
+                })
+                .collect(),
+            (None, names, Some(generate)) if names.is_empty() => {
+                let (start, end) = generator_range(Some(&generate.range)).ok()?;
+
+                (start..=end)
+                    .map(|i| generate.name.replace("{i}", &i.to_string()))
+                    .collect()
+            }
+            _ => return None,
+        };
+
+        Some((ty, names))
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum IndexedName {
+    Name(String),
+    Overrides { name: String },
+}
+
+/// The names of the prototypes of a generator entry, see `expand_generator`.
+#[derive(Deserialize)]
+struct IndexedGenerator {
+    name: String,
+    range: JsonValue,
+}
+
+#[derive(Default)]
+pub(crate) struct PrototypesIndexLoader;
+
+impl AssetLoader for PrototypesIndexLoader {
+    type Asset = PrototypesIndex;
+    type Settings = ();
+    type Error = std::io::Error;
+
+    async fn load(
+        &self,
+        reader: &mut dyn AssetReader,
+        _settings: &Self::Settings,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        let path = load_context.path();
+
+        let entries = if is_ndjson_file(path) {
+            serde_json::Deserializer::from_slice(&bytes)
+                .into_iter::<IndexedEntry>()
+                .collect::<Result<Vec<_>, _>>()?
+        } else if bytes.trim_ascii_start().starts_with(b"[") {
+            serde_json::from_slice::<Vec<IndexedEntry>>(&bytes)?
+        } else {
+            vec![named_entry(serde_json::from_slice(&bytes)?, path)]
+        };
+
+        let entries = entries
+            .into_iter()
+            .enumerate()
+            // Entries only declaring fragments have no prototype.
+            .filter(|(_, entry)| {
+                entry.fragments.is_none() || entry.ty.is_some() || entry.name.is_some()
+            })
+            .flat_map(|(index, entry)| match entry.prototypes() {
+                Some((ty, names)) => names.into_iter().map(|name| (ty.clone(), name)).collect(),
+                None => {
+                    warn!(
+                        "Prototype entry {index} of {} has no type or name, it can't be indexed",
+                        path.display()
+                    );
+                    Vec::new()
+                }
+            })
+            .collect();
+
+        Ok(PrototypesIndex { entries })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        PROTOTYPE_INDEX_EXTENSIONS
+    }
+}
+
+/// Takes the missing type and name of a single-prototype file from its file name.
+fn named_entry(entry: IndexedEntry, path: &Path) -> IndexedEntry {
+    let mut fields = JsonMap::new();
+
+    if let Some(ty) = entry.ty {
+        fields.insert("type".to_string(), ty.into());
+    }
+
+    if let Some(name) = entry.name {
+        fields.insert("name".to_string(), name.into());
+    }
+
+    let named: IndexedEntry =
+        serde_json::from_value(named_from_file(JsonValue::Object(fields), path))
+            .unwrap_or_default();
+
+    // Entries with `names` or generators aren't named after their file.
+    IndexedEntry {
+        ty: named.ty,
+        name: named
+            .name
+            .filter(|_| entry.names.is_empty() && entry.generate.is_none()),
+        names: entry.names,
+        generate: entry.generate,
+        fragments: entry.fragments,
+    }
+}
+
+impl PrototypeServer<'_> {
+    /// Indexes the prototypes of a file without loading them, the file is loaded the first time
+    /// [`Reg::get`](crate::Reg::get) misses one of its prototypes, e.g. for games with enormous
+    /// catalogs only using a fraction of them at once.
+    ///
+    /// Until then [`Reg::state`](crate::Reg::state) returns [`PrototypeState::Pending`] for them.
+    /// The whole file of a missed prototype is loaded, prototypes taking their defaults from
+    /// another file need that file to be loaded first. Tables are loaded right away.
+    pub fn index_prototypes(&mut self, path: &str) {
+        let is_indexable = PROTOTYPE_INDEX_EXTENSIONS
+            .iter()
+            .any(|extension| path.ends_with(&format!(".{extension}")));
+
+        if !is_indexable {
+            self.request_prototypes(path, PrototypeLoadPriority::Normal);
+            return;
+        }
+
+        let handle: Handle<PrototypesIndex> = self.asset_server.load(path);
+        self.indexing_files.insert(handle.id(), handle);
+    }
+
+    /// Indexes the prototypes files of a folder, see [`PrototypeServer::index_prototypes`].
+    ///
+    /// Folders that can't be listed are loaded right away, see
+    /// [`PrototypeServer::load_prototypes_folder`].
+    pub fn index_prototypes_folder(&mut self, path: &str) {
+        let folder: AssetPath<'_> = path.into();
+
+        let Some(files) = self.list_prototypes_folder(&folder) else {
+            self.request_folder(path, PrototypeLoadPriority::Normal);
+            return;
+        };
+
+        for file in files {
+            self.index_prototypes(&file);
+        }
+    }
+}
+
+pub(crate) fn on_prototypes_index_loaded(
+    mut events_rx: EventReader<AssetEvent<PrototypesIndex>>,
+    mut assets: ResMut<Assets<PrototypesIndex>>,
+    mut indexing_files: ResMut<IndexingPrototypesFiles>,
+    mut lazy: ResMut<LazyPrototypes>,
+    prototype_types: Res<AppPrototypeTypeRegistry>,
+) {
+    for event in events_rx.read() {
+        let AssetEvent::LoadedWithDependencies { id } = event else {
+            continue;
+        };
+
+        let Some(handle) = indexing_files.remove(id) else {
+            continue;
+        };
+
+        let (Some(path), Some(index)) = (handle.path().cloned(), assets.remove(*id)) else {
+            continue;
+        };
+
+        let aliases = &prototype_types.0;
+        let prototype_types = prototype_types.0.read();
+
+        for (ty, name) in index.entries {
+            let Some(type_id) = prototype_types.get(&*aliases.canonical_or(&ty)) else {
+                warn!("Unknown prototype type {ty} of prototype {name} indexed in {path}");
+                continue;
+            };
+
+            lazy.files.insert(
+                (*type_id, ErasedPrototypeId::from_name(&name)),
+                path.clone_owned(),
+            );
+        }
+    }
+}
+
+/// Loads the files of the prototypes missed by [`Reg::get`](crate::Reg::get).
+pub(crate) fn load_requested_prototypes(
+    mut lazy: ResMut<LazyPrototypes>,
+    mut server: PrototypeServer,
+) {
+    if !lazy.loading.is_empty() {
+        let LazyPrototypes {
+            loading, applied, ..
+        } = &mut *lazy;
+
+        loading.retain(|(id, file)| {
+            let is_loading = server.loading_prototypes_handles.contains_key(id);
+
+            if !is_loading {
+                applied.insert(file.clone());
+            }
+
+            is_loading
+        });
+    }
+
+    let requested = core::mem::take(
+        lazy.bypass_change_detection()
+            .requested
+            .get_mut()
+            .unwrap_or_else(std::sync::PoisonError::into_inner),
+    );
+
+    let mut files = requested
+        .into_iter()
+        .filter(|file| lazy.loads.insert(file.clone()))
+        .collect::<Vec<_>>();
+    files.sort_by_key(ToString::to_string);
+
+    for file in files {
+        debug!("Loading prototypes file {file} on demand");
+
+        let requests = server.request_prototypes(&file.to_string(), PrototypeLoadPriority::Normal);
+        lazy.loading
+            .extend(requests.files.into_iter().map(|id| (id, file.clone())));
+    }
+}
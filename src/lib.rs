@@ -1,22 +1,134 @@
+use core::{any::TypeId, time::Duration};
+
 use bevy::{
-    asset::AssetPath, ecs::system::SystemParam, platform::collections::HashMap, prelude::*,
+    app::AppLabel,
+    asset::{
+        AssetLoadFailedEvent, AssetPath, LoadState, RecursiveDependencyLoadState, UntypedAssetId,
+    },
+    ecs::system::SystemParam,
+    platform::{
+        collections::{HashMap, HashSet},
+        time::Instant,
+    },
+    prelude::*,
 };
+#[cfg(feature = "schema")]
 use serde_json::{Map as JsonMap, Value as JsonValue, json};
+#[cfg(feature = "schema")]
+use std::path::Path;
+use thiserror::Error;
+
+// The derives of the unit tests refer to the crate by its name.
+#[cfg(test)]
+extern crate self as bevy_histrion_proto;
 
+#[cfg(feature = "access_stats")]
+mod access_stats;
+mod cache;
+mod collection;
+mod diff;
+mod dump;
+mod experiment;
+mod generate;
+mod group;
 mod identifier;
+mod index;
+#[cfg(feature = "integrity")]
+mod integrity;
+mod lazy;
+mod lint;
+mod locale;
+mod lookup;
+mod manifest;
+mod merge;
+mod overlay;
+mod pack;
+mod pack_info;
+mod precision;
+mod profile;
 mod prototype;
+mod raw;
 mod registry;
+#[cfg(feature = "http")]
+mod remote;
+#[cfg(feature = "remote_tables")]
+mod remote_table;
+mod report;
+mod save;
+mod scene;
+#[cfg(feature = "schema")]
 mod schema;
+#[cfg(feature = "lock_free")]
+mod shared;
+mod spawn;
+mod storage;
+#[cfg(feature = "sync")]
+mod sync;
+mod table;
+mod tag;
+mod telemetry;
+mod variant;
 
+#[cfg(feature = "access_stats")]
+pub use access_stats::*;
 pub use bevy_histrion_proto_derive::*;
+#[cfg(feature = "schema")]
+use bevy_histrion_proto_format::PROTOTYPE_NDJSON_EXTENSION;
+pub use bevy_histrion_proto_format::TableFormat;
+pub(crate) use bevy_histrion_proto_format::{
+    PROTOTYPE_ASSET_EXTENSIONS, declared_fragments, expand_fragments, fragment_reference,
+    is_ndjson_file, is_prototype_table_file, line_column, read_ndjson, table_to_entries,
+    table_type, untyped_cell_value,
+};
+#[cfg(feature = "kdl")]
+pub(crate) use bevy_histrion_proto_format::{PROTOTYPE_KDL_EXTENSIONS, read_kdl_file};
+pub use cache::*;
+pub use collection::*;
+pub use diff::*;
+pub use dump::*;
+pub use experiment::*;
+pub use group::*;
 pub use identifier::*;
+pub use index::*;
+#[cfg(feature = "integrity")]
+pub use integrity::*;
+pub use lazy::*;
+pub use lint::*;
+pub use locale::*;
+pub use lookup::*;
+pub use manifest::*;
+pub use pack::*;
+pub use pack_info::*;
+pub use precision::*;
 pub use prototype::*;
 pub use registry::*;
+#[cfg(feature = "http")]
+pub use remote::*;
+#[cfg(feature = "remote_tables")]
+pub use remote_table::*;
+pub use report::*;
+pub use save::*;
+pub use scene::*;
+#[cfg(feature = "schema")]
 pub use schema::*;
+#[cfg(feature = "lock_free")]
+pub use shared::*;
+pub use spawn::*;
+#[cfg(feature = "sync")]
+pub use sync::*;
+pub use table::*;
+pub use tag::*;
+pub use telemetry::*;
+pub use variant::*;
 
 pub mod prelude {
+    #[cfg(feature = "schema")]
+    pub use crate::JsonSchema;
     pub use crate::{
-        JsonSchema, PrototypeAppExt, PrototypeServer, identifier::*, prototype::*, registry::*,
+        PrototypeAppExt, PrototypeCommandsExt, PrototypeComponent, PrototypeEntityCommandsExt,
+        PrototypeLoadPriority, PrototypeServer, SpawnablePrototype, SpawnedFromPrototype, TagQuery,
+        all_prototypes_loaded, critical_prototypes_loaded, identifier::*, prototype::*,
+        registry::*,
     };
     pub use bevy_histrion_proto_derive::*;
 }
@@ -25,89 +137,650 @@ pub struct PrototypesPlugin;
 
 impl Plugin for PrototypesPlugin {
     fn build(&self, app: &mut App) {
-        let app_prototype_type_registry = AppPrototypeTypeRegistry::default();
+        build_prototypes_plugin(app, true);
+    }
+
+    fn finish(&self, app: &mut App) {
+        finish_prototypes_plugin(app);
+    }
+}
+
+/// The [`PrototypesPlugin`] for headless game servers, e.g. with only the `MinimalPlugins` and
+/// the `AssetPlugin`, consuming the same data packs as the game.
+///
+/// The assets referenced by the handle fields of the prototypes, e.g. their icons and meshes,
+/// aren't loaded, the fields keep their default handle. Their loaders and asset types, often
+/// registered by the rendering plugins, aren't needed then. Add it instead of the
+/// [`PrototypesPlugin`]:
+///
+/// ```no_run
+/// # use std::time::Duration;
+/// # use bevy::prelude::*;
+/// # use bevy_histrion_proto::{BlockingLoadError, ServerPrototypesPlugin, prelude::*};
+/// # #[derive(Debug, Clone, Default, Reflect, JsonSchema, Prototype)]
+/// # #[proto(name = "sword")]
+/// # struct Sword { damage: f32 }
+/// # fn main() -> Result<(), BlockingLoadError> {
+/// App::new()
+///     .add_plugins((MinimalPlugins, AssetPlugin::default(), ServerPrototypesPlugin))
+///     .register_prototype::<Sword>()
+///     .load_prototypes_blocking(["prototypes"], Duration::from_secs(30))?
+///     .run();
+/// # Ok(())
+/// # }
+/// ```
+pub struct ServerPrototypesPlugin;
+
+impl Plugin for ServerPrototypesPlugin {
+    fn build(&self, app: &mut App) {
+        build_prototypes_plugin(app, false);
+    }
+
+    fn finish(&self, app: &mut App) {
+        finish_prototypes_plugin(app);
+    }
+}
+
+fn build_prototypes_plugin(app: &mut App, load_handles: bool) {
+    let app_prototype_type_registry = AppPrototypeTypeRegistry::default();
+    let app_prototype_profiles = profile::AppPrototypeProfiles::default();
+
+    app.register_type::<ErasedPrototypeId>()
+        .register_type::<ErasedPrototypeName>()
+        .register_type::<PrototypeRegistries>()
+        .init_resource::<PrototypeRegistries>()
+        .init_resource::<LoadingPrototypesHandles>()
+        .init_resource::<LoadingPackManifests>()
+        .init_resource::<LoadedPrototypesFiles>()
+        .init_resource::<PrototypesAssetRetention>()
+        .init_resource::<RetainedPrototypesAssets>()
+        .init_resource::<PrototypeCollections>()
+        .init_resource::<LoadingCollections>()
+        .init_resource::<TagAliases>()
+        .init_resource::<AllowedTags>()
+        .init_resource::<PrototypeLoadReports>()
+        .init_resource::<PanicOnPrototypeErrors>()
+        .init_resource::<PrototypeNumberPrecision>()
+        .add_event::<PrototypeWriteRejected>()
+        .init_resource::<PrototypeLoadGroups>()
+        .init_resource::<LoadedPacks>()
+        .init_resource::<lazy::LazyPrototypes>()
+        .init_resource::<lazy::IndexingPrototypesFiles>()
+        .init_resource::<LintConfig>()
+        .init_resource::<locale::LocaleOverrides>()
+        .init_resource::<PrototypeVariants>()
+        .init_resource::<PrototypeExperiments>()
+        .init_resource::<variant::VariantLayers>()
+        .add_event::<PrototypeLoadReport>()
+        .add_event::<PrototypeLocaleChanged>()
+        .add_event::<PrototypeVariantChanged>()
+        .add_event::<PrototypeLoadGroupLoaded>()
+        .add_event::<IncompatiblePackEvent>()
+        .insert_resource(app_prototype_type_registry.clone())
+        .insert_resource(app_prototype_profiles.clone());
+
+    #[cfg(feature = "schema")]
+    app.init_resource::<PrototypesSchemas>();
+
+    let type_registry = app.world().resource::<AppTypeRegistry>().0.clone();
+
+    let prototypes_asset_loader = PrototypesAssetLoader {
+        prototype_type_registry: app_prototype_type_registry.0.clone(),
+        type_registry: type_registry.clone(),
+        profiles: app_prototype_profiles,
+        load_handles,
+    };
+
+    let registry_scene_loader = RegistrySceneLoader {
+        prototype_type_registry: app_prototype_type_registry.0.clone(),
+        type_registry: type_registry.clone(),
+        load_handles,
+    };
+
+    // Registered first, the prototypes loader stays the default one of the shared extensions.
+    app.init_asset::<lazy::PrototypesIndex>()
+        .init_asset_loader::<lazy::PrototypesIndexLoader>();
+
+    app.init_asset::<PrototypesAsset>()
+        .init_asset::<PackManifest>()
+        .init_asset::<PackInfo>()
+        .init_asset::<PrototypeCollectionsAsset>()
+        .init_asset::<locale::PrototypeLocaleOverrides>()
+        .init_asset::<variant::PrototypeVariantLayer>()
+        // Registered last of the loaders of prototypes, the prototypes loader is the one the files
+        // named like override files without a declared suffix fall back to.
+        .register_asset_loader(registry_scene_loader)
+        .register_asset_loader(prototypes_asset_loader)
+        .init_asset_loader::<PackManifestLoader>()
+        .init_asset_loader::<pack_info::PackInfoLoader>()
+        .init_asset_loader::<PrototypeCollectionsLoader>()
+        .init_asset_loader::<locale::PrototypeLocaleOverridesLoader>()
+        .init_asset_loader::<variant::PrototypeVariantLayerLoader>()
+        .add_systems(
+            Update,
+            (
+                on_pack_manifest_loaded,
+                on_prototypes_asset_loaded,
+                on_collections_loaded,
+                pack_info::on_pack_info_loaded.before(on_prototypes_asset_loaded),
+                pack_info::verify_loaded_packs
+                    .after(pack_info::on_pack_info_loaded)
+                    .after(on_prototypes_asset_loaded),
+                group::complete_load_groups
+                    .after(on_pack_manifest_loaded)
+                    .after(on_prototypes_asset_loaded),
+                lazy::on_prototypes_index_loaded,
+                locale::apply_locale_overrides.after(on_prototypes_asset_loaded),
+                variant::apply_variant_layers.after(on_prototypes_asset_loaded),
+            ),
+        )
+        .add_systems(
+            Last,
+            (
+                lazy::load_requested_prototypes,
+                registry::send_rejected_writes,
+            ),
+        );
+
+    #[cfg(feature = "access_stats")]
+    app.init_resource::<PrototypeAccessStats>();
+
+    #[cfg(feature = "integrity")]
+    app.init_resource::<integrity::ExpectedPackHashes>()
+        .add_event::<TamperedPackEvent>();
 
-        app.register_type::<ErasedPrototypeId>()
-            .register_type::<ErasedPrototypeName>()
-            .init_resource::<PrototypeRegistries>()
-            .init_resource::<LoadingPrototypesHandles>()
-            .init_resource::<PrototypesSchemas>()
-            .insert_resource(app_prototype_type_registry.clone());
+    #[cfg(feature = "lock_free")]
+    app.init_resource::<SharedPrototypes>()
+        .add_systems(Last, shared::publish_shared_prototypes);
+}
+
+fn finish_prototypes_plugin(app: &mut App) {
+    let Some(pending) = app
+        .world_mut()
+        .remove_resource::<PendingPrototypeRegistrations>()
+    else {
+        return;
+    };
 
-        let type_registry = app.world().resource::<AppTypeRegistry>().0.clone();
+    for register in pending.0 {
+        register(app);
+    }
+}
+
+fn on_pack_manifest_loaded(
+    mut events_rx: EventReader<AssetEvent<PackManifest>>,
+    mut failed_events_rx: EventReader<AssetLoadFailedEvent<PackManifest>>,
+    mut assets: ResMut<Assets<PackManifest>>,
+    mut server: PrototypeServer,
+) {
+    for AssetLoadFailedEvent { id, path, error } in failed_events_rx.read() {
+        if server.loading_pack_manifests.remove(id).is_some() {
+            error!("Failed to load pack manifest {path}: {error}");
+        }
+    }
 
-        let prototypes_asset_loader = PrototypesAssetLoader {
-            prototype_type_registry: app_prototype_type_registry.0.clone(),
-            type_registry: type_registry.clone(),
+    for event in events_rx.read() {
+        let AssetEvent::LoadedWithDependencies { id } = event else {
+            continue;
         };
 
-        app.init_asset::<PrototypesAsset>()
-            .register_asset_loader(prototypes_asset_loader)
-            .add_systems(Update, on_prototypes_asset_loaded);
+        let Some((handle, priority)) = server.loading_pack_manifests.remove(id) else {
+            continue;
+        };
+
+        let (Some(manifest), Some(path)) = (assets.remove(*id), handle.path()) else {
+            warn!("Pack manifest {id} not found");
+            continue;
+        };
+
+        let folder = path.parent().unwrap_or_default();
+        let files = manifest
+            .files
+            .keys()
+            .filter_map(|file| folder.resolve(file).ok())
+            .map(|file| file.to_string())
+            .collect::<Vec<_>>();
+
+        #[cfg(feature = "integrity")]
+        let files = server
+            .integrity
+            .verify_folder(&folder, Some(manifest), files);
+
+        let files = files
+            .iter()
+            .flat_map(|file| server.request_prototypes(file, priority).files)
+            .collect::<Vec<_>>();
+
+        server.load_groups.add_manifest_files(*id, &files);
     }
 }
 
 fn on_prototypes_asset_loaded(
     mut events_rx: EventReader<AssetEvent<PrototypesAsset>>,
+    mut failed_events_rx: EventReader<AssetLoadFailedEvent<PrototypesAsset>>,
     mut assets: ResMut<Assets<PrototypesAsset>>,
     mut registries: ResMut<PrototypeRegistries>,
     mut loading_prototypes_handles: ResMut<LoadingPrototypesHandles>,
+    loading_pack_manifests: Res<LoadingPackManifests>,
+    mut reports: ResMut<PrototypeLoadReports>,
+    mut reports_tx: EventWriter<PrototypeLoadReport>,
     type_registry: Res<AppTypeRegistry>,
+    prototype_types: Res<AppPrototypeTypeRegistry>,
+    tag_aliases: Res<TagAliases>,
+    allowed_tags: Res<AllowedTags>,
+    (asset_server, lint_config, experiments, telemetry, panic_on_errors, number_precision): (
+        Res<AssetServer>,
+        Res<LintConfig>,
+        Res<PrototypeExperiments>,
+        Option<Res<PrototypeTelemetry>>,
+        Res<PanicOnPrototypeErrors>,
+        Res<PrototypeNumberPrecision>,
+    ),
+    (retention, mut retained, mut deferred, packs): (
+        Res<PrototypesAssetRetention>,
+        ResMut<RetainedPrototypesAssets>,
+        Local<Vec<AssetId<PrototypesAsset>>>,
+        Res<LoadedPacks>,
+    ),
+    #[cfg(feature = "integrity")] mut integrity: integrity::PackIntegrity,
 ) {
-    use bevy::reflect::DynamicStruct;
-
     let type_registry = type_registry.read();
+    let prototype_types = prototype_types.0.read();
 
-    for event in events_rx.read() {
-        let AssetEvent::LoadedWithDependencies { id } = event else {
+    for AssetLoadFailedEvent { id, path, error } in failed_events_rx.read() {
+        let Some((_, requested, _)) = loading_prototypes_handles.remove(id) else {
             continue;
         };
 
-        let Some(prototypes) = assets.remove(*id) else {
+        let report = PrototypeLoadReport {
+            file: Some(path.clone()),
+            failed_files: vec![(path.clone(), error.to_string())],
+            load_duration: requested.elapsed(),
+            ..default()
+        };
+
+        if **panic_on_errors {
+            report.panic_on_errors();
+        }
+
+        reports.add_file(requested, &report);
+        reports_tx.write(report);
+    }
+
+    let mut loaded = core::mem::take(&mut *deferred)
+        .into_iter()
+        .chain(events_rx.read().filter_map(|event| match event {
+            AssetEvent::LoadedWithDependencies { id } => Some(*id),
+            _ => None,
+        }))
+        .map(|id| {
+            let priority = loading_prototypes_handles
+                .get(&id)
+                .map(|(_, _, priority)| *priority)
+                .unwrap_or_default();
+
+            (id, priority)
+        })
+        .collect::<Vec<_>>();
+
+    // Critical files are applied first, the other files wait for the critical ones still loading.
+    loaded.sort_by_key(|(_, priority)| *priority);
+
+    let critical_loading = loading_pack_manifests
+        .values()
+        .any(|(_, priority)| *priority == PrototypeLoadPriority::Critical)
+        || loading_prototypes_handles
+            .iter()
+            .any(|(id, (_, _, priority))| {
+                *priority == PrototypeLoadPriority::Critical
+                    && !loaded.iter().any(|(loaded, _)| loaded == id)
+            });
+
+    for (id, priority) in &loaded {
+        if critical_loading && *priority != PrototypeLoadPriority::Critical {
+            deferred.push(*id);
+            continue;
+        }
+
+        let Some(prototypes) = assets.get(*id) else {
             warn!("Asset {id} not found");
             continue;
         };
 
-        loading_prototypes_handles.remove(id);
+        // The files of a pack wait for its pack file to check the game data version.
+        if packs.is_pending(&prototypes.path) {
+            deferred.push(*id);
+            continue;
+        }
 
-        for (ty, DynamicPrototype { name, tags, proto }) in &*prototypes {
-            let Some(proto_ty) = type_registry.get(*ty) else {
-                error!("Type {:?} not found in registry", ty);
+        let retain = retention.retains(&prototypes.path);
+        let removed;
+        let prototypes = if retain {
+            prototypes
+        } else {
+            let Some(prototypes) = assets.remove(*id) else {
                 continue;
             };
 
-            let proto_data_short_path = proto_ty.type_info().type_path_table().short_path();
-            let proto_short_path = format!("Prototype<{proto_data_short_path}>");
+            removed = prototypes;
+            &removed
+        };
 
-            // Get prototype type and check for errors
-            let Some(proto_ty) = type_registry.get_with_short_type_path(&proto_short_path) else {
-                error!("Failed to find prototype type {proto_short_path}");
-                continue;
-            };
+        let (handle, requested) = loading_prototypes_handles
+            .remove(id)
+            .map(|(handle, requested, _)| (handle, requested))
+            .unzip();
+        let requested = requested.unwrap_or_else(Instant::now);
 
-            let Some(dyn_proto) = proto_ty.data::<ReflectDefault>() else {
-                error!("Failed to find default for prototype type {proto_short_path}");
-                continue;
-            };
+        // Reloads leave the prototypes whose entry didn't change since the retained copy as they
+        // are.
+        let (previous_handle, previous) = retained
+            .remove(id)
+            .map(|retained| (retained.handle, retained.fingerprints))
+            .unzip();
+        let previous = previous.unwrap_or_default();
+        let mut fingerprints = PrototypeIdMap::default();
+        let mut changed = HashSet::new();
 
-            let mut dyn_proto = dyn_proto.default();
+        #[cfg(feature = "integrity")]
+        if !integrity.verify_asset(prototypes) {
+            continue;
+        }
 
-            // Create dynamic structure for the prototype
-            let mut dyn_struct = DynamicStruct::default();
-            dyn_struct.insert("name", name.clone());
-            dyn_struct.insert("tags", tags.clone());
-            dyn_struct.insert_boxed("data", proto.to_dynamic());
+        if packs.is_skipped(&prototypes.path) {
+            debug!(
+                "Skipping prototypes file {}, its pack is incompatible",
+                prototypes.path
+            );
+            continue;
+        }
 
-            if let Err(err) = dyn_proto.try_apply(dyn_struct.as_partial_reflect()) {
-                error!("Error applying dynamic prototype: {err}");
-                continue;
+        let started = Instant::now();
+        let mut report = PrototypeLoadReport {
+            file: Some(prototypes.path.clone()),
+            skipped: prototypes.skipped.clone(),
+            load_duration: started.duration_since(requested),
+            ..default()
+        };
+
+        // Entries of the assigned experiment groups replace the entries of the same name without
+        // experiment, the entries of the other groups aren't applied.
+        let replaced = prototypes
+            .iter()
+            .filter(|(_, prototype)| {
+                prototype
+                    .experiment
+                    .as_ref()
+                    .is_some_and(|experiment| experiments.is_assigned(experiment))
+            })
+            .map(|(ty, prototype)| (*ty, prototype.name.id()))
+            .collect::<HashSet<_>>();
+
+        // Prototypes taking their defaults from prototypes of the same file are applied after
+        // them.
+        let mut pending = prototypes
+            .iter()
+            .filter(|(ty, prototype)| match &prototype.experiment {
+                Some(experiment) => experiments.is_assigned(experiment),
+                None => !replaced.contains(&(*ty, prototype.name.id())),
+            })
+            .collect::<Vec<_>>();
+
+        while !pending.is_empty() {
+            let (ready, waiting): (Vec<_>, Vec<_>) =
+                pending.iter().copied().partition(|(ty, prototype)| {
+                    prototype.defaults_from.is_none_or(|source| {
+                        !pending
+                            .iter()
+                            .any(|(other_ty, other)| other_ty == ty && other.name.id() == source)
+                    })
+                });
+
+            if ready.is_empty() {
+                for (_, prototype) in waiting {
+                    report.skip(
+                        &prototype.name,
+                        Some(&prototype.source),
+                        format!(
+                            "Prototype {} at {} has cyclic defaults_from references",
+                            prototype.name, prototype.source
+                        ),
+                    );
+                }
+                break;
+            }
+
+            for (ty, prototype) in ready {
+                let key = (*ty, prototype.name.id());
+                let unchanged = prototype
+                    .fingerprint
+                    .is_some_and(|fingerprint| previous.get(&key) == Some(&fingerprint))
+                    && prototype
+                        .defaults_from
+                        .is_none_or(|source| !changed.contains(&(*ty, source)));
+
+                if let Some(fingerprint) = prototype.fingerprint {
+                    fingerprints.insert(key, fingerprint);
+                }
+
+                if unchanged {
+                    report.unchanged += 1;
+                    continue;
+                }
+
+                changed.insert(key);
+
+                match apply_dynamic_prototype(
+                    ty,
+                    prototype,
+                    &mut registries,
+                    &type_registry,
+                    &tag_aliases,
+                    &allowed_tags,
+                    **number_precision,
+                    &mut report,
+                ) {
+                    Ok(()) => {
+                        let ty = prototype_types
+                            .iter()
+                            .find_map(|(name, type_id)| (type_id == ty).then_some(&**name))
+                            .unwrap_or_default();
+
+                        *report.loaded.entry(ty.to_string()).or_default() += 1;
+
+                        if let Some(telemetry) = &telemetry {
+                            telemetry.send(PrototypeTelemetryEvent::Loaded {
+                                ty,
+                                name: &prototype.name,
+                                file: &prototypes.path,
+                            });
+                        }
+                    }
+                    Err(err) => {
+                        fingerprints.remove(&key);
+                        report.skip(&prototype.name, Some(&prototype.source), err);
+                    }
+                }
             }
 
-            registries.insert_dyn(ty, name.id(), dyn_proto);
+            pending = waiting;
+        }
+
+        if retain && let Some(handle) = handle.or(previous_handle) {
+            retained.insert(
+                *id,
+                RetainedPrototypesAsset {
+                    handle,
+                    fingerprints,
+                },
+            );
         }
+
+        report.apply_duration = started.elapsed();
+
+        if **panic_on_errors {
+            report.panic_on_errors();
+        }
+
+        reports.add_file(requested, &report);
+        reports_tx.write(report);
+    }
+
+    // Files of folders and manifests are requested once their manifest is loaded.
+    if loading_prototypes_handles.is_empty()
+        && loading_pack_manifests.is_empty()
+        && let Some(report) = reports.complete_batch(|| {
+            if !lint_config.is_enabled() {
+                return Vec::new();
+            }
+
+            lint::lint_registries(
+                &registries,
+                &prototype_types,
+                &type_registry,
+                &allowed_tags,
+                &asset_server,
+                &lint_config,
+            )
+        })
+    {
+        reports_tx.write(report);
     }
 }
 
+fn apply_dynamic_prototype(
+    ty: &TypeId,
+    DynamicPrototype {
+        source,
+        name,
+        tags,
+        defaults_from,
+        metadata,
+        experiment: _,
+        uuid,
+        proto,
+        list_merges,
+        lossy_numbers,
+        fingerprint: _,
+    }: &DynamicPrototype,
+    registries: &mut PrototypeRegistries,
+    type_registry: &bevy::reflect::TypeRegistry,
+    tag_aliases: &TagAliases,
+    allowed_tags: &AllowedTags,
+    number_precision: LintSeverity,
+    report: &mut PrototypeLoadReport,
+) -> Result<(), String> {
+    use bevy::reflect::DynamicStruct;
+
+    match number_precision {
+        LintSeverity::Allow => {}
+        _ if lossy_numbers.is_empty() => {}
+        LintSeverity::Warn => {
+            for number in lossy_numbers {
+                report.warn(format!("Prototype {name} at {source}: {number}"));
+            }
+        }
+        LintSeverity::Deny => {
+            return Err(format!(
+                "Prototype {name} at {source} has numbers losing precision: {}",
+                lossy_numbers.join(", ")
+            ));
+        }
+    }
+
+    let Some(proto_ty) = type_registry.get(*ty) else {
+        return Err(format!("Type {ty:?} not found in registry"));
+    };
+
+    let proto_data_short_path = proto_ty.type_info().type_path_table().short_path();
+    let proto_short_path = format!("Prototype<{proto_data_short_path}>");
+
+    // Get prototype type and check for errors
+    let Some(proto_ty) = type_registry.get_with_short_type_path(&proto_short_path) else {
+        return Err(format!("Failed to find prototype type {proto_short_path}"));
+    };
+
+    let Some(dyn_proto) = proto_ty.data::<ReflectDefault>() else {
+        return Err(format!(
+            "Failed to find default for prototype type {proto_short_path}"
+        ));
+    };
+
+    // Unspecified fields keep the values of the `defaults_from` prototype instead of the
+    // default ones.
+    let mut dyn_proto = match defaults_from {
+        Some(defaults) => match registries
+            .get_dyn(ty, defaults)
+            .map(<dyn Reflect>::reflect_clone)
+        {
+            Some(Ok(defaults)) => defaults,
+            Some(Err(err)) => {
+                return Err(format!(
+                    "Failed to copy the defaults of prototype {name} at {source}: {err}"
+                ));
+            }
+            None => {
+                return Err(format!(
+                    "Prototype {name} at {source} takes its defaults from unknown prototype {defaults}"
+                ));
+            }
+        },
+        None => dyn_proto.default(),
+    };
+
+    let mut tags = tags.clone();
+    tag_aliases.canonicalize(&mut tags, |alias, canonical| {
+        report.warn(format!(
+            "Prototype {name} at {source} uses the tag alias {alias}, use {canonical} instead"
+        ));
+    });
+
+    let disallowed_tags = allowed_tags.disallowed(ty, &tags);
+    if !disallowed_tags.is_empty() {
+        return Err(format!(
+            "Prototype {name} at {source} has the tags {disallowed_tags:?} not allowed for its type, allowed tags are {:?}",
+            allowed_tags[ty]
+        ));
+    }
+
+    // Create dynamic structure for the prototype
+    let mut dyn_struct = DynamicStruct::default();
+    dyn_struct.insert("name", name.clone());
+    dyn_struct.insert("tags", tags);
+    dyn_struct.insert("metadata", PrototypeMetadata(metadata.clone()));
+    dyn_struct.insert("uuid", *uuid);
+    dyn_struct.insert("source", Some(source.clone()));
+
+    dyn_proto
+        .try_apply(dyn_struct.as_partial_reflect())
+        .map_err(|err| format!("Error applying dynamic prototype {name} at {source}: {err}"))?;
+
+    let bevy::reflect::ReflectMut::Struct(dyn_proto_struct) = dyn_proto.reflect_mut() else {
+        return Err(format!("Prototype type {proto_short_path} isn't a struct"));
+    };
+
+    let Some(data) = dyn_proto_struct.field_mut("data") else {
+        return Err(format!(
+            "Prototype type {proto_short_path} has no data field"
+        ));
+    };
+
+    // The data is applied as deserialized, converting it to a dynamic value first would turn
+    // the keys of its maps into dynamic values that can't be hashed.
+    data.try_apply(proto.as_partial_reflect())
+        .map_err(|err| format!("Error applying dynamic prototype {name} at {source}: {err}"))?;
+
+    for list_merge in list_merges {
+        merge::apply_list_merge(data, list_merge).map_err(|err| {
+            format!("Failed to merge the lists of prototype {name} at {source}: {err}")
+        })?;
+    }
+
+    registries.insert_dyn(ty, name.id(), dyn_proto);
+
+    Ok(())
+}
+
 mod private {
     pub trait Sealed {}
 }
@@ -115,37 +788,290 @@ mod private {
 impl private::Sealed for App {}
 
 pub trait PrototypeAppExt: private::Sealed {
+    /// Registers the `D` prototypes.
+    ///
+    /// Registrations made before adding the [`PrototypesPlugin`] are queued and applied once the
+    /// plugins are built. Prototypes can also be registered from [`Plugin::finish`] or
+    /// [`Plugin::cleanup`], e.g. by plugins enabling prototypes behind their features, the
+    /// schemas are generated from the registered prototypes when requested.
     fn register_prototype<D: PrototypeData>(&mut self) -> &mut Self;
+
+    /// Registers the `P` prototypes and applies them again on the entities spawned from them,
+    /// see [`SpawnablePrototype`], each time they are modified or reloaded.
+    fn register_spawnable_prototype<P: SpawnablePrototype>(&mut self) -> &mut Self;
+
+    /// Returns the schema of the registered prototypes, call [`App::finish`] and
+    /// [`App::cleanup`] first to include the prototypes registered while finishing the plugins.
+    #[cfg(feature = "schema")]
     fn get_prototypes_schemas(&self) -> String;
+
+    /// Writes the prototypes schema into `dir` and associates it with the prototype files
+    /// extensions in the `settings.json` of that folder, existing settings are preserved.
+    ///
+    /// Like [`get_prototypes_schemas`](Self::get_prototypes_schemas), only the prototypes
+    /// registered so far are included.
+    ///
+    /// `dir` is expected to be the `.vscode` folder of the workspace, relative to its root.
+    #[cfg(feature = "schema")]
+    fn write_vscode_schema_settings(&self, dir: impl AsRef<Path>) -> std::io::Result<()>;
+
+    /// Returns one schema per registered prototype type and the definitions file they share,
+    /// by file name, e.g. `sword.schema.json` and `prototypes.definitions.schema.json`.
+    ///
+    /// Editors validating the files of a folder holding a single prototype type only load the
+    /// small schema of that type and the definitions, instead of the combined schema of
+    /// [`get_prototypes_schemas`](Self::get_prototypes_schemas).
+    #[cfg(feature = "schema")]
+    fn get_prototypes_split_schemas(&self) -> Vec<(String, String)>;
+
+    /// Writes the split schemas of [`get_prototypes_split_schemas`](Self::get_prototypes_split_schemas)
+    /// into `dir` and associates the schema of each prototype type with the prototype files of
+    /// its folder in the `settings.json` of `dir`, existing settings are preserved.
+    ///
+    /// `directories` lists the folders by prototype type, relative to the root of the
+    /// workspace.
+    ///
+    /// ```no_run
+    /// # use bevy::prelude::*;
+    /// # use bevy_histrion_proto::prelude::*;
+    /// # fn f(app: &mut App) -> std::io::Result<()> {
+    /// app.write_vscode_split_schema_settings(
+    ///     ".vscode",
+    ///     [("sword", "assets/swords"), ("effect", "assets/effects")],
+    /// )?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "schema")]
+    fn write_vscode_split_schema_settings(
+        &self,
+        dir: impl AsRef<Path>,
+        directories: impl IntoIterator<Item = (impl Into<String>, impl Into<String>)>,
+    ) -> std::io::Result<()>;
+
+    /// Makes the prototypes of this app available to the `label` sub-app, e.g. a simulation or
+    /// server sub-app, so it doesn't have to load them again.
+    ///
+    /// Registries are copied into the sub-app during its extraction, only when they changed.
+    fn share_prototypes_with(&mut self, label: impl AppLabel) -> &mut Self;
+
+    /// Replaces the `alias` tag by the `canonical` tag in the loaded prototypes, e.g. while
+    /// renaming a tag, a warning is logged for each prototype still using `alias`.
+    fn add_tag_alias(
+        &mut self,
+        alias: impl Into<String>,
+        canonical: impl Into<String>,
+    ) -> &mut Self;
+
+    /// Loads the prototypes whose `type` is `alias` as `canonical` prototypes, e.g. after
+    /// renaming the `weapon` prototype type to `sword`, so the files written before the rename
+    /// keep loading.
+    ///
+    /// A warning is logged for each prototypes file still using `alias`.
+    ///
+    /// ```no_run
+    /// # use bevy::prelude::*;
+    /// # use bevy_histrion_proto::prelude::*;
+    /// # fn f(app: &mut App) {
+    /// app.register_prototype_type_alias("weapon", "sword");
+    /// # }
+    /// ```
+    fn register_prototype_type_alias(
+        &mut self,
+        alias: impl Into<String>,
+        canonical: impl Into<String>,
+    ) -> &mut Self;
+
+    /// Adds a [`PrototypeCollection`], replacing the collection with the same name if any.
+    fn add_prototype_collection(&mut self, collection: PrototypeCollection) -> &mut Self;
+
+    /// Declares the override files of the `profile` profile, e.g. `graphics.proto.mobile.json`
+    /// for `mobile`, to tune the prototypes per platform or build without code branches.
+    ///
+    /// When `enabled` the entries of the override files are merged into the prototypes of their
+    /// base file (`graphics.proto.json`) as it's loaded, profiles declared later taking
+    /// precedence, otherwise the override files are ignored. Declare the profiles before loading
+    /// any prototypes file.
+    ///
+    /// ```no_run
+    /// # use bevy::prelude::*;
+    /// # use bevy_histrion_proto::prelude::*;
+    /// # fn f(app: &mut App) {
+    /// app.add_prototype_profile("mobile", cfg!(any(target_os = "android", target_os = "ios")))
+    ///     .add_prototype_profile("debug", cfg!(debug_assertions));
+    /// # }
+    /// ```
+    fn add_prototype_profile(&mut self, profile: impl Into<String>, enabled: bool) -> &mut Self;
+
+    /// Declares the `values` of the `variant` variant, e.g. the difficulty tiers, whose layer
+    /// files, e.g. `stats.proto.hard.json` for `hard`, hold the fields that differ from the base
+    /// prototypes.
+    ///
+    /// The layers of the value selected in [`PrototypeVariants`] patch the loaded prototypes and
+    /// are swapped at runtime when the selection changes, declare the variants before loading
+    /// any layer file.
+    ///
+    /// ```no_run
+    /// # use bevy::prelude::*;
+    /// # use bevy_histrion_proto::prelude::*;
+    /// # fn f(app: &mut App) {
+    /// app.add_prototype_variant("difficulty", ["easy", "hard"]);
+    /// # }
+    /// ```
+    fn add_prototype_variant(
+        &mut self,
+        variant: impl Into<String>,
+        values: impl IntoIterator<Item = impl Into<String>>,
+    ) -> &mut Self;
+
+    /// Declares the override files of the `locale` locale, e.g. `items.proto.fr.json` for `fr`,
+    /// patching the string fields of the prototypes while it's the [`CurrentLocale`]. Declare the
+    /// locales before loading any override file.
+    ///
+    /// ```no_run
+    /// # use bevy::prelude::*;
+    /// # use bevy_histrion_proto::prelude::*;
+    /// # fn f(app: &mut App) {
+    /// app.add_prototype_locale("fr").add_prototype_locale("de");
+    /// # }
+    /// ```
+    fn add_prototype_locale(&mut self, locale: impl Into<String>) -> &mut Self;
+
+    /// Makes only the `P` prototypes of this app available to the `label` sub-app, e.g. the
+    /// render app to read the prototypes of custom materials during extraction.
+    ///
+    /// Like [`share_prototypes_with`](Self::share_prototypes_with), the registry is copied
+    /// during the extraction of the sub-app when the registries changed.
+    fn share_prototype_registry_with<P: PrototypeData>(
+        &mut self,
+        label: impl AppLabel,
+    ) -> &mut Self;
+
+    /// Adds a [`PrototypeTelemetrySink`] receiving the [`PrototypeTelemetryEvent`]s, e.g. the
+    /// prototypes loaded, the lookups of missing prototypes and the overrides applied.
+    ///
+    /// ```no_run
+    /// # use bevy::prelude::*;
+    /// # use bevy_histrion_proto::prelude::*;
+    /// # use bevy_histrion_proto::PrototypeTelemetryEvent;
+    /// # fn f(app: &mut App) {
+    /// app.add_prototype_telemetry_sink(|event: &PrototypeTelemetryEvent| {
+    ///     if let PrototypeTelemetryEvent::LookupMissed { ty, id } = event {
+    ///         warn!("the {id} {ty} prototype was looked up before being loaded");
+    ///     }
+    /// });
+    /// # }
+    /// ```
+    fn add_prototype_telemetry_sink(&mut self, sink: impl PrototypeTelemetrySink) -> &mut Self;
+
+    /// Adds the `I` [`PrototypeIndex`] of the `P` prototypes, initialized with its default
+    /// value and kept in sync with the registry each frame.
+    ///
+    /// ```no_run
+    /// # use bevy::prelude::*;
+    /// # use bevy_histrion_proto::prelude::*;
+    /// # use bevy_histrion_proto::PrototypeIndex;
+    /// # #[derive(Debug, Clone, Default, Reflect, JsonSchema, Prototype)]
+    /// # #[proto(name = "spell")]
+    /// # struct Spell { mana: u32 }
+    /// # #[derive(Resource, Default)]
+    /// # struct SpellsByElement;
+    /// # impl PrototypeIndex<Spell> for SpellsByElement {
+    /// #     fn on_insert(&mut self, _: &Prototype<Spell>) {}
+    /// #     fn on_remove(&mut self, _: &PrototypeId<Spell>) {}
+    /// #     fn on_modify(&mut self, _: &Prototype<Spell>) {}
+    /// # }
+    /// # fn f(app: &mut App) {
+    /// app.register_prototype::<Spell>()
+    ///     .add_prototype_index::<Spell, SpellsByElement>();
+    /// # }
+    /// ```
+    fn add_prototype_index<P: PrototypeData, I: PrototypeIndex<P> + Default>(
+        &mut self,
+    ) -> &mut Self;
+
+    /// Sets the [`PrototypeGameDataVersion`] the `game_data_version` requirement of the
+    /// [`PackInfo`]s is checked against, e.g. `2.1.0`, packs are only checked once it's set.
+    ///
+    /// ```no_run
+    /// # use bevy::prelude::*;
+    /// # use bevy_histrion_proto::prelude::*;
+    /// # fn f(app: &mut App) {
+    /// app.set_game_data_version(env!("CARGO_PKG_VERSION"));
+    /// # }
+    /// ```
+    fn set_game_data_version(&mut self, version: impl Into<String>) -> &mut Self;
+
+    /// Loads the prototypes files, folders and pack manifests of `paths`, like
+    /// [`PrototypeServer::load_group`], and updates the app until every requested prototypes file
+    /// is applied, e.g. for CLI tools and dedicated servers reading their data before `run`.
+    ///
+    /// The plugins are finished first, call it once they are all added. The app systems run with
+    /// each update. Blocking isn't possible on the web, load the prototypes from a system there.
+    ///
+    /// Returns an error as soon as a requested file, or an asset it depends on, fails to load,
+    /// or once `timeout` is elapsed while files are still loading.
+    ///
+    /// ```no_run
+    /// # use std::time::Duration;
+    /// # use bevy::prelude::*;
+    /// # use bevy_histrion_proto::{BlockingLoadError, PrototypesPlugin, prelude::*};
+    /// # #[derive(Debug, Clone, Default, Reflect, JsonSchema, Prototype)]
+    /// # #[proto(name = "item")]
+    /// # struct Item { price: u32 }
+    /// # fn main() -> Result<(), BlockingLoadError> {
+    /// App::new()
+    ///     .add_plugins((MinimalPlugins, AssetPlugin::default(), PrototypesPlugin))
+    ///     .register_prototype::<Item>()
+    ///     .load_prototypes_blocking(["prototypes"], Duration::from_secs(30))?
+    ///     .run();
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn load_prototypes_blocking(
+        &mut self,
+        paths: impl IntoIterator<Item = impl AsRef<str>>,
+        timeout: Duration,
+    ) -> Result<&mut Self, BlockingLoadError>;
 }
 
-#[derive(Default, Resource)]
-pub(crate) struct PrototypesSchemas {
-    prototypes: HashMap<String, String>,
-    refs: JsonMap<String, JsonValue>,
+/// Errors raised by [`PrototypeAppExt::load_prototypes_blocking`].
+#[derive(Debug, Error)]
+pub enum BlockingLoadError {
+    #[error("failed to request the prototypes to load: {0}")]
+    Request(String),
+    #[error("failed to load {path}: {reason}")]
+    Failed { path: String, reason: String },
+    #[error("the prototypes didn't load within {0:?}")]
+    Timeout(Duration),
 }
 
 impl PrototypeAppExt for App {
     fn register_prototype<D: PrototypeData>(&mut self) -> &mut Self {
-        self.register_type::<Prototype<D>>();
-
-        if let Some(mut registries) = self.world_mut().get_resource_mut::<PrototypeRegistries>() {
-            registries.new_registry::<D>();
-        } else {
-            error!("PrototypeRegistries resource not found");
+        if !self.world().contains_resource::<PrototypeRegistries>() {
+            self.world_mut()
+                .get_resource_or_init::<PendingPrototypeRegistrations>()
+                .0
+                .push(|app| {
+                    app.register_prototype::<D>();
+                });
             return self;
         }
 
+        self.register_type::<Prototype<D>>();
+
+        self.world_mut()
+            .resource_mut::<PrototypeRegistries>()
+            .new_registry::<D>();
+
+        #[cfg(feature = "schema")]
         if let Some(mut schemas) = self.world_mut().get_resource_mut::<PrototypesSchemas>() {
             schemas.prototypes.insert(
                 D::prototype_name().into(),
                 <Prototype<D> as JsonSchema>::schema_ref(),
             );
 
-            let schema = <Prototype<D> as JsonSchema>::json_schema(&mut schemas.refs);
-            schemas
-                .refs
-                .insert(<Prototype<D> as JsonSchema>::schema_title(), schema);
+            <Prototype<D> as JsonSchema>::register_schema(&mut schemas.refs);
         } else {
             error!("PrototypesSchemas resource not found");
             return self;
@@ -161,119 +1087,867 @@ impl PrototypeAppExt for App {
             return self;
         }
 
+        if let Some(tags) = D::allowed_tags()
+            && let Some(mut allowed_tags) = self.world_mut().get_resource_mut::<AllowedTags>()
+        {
+            allowed_tags.insert(core::any::TypeId::of::<D>(), tags);
+        }
+
+        self.add_event::<RegistryEvent<D>>()
+            .add_event::<index::PrototypeChange<D>>()
+            .add_systems(
+                Update,
+                registry::send_reloaded_events::<D>
+                    .after(on_prototypes_asset_loaded)
+                    .after(locale::apply_locale_overrides)
+                    .after(variant::apply_variant_layers),
+            );
+
+        self
+    }
+
+    fn register_spawnable_prototype<P: SpawnablePrototype>(&mut self) -> &mut Self {
+        self.register_prototype::<P>()
+            .init_resource::<spawn::PooledEntities<P>>()
+            .add_systems(
+                Update,
+                spawn::refresh_spawned_prototypes::<P>.after(registry::send_reloaded_events::<P>),
+            )
+    }
+
+    fn add_tag_alias(
+        &mut self,
+        alias: impl Into<String>,
+        canonical: impl Into<String>,
+    ) -> &mut Self {
+        if let Some(mut tag_aliases) = self.world_mut().get_resource_mut::<TagAliases>() {
+            tag_aliases.insert(alias.into(), canonical.into());
+        } else {
+            error!("TagAliases resource not found");
+        }
+
+        self
+    }
+
+    fn register_prototype_type_alias(
+        &mut self,
+        alias: impl Into<String>,
+        canonical: impl Into<String>,
+    ) -> &mut Self {
+        if let Some(prototypes) = self.world().get_resource::<AppPrototypeTypeRegistry>() {
+            prototypes.0.insert_alias(
+                alias.into().into_boxed_str(),
+                canonical.into().into_boxed_str(),
+            );
+        } else {
+            error!("AppPrototypeTypeRegistry resource not found");
+        }
+
+        self
+    }
+
+    fn add_prototype_collection(&mut self, collection: PrototypeCollection) -> &mut Self {
+        if let Some(mut collections) = self.world_mut().get_resource_mut::<PrototypeCollections>() {
+            collections.insert(collection.name().to_string(), collection);
+        } else {
+            error!("PrototypeCollections resource not found");
+        }
+
+        self
+    }
+
+    fn add_prototype_profile(&mut self, profile: impl Into<String>, enabled: bool) -> &mut Self {
+        if let Some(profiles) = self.world().get_resource::<profile::AppPrototypeProfiles>() {
+            profiles.insert(profile.into(), enabled);
+        } else {
+            error!("AppPrototypeProfiles resource not found");
+        }
+
+        self
+    }
+
+    fn add_prototype_variant(
+        &mut self,
+        variant: impl Into<String>,
+        values: impl IntoIterator<Item = impl Into<String>>,
+    ) -> &mut Self {
+        if let Some(mut layers) = self
+            .world_mut()
+            .get_resource_mut::<variant::VariantLayers>()
+        {
+            layers.declare(variant.into(), values.into_iter().map(Into::into).collect());
+        } else {
+            error!("VariantLayers resource not found");
+        }
+
+        self
+    }
+
+    fn add_prototype_locale(&mut self, locale: impl Into<String>) -> &mut Self {
+        if let Some(mut overrides) = self
+            .world_mut()
+            .get_resource_mut::<locale::LocaleOverrides>()
+        {
+            overrides.declare(locale.into());
+        } else {
+            error!("LocaleOverrides resource not found");
+        }
+
+        self
+    }
+
+    fn share_prototypes_with(&mut self, label: impl AppLabel) -> &mut Self {
+        add_extract(self, label, extract_prototype_registries)
+    }
+
+    fn share_prototype_registry_with<P: PrototypeData>(
+        &mut self,
+        label: impl AppLabel,
+    ) -> &mut Self {
+        add_extract(self, label, extract_prototype_registry::<P>)
+    }
+
+    fn add_prototype_telemetry_sink(&mut self, sink: impl PrototypeTelemetrySink) -> &mut Self {
+        self.world_mut()
+            .get_resource_or_init::<PrototypeTelemetry>()
+            .add(sink);
+
         self
     }
 
+    fn add_prototype_index<P: PrototypeData, I: PrototypeIndex<P> + Default>(
+        &mut self,
+    ) -> &mut Self {
+        self.init_resource::<I>().add_systems(
+            Update,
+            index::update_prototype_index::<P, I>.after(registry::send_reloaded_events::<P>),
+        )
+    }
+
+    fn set_game_data_version(&mut self, version: impl Into<String>) -> &mut Self {
+        self.insert_resource(PrototypeGameDataVersion(version.into()))
+    }
+
+    fn load_prototypes_blocking(
+        &mut self,
+        paths: impl IntoIterator<Item = impl AsRef<str>>,
+        timeout: Duration,
+    ) -> Result<&mut Self, BlockingLoadError> {
+        use bevy::app::PluginsState;
+
+        // Like the runners, the asset server only starts once the plugins are finished.
+        if self.plugins_state() != PluginsState::Cleaned {
+            while self.plugins_state() == PluginsState::Adding {
+                #[cfg(not(target_arch = "wasm32"))]
+                bevy::tasks::tick_global_task_pools_on_main_thread();
+            }
+
+            self.finish();
+            self.cleanup();
+        }
+
+        let paths = paths
+            .into_iter()
+            .map(|path| path.as_ref().to_string())
+            .collect::<Vec<_>>();
+
+        let mut reports = self
+            .world()
+            .resource::<Events<PrototypeLoadReport>>()
+            .get_cursor_current();
+
+        self.world_mut()
+            .run_system_cached_with(request_blocking_paths, paths)
+            .map_err(|err| BlockingLoadError::Request(err.to_string()))?;
+
+        let started = Instant::now();
+        // The handles are kept until the end, the failed ones are dropped from the loading
+        // handles once their failure is reported.
+        let mut waited = HashMap::<UntypedAssetId, UntypedHandle>::default();
+
+        loop {
+            let world = self.world();
+            let prototypes = world.resource::<LoadingPrototypesHandles>();
+            let manifests = world.resource::<LoadingPackManifests>();
+
+            let failed = reports
+                .read(world.resource::<Events<PrototypeLoadReport>>())
+                .find_map(|report| report.failed_files.first());
+
+            if let Some((path, reason)) = failed {
+                return Err(BlockingLoadError::Failed {
+                    path: path.to_string(),
+                    reason: reason.clone(),
+                });
+            }
+
+            if prototypes.is_empty() && manifests.is_empty() {
+                return Ok(self);
+            }
+
+            waited.extend(
+                prototypes
+                    .values()
+                    .map(|(handle, ..)| handle.clone().untyped())
+                    .chain(
+                        manifests
+                            .values()
+                            .map(|(handle, _)| handle.clone().untyped()),
+                    )
+                    .map(|handle| (handle.id(), handle)),
+            );
+
+            let asset_server = world.resource::<AssetServer>();
+
+            // A file whose dependencies failed is never applied, e.g. with a missing icon.
+            for id in waited.keys() {
+                let Some(
+                    (LoadState::Failed(error), ..)
+                    | (_, _, RecursiveDependencyLoadState::Failed(error)),
+                ) = asset_server.get_load_states(*id)
+                else {
+                    continue;
+                };
+
+                return Err(BlockingLoadError::Failed {
+                    path: asset_server
+                        .get_path(*id)
+                        .map(|path| path.to_string())
+                        .unwrap_or_default(),
+                    reason: error.to_string(),
+                });
+            }
+
+            if started.elapsed() > timeout {
+                return Err(BlockingLoadError::Timeout(timeout));
+            }
+
+            self.update();
+        }
+    }
+
+    #[cfg(feature = "schema")]
     fn get_prototypes_schemas(&self) -> String {
-        let PrototypesSchemas { prototypes, refs } = self.world().resource::<PrototypesSchemas>();
-        let mut refs = refs.clone();
-
-        refs.insert(
-            "PrototypeAny".to_string(),
-            json!({
-                "type": "object",
-                "required": ["type", "name"],
-                "oneOf": prototypes.keys().map(|key| json!({
-                    "type": "object",
-                    "allOf": [
-                        {
-                            "type": "object",
-                            "properties": {
-                                "type": {
-                                    "enum": [key],
-                                },
-                            },
-                        },
-                        {
-                            "$ref": prototypes.get(key).unwrap()
-                        }
-                    ]
-                })).collect::<Vec<_>>(),
-            }),
-        );
+        self.world()
+            .resource::<PrototypesSchemas>()
+            .to_json_string(&self.world().resource::<AppTypeRegistry>().read())
+    }
 
-        serde_json::to_string_pretty(&json!({
-            "$schema": "http://json-schema.org/draft-07/schema#",
-            "title": "Prototype",
-            "type": ["object", "array"],
-            "oneOf": [
-                {
-                    "$ref": "#/definitions/PrototypeAny"
-                },
-                {
-                    "type": "array",
-                    "items": {
-                        "$ref": "#/definitions/PrototypeAny"
-                    },
-                }
-            ],
-            "definitions": refs,
-        }))
-        .unwrap()
+    #[cfg(feature = "schema")]
+    fn write_vscode_schema_settings(&self, dir: impl AsRef<Path>) -> std::io::Result<()> {
+        let dir = dir.as_ref();
+        let schema_path = dir.join("prototypes.schema.json");
+
+        std::fs::create_dir_all(dir)?;
+        std::fs::write(&schema_path, self.get_prototypes_schemas())?;
+
+        // Newline-delimited files aren't a single JSON document.
+        let file_match = PROTOTYPE_ASSET_EXTENSIONS
+            .iter()
+            .filter(|extension| **extension != PROTOTYPE_NDJSON_EXTENSION)
+            .map(|extension| format!("*.{extension}"))
+            .collect::<Vec<_>>();
+
+        write_vscode_settings(dir, vec![(schema_url(&schema_path), file_match)])
     }
+
+    #[cfg(feature = "schema")]
+    fn get_prototypes_split_schemas(&self) -> Vec<(String, String)> {
+        self.world()
+            .resource::<PrototypesSchemas>()
+            .to_split_json_strings(&self.world().resource::<AppTypeRegistry>().read())
+    }
+
+    #[cfg(feature = "schema")]
+    fn write_vscode_split_schema_settings(
+        &self,
+        dir: impl AsRef<Path>,
+        directories: impl IntoIterator<Item = (impl Into<String>, impl Into<String>)>,
+    ) -> std::io::Result<()> {
+        let dir = dir.as_ref();
+
+        std::fs::create_dir_all(dir)?;
+
+        for (file, schema) in self.get_prototypes_split_schemas() {
+            std::fs::write(dir.join(file), schema)?;
+        }
+
+        let schemas = directories
+            .into_iter()
+            .map(|(ty, directory)| {
+                let (ty, directory) = (ty.into(), directory.into());
+                let directory = directory.trim_end_matches('/');
+
+                let file_match = PROTOTYPE_ASSET_EXTENSIONS
+                    .iter()
+                    .filter(|extension| **extension != PROTOTYPE_NDJSON_EXTENSION)
+                    .map(|extension| format!("{directory}/**/*.{extension}"))
+                    .collect();
+
+                (
+                    schema_url(&dir.join(schema::split_schema_file_name(&ty))),
+                    file_match,
+                )
+            })
+            .collect();
+
+        write_vscode_settings(dir, schemas)
+    }
+}
+
+fn request_blocking_paths(In(paths): In<Vec<String>>, mut server: PrototypeServer) {
+    server.request_paths(paths);
 }
 
+/// Returns the url of a schema written in the `.vscode` folder, relative to the workspace.
+#[cfg(feature = "schema")]
+fn schema_url(schema_path: &Path) -> String {
+    format!(
+        "./{}",
+        schema_path
+            .to_string_lossy()
+            .replace('\\', "/")
+            .trim_start_matches("./")
+    )
+}
+
+/// Associates the prototype files extensions with the `schemas` urls and file matches in the
+/// `settings.json` of `dir`, existing settings are preserved.
+#[cfg(feature = "schema")]
+fn write_vscode_settings(dir: &Path, schemas: Vec<(String, Vec<String>)>) -> std::io::Result<()> {
+    use std::io::{Error, ErrorKind};
+
+    let settings_path = dir.join("settings.json");
+
+    let mut settings = match std::fs::read(&settings_path) {
+        Ok(bytes) => serde_json::from_slice::<JsonMap<String, JsonValue>>(&bytes)
+            .map_err(|err| Error::new(ErrorKind::InvalidData, err))?,
+        Err(err) if err.kind() == ErrorKind::NotFound => JsonMap::new(),
+        Err(err) => return Err(err),
+    };
+
+    // `.proto` isn't known to be JSON by the editor.
+    if let Some(associations) = settings
+        .entry("files.associations")
+        .or_insert_with(|| json!({}))
+        .as_object_mut()
+    {
+        for extension in PROTOTYPE_ASSET_EXTENSIONS {
+            if !extension.ends_with("json") {
+                associations.insert(format!("*.{extension}"), json!("json"));
+            }
+        }
+    }
+
+    if let Some(entries) = settings
+        .entry("json.schemas")
+        .or_insert_with(|| json!([]))
+        .as_array_mut()
+    {
+        for (url, file_match) in schemas {
+            entries.retain(|schema| schema.get("url").and_then(JsonValue::as_str) != Some(&url));
+            entries.push(json!({
+                "fileMatch": file_match,
+                "url": url,
+            }));
+        }
+    }
+
+    let mut bytes = to_json_pretty(&settings)?;
+    bytes.push(b'\n');
+
+    std::fs::write(settings_path, bytes)
+}
+
+/// Prototypes registered before the [`PrototypesPlugin`] was added, registered in
+/// [`Plugin::finish`].
+#[derive(Default, Resource)]
+struct PendingPrototypeRegistrations(Vec<fn(&mut App)>);
+
 #[derive(Default, Resource, Deref, DerefMut)]
 pub(crate) struct LoadingPrototypesHandles(
-    HashMap<AssetId<PrototypesAsset>, Handle<PrototypesAsset>>,
+    HashMap<AssetId<PrototypesAsset>, (Handle<PrototypesAsset>, Instant, PrototypeLoadPriority)>,
 );
 
+#[derive(Default, Resource, Deref, DerefMut)]
+pub(crate) struct LoadingPackManifests(
+    HashMap<AssetId<PackManifest>, (Handle<PackManifest>, PrototypeLoadPriority)>,
+);
+
+/// Priority of the prototypes files requested with [`PrototypeServer`].
+///
+/// Critical files, e.g. the prototypes the main menu needs, are applied before any other file,
+/// which waits for the critical files still loading. Use [`critical_prototypes_loaded`] to start
+/// as soon as they are applied while the bulk of the content streams in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PrototypeLoadPriority {
+    Critical,
+    #[default]
+    Normal,
+}
+
+/// Run condition returning `true` once the critical prototypes files requested so far are
+/// applied, see [`PrototypeLoadPriority`].
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_histrion_proto::prelude::*;
+/// # fn show_main_menu() {}
+/// # fn f(app: &mut App) {
+/// app.add_systems(Update, show_main_menu.run_if(critical_prototypes_loaded()));
+/// # }
+/// ```
+pub fn critical_prototypes_loaded() -> impl Condition<()> {
+    IntoSystem::into_system(
+        |loading_prototypes_handles: Res<LoadingPrototypesHandles>,
+         loading_pack_manifests: Res<LoadingPackManifests>| {
+            !loading_pack_manifests
+                .values()
+                .any(|(_, priority)| *priority == PrototypeLoadPriority::Critical)
+                && !loading_prototypes_handles
+                    .values()
+                    .any(|(_, _, priority)| *priority == PrototypeLoadPriority::Critical)
+        },
+    )
+}
+
+/// Run condition returning `true` once every prototypes file requested so far is applied.
+pub fn all_prototypes_loaded() -> impl Condition<()> {
+    IntoSystem::into_system(
+        |loading_prototypes_handles: Res<LoadingPrototypesHandles>,
+         loading_pack_manifests: Res<LoadingPackManifests>| {
+            loading_prototypes_handles.is_empty() && loading_pack_manifests.is_empty()
+        },
+    )
+}
+
+/// Whether the [`PrototypesAsset`]s are kept in `Assets<PrototypesAsset>` once applied.
+///
+/// Retained files are hot-reloaded by the asset server and can be inspected by other systems,
+/// only the prototypes whose entry changed are applied again. Defaults to `Retain` in debug builds
+/// and to `Drop` in release builds to free the parsed data.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrototypesAssetRetention {
+    Retain,
+    Drop,
+}
+
+impl Default for PrototypesAssetRetention {
+    fn default() -> Self {
+        if cfg!(debug_assertions) {
+            Self::Retain
+        } else {
+            Self::Drop
+        }
+    }
+}
+
+impl PrototypesAssetRetention {
+    fn retains(
+        self,
+        #[cfg_attr(not(feature = "sync"), allow(unused_variables))] path: &AssetPath,
+    ) -> bool {
+        // Synchronized prototypes are applied once, their files are dropped.
+        #[cfg(feature = "sync")]
+        if path.source() == &bevy::asset::io::AssetSourceId::from(SYNC_SOURCE) {
+            return false;
+        }
+
+        self == Self::Retain
+    }
+}
+
+/// A retained prototypes file with the fingerprints of the prototypes applied from it.
+pub(crate) struct RetainedPrototypesAsset {
+    handle: Handle<PrototypesAsset>,
+    fingerprints: PrototypeIdMap<(TypeId, ErasedPrototypeId), u64>,
+}
+
+#[derive(Default, Resource, Deref, DerefMut)]
+pub(crate) struct RetainedPrototypesAssets(
+    HashMap<AssetId<PrototypesAsset>, RetainedPrototypesAsset>,
+);
+
+/// The prototypes files requested with [`PrototypeServer::load_prototypes`], reloaded by
+/// [`PrototypeServer::reload_all`].
+#[derive(Default, Resource, Deref, DerefMut)]
+pub(crate) struct LoadedPrototypesFiles(HashSet<AssetPath<'static>>);
+
+/// Runs `extract` after the current extract function of the `label` sub-app.
+fn add_extract(
+    app: &mut App,
+    label: impl AppLabel,
+    extract: fn(&mut World, &mut World),
+) -> &mut App {
+    let Some(sub_app) = app.get_sub_app_mut(label) else {
+        error!("Sub-app not found, add it before sharing the prototypes with it");
+        return app;
+    };
+
+    let previous = sub_app.take_extract();
+
+    sub_app.set_extract(move |main_world, sub_world| {
+        if let Some(previous) = &previous {
+            previous(main_world, sub_world);
+        }
+
+        extract(main_world, sub_world);
+    });
+
+    app
+}
+
 #[derive(SystemParam)]
 pub struct PrototypeServer<'w> {
     asset_server: Res<'w, AssetServer>,
     loading_prototypes_handles: ResMut<'w, LoadingPrototypesHandles>,
+    loading_pack_manifests: ResMut<'w, LoadingPackManifests>,
+    loading_collections: ResMut<'w, LoadingCollections>,
+    loaded_files: ResMut<'w, LoadedPrototypesFiles>,
+    load_groups: ResMut<'w, PrototypeLoadGroups>,
+    loaded_packs: ResMut<'w, LoadedPacks>,
+    indexing_files: ResMut<'w, lazy::IndexingPrototypesFiles>,
+    locale_overrides: ResMut<'w, locale::LocaleOverrides>,
+    profiles: Res<'w, profile::AppPrototypeProfiles>,
+    variant_layers: ResMut<'w, variant::VariantLayers>,
+    #[cfg(feature = "integrity")]
+    integrity: integrity::PackIntegrity<'w>,
 }
 
 impl PrototypeServer<'_> {
     /// Loads a prototypes file from the given path.
+    ///
+    /// The override files of the declared locales, e.g. `items.proto.fr.json`, patch the string
+    /// fields of the prototypes of the [`CurrentLocale`] instead, and the layer files of the
+    /// declared variants, e.g. `stats.proto.hard.json`, the prototypes of the selected
+    /// [`PrototypeVariants`]. The files named like override files whose suffix isn't a declared
+    /// profile, variant or locale are loaded as prototypes files, with a warning.
     pub fn load_prototypes(&mut self, path: &str) {
+        self.load_prototypes_with_priority(path, PrototypeLoadPriority::Normal);
+    }
+
+    /// Like [`PrototypeServer::load_prototypes`], with the given priority.
+    pub fn load_prototypes_with_priority(&mut self, path: &str, priority: PrototypeLoadPriority) {
+        self.request_prototypes(path, priority);
+    }
+
+    pub(crate) fn request_prototypes(
+        &mut self,
+        path: &str,
+        priority: PrototypeLoadPriority,
+    ) -> LoadRequests {
+        // Merged into their base file by its loader.
+        if self.profiles.is_profile_file(std::path::Path::new(path)) {
+            debug!("Skipping profile override file {path}, it's loaded with its base file");
+            return LoadRequests::default();
+        }
+
+        if pack_info::is_pack_info_file(std::path::Path::new(path)) {
+            let handle = self.asset_server.load(path);
+            self.loaded_packs.add(handle);
+            return LoadRequests::default();
+        }
+
+        self.loaded_files.insert(AssetPath::from(path).into_owned());
+
+        if self
+            .variant_layers
+            .is_layer_file(std::path::Path::new(path))
+        {
+            let handle = self.asset_server.load(path);
+            self.variant_layers.add(handle);
+            return LoadRequests::default();
+        }
+
+        if self
+            .locale_overrides
+            .is_locale_file(std::path::Path::new(path))
+        {
+            let handle = self.asset_server.load(path);
+            self.locale_overrides.add(handle);
+            return LoadRequests::default();
+        }
+
+        if let Some(suffix) = profile::override_suffix(std::path::Path::new(path)) {
+            warn!(
+                "{path} is named like an override file but {suffix} isn't a declared profile, variant or locale, it's loaded as a prototypes file"
+            );
+        }
+
+        let handle: Handle<PrototypesAsset> = self.asset_server.load(path);
+        let id = handle.id();
+        self.loading_prototypes_handles
+            .insert(id, (handle, Instant::now(), priority));
+
+        LoadRequests {
+            files: vec![id],
+            manifests: Vec::new(),
+        }
+    }
+
+    /// Reads the prototypes file at `path` again and applies it into the registries, like a
+    /// hot-reload but without relying on a file watcher, e.g. on consoles or for network sources.
+    pub fn reload(&mut self, path: &str) {
+        if self.profiles.is_profile_file(std::path::Path::new(path)) {
+            debug!("Skipping profile override file {path}, reload its base file instead");
+            return;
+        }
+
+        if self
+            .variant_layers
+            .is_layer_file(std::path::Path::new(path))
+            || self
+                .locale_overrides
+                .is_locale_file(std::path::Path::new(path))
+        {
+            self.asset_server.reload(path);
+            return;
+        }
+
         let handle: Handle<PrototypesAsset> = self.asset_server.load(path);
-        self.loading_prototypes_handles.insert(handle.id(), handle);
+
+        // The asset server only requests a load if the file isn't loaded already.
+        if !matches!(
+            self.asset_server.get_load_state(&handle),
+            Some(LoadState::Loading)
+        ) {
+            self.asset_server.reload(path);
+        }
+
+        self.loaded_files.insert(AssetPath::from(path).into_owned());
+        self.loading_prototypes_handles.insert(
+            handle.id(),
+            (handle, Instant::now(), PrototypeLoadPriority::Normal),
+        );
+    }
+
+    /// Reloads every prototypes file loaded so far, see [`PrototypeServer::reload`].
+    pub fn reload_all(&mut self) {
+        let mut files = self
+            .loaded_files
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        files.sort();
+
+        for file in files {
+            self.reload(&file);
+        }
+    }
+
+    /// Loads the [`PrototypeCollection`]s of a `.collection.json` file, they replace the loaded
+    /// collections with the same names.
+    pub fn load_collections(&mut self, path: &str) {
+        let handle: Handle<PrototypeCollectionsAsset> = self.asset_server.load(path);
+        self.loading_collections.insert(handle.id(), handle);
+    }
+
+    /// Loads the prototypes files listed by a [`PackManifest`], relative to its folder.
+    ///
+    /// The files are loaded once the manifest itself is loaded, this works with any asset source.
+    pub fn load_prototypes_manifest(&mut self, path: &str) {
+        self.load_prototypes_manifest_with_priority(path, PrototypeLoadPriority::Normal);
+    }
+
+    /// Like [`PrototypeServer::load_prototypes_manifest`], with the given priority for its files.
+    pub fn load_prototypes_manifest_with_priority(
+        &mut self,
+        path: &str,
+        priority: PrototypeLoadPriority,
+    ) {
+        self.request_manifest(path, priority);
+    }
+
+    pub(crate) fn request_manifest(
+        &mut self,
+        path: &str,
+        priority: PrototypeLoadPriority,
+    ) -> LoadRequests {
+        let handle: Handle<PackManifest> = self.asset_server.load(path);
+        let id = handle.id();
+        self.loading_pack_manifests.insert(id, (handle, priority));
+
+        LoadRequests {
+            files: Vec::new(),
+            manifests: vec![id],
+        }
     }
 
     /// Loads all prototypes files from the given folder.
+    ///
+    /// When the asset source can't list the folder (e.g. on the web or for remote sources), the
+    /// files are loaded from the [`PACK_MANIFEST_FILE`] of the folder instead, see
+    /// [`PrototypeServer::load_prototypes_manifest`].
     pub fn load_prototypes_folder(&mut self, path: &str) {
+        self.load_prototypes_folder_with_priority(path, PrototypeLoadPriority::Normal);
+    }
+
+    /// Like [`PrototypeServer::load_prototypes_folder`], with the given priority for its files.
+    pub fn load_prototypes_folder_with_priority(
+        &mut self,
+        path: &str,
+        priority: PrototypeLoadPriority,
+    ) {
+        self.request_folder(path, priority);
+    }
+
+    pub(crate) fn request_folder(
+        &mut self,
+        path: &str,
+        priority: PrototypeLoadPriority,
+    ) -> LoadRequests {
+        let folder: AssetPath<'_> = path.into();
+
+        let Some(files) = self.list_prototypes_folder(&folder) else {
+            return self.request_folder_manifest(&folder, priority);
+        };
+
+        let mut requests = LoadRequests::default();
+
+        for file in files {
+            requests.extend(self.request_prototypes(&file, priority));
+        }
+
+        requests
+    }
+
+    /// Lists the prototypes files of a folder to load, `None` when the folder can't be listed and
+    /// its [`PACK_MANIFEST_FILE`] should be used instead.
+    pub(crate) fn list_prototypes_folder(&mut self, folder: &AssetPath) -> Option<Vec<String>> {
+        // The web reader "succeeds" with an empty listing, don't even try.
+        if cfg!(target_arch = "wasm32") {
+            return None;
+        }
+
+        let files = match self.read_prototypes_folder(folder) {
+            // Android assets packed in the APK often can't be enumerated and come back empty.
+            Ok(files) if files.is_empty() && cfg!(target_os = "android") => return None,
+            Ok(files) => files,
+            Err(err) => {
+                debug!("Failed to list prototypes folder {folder}, using its manifest: {err}");
+                return None;
+            }
+        };
+
+        #[cfg(feature = "integrity")]
         let files = {
-            let path: AssetPath<'_> = path.into();
-            let source = self.asset_server.get_source(path.source()).unwrap();
-            let source = source.reader();
+            let manifest = self.read_pack_manifest(folder);
+            self.integrity.verify_folder(folder, manifest, files)
+        };
 
-            bevy::tasks::block_on(async move {
-                use bevy::tasks::futures_lite::StreamExt;
+        Some(files)
+    }
 
-                let mut folder = source.read_directory(path.path()).await.unwrap();
-                let mut files = Vec::new();
+    fn request_folder_manifest(
+        &mut self,
+        folder: &AssetPath,
+        priority: PrototypeLoadPriority,
+    ) -> LoadRequests {
+        match folder.resolve(PACK_MANIFEST_FILE) {
+            Ok(manifest) => self.request_manifest(&manifest.to_string(), priority),
+            Err(err) => {
+                error!("Invalid prototypes folder {folder}: {err}");
+                LoadRequests::default()
+            }
+        }
+    }
 
-                while let Some(file) = folder.next().await {
-                    if !source.is_directory(&file).await.unwrap() {
-                        let file = file.to_string_lossy().to_string();
-                        let asset_path: AssetPath<'_> = (&file).into();
+    /// Lists the prototypes files of a folder, sub-folders are not included.
+    fn read_prototypes_folder(&self, folder: &AssetPath) -> Result<Vec<String>, String> {
+        let source = self
+            .asset_server
+            .get_source(folder.source())
+            .map_err(|err| err.to_string())?;
+        let source = source.reader();
 
-                        let is_prototype_file = {
-                            let Some(full_extension) = asset_path.get_full_extension() else {
-                                continue;
-                            };
+        bevy::tasks::block_on(async move {
+            use bevy::tasks::futures_lite::StreamExt;
 
-                            PROTOTYPE_ASSET_EXTENSIONS.contains(&full_extension.as_str())
-                        };
+            let mut entries = source
+                .read_directory(folder.path())
+                .await
+                .map_err(|err| err.to_string())?;
+            let mut files = Vec::new();
 
-                        if is_prototype_file {
-                            files.push(file);
-                        }
-                    }
+            while let Some(file) = entries.next().await {
+                // Sources failing to tell are assumed to only contain files.
+                if source.is_directory(&file).await.unwrap_or(false) {
+                    continue;
                 }
 
-                files
-            })
-        };
+                // Listed paths are relative to the source root, keep the source of the folder.
+                let asset_path =
+                    AssetPath::from_path(&file).with_source(folder.source().clone_owned());
 
-        for file in files {
-            self.load_prototypes(&file);
+                // Single-prototype files have the type in their extension, match the suffix.
+                if is_prototype_file(&file)
+                    || is_prototype_table_file(&file)
+                    || profile::is_override_file(&file)
+                    || pack_info::is_pack_info_file(&file)
+                {
+                    files.push(asset_path.to_string());
+                }
+            }
+
+            Ok(files)
+        })
+    }
+
+    /// Reads the [`PackManifest`] of a prototypes folder, if it has one.
+    #[cfg(feature = "integrity")]
+    fn read_pack_manifest(&self, folder: &AssetPath) -> Option<PackManifest> {
+        let source = self.asset_server.get_source(folder.source()).ok()?;
+        let path = folder.path().join(PACK_MANIFEST_FILE);
+
+        let bytes = bevy::tasks::block_on(async {
+            let mut reader = source.reader().read(&path).await.ok()?;
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await.ok()?;
+            Some(bytes)
+        })?;
+
+        match serde_json::from_slice(&bytes) {
+            Ok(manifest) => Some(manifest),
+            Err(err) => {
+                // Still verify against an empty manifest, a broken one must not disable the checks.
+                error!("Invalid pack manifest {}: {err}", path.display());
+                Some(PackManifest::default())
+            }
         }
     }
 }
 
 #[doc(hidden)]
 pub mod _private {
+    #[cfg(feature = "schema")]
+    pub use crate::schema::{reflect_schema_ref, register_reflect_schema, schema_default_value};
+    pub use bevy::ecs::world::EntityWorldMut;
+    pub use bevy::reflect::{TypePath, utility::GenericTypePathCell};
     pub use serde_json;
+
+    /// Returns the name of a type parameter in the name of a generic prototype, its type name in
+    /// `snake_case`, e.g. `strength` for `Strength` in `stat<strength>`.
+    pub fn prototype_param_name<T: TypePath + ?Sized>() -> String {
+        let chars = T::short_type_path().chars().collect::<Vec<_>>();
+        let mut name = String::with_capacity(chars.len());
+
+        for (index, char) in chars.iter().enumerate() {
+            if char.is_uppercase() && index > 0 {
+                let previous = chars[index - 1];
+                let next_is_lowercase =
+                    chars.get(index + 1).is_some_and(|next| next.is_lowercase());
+
+                if previous.is_lowercase()
+                    || previous.is_ascii_digit()
+                    || (previous.is_uppercase() && next_is_lowercase)
+                {
+                    name.push('_');
+                }
+            }
+
+            name.extend(char.to_lowercase());
+        }
+
+        name
+    }
 }
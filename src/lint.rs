@@ -0,0 +1,538 @@
+use core::any::TypeId;
+use std::path::Path;
+
+use bevy::{
+    asset::LoadState,
+    platform::collections::{HashMap, HashSet},
+    prelude::*,
+    reflect::{ReflectRef, TypeRegistry, serde::TypedReflectSerializer},
+};
+use serde_json::Value as JsonValue;
+
+use crate::{
+    AllowedTags, ErasedPrototypeId, PackError, PrototypeRegistries, PrototypeSource,
+    prototype_data_to_json, read_pack,
+};
+
+/// A content hygiene rule checked by the lints, see [`LintConfig`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum LintRule {
+    /// Prototype names must be `snake_case`, e.g. `wooden_stick`.
+    SnakeCaseNames,
+    /// Every tag listed by [`LintConfig::known_tags`] or allowed for a prototype type must be used
+    /// by at least one prototype of the type.
+    UnusedTags,
+    /// Every prototype, except the ones of the [`LintConfig::entry_types`], must be referenced by
+    /// another prototype. References from the Rust code aren't seen.
+    UnreferencedPrototypes,
+    /// The prototypes of a type with an icon field, see [`LintConfig::icon_fields`], must have an
+    /// icon and the icon asset must exist.
+    MissingIcons,
+}
+
+impl LintRule {
+    /// Every lint rule.
+    pub const ALL: [Self; 4] = [
+        Self::SnakeCaseNames,
+        Self::UnusedTags,
+        Self::UnreferencedPrototypes,
+        Self::MissingIcons,
+    ];
+
+    /// Returns the name of the rule, as used by the `pack validate` command.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::SnakeCaseNames => "snake_case_names",
+            Self::UnusedTags => "unused_tags",
+            Self::UnreferencedPrototypes => "unreferenced_prototypes",
+            Self::MissingIcons => "missing_icons",
+        }
+    }
+
+    /// Returns the rule with the given [`LintRule::name`].
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|rule| rule.name() == name)
+    }
+}
+
+impl core::fmt::Display for LintRule {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// How a [`LintRule`] violation is reported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub enum LintSeverity {
+    /// The rule isn't checked.
+    #[default]
+    Allow,
+    /// Violations are logged as warnings.
+    Warn,
+    /// Violations are logged as errors and fail the `pack validate` command.
+    Deny,
+}
+
+impl LintSeverity {
+    /// Returns the severity with the given name, `allow`, `warn` or `deny`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "allow" => Some(Self::Allow),
+            "warn" => Some(Self::Warn),
+            "deny" => Some(Self::Deny),
+            _ => None,
+        }
+    }
+}
+
+impl core::fmt::Display for LintSeverity {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            Self::Allow => "allow",
+            Self::Warn => "warn",
+            Self::Deny => "deny",
+        })
+    }
+}
+
+/// The severities and settings of the lints.
+///
+/// Every rule is allowed by default, the enabled rules are checked once every requested
+/// prototypes file is applied and their violations are added to the batch
+/// [`PrototypeLoadReport`](crate::PrototypeLoadReport):
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_histrion_proto::{LintConfig, LintRule, LintSeverity};
+/// # fn f(app: &mut App) {
+/// app.insert_resource(
+///     LintConfig::default()
+///         .with(LintRule::SnakeCaseNames, LintSeverity::Deny)
+///         .with(LintRule::UnreferencedPrototypes, LintSeverity::Warn)
+///         .with_entry_types(["sword"]),
+/// );
+/// # }
+/// ```
+#[derive(Resource, Debug, Clone)]
+pub struct LintConfig {
+    severities: HashMap<LintRule, LintSeverity>,
+    /// Names of the fields holding the icon of a prototype, `icon` by default.
+    pub icon_fields: Vec<String>,
+    /// Tags expected to be used, by prototype type. The allowed tags of the registered prototype
+    /// types are checked too when linting the registries.
+    pub known_tags: HashMap<String, Vec<String>>,
+    /// Prototype types used directly by the game, which don't need to be referenced.
+    pub entry_types: Vec<String>,
+}
+
+impl Default for LintConfig {
+    fn default() -> Self {
+        Self {
+            severities: HashMap::default(),
+            icon_fields: vec!["icon".to_string()],
+            known_tags: HashMap::default(),
+            entry_types: Vec::new(),
+        }
+    }
+}
+
+impl LintConfig {
+    /// Sets the severity of `rule`.
+    #[must_use]
+    pub fn with(mut self, rule: LintRule, severity: LintSeverity) -> Self {
+        self.set(rule, severity);
+        self
+    }
+
+    /// Adds prototype types to the [`LintConfig::entry_types`].
+    #[must_use]
+    pub fn with_entry_types(mut self, types: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.entry_types.extend(types.into_iter().map(Into::into));
+        self
+    }
+
+    /// Sets the severity of `rule`.
+    pub fn set(&mut self, rule: LintRule, severity: LintSeverity) {
+        self.severities.insert(rule, severity);
+    }
+
+    /// Returns the severity of `rule`.
+    pub fn severity(&self, rule: LintRule) -> LintSeverity {
+        self.severities.get(&rule).copied().unwrap_or_default()
+    }
+
+    /// Returns `true` if at least one rule isn't allowed.
+    pub fn is_enabled(&self) -> bool {
+        self.severities
+            .values()
+            .any(|severity| *severity != LintSeverity::Allow)
+    }
+}
+
+/// A prototype checked by the lints, with its data as written in the prototypes files.
+#[derive(Debug, Clone)]
+pub struct LintedPrototype {
+    pub ty: String,
+    pub name: String,
+    pub id: ErasedPrototypeId,
+    pub tags: Vec<String>,
+    /// The fields of the prototype data.
+    pub data: JsonValue,
+    /// Where the prototype is defined, e.g. its file.
+    pub location: String,
+}
+
+/// A violation of a [`LintRule`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintIssue {
+    pub rule: LintRule,
+    pub severity: LintSeverity,
+    /// The prototype type.
+    pub ty: String,
+    /// The prototype name, empty for the issues of a whole type.
+    pub name: String,
+    /// Where the prototype is defined, empty for the issues of a whole type.
+    pub location: String,
+    pub message: String,
+}
+
+impl core::fmt::Display for LintIssue {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if !self.location.is_empty() {
+            write!(f, "{}: ", self.location)?;
+        }
+
+        write!(f, "{}[{}] {}", self.severity, self.rule, self.ty)?;
+
+        if !self.name.is_empty() {
+            write!(f, " {}", self.name)?;
+        }
+
+        write!(f, ": {}", self.message)
+    }
+}
+
+/// Checks the enabled rules of `config` on `prototypes`.
+///
+/// `asset_exists` tells if the icon asset at the given path, as written in the prototype data of
+/// the prototype at the given index, exists.
+pub fn lint_prototypes(
+    prototypes: &[LintedPrototype],
+    config: &LintConfig,
+    mut asset_exists: impl FnMut(usize, &str) -> bool,
+) -> Vec<LintIssue> {
+    let mut issues = Vec::new();
+    let mut issue = |rule, prototype: Option<&LintedPrototype>, ty: &str, message: String| {
+        let severity = config.severity(rule);
+
+        issues.push(LintIssue {
+            rule,
+            severity,
+            ty: ty.to_string(),
+            name: prototype.map(|p| p.name.clone()).unwrap_or_default(),
+            location: prototype.map(|p| p.location.clone()).unwrap_or_default(),
+            message,
+        });
+    };
+
+    if config.severity(LintRule::SnakeCaseNames) != LintSeverity::Allow {
+        for prototype in prototypes.iter().filter(|p| !is_snake_case(&p.name)) {
+            issue(
+                LintRule::SnakeCaseNames,
+                Some(prototype),
+                &prototype.ty,
+                "name isn't snake_case".to_string(),
+            );
+        }
+    }
+
+    if config.severity(LintRule::UnusedTags) != LintSeverity::Allow {
+        let mut known_tags = config.known_tags.iter().collect::<Vec<_>>();
+        known_tags.sort();
+
+        for (ty, tags) in known_tags {
+            for tag in tags {
+                let is_used = prototypes
+                    .iter()
+                    .any(|prototype| prototype.ty == *ty && prototype.tags.contains(tag));
+
+                if !is_used {
+                    issue(
+                        LintRule::UnusedTags,
+                        None,
+                        ty,
+                        format!("tag {tag} isn't used by any prototype"),
+                    );
+                }
+            }
+        }
+    }
+
+    if config.severity(LintRule::UnreferencedPrototypes) != LintSeverity::Allow {
+        let mut referenced = HashSet::new();
+
+        for prototype in prototypes {
+            let mut references = HashSet::new();
+            collect_references(&prototype.data, &mut references);
+
+            // A prototype referencing itself is still unused.
+            references.remove(&prototype.id);
+            referenced.extend(references);
+        }
+
+        for prototype in prototypes {
+            if config.entry_types.contains(&prototype.ty) {
+                continue;
+            }
+
+            if !referenced.contains(&prototype.id) {
+                issue(
+                    LintRule::UnreferencedPrototypes,
+                    Some(prototype),
+                    &prototype.ty,
+                    "isn't referenced by any prototype".to_string(),
+                );
+            }
+        }
+    }
+
+    if config.severity(LintRule::MissingIcons) != LintSeverity::Allow {
+        // Fields with a default value can be omitted, a type has icons when any of its
+        // prototypes sets one of the icon fields.
+        let types_with_icons = prototypes
+            .iter()
+            .filter(|prototype| icon_field(prototype, config).is_some())
+            .map(|prototype| prototype.ty.as_str())
+            .collect::<HashSet<_>>();
+
+        for (index, prototype) in prototypes.iter().enumerate() {
+            if !types_with_icons.contains(prototype.ty.as_str()) {
+                continue;
+            }
+
+            let message = match icon_field(prototype, config) {
+                Some((_, JsonValue::String(path))) if !path.is_empty() => {
+                    if asset_exists(index, path) {
+                        continue;
+                    }
+
+                    format!("icon {path} doesn't exist")
+                }
+                Some((field, _)) => format!("{field} is empty"),
+                None => "has no icon".to_string(),
+            };
+
+            issue(
+                LintRule::MissingIcons,
+                Some(prototype),
+                &prototype.ty,
+                message,
+            );
+        }
+    }
+
+    issues
+}
+
+/// Checks the enabled rules of `config` on the prototypes of the `pack` folder or file (see
+/// [`read_pack`]), icons are searched relative to the file of their prototype and to
+/// `asset_root` for the paths starting with a `/`.
+pub fn lint_pack(
+    pack: impl AsRef<Path>,
+    asset_root: impl AsRef<Path>,
+    config: &LintConfig,
+) -> Result<Vec<LintIssue>, PackError> {
+    let entries = read_pack(pack)?;
+    let asset_root = asset_root.as_ref();
+
+    let prototypes = entries
+        .iter()
+        .map(|entry| {
+            let mut data = entry.value.clone();
+            let tags = match &mut data {
+                JsonValue::Object(fields) => {
+                    for key in ["type", "name", "$meta", "$editor"] {
+                        fields.remove(key);
+                    }
+
+                    fields
+                        .remove("tags")
+                        .and_then(|tags| serde_json::from_value(tags).ok())
+                        .unwrap_or_default()
+                }
+                _ => Vec::new(),
+            };
+
+            LintedPrototype {
+                ty: entry.key.ty.clone(),
+                name: entry.key.name.clone(),
+                id: ErasedPrototypeId::from_name(&entry.key.name),
+                tags,
+                data,
+                location: entry.file.display().to_string(),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    Ok(lint_prototypes(&prototypes, config, |index, path| {
+        let path = path.split_once('#').map_or(path, |(path, _)| path);
+
+        match path.strip_prefix('/') {
+            Some(path) => asset_root.join(path),
+            None => entries[index].file.with_file_name(path),
+        }
+        .exists()
+    }))
+}
+
+/// Checks the enabled rules of `config` on the prototypes of the registries, logging the
+/// violations.
+pub(crate) fn lint_registries(
+    registries: &PrototypeRegistries,
+    prototype_types: &HashMap<Box<str>, TypeId>,
+    type_registry: &TypeRegistry,
+    allowed_tags: &AllowedTags,
+    asset_server: &AssetServer,
+    config: &LintConfig,
+) -> Vec<LintIssue> {
+    let mut config = config.clone();
+    let mut types = prototype_types.iter().collect::<Vec<_>>();
+    types.sort_by_key(|(ty, _)| *ty);
+
+    for (ty, type_id) in &types {
+        if let Some(tags) = allowed_tags.get(*type_id) {
+            let known_tags = config.known_tags.entry(ty.to_string()).or_default();
+
+            for tag in tags.iter().map(ToString::to_string) {
+                if !known_tags.contains(&tag) {
+                    known_tags.push(tag);
+                }
+            }
+        }
+    }
+
+    let prototypes = types
+        .into_iter()
+        .flat_map(|(ty, type_id)| {
+            registries
+                .iter_dyn(type_id)
+                .filter_map(|proto| linted_prototype(ty, proto, type_registry))
+        })
+        .collect::<Vec<_>>();
+
+    let issues = lint_prototypes(&prototypes, &config, |_, path| {
+        let path = path.strip_prefix('/').unwrap_or(path);
+
+        asset_server
+            .get_path_id(path)
+            .and_then(|id| asset_server.get_load_state(id))
+            .is_none_or(|state| !matches!(state, LoadState::Failed(_)))
+    });
+
+    for issue in &issues {
+        match issue.severity {
+            LintSeverity::Allow => {}
+            LintSeverity::Warn => warn!("{issue}"),
+            LintSeverity::Deny => error!("{issue}"),
+        }
+    }
+
+    issues
+}
+
+fn linted_prototype(
+    ty: &str,
+    proto: &dyn Reflect,
+    type_registry: &TypeRegistry,
+) -> Option<LintedPrototype> {
+    let ReflectRef::Struct(proto) = proto.reflect_ref() else {
+        return None;
+    };
+
+    // Stripped names are serialized as their id.
+    let (name, id) = match serde_json::to_value(TypedReflectSerializer::new(
+        proto.field("name")?,
+        type_registry,
+    ))
+    .ok()?
+    {
+        JsonValue::String(name) => {
+            let id = ErasedPrototypeId::from_name(&name);
+            (name, id)
+        }
+        JsonValue::Number(id) => {
+            let id = ErasedPrototypeId::from_raw(id.as_u64()?);
+            (id.to_string(), id)
+        }
+        _ => return None,
+    };
+
+    let location = proto
+        .field("source")
+        .and_then(<dyn PartialReflect>::try_downcast_ref::<Option<PrototypeSource>>)
+        .and_then(Option::as_ref)
+        .map(ToString::to_string)
+        .unwrap_or_default();
+
+    Some(LintedPrototype {
+        ty: ty.to_string(),
+        name,
+        id,
+        tags: proto
+            .field("tags")
+            .and_then(<dyn PartialReflect>::try_downcast_ref::<Vec<String>>)
+            .cloned()
+            .unwrap_or_default(),
+        data: prototype_data_to_json(proto.field("data")?, type_registry, None),
+        location,
+    })
+}
+
+fn is_snake_case(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with('_')
+        && !name.ends_with('_')
+        && !name.contains("__")
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+fn icon_field<'a>(
+    prototype: &'a LintedPrototype,
+    config: &'a LintConfig,
+) -> Option<(&'a str, &'a JsonValue)> {
+    config.icon_fields.iter().find_map(|field| {
+        prototype
+            .data
+            .get(field)
+            .map(|value| (field.as_str(), value))
+    })
+}
+
+/// Collects the prototype ids referenced by `value`, written as names or as ids.
+fn collect_references(value: &JsonValue, references: &mut HashSet<ErasedPrototypeId>) {
+    match value {
+        JsonValue::String(name) => {
+            references.insert(ErasedPrototypeId::from_name(name));
+        }
+        JsonValue::Number(id) => {
+            if let Some(id) = id.as_u64() {
+                references.insert(ErasedPrototypeId::from_raw(id));
+            }
+        }
+        JsonValue::Array(items) => {
+            for item in items {
+                collect_references(item, references);
+            }
+        }
+        JsonValue::Object(fields) => {
+            // Maps keyed by prototype ids reference them too.
+            for (key, value) in fields {
+                references.insert(ErasedPrototypeId::from_name(key));
+                collect_references(value, references);
+            }
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,293 @@
+use std::path::Path;
+
+use bevy::{
+    asset::{AssetLoader, LoadContext, io::Reader as AssetReader},
+    platform::collections::{HashMap, HashSet},
+    prelude::*,
+};
+use serde_json::Value as JsonValue;
+
+use crate::{
+    AppPrototypeTypeRegistry, ErasedPrototypeId,
+    overlay::PrototypeOverlay,
+    pack::join_field_path,
+    profile::override_suffix,
+    registry::PrototypeRegistries,
+    telemetry::{PrototypeOverrideKind, PrototypeTelemetry, PrototypeTelemetryEvent},
+};
+
+/// The locale whose override files patch the string fields of the prototypes, e.g. `fr` for
+/// `items.proto.fr.json`, declared with
+/// [`PrototypeAppExt::add_prototype_locale`](crate::PrototypeAppExt::add_prototype_locale).
+///
+/// Changing or removing it applies the overrides of the new locale, or restores the strings of
+/// the base prototypes, and sends a [`PrototypeLocaleChanged`] event.
+#[derive(Resource, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CurrentLocale(pub String);
+
+/// Sent once the overrides of a new [`CurrentLocale`] are applied, along with a
+/// [`RegistryEvent::Modified`](crate::RegistryEvent::Modified) for each patched prototype.
+#[derive(Event, Debug, Clone, PartialEq, Eq)]
+pub struct PrototypeLocaleChanged {
+    pub previous: Option<String>,
+    pub current: Option<String>,
+}
+
+/// The string fields of the prototypes set by a locale override file.
+#[derive(Asset, TypePath, Debug)]
+pub(crate) struct PrototypeLocaleOverrides {
+    locale: String,
+    entries: Vec<LocaleOverride>,
+}
+
+#[derive(Debug)]
+struct LocaleOverride {
+    ty: String,
+    name: String,
+    /// Reflect paths in the data of the prototype with their localized string.
+    strings: Vec<(String, String)>,
+}
+
+/// Loads the locale override files, there is no extension to register as the locale is part of
+/// it, they are loaded as typed assets.
+#[derive(Default)]
+pub(crate) struct PrototypeLocaleOverridesLoader;
+
+impl AssetLoader for PrototypeLocaleOverridesLoader {
+    type Asset = PrototypeLocaleOverrides;
+    type Settings = ();
+    type Error = std::io::Error;
+
+    async fn load(
+        &self,
+        reader: &mut dyn AssetReader,
+        _settings: &Self::Settings,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        use std::io::{Error, ErrorKind};
+
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        let path = load_context.path();
+        let Some(locale) = override_suffix(path) else {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("{} isn't a locale override file", path.display()),
+            ));
+        };
+
+        let entries = match serde_json::from_slice(&bytes)? {
+            JsonValue::Array(entries) => entries,
+            entry => vec![entry],
+        };
+
+        let entries = entries
+            .into_iter()
+            .enumerate()
+            .filter_map(|(index, entry)| {
+                let (Some(ty), Some(name)) = (
+                    entry.get("type").and_then(JsonValue::as_str),
+                    entry.get("name").and_then(JsonValue::as_str),
+                ) else {
+                    warn!(
+                        "Locale override {index} of {} has no type or name",
+                        path.display()
+                    );
+                    return None;
+                };
+
+                let mut strings = Vec::new();
+
+                for (field, value) in entry.as_object().into_iter().flatten() {
+                    if !matches!(
+                        field.as_str(),
+                        "type"
+                            | "name"
+                            | "tags"
+                            | "defaults_from"
+                            | "experiment"
+                            | "uuid"
+                            | "$meta"
+                            | "$editor"
+                    ) {
+                        collect_strings(field.clone(), value, &mut strings, path);
+                    }
+                }
+
+                Some(LocaleOverride {
+                    ty: ty.to_string(),
+                    name: name.to_string(),
+                    strings,
+                })
+            })
+            .collect();
+
+        Ok(PrototypeLocaleOverrides {
+            locale: locale.to_string(),
+            entries,
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &[]
+    }
+}
+
+fn collect_strings(
+    path: String,
+    value: &JsonValue,
+    strings: &mut Vec<(String, String)>,
+    file: &Path,
+) {
+    match value {
+        JsonValue::String(value) => strings.push((path, value.clone())),
+        JsonValue::Object(fields) => {
+            for (field, value) in fields {
+                collect_strings(join_field_path(&path, field), value, strings, file);
+            }
+        }
+        JsonValue::Array(items) => {
+            for (i, value) in items.iter().enumerate() {
+                collect_strings(format!("{path}[{i}]"), value, strings, file);
+            }
+        }
+        _ => warn!(
+            "Field {path} of {} isn't a string, locale overrides only patch strings",
+            file.display()
+        ),
+    }
+}
+
+/// The declared locales, the loaded locale override files and the strings they patched, see
+/// [`CurrentLocale`].
+#[derive(Resource, Default)]
+pub(crate) struct LocaleOverrides {
+    declared: HashSet<String>,
+    /// Override files in their load order, later files take precedence.
+    files: Vec<Handle<PrototypeLocaleOverrides>>,
+    overlay: PrototypeOverlay,
+    locale: Option<String>,
+}
+
+impl LocaleOverrides {
+    pub(crate) fn declare(&mut self, locale: String) {
+        self.declared.insert(locale);
+    }
+
+    /// Returns `true` for the override files of the declared locales, e.g. `items.proto.fr.json`.
+    pub(crate) fn is_locale_file(&self, path: &Path) -> bool {
+        override_suffix(path).is_some_and(|locale| self.declared.contains(locale))
+    }
+
+    pub(crate) fn add(&mut self, handle: Handle<PrototypeLocaleOverrides>) {
+        if !self.files.contains(&handle) {
+            self.files.push(handle);
+        }
+    }
+}
+
+/// Patches the string fields of the prototypes with the overrides of the [`CurrentLocale`], when
+/// it changes, when override files are loaded and when base prototypes are reloaded.
+pub(crate) fn apply_locale_overrides(
+    mut events_rx: EventReader<AssetEvent<PrototypeLocaleOverrides>>,
+    assets: Res<Assets<PrototypeLocaleOverrides>>,
+    current_locale: Option<Res<CurrentLocale>>,
+    mut overrides: ResMut<LocaleOverrides>,
+    mut registries: ResMut<PrototypeRegistries>,
+    prototype_types: Res<AppPrototypeTypeRegistry>,
+    mut locale_changed_tx: EventWriter<PrototypeLocaleChanged>,
+    telemetry: Option<Res<PrototypeTelemetry>>,
+) {
+    let files_changed = events_rx.read().count() > 0;
+    let locale = current_locale.as_ref().map(|locale| locale.0.clone());
+    let locale_changed = locale != overrides.locale;
+
+    if !files_changed && !locale_changed && !registries.is_changed() {
+        return;
+    }
+
+    let overrides = overrides.bypass_change_detection();
+    let prototype_types = prototype_types.0.read();
+
+    let mut wanted = HashMap::new();
+
+    for file in overrides
+        .files
+        .iter()
+        .filter_map(|handle| assets.get(handle))
+    {
+        if Some(&file.locale) != locale.as_ref() {
+            continue;
+        }
+
+        for entry in &file.entries {
+            let Some(type_id) = prototype_types.get(entry.ty.as_str()) else {
+                continue;
+            };
+
+            let id = ErasedPrototypeId::from_name(&entry.name);
+
+            for (path, value) in &entry.strings {
+                wanted.insert(
+                    (*type_id, id, format!("data.{path}")),
+                    (entry.name.as_str(), value.as_str()),
+                );
+            }
+        }
+    }
+
+    let modified = overrides.overlay.apply(
+        registries.bypass_change_detection(),
+        wanted,
+        |field, value: &&str| {
+            if field.try_downcast_ref::<String>().is_some() {
+                Ok(Box::new(value.to_string()))
+            } else if field.try_downcast_ref::<Option<String>>().is_some() {
+                Ok(Box::new(Some(value.to_string())))
+            } else {
+                Err("locale overrides only patch string fields".to_string())
+            }
+        },
+    );
+
+    for (type_id, id) in modified {
+        registries.mark_modified(&type_id, id);
+
+        if let Some(telemetry) = &telemetry
+            && let Some(ty) = prototype_types
+                .iter()
+                .find_map(|(name, ty)| (*ty == type_id).then_some(&**name))
+        {
+            telemetry.send(PrototypeTelemetryEvent::OverrideApplied {
+                ty,
+                id,
+                kind: PrototypeOverrideKind::Locale,
+            });
+        }
+    }
+
+    if locale_changed {
+        let previous = core::mem::replace(&mut overrides.locale, locale.clone());
+
+        locale_changed_tx.write(PrototypeLocaleChanged {
+            previous,
+            current: locale,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_the_declared_locales_have_override_files() {
+        let mut overrides = LocaleOverrides::default();
+        overrides.declare("fr".to_string());
+
+        assert!(overrides.is_locale_file(Path::new("items.proto.fr.json")));
+        assert!(!overrides.is_locale_file(Path::new("graphics.proto.mobile.json")));
+        assert!(!overrides.is_locale_file(Path::new("items.proto.json")));
+    }
+}
@@ -0,0 +1,145 @@
+use crate::ErasedPrototypeId;
+
+/// Why [`Reg::try_get`](crate::Reg::try_get) didn't return a prototype.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PrototypeLookupError {
+    /// The `ty` prototypes were never registered with
+    /// [`PrototypeAppExt::register_prototype`](crate::PrototypeAppExt::register_prototype).
+    Unregistered { ty: &'static str },
+    /// The `ty` registry has no prototype with the `id` id.
+    Missing {
+        ty: &'static str,
+        id: ErasedPrototypeId,
+        /// Name of the prototype, found when prototypes of other types have the same id.
+        name: Option<String>,
+        /// Whether the file of the prototype is being loaded on demand, see
+        /// [`Reg::state`](crate::Reg::state).
+        pending: bool,
+        /// The other prototype types with a prototype of this id, e.g. when the prototype was
+        /// looked up with the wrong type.
+        other_types: Vec<&'static str>,
+        /// Names of the `ty` prototypes close to `name`, e.g. for a typo.
+        suggestions: Vec<String>,
+    },
+}
+
+impl core::fmt::Display for PrototypeLookupError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Unregistered { ty } => write!(
+                f,
+                "the {ty} prototypes aren't registered, register them with `register_prototype`"
+            ),
+            Self::Missing {
+                ty,
+                id,
+                name,
+                pending,
+                other_types,
+                suggestions,
+            } => {
+                match name {
+                    Some(name) => write!(f, "no {ty} prototype named {name}")?,
+                    None => write!(f, "no {ty} prototype with id {id}")?,
+                }
+
+                if *pending {
+                    write!(f, ", its file is still loading")?;
+                }
+
+                if !other_types.is_empty() {
+                    write!(f, ", found as a {} prototype", other_types.join(" and "))?;
+                }
+
+                if !suggestions.is_empty() {
+                    write!(f, ", did you mean {}?", suggestions.join(", "))?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for PrototypeLookupError {}
+
+/// Returns up to 3 of the `names` close to `name`, the closest first.
+#[cfg(not(feature = "strip_names"))]
+pub(crate) fn similar_names<'a>(name: &str, names: impl Iterator<Item = &'a str>) -> Vec<String> {
+    let max_distance = (name.chars().count() / 3).max(1);
+
+    let mut similar = names
+        .filter(|other| *other != name)
+        .map(|other| (edit_distance(name, other), other))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .collect::<Vec<_>>();
+    similar.sort();
+
+    similar
+        .into_iter()
+        .take(3)
+        .map(|(_, other)| other.to_string())
+        .collect()
+}
+
+/// Levenshtein distance between `a` and `b`.
+#[cfg(not(feature = "strip_names"))]
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut distances = (0..=b.len()).collect::<Vec<_>>();
+
+    for (i, a) in a.chars().enumerate() {
+        let mut previous = distances[0];
+        distances[0] = i + 1;
+
+        for (j, b) in b.iter().enumerate() {
+            let substitution = previous + usize::from(a != *b);
+            previous = distances[j + 1];
+            distances[j + 1] = substitution.min(previous + 1).min(distances[j] + 1);
+        }
+    }
+
+    distances[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(not(feature = "strip_names"))]
+    #[test]
+    fn similar_names_are_the_closest_ones() {
+        assert_eq!(edit_distance("sword", "swrod"), 2);
+        assert_eq!(edit_distance("", "bow"), 3);
+
+        let names = [
+            "long_sword",
+            "short_sword",
+            "long_bow",
+            "long_swords",
+            "axe",
+        ];
+        assert_eq!(
+            similar_names("long_swrd", names.into_iter()),
+            ["long_sword", "long_swords"]
+        );
+        assert!(similar_names("dagger", names.into_iter()).is_empty());
+    }
+
+    #[test]
+    fn missing_prototypes_explain_why() {
+        let error = PrototypeLookupError::Missing {
+            ty: "sword",
+            id: ErasedPrototypeId::from_name("stick"),
+            name: Some("stick".to_string()),
+            pending: true,
+            other_types: vec!["shield"],
+            suggestions: vec!["sticks".to_string()],
+        };
+
+        assert_eq!(
+            error.to_string(),
+            "no sword prototype named stick, its file is still loading, found as a shield prototype, did you mean sticks?"
+        );
+    }
+}
@@ -0,0 +1,106 @@
+use std::{collections::BTreeMap, path::Path};
+
+use bevy::{
+    asset::{AssetLoader, LoadContext, io::Reader as AssetReader},
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    PackError, is_prototype_file, is_prototype_table_file, pack_info::is_pack_info_file,
+    profile::is_override_file,
+};
+
+/// Name of the manifest file listing the prototypes files of a folder.
+pub const PACK_MANIFEST_FILE: &str = "pack.manifest.json";
+
+/// The prototypes files of a folder, generated at pack time.
+///
+/// It lets [`PrototypeServer::load_prototypes_folder`](crate::PrototypeServer::load_prototypes_folder)
+/// load folders from asset sources that can't list directories, and with the `integrity` feature
+/// the listed hashes are verified at load.
+#[derive(Asset, TypePath, Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PackManifest {
+    /// BLAKE3 hash of each prototypes file keyed by file name, empty when generated with
+    /// [`PackManifest::list`].
+    pub files: BTreeMap<String, String>,
+    /// Keyed BLAKE3 hash of `files`, see `PackManifest::sign`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+}
+
+impl PackManifest {
+    /// Lists the prototypes files of the `dir` folder, without hashing them.
+    ///
+    /// Like [`PrototypeServer::load_prototypes_folder`](crate::PrototypeServer::load_prototypes_folder),
+    /// sub-folders are not included.
+    pub fn list(dir: impl AsRef<Path>) -> Result<Self, PackError> {
+        let dir = dir.as_ref();
+        let io_error = |source| PackError::Io {
+            path: dir.to_path_buf(),
+            source,
+        };
+
+        let mut files = BTreeMap::new();
+
+        for entry in std::fs::read_dir(dir).map_err(io_error)? {
+            let path = entry.map_err(io_error)?.path();
+
+            if !path.is_file()
+                || !(is_prototype_file(&path)
+                    || is_prototype_table_file(&path)
+                    || is_override_file(&path)
+                    || is_pack_info_file(&path))
+            {
+                continue;
+            }
+
+            let name = path.file_name().unwrap().to_string_lossy().to_string();
+            files.insert(name, String::new());
+        }
+
+        Ok(Self {
+            files,
+            signature: None,
+        })
+    }
+
+    /// Writes the manifest as [`PACK_MANIFEST_FILE`] in the `dir` folder.
+    pub fn write(&self, dir: impl AsRef<Path>) -> Result<(), PackError> {
+        let path = dir.as_ref().join(PACK_MANIFEST_FILE);
+        let io_error = |source| PackError::Io {
+            path: path.clone(),
+            source,
+        };
+
+        let mut bytes = crate::to_json_pretty(self).map_err(io_error)?;
+        bytes.push(b'\n');
+
+        std::fs::write(&path, bytes).map_err(io_error)
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct PackManifestLoader;
+
+impl AssetLoader for PackManifestLoader {
+    type Asset = PackManifest;
+    type Settings = ();
+    type Error = std::io::Error;
+
+    async fn load(
+        &self,
+        reader: &mut dyn AssetReader,
+        _settings: &Self::Settings,
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["manifest.json"]
+    }
+}
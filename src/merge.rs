@@ -0,0 +1,227 @@
+use bevy::{
+    prelude::*,
+    reflect::{
+        ReflectMut, ReflectRef, TypeRegistration, TypeRegistry,
+        serde::{ReflectDeserializerProcessor, TypedReflectDeserializer},
+    },
+};
+use serde::de::DeserializeSeed;
+
+use crate::raw::{RawFields, without_position};
+
+/// How the items of a list field are combined with the inherited list, e.g. the one of the
+/// `defaults_from` prototype, chosen by the suffix of the field name.
+///
+/// A field without suffix replaces the inherited list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum ListMergeStrategy {
+    /// `"effects+": [...]` appends the items to the inherited list.
+    Append,
+    /// `"effects@name": [...]` merges each item into the inherited item with the same `name`
+    /// field, items without a match are appended.
+    MergeByKey(String),
+}
+
+impl ListMergeStrategy {
+    /// Splits a field name into the name of the field and its merge strategy, if any.
+    fn parse(key: &str) -> Option<(&str, Self)> {
+        if let Some(field) = key.strip_suffix('+') {
+            return Some((field, Self::Append));
+        }
+
+        let (field, merge_key) = key.split_once('@')?;
+
+        Some((field, Self::MergeByKey(merge_key.to_string())))
+    }
+}
+
+/// The items of a list field to merge into the inherited list.
+pub(crate) struct ListMerge {
+    pub field: String,
+    pub strategy: ListMergeStrategy,
+    pub items: Box<dyn PartialReflect>,
+}
+
+/// Returns `true` if `key` is a list field with a merge strategy, see [`read_list_merges`].
+pub(crate) fn is_list_merge_key(key: &str) -> bool {
+    ListMergeStrategy::parse(key).is_some()
+}
+
+/// Deserializes the list fields with a merge strategy of the `fields` of an entry as the fields
+/// of the `registration` struct, the other fields are left to the prototype data.
+pub(crate) fn read_list_merges<P: ReflectDeserializerProcessor>(
+    fields: &RawFields<'_>,
+    registration: &TypeRegistration,
+    registry: &TypeRegistry,
+    processor: &mut P,
+) -> Result<Vec<ListMerge>, String> {
+    let mut merges = Vec::new();
+
+    for (key, items) in fields {
+        let Some((field, strategy)) = ListMergeStrategy::parse(key) else {
+            continue;
+        };
+
+        let bevy::reflect::TypeInfo::Struct(struct_info) = registration.type_info() else {
+            return Err("list merges are only supported on struct fields".to_string());
+        };
+
+        let Some(field_registration) = struct_info
+            .field(field)
+            .and_then(|field| registry.get(field.type_id()))
+        else {
+            return Err(format!("unknown field `{field}` of `{key}`"));
+        };
+
+        let items =
+            TypedReflectDeserializer::with_processor(field_registration, registry, &mut *processor)
+                .deserialize(&**items)
+                .map_err(|err| format!("invalid `{key}`: {}", without_position(err)))?;
+
+        if !matches!(items.reflect_ref(), ReflectRef::List(_)) {
+            return Err(format!("`{key}` merges into `{field}` which isn't a list"));
+        }
+
+        merges.push(ListMerge {
+            field: field.to_string(),
+            strategy,
+            items,
+        });
+    }
+
+    Ok(merges)
+}
+
+/// Merges the items of `merge` into the list field of the `data` struct.
+pub(crate) fn apply_list_merge(
+    data: &mut dyn PartialReflect,
+    merge: &ListMerge,
+) -> Result<(), String> {
+    let ReflectMut::Struct(data) = data.reflect_mut() else {
+        return Err("prototype data isn't a struct".to_string());
+    };
+
+    let Some(ReflectMut::List(list)) = data
+        .field_mut(&merge.field)
+        .map(PartialReflect::reflect_mut)
+    else {
+        return Err(format!("field `{}` isn't a list", merge.field));
+    };
+
+    let ReflectRef::List(items) = merge.items.reflect_ref() else {
+        return Err(format!("items merged into `{}` aren't a list", merge.field));
+    };
+
+    for item in items.iter() {
+        let existing = match &merge.strategy {
+            ListMergeStrategy::Append => None,
+            ListMergeStrategy::MergeByKey(key) => {
+                let item_key = struct_field(item, key)
+                    .ok_or_else(|| format!("item merged into `{}` has no `{key}`", merge.field))?;
+
+                (0..list.len()).find(|i| {
+                    list.get(*i)
+                        .and_then(|existing| struct_field(existing, key))
+                        .and_then(|existing_key| existing_key.reflect_partial_eq(item_key))
+                        .unwrap_or(false)
+                })
+            }
+        };
+
+        match existing.and_then(|i| list.get_mut(i)) {
+            Some(existing) => existing
+                .try_apply(item)
+                .map_err(|err| format!("failed to merge item into `{}`: {err}", merge.field))?,
+            None => list.push(item.to_dynamic()),
+        }
+    }
+
+    Ok(())
+}
+
+fn struct_field<'a>(value: &'a dyn PartialReflect, field: &str) -> Option<&'a dyn PartialReflect> {
+    match value.reflect_ref() {
+        ReflectRef::Struct(value) => value.field(field),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, Default, PartialEq, Reflect)]
+    struct Effect {
+        name: String,
+        power: u32,
+    }
+
+    #[derive(Debug, Clone, Default, PartialEq, Reflect)]
+    struct Sword {
+        effects: Vec<Effect>,
+    }
+
+    fn effect(name: &str, power: u32) -> Effect {
+        Effect {
+            name: name.to_string(),
+            power,
+        }
+    }
+
+    fn merge(key: &str, items: Vec<Effect>) -> ListMerge {
+        let (field, strategy) = ListMergeStrategy::parse(key).unwrap();
+
+        ListMerge {
+            field: field.to_string(),
+            strategy,
+            items: Box::new(items),
+        }
+    }
+
+    #[test]
+    fn suffixes_choose_the_merge_strategy() {
+        assert_eq!(
+            ListMergeStrategy::parse("effects+"),
+            Some(("effects", ListMergeStrategy::Append))
+        );
+        assert_eq!(
+            ListMergeStrategy::parse("effects@name"),
+            Some(("effects", ListMergeStrategy::MergeByKey("name".to_string())))
+        );
+        assert!(!is_list_merge_key("effects"));
+    }
+
+    #[test]
+    fn items_are_appended_or_merged_by_key() {
+        let mut sword = Sword {
+            effects: vec![effect("fire", 1), effect("ice", 2)],
+        };
+
+        apply_list_merge(&mut sword, &merge("effects+", vec![effect("fire", 3)])).unwrap();
+        assert_eq!(
+            sword.effects,
+            [effect("fire", 1), effect("ice", 2), effect("fire", 3)]
+        );
+
+        let mut sword = Sword {
+            effects: vec![effect("fire", 1), effect("ice", 2)],
+        };
+
+        apply_list_merge(
+            &mut sword,
+            &merge("effects@name", vec![effect("ice", 5), effect("poison", 1)]),
+        )
+        .unwrap();
+        assert_eq!(
+            sword.effects,
+            [effect("fire", 1), effect("ice", 5), effect("poison", 1)]
+        );
+    }
+
+    #[test]
+    fn merges_into_other_fields_are_rejected() {
+        let mut sword = Sword::default();
+
+        assert!(apply_list_merge(&mut sword, &merge("power+", Vec::new())).is_err());
+    }
+}
@@ -0,0 +1,221 @@
+use core::any::TypeId;
+
+use bevy::{
+    platform::collections::{HashMap, HashSet},
+    prelude::*,
+    reflect::{GetPath, PartialReflect},
+};
+
+use crate::{ErasedPrototypeId, registry::PrototypeRegistries};
+
+/// A field of a prototype, as its reflect path in the prototype, e.g. `data.damage`.
+pub(crate) type OverlayKey = (TypeId, ErasedPrototypeId, String);
+
+/// A field patched by an overlay, with the value it had in the base prototype.
+struct OverlaidField {
+    base: Box<dyn Reflect>,
+    value: Box<dyn Reflect>,
+}
+
+/// Fields of the prototypes patched in place at runtime, e.g. by the locale override files, that
+/// can be restored to the values of their base prototype.
+#[derive(Default)]
+pub(crate) struct PrototypeOverlay {
+    applied: HashMap<OverlayKey, OverlaidField>,
+    /// Fields already reported as invalid, so they aren't reported each time.
+    invalid: HashSet<OverlayKey>,
+}
+
+impl PrototypeOverlay {
+    /// Patches the `wanted` fields, given with the name of their prototype, and restores the base
+    /// values of the fields patched before and no longer wanted.
+    ///
+    /// `to_value` converts a wanted value into a value of the type of the field. Fields of
+    /// prototypes replaced since they were patched, e.g. by a reload, are patched again from their
+    /// new base value.
+    ///
+    /// Returns the modified prototypes.
+    pub(crate) fn apply<V>(
+        &mut self,
+        registries: &mut PrototypeRegistries,
+        wanted: HashMap<OverlayKey, (&str, V)>,
+        mut to_value: impl FnMut(&dyn PartialReflect, &V) -> Result<Box<dyn Reflect>, String>,
+    ) -> HashSet<(TypeId, ErasedPrototypeId)> {
+        let mut modified = HashSet::new();
+
+        self.applied.retain(|key @ (type_id, id, path), applied| {
+            let Some(field) = registries
+                .get_dyn_mut(type_id, id)
+                .and_then(|proto| proto.reflect_path_mut(path.as_str()).ok())
+            else {
+                return false;
+            };
+
+            if field.reflect_partial_eq(applied.value.as_partial_reflect()) != Some(true) {
+                return false;
+            }
+
+            if wanted.contains_key(key) {
+                return true;
+            }
+
+            if set_field(field, applied.base.as_ref()).is_ok() {
+                modified.insert((*type_id, *id));
+            }
+
+            false
+        });
+
+        for (key, (name, value)) in wanted {
+            let (type_id, id, path) = &key;
+
+            let Some(proto) = registries.get_dyn_mut(type_id, id) else {
+                continue;
+            };
+
+            let Ok(field) = proto.reflect_path_mut(path.as_str()) else {
+                self.report(&key, name, "unknown field");
+                continue;
+            };
+
+            let value = match to_value(field, &value) {
+                Ok(value) => value,
+                Err(err) => {
+                    self.report(&key, name, &err);
+                    continue;
+                }
+            };
+
+            let base = match self.applied.get(&key) {
+                Some(applied)
+                    if applied.value.reflect_partial_eq(value.as_partial_reflect())
+                        == Some(true) =>
+                {
+                    continue;
+                }
+                Some(applied) => applied.base.reflect_clone(),
+                None => field.reflect_clone(),
+            };
+
+            let result = base
+                .map_err(|err| err.to_string())
+                .and_then(|base| set_field(field, value.as_ref()).map(|()| base));
+
+            match result {
+                Ok(base) => {
+                    self.applied
+                        .insert(key.clone(), OverlaidField { base, value });
+                    modified.insert((*type_id, *id));
+                }
+                Err(err) => self.report(&key, name, &err),
+            }
+        }
+
+        modified
+    }
+
+    fn report(&mut self, key: &OverlayKey, name: &str, err: &str) {
+        if self.invalid.insert(key.clone()) {
+            warn!(
+                "Failed to override field {} of prototype {name}: {err}",
+                key.2.trim_start_matches("data.")
+            );
+        }
+    }
+}
+
+fn set_field(field: &mut dyn PartialReflect, value: &dyn Reflect) -> Result<(), String> {
+    let value = value.reflect_clone().map_err(|err| err.to_string())?;
+
+    field
+        .try_as_reflect_mut()
+        .ok_or_else(|| "the field isn't fully reflected".to_string())?
+        .set(value)
+        .map_err(|_| "mismatched types".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{JsonSchema, Prototype, PrototypeId};
+
+    #[derive(Debug, Clone, Default, Reflect, crate::Prototype, JsonSchema)]
+    #[proto(name = "card")]
+    struct Card {
+        cost: u32,
+    }
+
+    fn cost(registries: &PrototypeRegistries) -> u32 {
+        registries
+            .get(&PrototypeId::<Card>::from_name("goblin"))
+            .unwrap()
+            .cost
+    }
+
+    fn wanted(path: &str, cost: u32) -> HashMap<OverlayKey, (&'static str, u32)> {
+        let key = (
+            TypeId::of::<Card>(),
+            ErasedPrototypeId::from_name("goblin"),
+            path.to_string(),
+        );
+
+        HashMap::from_iter([(key, ("goblin", cost))])
+    }
+
+    fn to_value(_: &dyn PartialReflect, cost: &u32) -> Result<Box<dyn Reflect>, String> {
+        Ok(Box::new(*cost))
+    }
+
+    #[test]
+    fn overlaid_fields_are_restored_once_no_longer_wanted() {
+        let mut registries = PrototypeRegistries::default();
+        registries.new_registry::<Card>();
+        registries.insert(Prototype::for_test("goblin", &[], Card { cost: 1 }));
+
+        let mut overlay = PrototypeOverlay::default();
+        let goblin = (TypeId::of::<Card>(), ErasedPrototypeId::from_name("goblin"));
+
+        let modified = overlay.apply(&mut registries, wanted("data.cost", 5), to_value);
+        assert_eq!(cost(&registries), 5);
+        assert!(modified.contains(&goblin));
+
+        let modified = overlay.apply(&mut registries, wanted("data.cost", 5), to_value);
+        assert!(modified.is_empty());
+
+        let modified = overlay.apply(&mut registries, HashMap::default(), to_value);
+        assert_eq!(cost(&registries), 1);
+        assert!(modified.contains(&goblin));
+    }
+
+    #[test]
+    fn replaced_prototypes_are_overlaid_from_their_new_base() {
+        let mut registries = PrototypeRegistries::default();
+        registries.new_registry::<Card>();
+        registries.insert(Prototype::for_test("goblin", &[], Card { cost: 1 }));
+
+        let mut overlay = PrototypeOverlay::default();
+        overlay.apply(&mut registries, wanted("data.cost", 5), to_value);
+
+        // Reloaded.
+        registries.insert(Prototype::for_test("goblin", &[], Card { cost: 2 }));
+
+        overlay.apply(&mut registries, wanted("data.cost", 5), to_value);
+        assert_eq!(cost(&registries), 5);
+
+        overlay.apply(&mut registries, HashMap::default(), to_value);
+        assert_eq!(cost(&registries), 2);
+    }
+
+    #[test]
+    fn unknown_fields_are_left_alone() {
+        let mut registries = PrototypeRegistries::default();
+        registries.new_registry::<Card>();
+        registries.insert(Prototype::for_test("goblin", &[], Card { cost: 1 }));
+
+        let mut overlay = PrototypeOverlay::default();
+        let modified = overlay.apply(&mut registries, wanted("data.power", 5), to_value);
+
+        assert!(modified.is_empty());
+        assert_eq!(cost(&registries), 1);
+    }
+}
@@ -0,0 +1,950 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use serde_json::Value as JsonValue;
+use thiserror::Error;
+
+use crate::{
+    ErasedPrototypeId, PROTOTYPE_ASSET_EXTENSIONS, PROTOTYPE_KDL_EXTENSIONS,
+    derived_prototype_uuid, expand_entry, expand_fragments, is_ndjson_file, named_from_file,
+    read_kdl_file, read_ndjson, write_back_prototype,
+};
+
+/// Errors raised while reading prototype packs from disk.
+#[derive(Debug, Error)]
+pub enum PackError {
+    #[error("failed to read {path}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("failed to parse {path}: {source}")]
+    Json {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+    #[error("invalid prototype entry {index} in {path}: {reason}")]
+    InvalidEntry {
+        path: PathBuf,
+        index: usize,
+        reason: String,
+    },
+    #[error("invalid table {path}: {reason}")]
+    InvalidTable { path: PathBuf, reason: String },
+    #[error("failed to parse {path} at line {line}: {reason}")]
+    InvalidSource {
+        path: PathBuf,
+        line: usize,
+        reason: String,
+    },
+    #[error("{key} is defined in multiple files: {files:?}")]
+    Conflict {
+        key: PrototypeKey,
+        files: Vec<PathBuf>,
+    },
+}
+
+/// Identifies a prototype entry inside of a pack by its `type` and `name`, and the experiment
+/// group it's applied for, if any, see [`PrototypeExperiment`](crate::PrototypeExperiment).
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PrototypeKey {
+    pub ty: String,
+    pub name: String,
+    /// The name and group of the experiment of the entry.
+    pub experiment: Option<(String, String)>,
+}
+
+impl core::fmt::Display for PrototypeKey {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{} {}", self.ty, self.name)?;
+
+        match &self.experiment {
+            Some((experiment, group)) => write!(f, " ({experiment} {group})"),
+            None => Ok(()),
+        }
+    }
+}
+
+/// A raw prototype entry read from a pack, before any reflection happens.
+#[derive(Debug, Clone)]
+pub struct PackEntry {
+    pub key: PrototypeKey,
+    /// The whole entry, including its `type` and `name`.
+    pub value: JsonValue,
+    /// The file this entry comes from.
+    pub file: PathBuf,
+}
+
+/// Reads all the prototype entries of a pack, which is either a single prototypes file or a
+/// folder searched recursively for prototypes files.
+///
+/// Entries are sorted by file path and then by position in their file.
+pub fn read_pack(path: impl AsRef<Path>) -> Result<Vec<PackEntry>, PackError> {
+    let mut files = Vec::new();
+    collect_files(path.as_ref(), is_prototype_file, &mut files)?;
+    files.sort();
+
+    let mut entries = Vec::new();
+
+    for file in files {
+        entries.extend(read_pack_file(&file)?);
+    }
+
+    Ok(entries)
+}
+
+/// Reads the prototype entries of a single prototypes file.
+pub fn read_pack_file(path: impl AsRef<Path>) -> Result<Vec<PackEntry>, PackError> {
+    let path = path.as_ref();
+    let bytes = std::fs::read(path).map_err(|source| PackError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    let json_error = |source| PackError::Json {
+        path: path.to_path_buf(),
+        source,
+    };
+
+    let values = if let Some(entries) =
+        read_kdl_file(path, &bytes).map_err(|source| PackError::Io {
+            path: path.to_path_buf(),
+            source,
+        })? {
+        entries.into_iter().map(|(_, entry)| entry).collect()
+    } else if is_ndjson_file(path) {
+        read_ndjson(&bytes).map_err(json_error)?
+    } else {
+        match serde_json::from_slice(&bytes).map_err(json_error)? {
+            JsonValue::Array(values) => values,
+            value => vec![named_from_file(value, path)],
+        }
+    };
+
+    let values = expand_fragments(values).map_err(|(index, reason)| PackError::InvalidEntry {
+        path: path.to_path_buf(),
+        index,
+        reason,
+    })?;
+
+    let mut entries = Vec::new();
+
+    for (index, value) in values.into_iter().enumerate() {
+        let invalid = |reason: &str| PackError::InvalidEntry {
+            path: path.to_path_buf(),
+            index,
+            reason: reason.to_string(),
+        };
+
+        let Some(value) = value else {
+            continue;
+        };
+
+        for value in expand_entry(value).map_err(|reason| invalid(&reason))? {
+            let ty = value
+                .get("type")
+                .and_then(JsonValue::as_str)
+                .ok_or_else(|| invalid("missing string `type`"))?;
+            let name = value
+                .get("name")
+                .and_then(JsonValue::as_str)
+                .ok_or_else(|| invalid("missing string `name`"))?;
+            let experiment = match value.get("experiment") {
+                None => None,
+                Some(experiment) => {
+                    let field = |field| experiment.get(field).and_then(JsonValue::as_str);
+
+                    let (Some(experiment), Some(group)) = (field("name"), field("group")) else {
+                        return Err(invalid("`experiment` without string `name` and `group`"));
+                    };
+
+                    Some((experiment.to_string(), group.to_string()))
+                }
+            };
+
+            entries.push(PackEntry {
+                key: PrototypeKey {
+                    ty: ty.to_string(),
+                    name: name.to_string(),
+                    experiment,
+                },
+                file: path.to_path_buf(),
+                value,
+            });
+        }
+    }
+
+    Ok(entries)
+}
+
+pub(crate) fn is_prototype_file(path: &Path) -> bool {
+    let Some(file_name) = path.file_name().and_then(|name| name.to_str()) else {
+        return false;
+    };
+
+    PROTOTYPE_ASSET_EXTENSIONS
+        .iter()
+        .chain(PROTOTYPE_KDL_EXTENSIONS)
+        .any(|extension| file_name.ends_with(&format!(".{extension}")))
+}
+
+fn collect_files(
+    path: &Path,
+    filter: fn(&Path) -> bool,
+    files: &mut Vec<PathBuf>,
+) -> Result<(), PackError> {
+    let io_error = |source| PackError::Io {
+        path: path.to_path_buf(),
+        source,
+    };
+
+    if !path.is_dir() {
+        files.push(path.to_path_buf());
+        return Ok(());
+    }
+
+    for entry in std::fs::read_dir(path).map_err(io_error)? {
+        let entry_path = entry.map_err(io_error)?.path();
+
+        if entry_path.is_dir() {
+            collect_files(&entry_path, filter, files)?;
+        } else if filter(&entry_path) {
+            files.push(entry_path);
+        }
+    }
+
+    Ok(())
+}
+
+/// A change of a single field between two versions of a prototype.
+///
+/// `path` uses the reflection path syntax (`stats.damage`, `effects[1]`), `old` and `new` are
+/// `None` when the field is absent from the respective version.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldChange {
+    pub path: String,
+    pub old: Option<JsonValue>,
+    pub new: Option<JsonValue>,
+}
+
+impl core::fmt::Display for FieldChange {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let display = |value: &Option<JsonValue>| match value {
+            Some(value) => value.to_string(),
+            None => "<none>".to_string(),
+        };
+
+        write!(
+            f,
+            "{}: {} -> {}",
+            self.path,
+            display(&self.old),
+            display(&self.new)
+        )
+    }
+}
+
+/// A prototype present in both packs with different data.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChangedPrototype {
+    pub key: PrototypeKey,
+    pub changes: Vec<FieldChange>,
+}
+
+/// Differences between two prototype packs, see [`diff_packs`].
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct PackDiff {
+    pub added: Vec<PrototypeKey>,
+    pub removed: Vec<PrototypeKey>,
+    pub changed: Vec<ChangedPrototype>,
+}
+
+impl PackDiff {
+    /// Returns `true` if both packs define the same prototypes with the same data.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl core::fmt::Display for PackDiff {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for key in &self.added {
+            writeln!(f, "+ {key}")?;
+        }
+
+        for key in &self.removed {
+            writeln!(f, "- {key}")?;
+        }
+
+        for changed in &self.changed {
+            writeln!(f, "~ {}", changed.key)?;
+
+            for change in &changed.changes {
+                writeln!(f, "    {change}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Compares the prototypes of the `a` and `b` packs (see [`read_pack`]).
+///
+/// Prototypes are matched by `type` and `name`, those only in `b` are added and those only in `a`
+/// are removed. When a pack defines a prototype more than once the last definition is used.
+pub fn diff_packs(a: impl AsRef<Path>, b: impl AsRef<Path>) -> Result<PackDiff, PackError> {
+    let a = index_entries(read_pack(a)?);
+    let b = index_entries(read_pack(b)?);
+
+    let mut diff = PackDiff::default();
+
+    for (key, old) in &a {
+        match b.get(key) {
+            Some(new) => {
+                let mut changes = Vec::new();
+                diff_json(String::new(), Some(old), Some(new), &mut changes);
+
+                if !changes.is_empty() {
+                    diff.changed.push(ChangedPrototype {
+                        key: key.clone(),
+                        changes,
+                    });
+                }
+            }
+            None => diff.removed.push(key.clone()),
+        }
+    }
+
+    diff.added = b
+        .keys()
+        .filter(|key| !a.contains_key(key))
+        .cloned()
+        .collect();
+
+    Ok(diff)
+}
+
+fn index_entries(entries: Vec<PackEntry>) -> BTreeMap<PrototypeKey, JsonValue> {
+    entries
+        .into_iter()
+        .map(|entry| (entry.key, entry.value))
+        .collect()
+}
+
+/// Collects the field changes between `old` and `new` into `changes`.
+pub(crate) fn diff_json(
+    path: String,
+    old: Option<&JsonValue>,
+    new: Option<&JsonValue>,
+    changes: &mut Vec<FieldChange>,
+) {
+    match (old, new) {
+        (Some(JsonValue::Object(old)), Some(JsonValue::Object(new))) => {
+            for (key, old_value) in old {
+                let path = join_field_path(&path, key);
+                diff_json(path, Some(old_value), new.get(key), changes);
+            }
+
+            for (key, new_value) in new {
+                if !old.contains_key(key) {
+                    let path = join_field_path(&path, key);
+                    diff_json(path, None, Some(new_value), changes);
+                }
+            }
+        }
+        (Some(JsonValue::Array(old)), Some(JsonValue::Array(new))) if old.len() == new.len() => {
+            for (i, (old, new)) in old.iter().zip(new).enumerate() {
+                diff_json(format!("{path}[{i}]"), Some(old), Some(new), changes);
+            }
+        }
+        (old, new) if old != new => changes.push(FieldChange {
+            path,
+            old: old.cloned(),
+            new: new.cloned(),
+        }),
+        _ => {}
+    }
+}
+
+pub(crate) fn join_field_path(path: &str, field: &str) -> String {
+    if path.is_empty() {
+        field.to_string()
+    } else {
+        format!("{path}.{field}")
+    }
+}
+
+/// How [`merge_packs`] resolves prototypes defined by more than one input.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum MergeStrategy {
+    /// Keep the definition of the last input defining the prototype.
+    #[default]
+    LastWins,
+    /// Fail with [`PackError::Conflict`] on the first conflicting prototype.
+    Error,
+}
+
+/// A prototype defined more than once across the merged inputs.
+#[derive(Debug, Clone)]
+pub struct MergeConflict {
+    pub key: PrototypeKey,
+    /// Every definition of the prototype, in inputs order.
+    pub entries: Vec<PackEntry>,
+}
+
+/// Summary of a [`merge_packs`] operation.
+#[derive(Debug, Default, Clone)]
+pub struct MergeReport {
+    /// Number of prototypes written to the output pack.
+    pub written: usize,
+    /// The conflicting prototypes and the file of the definition that was kept.
+    pub resolved: Vec<(PrototypeKey, PathBuf)>,
+}
+
+/// Merges the prototypes of several packs (see [`read_pack`]) into a single prototypes file.
+///
+/// Prototypes keep the position of their first definition, conflicts are resolved following
+/// `strategy`.
+pub fn merge_packs(
+    inputs: &[impl AsRef<Path>],
+    output: impl AsRef<Path>,
+    strategy: MergeStrategy,
+) -> Result<MergeReport, PackError> {
+    merge_packs_with(inputs, output, |conflict| match strategy {
+        MergeStrategy::LastWins => Ok(conflict.entries.len() - 1),
+        MergeStrategy::Error => Err(PackError::Conflict {
+            key: conflict.key.clone(),
+            files: conflict
+                .entries
+                .iter()
+                .map(|entry| entry.file.clone())
+                .collect(),
+        }),
+    })
+}
+
+/// Same as [`merge_packs`] but conflicts are resolved by `resolve`, returning the index of the
+/// definition to keep in [`MergeConflict::entries`], e.g. after asking the user.
+pub fn merge_packs_with(
+    inputs: &[impl AsRef<Path>],
+    output: impl AsRef<Path>,
+    mut resolve: impl FnMut(&MergeConflict) -> Result<usize, PackError>,
+) -> Result<MergeReport, PackError> {
+    let mut order = Vec::<PrototypeKey>::new();
+    let mut definitions = std::collections::HashMap::<PrototypeKey, Vec<PackEntry>>::new();
+
+    for input in inputs {
+        for entry in read_pack(input)? {
+            let entries = definitions.entry(entry.key.clone()).or_default();
+
+            if entries.is_empty() {
+                order.push(entry.key.clone());
+            }
+
+            entries.push(entry);
+        }
+    }
+
+    let mut report = MergeReport::default();
+    let mut merged = Vec::with_capacity(order.len());
+
+    for key in order {
+        let mut entries = definitions.remove(&key).unwrap_or_default();
+
+        let index = if entries.len() > 1 {
+            let conflict = MergeConflict { key, entries };
+            let index = resolve(&conflict)?.min(conflict.entries.len() - 1);
+            entries = conflict.entries;
+            report
+                .resolved
+                .push((conflict.key, entries[index].file.clone()));
+            index
+        } else {
+            0
+        };
+
+        merged.push(entries.swap_remove(index).value);
+    }
+
+    let output = output.as_ref();
+    let io_error = |source| PackError::Io {
+        path: output.to_path_buf(),
+        source,
+    };
+
+    let mut bytes = crate::to_json_pretty(&merged).map_err(io_error)?;
+    bytes.push(b'\n');
+    std::fs::write(output, bytes).map_err(io_error)?;
+
+    report.written = merged.len();
+
+    Ok(report)
+}
+
+/// A prototype name used with [`proto_id!`](crate::proto_id) or [`proto_ids!`](crate::proto_ids).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProtoIdReference {
+    pub file: PathBuf,
+    pub line: usize,
+    /// The Rust type of the prototype, as written in the macro.
+    pub ty: String,
+    pub name: String,
+}
+
+impl core::fmt::Display for ProtoIdReference {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{}:{}: {} {}",
+            self.file.display(),
+            self.line,
+            self.ty,
+            self.name
+        )
+    }
+}
+
+/// Writes a `"uuid"` into the entries of the prototypes of the `pack` (see [`read_pack`]) that
+/// have none, so their [`Prototype::uuid`](crate::Prototype::uuid) is kept once renamed.
+///
+/// The written uuid is the one derived from the type and name of the prototype (see
+/// [`derived_prototype_uuid`]), the uuid of the prototypes doesn't change. Entries expanded into
+/// several prototypes (`names`, `generate`), the entries of experiments and the tables and KDL
+/// files are left as they are.
+///
+/// Returns the number of written uuids.
+pub fn assign_prototype_uuids(pack: impl AsRef<Path>) -> Result<usize, PackError> {
+    let mut written = 0;
+
+    for entry in read_pack(pack)? {
+        let is_json = entry
+            .file
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| {
+                PROTOTYPE_ASSET_EXTENSIONS
+                    .iter()
+                    .any(|extension| name.ends_with(&format!(".{extension}")))
+            });
+
+        if !is_json || entry.key.experiment.is_some() || entry.value.get("uuid").is_some() {
+            continue;
+        }
+
+        let uuid =
+            derived_prototype_uuid(&entry.key.ty, ErasedPrototypeId::from_name(&entry.key.name));
+
+        match write_back_prototype(
+            &entry.file,
+            &entry.key.ty,
+            &entry.key.name,
+            &serde_json::json!({ "uuid": uuid }),
+        ) {
+            Ok(()) => written += 1,
+            // Expanded entries have no `name` to find them by.
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+            Err(source) => {
+                return Err(PackError::Io {
+                    path: entry.file,
+                    source,
+                });
+            }
+        }
+    }
+
+    Ok(written)
+}
+
+/// Finds the prototypes referenced with [`proto_id!`](crate::proto_id) and
+/// [`proto_ids!`](crate::proto_ids) in the Rust files of `src` that aren't in the `pack` (see
+/// [`read_pack`]).
+///
+/// The Rust types written in the macros are matched with the `type` of the prototypes through
+/// the `#[proto(name = "...")]` of the `#[derive(Prototype)]` structs of `src`, the references
+/// to other types, e.g. generic prototypes, are only checked by name.
+pub fn find_unknown_proto_ids(
+    src: impl AsRef<Path>,
+    pack: impl AsRef<Path>,
+) -> Result<Vec<ProtoIdReference>, PackError> {
+    let keys = read_pack(pack)?
+        .into_iter()
+        .map(|entry| (entry.key.ty, entry.key.name))
+        .collect::<std::collections::HashSet<_>>();
+    let names = keys
+        .iter()
+        .map(|(_, name)| name.as_str())
+        .collect::<std::collections::HashSet<_>>();
+
+    let mut files = Vec::new();
+    collect_files(
+        src.as_ref(),
+        |path| path.extension().is_some_and(|extension| extension == "rs"),
+        &mut files,
+    )?;
+    files.sort();
+
+    let mut scan = ProtoIdScan::default();
+
+    for file in &files {
+        let source = std::fs::read_to_string(file).map_err(|source| PackError::Io {
+            path: file.clone(),
+            source,
+        })?;
+        let syntax = syn::parse_file(&source).map_err(|err| PackError::InvalidSource {
+            path: file.clone(),
+            line: err.span().start().line,
+            reason: err.to_string(),
+        })?;
+
+        scan.file = file.clone();
+        syn::visit::visit_file(&mut scan, &syntax);
+    }
+
+    let unknown = scan
+        .references
+        .into_iter()
+        .filter(|reference| {
+            let ident = type_ident(&reference.ty);
+
+            match scan.prototype_names.get(ident) {
+                Some(types) => !types
+                    .iter()
+                    .any(|ty| keys.contains(&(ty.clone(), reference.name.clone()))),
+                None => !names.contains(reference.name.as_str()),
+            }
+        })
+        .collect();
+
+    Ok(unknown)
+}
+
+/// Returns the name of a type as written in a macro without its path and generics, e.g. `Sword`
+/// for `crate::items::Sword`.
+fn type_ident(ty: &str) -> &str {
+    let ty = ty.split('<').next().unwrap_or(ty);
+    ty.rsplit("::").next().unwrap_or(ty)
+}
+
+/// Collects the `proto_id!(Type, "name")` and `proto_ids! { ID: Type = "name"; }` of Rust files,
+/// and the prototype names of their `#[derive(Prototype)]` structs.
+#[derive(Default)]
+struct ProtoIdScan {
+    file: PathBuf,
+    references: Vec<ProtoIdReference>,
+    /// Prototype names of the prototype structs without generics, by struct name.
+    prototype_names: std::collections::HashMap<String, Vec<String>>,
+}
+
+impl ProtoIdScan {
+    /// Collects the macros of `tokens`, including the ones passed to other macros.
+    fn scan_tokens(&mut self, tokens: proc_macro2::TokenStream) {
+        let mut tokens = tokens.into_iter().peekable();
+
+        while let Some(token) = tokens.next() {
+            let macro_name = match &token {
+                proc_macro2::TokenTree::Ident(ident) => ident.to_string(),
+                proc_macro2::TokenTree::Group(group) => {
+                    self.scan_tokens(group.stream());
+                    continue;
+                }
+                _ => continue,
+            };
+
+            if !matches!(
+                tokens.peek(),
+                Some(proc_macro2::TokenTree::Punct(punct)) if punct.as_char() == '!'
+            ) {
+                continue;
+            }
+
+            tokens.next();
+
+            if let Some(proc_macro2::TokenTree::Group(group)) = tokens.next() {
+                self.scan_macro(&macro_name, group.stream());
+            }
+        }
+    }
+
+    /// Collects the references of a `proto_id!` or `proto_ids!` invocation and the macros of the
+    /// arguments of the other macros. Invocations that don't parse, e.g. the ones of the
+    /// `macro_rules!` definitions, are skipped.
+    fn scan_macro(&mut self, name: &str, tokens: proc_macro2::TokenStream) {
+        let parsed = match name {
+            "proto_id" => syn::parse2::<ProtoIdArgs>(tokens.clone()).map(|args| vec![args]),
+            "proto_ids" => syn::parse2::<ProtoIdsArgs>(tokens.clone()).map(|args| args.0),
+            _ => {
+                self.scan_tokens(tokens);
+                return;
+            }
+        };
+
+        let Ok(references) = parsed else {
+            self.scan_tokens(tokens);
+            return;
+        };
+
+        self.references.extend(references.into_iter().map(|args| {
+            ProtoIdReference {
+                file: self.file.clone(),
+                line: args.name.span().start().line,
+                ty: quote::ToTokens::to_token_stream(&args.ty)
+                    .to_string()
+                    .replace(' ', ""),
+                name: args.name.value(),
+            }
+        }));
+    }
+}
+
+impl<'ast> syn::visit::Visit<'ast> for ProtoIdScan {
+    fn visit_macro(&mut self, mac: &'ast syn::Macro) {
+        let name = mac
+            .path
+            .segments
+            .last()
+            .map(|segment| segment.ident.to_string())
+            .unwrap_or_default();
+
+        self.scan_macro(&name, mac.tokens.clone());
+    }
+
+    fn visit_item_struct(&mut self, item: &'ast syn::ItemStruct) {
+        if item.generics.params.is_empty()
+            && is_prototype_derive(&item.attrs)
+            && let Some(name) = proto_name(&item.attrs)
+        {
+            self.prototype_names
+                .entry(item.ident.to_string())
+                .or_default()
+                .push(name);
+        }
+
+        syn::visit::visit_item_struct(self, item);
+    }
+}
+
+/// Returns `true` if the attributes derive `Prototype`.
+fn is_prototype_derive(attrs: &[syn::Attribute]) -> bool {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("derive"))
+        .any(|attr| {
+            attr.parse_args_with(
+                syn::punctuated::Punctuated::<syn::Path, syn::Token![,]>::parse_terminated,
+            )
+            .is_ok_and(|paths| {
+                paths.iter().any(|path| {
+                    path.segments
+                        .last()
+                        .is_some_and(|last| last.ident == "Prototype")
+                })
+            })
+        })
+}
+
+/// Returns the `name` of the `#[proto(...)]` attribute.
+fn proto_name(attrs: &[syn::Attribute]) -> Option<String> {
+    let mut name = None;
+
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("proto")) {
+        // The other arguments are checked by the derive.
+        let _ = attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                name = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+            } else if meta.input.peek(syn::Token![=]) {
+                meta.value()?.parse::<syn::Expr>()?;
+            } else if meta.input.peek(syn::token::Paren) {
+                meta.parse_nested_meta(|_| Ok(()))?;
+            }
+
+            Ok(())
+        });
+    }
+
+    name
+}
+
+/// The arguments of `proto_id!(Type, "name")`.
+struct ProtoIdArgs {
+    ty: syn::Type,
+    name: syn::LitStr,
+}
+
+impl syn::parse::Parse for ProtoIdArgs {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let ty = input.parse()?;
+        input.parse::<syn::Token![,]>()?;
+        let name = input.parse()?;
+        input.parse::<Option<syn::Token![,]>>()?;
+
+        Ok(Self { ty, name })
+    }
+}
+
+/// The declarations of `proto_ids! { pub ID: Type = "name"; }`.
+struct ProtoIdsArgs(Vec<ProtoIdArgs>);
+
+impl syn::parse::Parse for ProtoIdsArgs {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let mut declarations = Vec::new();
+
+        while !input.is_empty() {
+            input.parse::<syn::Visibility>()?;
+            input.parse::<syn::Ident>()?;
+            input.parse::<syn::Token![:]>()?;
+            let ty = input.parse()?;
+            input.parse::<syn::Token![=]>()?;
+            let name = input.parse()?;
+            input.parse::<syn::Token![;]>()?;
+
+            declarations.push(ProtoIdArgs { ty, name });
+        }
+
+        Ok(Self(declarations))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scan(source: &str) -> ProtoIdScan {
+        let mut scan = ProtoIdScan::default();
+        syn::visit::visit_file(&mut scan, &syn::parse_file(source).unwrap());
+        scan
+    }
+
+    fn references(source: &str) -> Vec<(usize, String, String)> {
+        scan(source)
+            .references
+            .into_iter()
+            .map(|reference| (reference.line, reference.ty, reference.name))
+            .collect()
+    }
+
+    #[test]
+    fn every_invocation_style_is_found() {
+        let source = r#"
+const A: PrototypeId<Sword> = proto_id!(Sword, "a");
+const B: PrototypeId<Sword> = proto_id! (Sword, "b");
+const C: PrototypeId<Sword> = proto_id![Sword, "c",];
+const D: PrototypeId<Sword> = bevy_histrion_proto::proto_id!{crate::items::Sword, "d"};
+
+proto_ids! {
+    pub E: Sword = "e";
+    F: Stat<Strength> = "f";
+}
+
+fn ids() -> Vec<PrototypeId<Effect>> {
+    vec![proto_id!(Effect, "g"), proto_id!(Wrapper<(u8, u16)>, "h")]
+}
+"#;
+
+        assert_eq!(
+            references(source),
+            [
+                (2, "Sword".to_string(), "a".to_string()),
+                (3, "Sword".to_string(), "b".to_string()),
+                (4, "Sword".to_string(), "c".to_string()),
+                (5, "crate::items::Sword".to_string(), "d".to_string()),
+                (8, "Sword".to_string(), "e".to_string()),
+                (9, "Stat<Strength>".to_string(), "f".to_string()),
+                (13, "Effect".to_string(), "g".to_string()),
+                (13, "Wrapper<(u8,u16)>".to_string(), "h".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn comments_and_macro_definitions_are_skipped() {
+        let source = r#"
+// proto_id!(Sword, "comment");
+/* proto_id!(Sword, "block") */
+/// `proto_id!(Sword, "doc")`
+#[doc = "proto_id!(Sword, \"attribute\")"]
+macro_rules! proto_id {
+    ($ty:ty, $name:expr $(,)?) => {
+        const { $crate::PrototypeId::<$ty>::from_name($name) }
+    };
+}
+macro_rules! ids {
+    ($($name:literal),*) => { [$(proto_id!(Sword, $name)),*] };
+}
+const NAME: &str = "proto_id!(Sword, \"string\")";
+"#;
+
+        assert_eq!(references(source), []);
+    }
+
+    #[test]
+    fn prototype_structs_give_their_type() {
+        let source = r#"
+#[derive(Clone, Reflect, bevy_histrion_proto::Prototype)]
+#[proto(name = "sword", allowed_tags("weapon"), ids)]
+struct Sword {}
+
+#[derive(Prototype)]
+#[proto(name = "{T}_stat")]
+struct Stat<T> {}
+
+#[derive(Clone)]
+#[proto(name = "not_a_prototype")]
+struct Other {}
+"#;
+
+        let names = scan(source).prototype_names;
+
+        assert_eq!(names.len(), 1);
+        assert_eq!(names["Sword"], ["sword"]);
+    }
+
+    #[test]
+    fn references_are_matched_with_the_type_of_the_prototypes() {
+        let folder = std::env::temp_dir().join(format!("bhp_proto_ids_{}", std::process::id()));
+        let (src, pack) = (folder.join("src"), folder.join("pack"));
+        std::fs::create_dir_all(&src).unwrap();
+        std::fs::create_dir_all(&pack).unwrap();
+
+        std::fs::write(
+            pack.join("all.proto.json"),
+            r#"[
+                { "type": "sword", "name": "iron" },
+                { "type": "effect", "name": "burn" },
+                { "type": "stat<strength>", "name": "base" }
+            ]"#,
+        )
+        .unwrap();
+        std::fs::write(
+            src.join("lib.rs"),
+            r#"
+#[derive(Prototype)]
+#[proto(name = "sword")]
+struct Sword {}
+
+const IRON: PrototypeId<Sword> = proto_id!(Sword, "iron");
+const BURN: PrototypeId<Sword> = proto_id!(items::Sword, "burn");
+const BASE: PrototypeId<Stat<Strength>> = proto_id!(Stat<Strength>, "base");
+const MISSING: PrototypeId<Stat<Strength>> = proto_id!(Stat<Strength>, "missing");
+"#,
+        )
+        .unwrap();
+
+        let unknown = find_unknown_proto_ids(&src, &pack);
+        std::fs::remove_dir_all(&folder).unwrap();
+
+        let unknown = unknown
+            .unwrap()
+            .into_iter()
+            .map(|reference| (reference.ty, reference.name))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            unknown,
+            [
+                ("items::Sword".to_string(), "burn".to_string()),
+                ("Stat<Strength>".to_string(), "missing".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,382 @@
+use std::{collections::BTreeMap, path::Path};
+
+use bevy::{
+    asset::{AssetLoadFailedEvent, AssetLoader, AssetPath, LoadContext, io::Reader as AssetReader},
+    platform::collections::HashSet,
+    prelude::*,
+    reflect::ReflectRef,
+};
+use semver::{Version, VersionReq};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    AppPrototypeTypeRegistry, LoadingPackManifests, LoadingPrototypesHandles, PrototypeSource,
+    registry::PrototypeRegistries,
+};
+
+/// Name of the file describing the pack of a prototypes folder, see [`PackInfo`].
+pub const PACK_INFO_FILE: &str = "pack.json";
+
+/// The description of a pack of prototypes, read from the optional [`PACK_INFO_FILE`] of its
+/// folder when the folder is loaded, e.g. for mod managers and debug UIs:
+///
+/// ```json
+/// {
+///     "name": "mega_dlc",
+///     "version": "1.2.0",
+///     "author": "Histrion",
+///     "counts": { "sword": 12, "effect": 4 },
+///     "dependencies": { "base": "^1.0" },
+///     "game_data_version": "^2.1"
+/// }
+/// ```
+///
+/// The loaded packs are listed by the [`LoadedPacks`] resource.
+#[derive(Asset, TypePath, Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PackInfo {
+    pub name: String,
+    /// Version of the pack, `MAJOR.MINOR.PATCH`.
+    pub version: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    /// Expected number of prototypes of each prototype type in the folder, a warning is logged
+    /// when the loaded prototypes don't match.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub counts: BTreeMap<String, usize>,
+    /// Version requirement of each pack this pack depends on, keyed by pack name, e.g. `^1.2`,
+    /// `~1.2`, `>=1.0.3, <2`, `=2.0.0` or `*`, like the cargo requirements. Bare versions are
+    /// caret requirements, the pack file fails to load with an invalid requirement.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub dependencies: BTreeMap<String, String>,
+    /// Requirement on the [`PrototypeGameDataVersion`] of the app, like the
+    /// [`PackInfo::dependencies`]. The prototypes files of incompatible packs aren't applied.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub game_data_version: Option<String>,
+}
+
+/// The version of the prototypes data the app reads, set with
+/// [`PrototypeAppExt::set_game_data_version`](crate::PrototypeAppExt::set_game_data_version)
+/// and checked against the [`PackInfo::game_data_version`] requirement of the packs.
+#[derive(Resource, Debug, Clone, PartialEq, Eq, Deref)]
+pub struct PrototypeGameDataVersion(pub String);
+
+/// Sent when the [`PackInfo::game_data_version`] requirement of a pack doesn't match the
+/// [`PrototypeGameDataVersion`] of the app, the prototypes files of the pack are skipped.
+#[derive(Event, Debug, Clone, PartialEq, Eq)]
+pub struct IncompatiblePackEvent {
+    pub pack: String,
+    pub folder: AssetPath<'static>,
+    pub requirement: String,
+    pub game_data_version: String,
+}
+
+/// A pack whose [`PACK_INFO_FILE`] was loaded, see [`LoadedPacks`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoadedPack {
+    pub info: PackInfo,
+    /// Folder of the pack.
+    pub folder: AssetPath<'static>,
+    /// Number of loaded prototypes of each prototype type in the folder, counted once every
+    /// requested prototypes file is applied.
+    pub counts: BTreeMap<String, usize>,
+    /// Whether the prototypes files of the pack are skipped, see [`IncompatiblePackEvent`].
+    pub skipped: bool,
+}
+
+/// The packs of the loaded prototypes folders having a [`PACK_INFO_FILE`], in their load order.
+#[derive(Resource, Debug, Default)]
+pub struct LoadedPacks {
+    packs: Vec<LoadedPack>,
+    handles: Vec<Handle<PackInfo>>,
+    /// Folders whose pack file is loading, their prototypes files wait for it.
+    pending: HashSet<AssetPath<'static>>,
+    /// Whether the packs were checked since the last pack was loaded.
+    verified: bool,
+}
+
+impl LoadedPacks {
+    /// Returns the `name` pack, if it's loaded.
+    pub fn get(&self, name: &str) -> Option<&LoadedPack> {
+        self.packs.iter().find(|pack| pack.info.name == name)
+    }
+
+    /// Iterates over the loaded packs, in their load order.
+    pub fn iter(&self) -> impl Iterator<Item = &LoadedPack> {
+        self.packs.iter()
+    }
+
+    /// Returns the dependencies of the `name` pack that aren't loaded or whose version doesn't
+    /// match the requirement, with their requirement.
+    pub fn unmet_dependencies(&self, name: &str) -> Vec<(&str, &str)> {
+        let Some(pack) = self.get(name) else {
+            return Vec::new();
+        };
+
+        pack.info
+            .dependencies
+            .iter()
+            .filter(|(dependency, requirement)| {
+                self.get(dependency).is_none_or(|dependency| {
+                    dependency.skipped || !version_matches(requirement, &dependency.info.version)
+                })
+            })
+            .map(|(dependency, requirement)| (dependency.as_str(), requirement.as_str()))
+            .collect()
+    }
+
+    pub(crate) fn add(&mut self, handle: Handle<PackInfo>) {
+        if self.handles.contains(&handle) {
+            return;
+        }
+
+        if let Some(folder) = handle.path().and_then(AssetPath::parent) {
+            self.pending.insert(folder);
+        }
+
+        self.handles.push(handle);
+    }
+
+    /// Returns `true` while the pack file of the folder of the `file` prototypes file is loading.
+    pub(crate) fn is_pending(&self, file: &AssetPath) -> bool {
+        file.parent()
+            .is_some_and(|folder| self.pending.contains(&folder))
+    }
+
+    /// Returns `true` if the `file` prototypes file belongs to a skipped pack.
+    pub(crate) fn is_skipped(&self, file: &AssetPath) -> bool {
+        let folder = file.parent();
+
+        self.packs
+            .iter()
+            .any(|pack| pack.skipped && folder.as_ref() == Some(&pack.folder))
+    }
+}
+
+/// Returns `true` for the [`PACK_INFO_FILE`] of a folder.
+pub(crate) fn is_pack_info_file(path: &Path) -> bool {
+    path.file_name().is_some_and(|name| name == PACK_INFO_FILE)
+}
+
+/// Returns `true` if `version` satisfies `requirement`, see [`PackInfo::dependencies`].
+///
+/// The requirements of the loaded [`PackInfo`]s are valid, `false` is returned for the invalid
+/// requirements or versions.
+fn version_matches(requirement: &str, version: &str) -> bool {
+    VersionReq::parse(requirement).is_ok_and(|requirement| {
+        Version::parse(version.trim()).is_ok_and(|version| requirement.matches(&version))
+    })
+}
+
+impl PackInfo {
+    /// Returns an error naming the first requirement that isn't a valid version requirement.
+    fn validate_requirements(&self) -> Result<(), String> {
+        for (dependency, requirement) in &self.dependencies {
+            VersionReq::parse(requirement).map_err(|err| {
+                format!(
+                    "invalid version requirement {requirement:?} of dependency {dependency}: {err}"
+                )
+            })?;
+        }
+
+        if let Some(requirement) = &self.game_data_version {
+            VersionReq::parse(requirement).map_err(|err| {
+                format!("invalid game data version requirement {requirement:?}: {err}")
+            })?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct PackInfoLoader;
+
+impl AssetLoader for PackInfoLoader {
+    type Asset = PackInfo;
+    type Settings = ();
+    type Error = std::io::Error;
+
+    async fn load(
+        &self,
+        reader: &mut dyn AssetReader,
+        _settings: &Self::Settings,
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        let info = serde_json::from_slice::<PackInfo>(&bytes)?;
+
+        info.validate_requirements()
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))?;
+
+        Ok(info)
+    }
+
+    // Loaded as typed assets, `pack.json` files aren't claimed by extension.
+    fn extensions(&self) -> &[&str] {
+        &[]
+    }
+}
+
+/// Adds the loaded and reloaded [`PackInfo`]s to the [`LoadedPacks`].
+pub(crate) fn on_pack_info_loaded(
+    mut events_rx: EventReader<AssetEvent<PackInfo>>,
+    mut failed_events_rx: EventReader<AssetLoadFailedEvent<PackInfo>>,
+    assets: Res<Assets<PackInfo>>,
+    mut packs: ResMut<LoadedPacks>,
+    game_data_version: Option<Res<PrototypeGameDataVersion>>,
+    mut incompatible_tx: EventWriter<IncompatiblePackEvent>,
+) {
+    for AssetLoadFailedEvent { path, error, .. } in failed_events_rx.read() {
+        error!("Failed to load pack file {path}: {error}");
+
+        if let Some(folder) = path.parent() {
+            packs.pending.remove(&folder);
+        }
+    }
+
+    for event in events_rx.read() {
+        let (AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id }) = event
+        else {
+            continue;
+        };
+
+        let Some(handle) = packs.handles.iter().find(|handle| handle.id() == *id) else {
+            continue;
+        };
+
+        let (Some(info), Some(path)) = (assets.get(*id), handle.path()) else {
+            continue;
+        };
+
+        let folder = path.parent().unwrap_or_default();
+
+        if Version::parse(info.version.trim()).is_err() {
+            warn!(
+                "Pack {} at {path} has an invalid version {}, expected MAJOR.MINOR.PATCH",
+                info.name, info.version
+            );
+        }
+
+        if let Some(other) = packs
+            .packs
+            .iter()
+            .find(|pack| pack.info.name == info.name && pack.folder != folder)
+        {
+            warn!(
+                "Pack {} at {path} has the name of the pack of {}",
+                info.name, other.folder
+            );
+        }
+
+        let requirement = info
+            .game_data_version
+            .as_ref()
+            .zip(game_data_version.as_ref());
+        let skipped = requirement
+            .is_some_and(|(requirement, version)| !version_matches(requirement, version));
+
+        if let Some((requirement, version)) = requirement
+            && skipped
+        {
+            warn!(
+                "Pack {} at {path} needs the game data version {requirement}, the app has {}, its prototypes are skipped",
+                info.name, version.0
+            );
+
+            incompatible_tx.write(IncompatiblePackEvent {
+                pack: info.name.clone(),
+                folder: folder.clone(),
+                requirement: requirement.clone(),
+                game_data_version: version.0.clone(),
+            });
+        }
+
+        packs.pending.remove(&folder);
+
+        let pack = LoadedPack {
+            info: info.clone(),
+            folder,
+            counts: BTreeMap::new(),
+            skipped,
+        };
+
+        match packs
+            .packs
+            .iter_mut()
+            .find(|other| other.folder == pack.folder)
+        {
+            Some(other) => *other = pack,
+            None => packs.packs.push(pack),
+        }
+
+        packs.verified = false;
+    }
+}
+
+/// Counts the prototypes of each pack once every requested prototypes file is applied, warning
+/// about the counts that don't match the declared ones and the unmet dependencies.
+pub(crate) fn verify_loaded_packs(
+    mut packs: ResMut<LoadedPacks>,
+    registries: Res<PrototypeRegistries>,
+    prototype_types: Res<AppPrototypeTypeRegistry>,
+    loading_prototypes_handles: Res<LoadingPrototypesHandles>,
+    loading_pack_manifests: Res<LoadingPackManifests>,
+) {
+    if packs.verified
+        || !loading_prototypes_handles.is_empty()
+        || !loading_pack_manifests.is_empty()
+    {
+        return;
+    }
+
+    packs.verified = true;
+
+    let prototype_types = prototype_types.0.read();
+
+    for pack in packs.packs.iter_mut().filter(|pack| !pack.skipped) {
+        pack.counts.clear();
+
+        for (ty, type_id) in prototype_types.iter() {
+            let count = registries
+                .iter_dyn(type_id)
+                .filter(|proto| {
+                    let ReflectRef::Struct(proto) = proto.reflect_ref() else {
+                        return false;
+                    };
+
+                    proto
+                        .field("source")
+                        .and_then(<dyn PartialReflect>::try_downcast_ref::<Option<PrototypeSource>>)
+                        .and_then(Option::as_ref)
+                        .is_some_and(|source| source.path.parent().as_ref() == Some(&pack.folder))
+                })
+                .count();
+
+            if count > 0 {
+                pack.counts.insert(ty.to_string(), count);
+            }
+        }
+
+        for (ty, expected) in &pack.info.counts {
+            let count = pack.counts.get(ty).copied().unwrap_or_default();
+
+            if count != *expected {
+                warn!(
+                    "Pack {} declares {expected} {ty} prototypes, {count} are loaded from {}",
+                    pack.info.name, pack.folder
+                );
+            }
+        }
+    }
+
+    for pack in packs.packs.iter().filter(|pack| !pack.skipped) {
+        for (dependency, requirement) in packs.unmet_dependencies(&pack.info.name) {
+            warn!(
+                "Pack {} depends on pack {dependency} {requirement}, it isn't loaded or has another version",
+                pack.info.name
+            );
+        }
+    }
+}
@@ -0,0 +1,312 @@
+use core::any::TypeId;
+use std::borrow::Cow;
+
+use bevy::{
+    prelude::*,
+    reflect::{TypeInfo, TypeRegistry, VariantInfo},
+};
+use serde_json::{Number, value::RawValue};
+
+use crate::{
+    LintSeverity,
+    pack::join_field_path,
+    raw::{RawFields, raw_array, raw_number, raw_object},
+};
+
+/// How the numbers of the prototypes files that lose precision when applied to their field are
+/// reported, e.g. `0.30000000000000004` into a `f32` or `16777217` into a `f32`, `Warn` by
+/// default.
+///
+/// With [`LintSeverity::Deny`] the prototypes with such numbers are skipped, the warnings and
+/// skipped prototypes of the [`PrototypeLoadReport`](crate::PrototypeLoadReport) give the field
+/// path of each number.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Deref, DerefMut)]
+pub struct PrototypeNumberPrecision(pub LintSeverity);
+
+impl Default for PrototypeNumberPrecision {
+    fn default() -> Self {
+        Self(LintSeverity::Warn)
+    }
+}
+
+/// Returns the numbers of the `fields` of an entry losing precision, or out of range, when
+/// deserialized as `type_id`, described with their field path.
+pub(crate) fn lossy_numbers(
+    fields: &RawFields<'_>,
+    type_id: TypeId,
+    registry: &TypeRegistry,
+) -> Vec<String> {
+    let mut lossy = Vec::new();
+    collect_lossy_numbers(
+        Json::Fields(fields),
+        type_id,
+        registry,
+        String::new(),
+        &mut lossy,
+    );
+    lossy
+}
+
+/// A JSON value of an entry, the fields of the entry at the top level.
+#[derive(Clone, Copy)]
+enum Json<'a> {
+    Raw(&'a RawValue),
+    Fields(&'a RawFields<'a>),
+}
+
+impl<'a> Json<'a> {
+    fn fields(self) -> Option<Vec<(Cow<'a, str>, &'a RawValue)>> {
+        match self {
+            Self::Raw(raw) => raw_object(raw),
+            Self::Fields(fields) => Some(
+                fields
+                    .iter()
+                    .map(|(name, value)| (Cow::Borrowed(&**name), &**value))
+                    .collect(),
+            ),
+        }
+    }
+
+    fn items(self) -> Option<Vec<&'a RawValue>> {
+        match self {
+            Self::Raw(raw) => raw_array(raw),
+            Self::Fields(_) => None,
+        }
+    }
+
+    fn is_null(self) -> bool {
+        matches!(self, Self::Raw(raw) if raw.get() == "null")
+    }
+}
+
+fn collect_lossy_numbers(
+    value: Json,
+    type_id: TypeId,
+    registry: &TypeRegistry,
+    path: String,
+    lossy: &mut Vec<String>,
+) {
+    if let Json::Raw(raw) = value
+        && let Some(number) = raw_number(raw)
+    {
+        if let Some(loss) = number_loss(&number, type_id) {
+            lossy.push(format!("{number} at {path} {loss}"));
+        }
+        return;
+    }
+
+    let Some(type_info) = registry.get_type_info(type_id) else {
+        return;
+    };
+
+    match type_info {
+        TypeInfo::Struct(info) => {
+            for (name, value) in value.fields().into_iter().flatten() {
+                if let Some(field) = info.field(&name) {
+                    let path = join_field_path(&path, &name);
+                    collect_lossy_numbers(Json::Raw(value), field.type_id(), registry, path, lossy);
+                }
+            }
+        }
+        TypeInfo::TupleStruct(info) => match value.items() {
+            Some(values) => {
+                for (i, (value, field)) in values.into_iter().zip(info.iter()).enumerate() {
+                    let path = format!("{path}[{i}]");
+                    collect_lossy_numbers(Json::Raw(value), field.type_id(), registry, path, lossy);
+                }
+            }
+            None if info.field_len() == 1 => {
+                let field = info.field_at(0).unwrap();
+                collect_lossy_numbers(value, field.type_id(), registry, path, lossy);
+            }
+            None => {}
+        },
+        TypeInfo::Tuple(info) => {
+            for (i, (value, field)) in value
+                .items()
+                .into_iter()
+                .flatten()
+                .zip(info.iter())
+                .enumerate()
+            {
+                let path = format!("{path}[{i}]");
+                collect_lossy_numbers(Json::Raw(value), field.type_id(), registry, path, lossy);
+            }
+        }
+        TypeInfo::List(info) => {
+            for (i, value) in value.items().into_iter().flatten().enumerate() {
+                let path = format!("{path}[{i}]");
+                collect_lossy_numbers(Json::Raw(value), info.item_ty().id(), registry, path, lossy);
+            }
+        }
+        TypeInfo::Array(info) => {
+            for (i, value) in value.items().into_iter().flatten().enumerate() {
+                let path = format!("{path}[{i}]");
+                collect_lossy_numbers(Json::Raw(value), info.item_ty().id(), registry, path, lossy);
+            }
+        }
+        TypeInfo::Set(info) => {
+            for (i, value) in value.items().into_iter().flatten().enumerate() {
+                let path = format!("{path}[{i}]");
+                collect_lossy_numbers(
+                    Json::Raw(value),
+                    info.value_ty().id(),
+                    registry,
+                    path,
+                    lossy,
+                );
+            }
+        }
+        TypeInfo::Map(info) => {
+            for (key, value) in value.fields().into_iter().flatten() {
+                let path = join_field_path(&path, &key);
+                collect_lossy_numbers(
+                    Json::Raw(value),
+                    info.value_ty().id(),
+                    registry,
+                    path,
+                    lossy,
+                );
+            }
+        }
+        TypeInfo::Enum(info) => {
+            // `Option` fields hold their value directly, the other enums are externally tagged.
+            if info.type_path().starts_with("core::option::Option<")
+                && let Some(VariantInfo::Tuple(some)) = info.variant("Some")
+                && !value.is_null()
+            {
+                let field = some.field_at(0).unwrap();
+                collect_lossy_numbers(value, field.type_id(), registry, path, lossy);
+                return;
+            }
+
+            for (name, value) in value.fields().into_iter().flatten() {
+                let path = join_field_path(&path, &name);
+
+                match info.variant(&name) {
+                    Some(VariantInfo::Struct(variant)) => {
+                        for (name, value) in raw_object(value).into_iter().flatten() {
+                            if let Some(field) = variant.field(&name) {
+                                let path = join_field_path(&path, &name);
+                                collect_lossy_numbers(
+                                    Json::Raw(value),
+                                    field.type_id(),
+                                    registry,
+                                    path,
+                                    lossy,
+                                );
+                            }
+                        }
+                    }
+                    Some(VariantInfo::Tuple(variant)) if variant.field_len() > 1 => {
+                        let values = raw_array(value).into_iter().flatten();
+
+                        for (i, (value, field)) in values.zip(variant.iter()).enumerate() {
+                            let path = format!("{path}[{i}]");
+                            collect_lossy_numbers(
+                                Json::Raw(value),
+                                field.type_id(),
+                                registry,
+                                path,
+                                lossy,
+                            );
+                        }
+                    }
+                    Some(VariantInfo::Tuple(variant)) if variant.field_len() == 1 => {
+                        let field = variant.field_at(0).unwrap();
+                        collect_lossy_numbers(
+                            Json::Raw(value),
+                            field.type_id(),
+                            registry,
+                            path,
+                            lossy,
+                        );
+                    }
+                    _ => {}
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Describes how `number` loses precision, or doesn't fit, when deserialized as `type_id`.
+fn number_loss(number: &Number, type_id: TypeId) -> Option<String> {
+    macro_rules! check_integer {
+        ($($ty:ty),*) => {
+            $(
+                if type_id == TypeId::of::<$ty>() {
+                    let fits = match (number.as_u64(), number.as_i64()) {
+                        (Some(value), _) => <$ty>::try_from(value).is_ok(),
+                        (None, Some(value)) => <$ty>::try_from(value).is_ok(),
+                        // Fractional numbers fail to deserialize as integers.
+                        (None, None) => true,
+                    };
+
+                    return (!fits).then(|| format!("is out of the range of {}", stringify!($ty)));
+                }
+            )*
+        };
+    }
+
+    check_integer!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+    if type_id == TypeId::of::<f32>() {
+        let value = number.as_f64()?;
+        let stored = value as f32;
+
+        if stored.is_infinite() {
+            return Some("is out of the range of f32".to_string());
+        }
+
+        // The shortest representation of the stored `f32` reads back as the written number
+        // when it's as precise as written, e.g. `0.1`.
+        return (stored.to_string().parse::<f64>().ok()? != value)
+            .then(|| format!("loses precision as a f32, stored as {stored}"));
+    }
+
+    if type_id == TypeId::of::<f64>() {
+        let stored = number.as_f64()?;
+        let exact = match (number.as_u64(), number.as_i64()) {
+            (Some(value), _) => stored as i128 == i128::from(value),
+            (None, Some(value)) => stored as i128 == i128::from(value),
+            (None, None) => true,
+        };
+
+        return (!exact).then(|| format!("loses precision as a f64, stored as {stored}"));
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn loss<T: 'static>(number: &str) -> Option<String> {
+        number_loss(&serde_json::from_str(number).unwrap(), TypeId::of::<T>())
+    }
+
+    #[test]
+    fn integers_must_fit_their_type() {
+        assert_eq!(loss::<u8>("255"), None);
+        assert_eq!(loss::<u8>("256").unwrap(), "is out of the range of u8");
+        assert_eq!(loss::<u32>("-1").unwrap(), "is out of the range of u32");
+        assert_eq!(loss::<i8>("-128"), None);
+        assert_eq!(loss::<u8>("1.5"), None);
+    }
+
+    #[test]
+    fn floats_must_keep_the_written_number() {
+        assert_eq!(loss::<f32>("0.1"), None);
+        assert_eq!(loss::<f32>("1.5"), None);
+        assert_eq!(
+            loss::<f32>("16777217").unwrap(),
+            "loses precision as a f32, stored as 16777216"
+        );
+        assert_eq!(loss::<f32>("1e39").unwrap(), "is out of the range of f32");
+        assert_eq!(loss::<f64>("0.30000000000000004"), None);
+        assert!(loss::<f64>("9007199254740993").is_some());
+        assert_eq!(loss::<String>("1"), None);
+    }
+}
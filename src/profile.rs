@@ -0,0 +1,323 @@
+use std::{
+    borrow::Cow,
+    path::Path,
+    sync::{Arc, RwLock},
+};
+
+use bevy::{
+    asset::{LoadContext, ReadAssetBytesError, io::AssetReaderError},
+    prelude::*,
+};
+use serde_json::Value as JsonValue;
+
+use crate::{
+    ErasedPrototypeId, OnDiskPrototypes, PROTOTYPE_LOADER_EXTENSIONS,
+    raw::{from_raw, to_raw},
+};
+
+/// The profiles declared with
+/// [`PrototypeAppExt::add_prototype_profile`](crate::PrototypeAppExt::add_prototype_profile), in
+/// their declaration order, with whether they are enabled.
+#[derive(Resource, Default, Clone)]
+pub(crate) struct AppPrototypeProfiles(Arc<RwLock<Vec<(String, bool)>>>);
+
+impl AppPrototypeProfiles {
+    pub(crate) fn insert(&self, profile: String, enabled: bool) {
+        let mut profiles = self
+            .0
+            .write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+
+        match profiles.iter_mut().find(|(name, _)| *name == profile) {
+            Some((_, is_enabled)) => *is_enabled = enabled,
+            None => profiles.push((profile, enabled)),
+        }
+    }
+
+    /// Returns `true` for the override files of the declared profiles, enabled or not, they are
+    /// merged into their base file instead of being loaded on their own.
+    pub(crate) fn is_profile_file(&self, path: &Path) -> bool {
+        let Some(suffix) = override_suffix(path) else {
+            return false;
+        };
+
+        self.0
+            .read()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .iter()
+            .any(|(profile, _)| profile == suffix)
+    }
+
+    fn enabled(&self) -> Vec<String> {
+        self.0
+            .read()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .iter()
+            .filter(|(_, enabled)| *enabled)
+            .map(|(profile, _)| profile.clone())
+            .collect()
+    }
+}
+
+/// Returns the suffix of an override file, e.g. `mobile` for `graphics.proto.mobile.json` or `fr`
+/// for `items.proto.fr.json`.
+pub(crate) fn override_suffix(path: &Path) -> Option<&str> {
+    let file_name = path.file_name()?.to_str()?;
+    let (stem, suffix) = file_name.strip_suffix(".json")?.rsplit_once('.')?;
+
+    (!suffix.is_empty() && (stem == "proto" || stem.ends_with(".proto"))).then_some(suffix)
+}
+
+/// Returns `true` for the files named like override files: the override files of the profiles,
+/// the locale override files and the layer files of the variants, the files whose suffix isn't
+/// declared being loaded as prototypes files.
+pub(crate) fn is_override_file(path: &Path) -> bool {
+    override_suffix(path).is_some()
+}
+
+/// Reads the override files of the enabled profiles next to the prototypes file being loaded,
+/// e.g. `graphics.proto.mobile.json` for `graphics.proto.json`, in the declaration order of
+/// their profiles.
+pub(crate) async fn read_profile_overrides(
+    profiles: &AppPrototypeProfiles,
+    load_context: &mut LoadContext<'_>,
+) -> std::io::Result<Vec<(String, JsonValue)>> {
+    use std::io::{Error, ErrorKind};
+
+    let profiles = profiles.enabled();
+
+    if profiles.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let Some(stem) = load_context
+        .path()
+        .file_name()
+        .and_then(|name| name.to_str())
+        .and_then(|name| {
+            PROTOTYPE_LOADER_EXTENSIONS
+                .iter()
+                .find_map(|extension| name.strip_suffix(&format!(".{extension}")))
+        })
+        .map(ToString::to_string)
+    else {
+        return Ok(Vec::new());
+    };
+
+    let mut overrides = Vec::new();
+
+    for profile in profiles {
+        let path = load_context
+            .asset_path()
+            .resolve_embed(&format!("{stem}.proto.{profile}.json"))
+            .map_err(|err| Error::new(ErrorKind::InvalidInput, err))?;
+
+        let bytes = match load_context.read_asset_bytes(&path).await {
+            Ok(bytes) => bytes,
+            Err(ReadAssetBytesError::AssetReaderError(AssetReaderError::NotFound(_))) => continue,
+            Err(err) => return Err(Error::other(err)),
+        };
+
+        let value = serde_json::from_slice(&bytes).map_err(|err| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("invalid profile override {path}: {err}"),
+            )
+        })?;
+
+        overrides.push((path.to_string(), value));
+    }
+
+    Ok(overrides)
+}
+
+/// Merges the entries of a profile override file into the prototypes of its base file.
+///
+/// Entries are matched by type and name, a single entry without them overrides the prototype of
+/// a single-prototype file. Objects are merged field by field, any other value is replaced.
+pub(crate) fn apply_profile_override(
+    prototypes: &mut OnDiskPrototypes<'_>,
+    file: &str,
+    overrides: JsonValue,
+) {
+    let overrides = match overrides {
+        JsonValue::Array(overrides) => overrides,
+        entry => vec![entry],
+    };
+
+    for (index, entry) in overrides.into_iter().enumerate() {
+        let JsonValue::Object(fields) = entry else {
+            warn!("Profile override {index} of {file} isn't an object");
+            continue;
+        };
+
+        let key = (
+            fields.get("type").and_then(JsonValue::as_str),
+            fields.get("name").and_then(JsonValue::as_str),
+        );
+
+        let prototype = match key {
+            (Some(ty), Some(name)) => {
+                let id = ErasedPrototypeId::from_name(name);
+
+                prototypes
+                    .iter_mut()
+                    .find(|prototype| &*prototype.ty == ty && prototype.name.id() == id)
+            }
+            (None, None) if prototypes.len() == 1 => prototypes.first_mut(),
+            _ => None,
+        };
+
+        let Some(prototype) = prototype else {
+            warn!(
+                "Profile override {index} of {file} doesn't match any prototype of its base file"
+            );
+            continue;
+        };
+
+        for (field, value) in fields {
+            match field.as_str() {
+                "type" | "name" | "experiment" | "uuid" | "$meta" | "$editor" => {}
+                "tags" => match serde_json::from_value::<Vec<String>>(value) {
+                    Ok(tags) => prototype.tags = tags.into_iter().map(Cow::Owned).collect(),
+                    Err(err) => warn!("Invalid tags in profile override {index} of {file}: {err}"),
+                },
+                "defaults_from" => match serde_json::from_value(value) {
+                    Ok(defaults_from) => prototype.defaults_from = defaults_from,
+                    Err(err) => {
+                        warn!("Invalid defaults_from in profile override {index} of {file}: {err}");
+                    }
+                },
+                _ => match prototype.proto.iter_mut().find(|(name, _)| *name == field) {
+                    Some((_, current)) => {
+                        let mut merged = from_raw(current);
+                        merge_json(&mut merged, value);
+                        *current = Cow::Owned(to_raw(&merged));
+                    }
+                    None => prototype
+                        .proto
+                        .push((Cow::Owned(field), Cow::Owned(to_raw(&value)))),
+                },
+            }
+        }
+    }
+}
+
+pub(crate) fn merge_json(current: &mut JsonValue, value: JsonValue) {
+    match (current, value) {
+        (JsonValue::Object(current), JsonValue::Object(fields)) => {
+            for (field, value) in fields {
+                match current.get_mut(&field) {
+                    Some(current) => merge_json(current, value),
+                    None => {
+                        current.insert(field, value);
+                    }
+                }
+            }
+        }
+        (current, value) => *current = value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::OnDiskPrototype;
+
+    #[test]
+    fn override_suffixes_follow_the_prototypes_extension() {
+        for (path, suffix) in [
+            ("graphics.proto.mobile.json", Some("mobile")),
+            ("items/proto.fr.json", Some("fr")),
+            ("graphics.proto.json", None),
+            ("graphics.mobile.json", None),
+            ("graphics.proto..json", None),
+            ("graphics.proto.mobile.csv", None),
+        ] {
+            assert_eq!(override_suffix(Path::new(path)), suffix, "{path}");
+        }
+    }
+
+    #[test]
+    fn only_the_declared_profiles_have_override_files() {
+        let profiles = AppPrototypeProfiles::default();
+        profiles.insert("mobile".to_string(), false);
+
+        assert!(profiles.is_profile_file(Path::new("graphics.proto.mobile.json")));
+        assert!(!profiles.is_profile_file(Path::new("graphics.proto.console.json")));
+        assert!(is_override_file(Path::new("graphics.proto.console.json")));
+        assert!(profiles.enabled().is_empty());
+
+        profiles.insert("mobile".to_string(), true);
+        assert_eq!(profiles.enabled(), ["mobile"]);
+    }
+
+    fn fields(prototype: &OnDiskPrototype<'_>) -> JsonValue {
+        prototype
+            .proto
+            .iter()
+            .map(|(field, value)| (field.to_string(), from_raw(value)))
+            .collect()
+    }
+
+    #[test]
+    fn overrides_patch_the_prototypes_of_their_base_file() {
+        let mut prototypes = serde_json::from_str::<OnDiskPrototypes>(
+            r#"[
+                { "type": "sword", "name": "stick", "damage": 1, "stats": { "range": 1, "speed": 2 } },
+                { "type": "sword", "name": "blade", "tags": ["melee"], "damage": 5 }
+            ]"#,
+        )
+        .unwrap();
+
+        apply_profile_override(
+            &mut prototypes,
+            "swords.proto.mobile.json",
+            json!([
+                { "type": "sword", "name": "stick", "stats": { "speed": 3 }, "icon": "stick.png" },
+                { "type": "sword", "name": "blade", "tags": ["melee", "heavy"] },
+                { "type": "sword", "name": "missing", "damage": 9 },
+            ]),
+        );
+
+        assert_eq!(
+            fields(&prototypes[0]),
+            json!({ "damage": 1, "stats": { "range": 1, "speed": 3 }, "icon": "stick.png" })
+        );
+        assert_eq!(prototypes[1].tags().collect::<Vec<_>>(), ["melee", "heavy"]);
+        assert_eq!(fields(&prototypes[1]), json!({ "damage": 5 }));
+        assert_eq!(prototypes.len(), 2);
+    }
+
+    #[test]
+    fn a_single_entry_overrides_a_single_prototype_file() {
+        let mut prototypes = serde_json::from_str::<OnDiskPrototypes>(
+            r#"{ "type": "sword", "name": "stick", "damage": 1 }"#,
+        )
+        .unwrap();
+
+        apply_profile_override(
+            &mut prototypes,
+            "stick.proto.mobile.json",
+            json!({ "damage": 2 }),
+        );
+
+        assert_eq!(fields(&prototypes[0]), json!({ "damage": 2 }));
+    }
+
+    #[test]
+    fn objects_are_merged_field_by_field() {
+        let mut current = json!({ "stats": { "hp": 10, "mp": 5 }, "tags": ["a"] });
+        merge_json(
+            &mut current,
+            json!({ "stats": { "hp": 20 }, "tags": ["b"], "speed": 1.5 }),
+        );
+
+        assert_eq!(
+            current,
+            json!({ "stats": { "hp": 20, "mp": 5 }, "tags": ["b"], "speed": 1.5 })
+        );
+    }
+}
@@ -1,23 +1,59 @@
-use core::any::{Any, TypeId};
-use std::sync::{Arc, RwLock};
+use core::{
+    any::{Any, TypeId},
+    hash::{Hash, Hasher},
+};
+use std::{
+    borrow::Cow,
+    path::Path,
+    sync::{Arc, RwLock},
+};
+
+use const_fnv1a_hash::fnv1a_hash_str_64;
 
 use bevy::platform::collections::HashMap;
 use bevy::reflect::{DynamicEnum, DynamicStruct, DynamicTuple, GenericInfo, Reflectable};
 use bevy::{
-    asset::{AssetLoader, AssetPath, LoadContext, io::Reader as AssetReader},
+    asset::{
+        AssetLoader, AssetPath, LoadContext,
+        io::Reader as AssetReader,
+        uuid::{self, Uuid},
+    },
     prelude::*,
     reflect::{
-        TypeRegistration, TypeRegistry, TypeRegistryArc,
+        TypePathTable, TypeRegistration, TypeRegistry, TypeRegistryArc,
         serde::{ReflectDeserializerProcessor, TypedReflectDeserializer},
     },
 };
-use serde::{Deserialize, de::DeserializeSeed};
+use serde::{Deserialize, Serialize, de::DeserializeSeed};
+use serde_json::{Map as JsonMap, Value as JsonValue, value::RawValue};
 
-use crate::{ErasedPrototypeName, JsonSchema, PrototypeId, PrototypeName};
+#[cfg(feature = "schema")]
+use crate::JsonSchema;
+#[cfg(feature = "kdl")]
+use crate::read_kdl_file;
+use crate::{
+    ErasedPrototypeId, ErasedPrototypeName, PROTOTYPE_ASSET_EXTENSIONS, PrototypeExperiment,
+    PrototypeId, PrototypeName, SkippedPrototype, TableFormat, expand_fragments,
+    generate::expand_generator,
+    is_ndjson_file, is_prototype_table_file, line_column,
+    merge::{ListMerge, is_list_merge_key, read_list_merges},
+    precision::lossy_numbers,
+    profile::{AppPrototypeProfiles, apply_profile_override, merge_json, read_profile_overrides},
+    raw::{
+        RawFields, RawFieldsDeserializer, RawStr, from_raw, hash_json, raw_array, raw_kind,
+        raw_object, to_raw, without_position,
+    },
+    read_ndjson,
+    table::typed_cell_value,
+    table_to_entries, table_type, untyped_cell_value,
+};
 
 #[derive(Default, Clone)]
 pub(crate) struct PrototypeTypeRegistry {
     internal: Arc<RwLock<HashMap<Box<str>, TypeId>>>,
+    /// Former names of the prototype types and their current name, see
+    /// [`PrototypeAppExt::register_prototype_type_alias`](crate::PrototypeAppExt::register_prototype_type_alias).
+    aliases: Arc<RwLock<HashMap<Box<str>, Box<str>>>>,
 }
 
 impl PrototypeTypeRegistry {
@@ -34,59 +70,594 @@ impl PrototypeTypeRegistry {
             .write()
             .unwrap_or_else(std::sync::PoisonError::into_inner)
     }
+
+    pub fn insert_alias(&self, alias: Box<str>, canonical: Box<str>) {
+        self.aliases
+            .write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .insert(alias, canonical);
+    }
+
+    /// Returns the current name of the prototype type formerly named `alias`.
+    pub fn canonical(&self, alias: &str) -> Option<Box<str>> {
+        self.aliases
+            .read()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .get(alias)
+            .cloned()
+    }
+
+    /// Returns `name`, or the current name of the prototype type if `name` is one of its former
+    /// names.
+    pub fn canonical_or<'a>(&self, name: &'a str) -> std::borrow::Cow<'a, str> {
+        match self.canonical(name) {
+            Some(canonical) => std::borrow::Cow::Owned(canonical.into()),
+            None => std::borrow::Cow::Borrowed(name),
+        }
+    }
 }
 
+/// The registered prototype types, by their name in the `type` field of the prototypes files.
+///
+/// Tools can read it to list the prototype types, e.g. for menus, or to validate the `type` of
+/// entries themselves.
 #[derive(Default, Resource, Clone)]
-pub(crate) struct AppPrototypeTypeRegistry(pub PrototypeTypeRegistry);
+pub struct AppPrototypeTypeRegistry(pub(crate) PrototypeTypeRegistry);
 
-#[derive(Clone, Deserialize)]
-pub(crate) struct OnDiskPrototype {
-    #[serde(rename = "type")]
-    pub ty: Box<str>,
-    pub name: ErasedPrototypeName,
-    #[serde(default)]
-    pub tags: Vec<String>,
-    #[serde(flatten)]
-    pub proto: serde_json::Value,
+impl AppPrototypeTypeRegistry {
+    /// Returns the names of the registered prototype types, sorted.
+    pub fn prototype_type_names(&self) -> Vec<String> {
+        let mut names = self
+            .0
+            .read()
+            .keys()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        names.sort();
+        names
+    }
+
+    /// Returns the [`TypeId`] of the data of the `name` prototype type, e.g. `sword`.
+    pub fn type_id_of(&self, name: &str) -> Option<TypeId> {
+        self.0.read().get(name).copied()
+    }
+
+    /// Returns the name of the prototype type whose data has the `type_id` [`TypeId`].
+    pub fn type_name_of(&self, type_id: TypeId) -> Option<String> {
+        self.0
+            .read()
+            .iter()
+            .find_map(|(name, id)| (*id == type_id).then(|| name.to_string()))
+    }
 }
 
-#[derive(Deref)]
-pub(crate) struct OnDiskPrototypes(Box<[OnDiskPrototype]>);
+/// A prototype entry of a prototypes file, as parsed by [`parse_prototypes`], before its data is
+/// deserialized into its prototype type.
+///
+/// The strings and the fields of the entry are borrowed from the bytes of the file, only the
+/// entries rewritten by the loader own theirs, e.g. the entries of the generators and of the
+/// tables.
+#[derive(Clone)]
+pub struct OnDiskPrototype<'de> {
+    /// The `type` of the entry.
+    pub(crate) ty: Cow<'de, str>,
+    pub(crate) name: ErasedPrototypeName,
+    pub(crate) tags: Vec<Cow<'de, str>>,
+    /// Prototype of the same type whose data is used for the fields the entry doesn't set.
+    pub(crate) defaults_from: Option<ErasedPrototypeId>,
+    /// Editor metadata, `$meta` or `$editor`, ignored by the loader but kept with the prototype.
+    pub(crate) metadata: Option<Cow<'de, RawValue>>,
+    pub(crate) experiment: Option<PrototypeExperiment>,
+    /// Stable id of the prototype kept across renames, see [`Prototype::uuid`].
+    pub(crate) uuid: Option<Uuid>,
+    /// The other fields of the entry, the data of the prototype.
+    pub(crate) proto: RawFields<'de>,
+    /// Index of the entry of the file the prototype comes from, several prototypes come from
+    /// the same entry when it has `names`.
+    pub(crate) entry: usize,
+}
+
+/// The prototype entries of a prototypes file, see [`parse_prototypes`].
+#[derive(Deref, DerefMut)]
+pub struct OnDiskPrototypes<'de> {
+    #[deref]
+    prototypes: Box<[OnDiskPrototype<'de>]>,
+    /// Whether the prototypes are the elements of a top-level array.
+    pub(crate) is_list: bool,
+    /// Line and column of each entry of the file, by entry index.
+    pub(crate) positions: Vec<(usize, usize)>,
+}
+
+impl OnDiskPrototype<'_> {
+    /// Returns the prototype type of the entry, e.g. `sword`.
+    pub fn ty(&self) -> &str {
+        &self.ty
+    }
+
+    /// Returns the name of the prototype.
+    pub fn name(&self) -> &ErasedPrototypeName {
+        &self.name
+    }
+
+    /// Returns the tags of the prototype, as written in the file.
+    pub fn tags(&self) -> impl Iterator<Item = &str> {
+        self.tags.iter().map(|tag| &**tag)
+    }
+
+    /// Returns the fields of the prototype data with their JSON, as written in the file.
+    pub fn data(&self) -> impl Iterator<Item = (&str, &RawValue)> {
+        self.proto.iter().map(|(field, value)| (&**field, &**value))
+    }
+}
 
-impl<'de> Deserialize<'de> for OnDiskPrototypes {
+impl<'de> OnDiskPrototype<'de> {
+    /// Builds the prototype of an expanded entry, the fields are moved out of the entry and the
+    /// fields left are the data of the prototype.
+    fn from_fields(mut fields: RawFields<'de>, index: usize) -> Result<Self, String> {
+        fn take<'de>(fields: &mut RawFields<'de>, key: &str) -> Option<Cow<'de, RawValue>> {
+            // Removed in place to keep the order of the data fields.
+            let position = fields.iter().position(|(field, _)| field == key)?;
+            Some(fields.remove(position).1)
+        }
+
+        fn parse<'a, T: Deserialize<'a>>(key: &str, raw: &'a RawValue) -> Result<T, String> {
+            serde_json::from_str(raw.get())
+                .map_err(|err| format!("invalid `{key}`: {}", without_position(err)))
+        }
+
+        // The strings are borrowed from the file when they're written without escapes.
+        fn parse_str<'de>(key: &str, raw: Cow<'de, RawValue>) -> Result<Cow<'de, str>, String> {
+            match raw {
+                Cow::Borrowed(raw) => parse::<RawStr>(key, raw).map(|string| string.0),
+                Cow::Owned(raw) => parse::<String>(key, &raw).map(Cow::Owned),
+            }
+        }
+
+        fn parse_strs<'de>(
+            key: &str,
+            raw: Cow<'de, RawValue>,
+        ) -> Result<Vec<Cow<'de, str>>, String> {
+            match raw {
+                Cow::Borrowed(raw) => parse::<Vec<RawStr>>(key, raw)
+                    .map(|strings| strings.into_iter().map(|string| string.0).collect()),
+                Cow::Owned(raw) => parse::<Vec<String>>(key, &raw)
+                    .map(|strings| strings.into_iter().map(Cow::Owned).collect()),
+            }
+        }
+
+        let ty = parse_str(
+            "type",
+            take(&mut fields, "type").ok_or("missing field `type`")?,
+        )?;
+        let name = parse(
+            "name",
+            &take(&mut fields, "name").ok_or("missing field `name`")?,
+        )?;
+        let metadata = match (take(&mut fields, "$meta"), take(&mut fields, "$editor")) {
+            (Some(_), Some(_)) => return Err("duplicate field `$meta`".to_string()),
+            (metadata, editor) => metadata.or(editor).filter(|raw| raw.get() != "null"),
+        };
+        let tags = take(&mut fields, "tags")
+            .map(|raw| parse_strs("tags", raw))
+            .transpose()?
+            .unwrap_or_default();
+        let defaults_from = take(&mut fields, "defaults_from")
+            .map(|raw| parse::<Option<_>>("defaults_from", &raw))
+            .transpose()?
+            .flatten();
+        let experiment = take(&mut fields, "experiment")
+            .map(|raw| parse::<Option<_>>("experiment", &raw))
+            .transpose()?
+            .flatten();
+        let uuid = take(&mut fields, "uuid")
+            .map(|raw| parse::<Option<_>>("uuid", &raw))
+            .transpose()?
+            .flatten();
+
+        Ok(Self {
+            ty,
+            name,
+            tags,
+            defaults_from,
+            metadata,
+            experiment,
+            uuid,
+            proto: fields,
+            entry: index,
+        })
+    }
+
+    /// Builds the prototypes of the fields of an entry, borrowing them unless the entry is
+    /// expanded by its generator or its `names`.
+    fn from_raw_fields(fields: RawFields<'de>, index: usize) -> Result<Vec<Self>, String> {
+        if !fields
+            .iter()
+            .any(|(field, _)| field == "generate" || field == "names")
+        {
+            return Ok(vec![Self::from_fields(fields, index)?]);
+        }
+
+        let entry = fields
+            .iter()
+            .map(|(field, value)| (field.to_string(), from_raw(value)))
+            .collect();
+
+        expand_entry(JsonValue::Object(entry))?
+            .into_iter()
+            .map(|entry| Self::from_entry(entry, index))
+            .collect()
+    }
+
+    /// Builds the prototype of an expanded entry parsed into a JSON value, e.g. a row of a table.
+    fn from_entry(entry: JsonValue, index: usize) -> Result<Self, String> {
+        let JsonValue::Object(fields) = entry else {
+            return Err(format!(
+                "invalid type: {}, expected a prototype object",
+                json_kind(&entry)
+            ));
+        };
+
+        let fields = fields
+            .into_iter()
+            .map(|(field, value)| (Cow::Owned(field), Cow::Owned(to_raw(&value))))
+            .collect();
+
+        Self::from_fields(fields, index)
+    }
+}
+
+/// Returns the kind of a JSON value for the error messages, e.g. `string`.
+fn json_kind(value: &JsonValue) -> &'static str {
+    match value {
+        JsonValue::Null => "null",
+        JsonValue::Bool(_) => "boolean",
+        JsonValue::Number(_) => "number",
+        JsonValue::String(_) => "string",
+        JsonValue::Array(_) => "sequence",
+        JsonValue::Object(_) => "map",
+    }
+}
+
+impl<'de> Deserialize<'de> for OnDiskPrototypes<'de> {
     fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
     where
         D: serde::Deserializer<'de>,
     {
-        let content = <serde::__private::de::Content as Deserialize>::deserialize(deserializer)?;
-        let deserializer = serde::__private::de::ContentRefDeserializer::<D::Error>::new(&content);
+        Self::from_value(serde_json::Value::deserialize(deserializer)?)
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// Key of the fragments declarations, the files declaring or referencing fragments are expanded
+/// as JSON values, see [`expand_fragments`].
+const FRAGMENTS_KEY: &[u8] = b"\"$fragments";
+
+impl<'de> OnDiskPrototypes<'de> {
+    /// Builds the prototypes of the entries of a file, a list of entries or a single entry,
+    /// consuming them.
+    fn from_value(value: JsonValue) -> Result<Self, String> {
+        let parse = |index: usize, entry: JsonValue| {
+            expand_entry(entry)?
+                .into_iter()
+                .map(|entry| OnDiskPrototype::from_entry(entry, index))
+                .collect::<Result<Vec<_>, _>>()
+        };
+
+        let invalid = |index: usize, err: String| format!("invalid prototype at /{index}: {err}");
+
+        if let serde_json::Value::Array(entries) = value {
+            let mut prototypes = Vec::new();
+
+            let entries = expand_fragments(entries).map_err(|(index, err)| invalid(index, err))?;
+
+            for (index, entry) in entries.into_iter().enumerate() {
+                let Some(entry) = entry else {
+                    continue;
+                };
+
+                prototypes.extend(parse(index, entry).map_err(|err| invalid(index, err))?);
+            }
 
-        if let Ok(prototypes) = <Box<[OnDiskPrototype]> as Deserialize>::deserialize(deserializer) {
-            return Ok(OnDiskPrototypes(prototypes));
+            return Ok(OnDiskPrototypes {
+                prototypes: prototypes.into_boxed_slice(),
+                is_list: true,
+                positions: Vec::new(),
+            });
         }
 
-        if let Ok(prototype) = <OnDiskPrototype as Deserialize>::deserialize(deserializer) {
-            return Ok(OnDiskPrototypes(Box::new([prototype])));
+        let Some(value) = expand_fragments(vec![value])
+            .map_err(|(_, err)| format!("invalid prototype: {err}"))?
+            .pop()
+            .flatten()
+            .filter(JsonValue::is_object)
+        else {
+            return Err("on disk prototypes must be a list or a single prototype".to_string());
+        };
+
+        let prototypes = parse(0, value).map_err(|err| format!("invalid prototype: {err}"))?;
+
+        Ok(OnDiskPrototypes {
+            prototypes: prototypes.into_boxed_slice(),
+            is_list: false,
+            positions: Vec::new(),
+        })
+    }
+
+    /// Like [`OnDiskPrototypes::from_value`], with the errors of the invalid data files.
+    fn from_file_value(value: JsonValue) -> std::io::Result<Self> {
+        Self::from_value(value)
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))
+    }
+
+    /// Builds the prototypes of a JSON or newline-delimited JSON file, borrowing their strings
+    /// and fields from `bytes`.
+    ///
+    /// The files with fragments are parsed into JSON values instead, the fragments being
+    /// substituted across the entries.
+    fn from_json_file(bytes: &'de [u8], path: &Path) -> std::io::Result<Self> {
+        let has_fragments = bytes
+            .windows(FRAGMENTS_KEY.len())
+            .any(|window| window == FRAGMENTS_KEY);
+
+        if is_ndjson_file(path) {
+            if has_fragments {
+                return Self::from_file_value(JsonValue::Array(read_ndjson(bytes)?));
+            }
+
+            let entries = serde_json::Deserializer::from_slice(bytes)
+                .into_iter::<&RawValue>()
+                .collect::<Result<Vec<_>, _>>()?;
+
+            return Self::from_raw_entries(entries)
+                .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err));
         }
 
-        Err(serde::de::Error::custom(
-            "on disk prototypes must be a list or a single prototype",
-        ))
+        if has_fragments {
+            return Self::from_file_value(named_from_file(serde_json::from_slice(bytes)?, path));
+        }
+
+        let value = serde_json::from_slice::<&RawValue>(bytes)?;
+
+        if let Some(entries) = raw_array(value) {
+            return Self::from_raw_entries(entries)
+                .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err));
+        }
+
+        let Some(fields) = raw_object(value) else {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "on disk prototypes must be a list or a single prototype",
+            ));
+        };
+
+        let mut fields = fields
+            .into_iter()
+            .map(|(field, value)| (field, Cow::Borrowed(value)))
+            .collect::<RawFields>();
+
+        if let Some((name, ty)) = file_prototype_name(path) {
+            let has = |key: &str| fields.iter().any(|(field, _)| field == key);
+            let mut named = RawFields::new();
+
+            if !has("type")
+                && let Some(ty) = ty
+            {
+                named.push(("type".into(), Cow::Owned(to_raw(&ty.into()))));
+            }
+
+            if !["name", "names", "generate"].into_iter().any(has) {
+                named.push(("name".into(), Cow::Owned(to_raw(&name.into()))));
+            }
+
+            named.append(&mut fields);
+            fields = named;
+        }
+
+        let prototypes = OnDiskPrototype::from_raw_fields(fields, 0).map_err(|err| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("invalid prototype: {err}"),
+            )
+        })?;
+
+        Ok(OnDiskPrototypes {
+            prototypes: prototypes.into_boxed_slice(),
+            is_list: false,
+            positions: Vec::new(),
+        })
+    }
+
+    /// Builds the prototypes of the entries of a list file, borrowing their fields.
+    fn from_raw_entries(entries: Vec<&'de RawValue>) -> Result<Self, String> {
+        let mut prototypes = Vec::new();
+
+        for (index, entry) in entries.into_iter().enumerate() {
+            let Some(fields) = raw_object(entry) else {
+                return Err(format!(
+                    "invalid prototype at /{index}: invalid type: {}, expected a prototype object",
+                    raw_kind(entry)
+                ));
+            };
+
+            let fields = fields
+                .into_iter()
+                .map(|(field, value)| (field, Cow::Borrowed(value)))
+                .collect();
+
+            prototypes.extend(
+                OnDiskPrototype::from_raw_fields(fields, index)
+                    .map_err(|err| format!("invalid prototype at /{index}: {err}"))?,
+            );
+        }
+
+        Ok(OnDiskPrototypes {
+            prototypes: prototypes.into_boxed_slice(),
+            is_list: true,
+            positions: Vec::new(),
+        })
     }
 }
 
-pub(crate) struct DynamicPrototype {
-    pub name: ErasedPrototypeName,
-    pub tags: Vec<String>,
-    pub proto: Box<dyn PartialReflect>,
+/// A prototype whose data is deserialized into its prototype type, as returned by
+/// [`reflect_prototypes`], before it's applied into its registry.
+pub struct DynamicPrototype {
+    pub(crate) source: PrototypeSource,
+    pub(crate) name: ErasedPrototypeName,
+    pub(crate) tags: Vec<String>,
+    pub(crate) defaults_from: Option<ErasedPrototypeId>,
+    pub(crate) metadata: Option<serde_json::Value>,
+    pub(crate) experiment: Option<PrototypeExperiment>,
+    pub(crate) uuid: Option<Uuid>,
+    pub(crate) proto: Box<dyn PartialReflect>,
+    pub(crate) list_merges: Vec<ListMerge>,
+    /// The numbers of the entry losing precision when applied to their field, see
+    /// [`PrototypeNumberPrecision`](crate::PrototypeNumberPrecision).
+    pub(crate) lossy_numbers: Vec<String>,
+    /// Hash of the on-disk entry, `None` if unknown, compared on reload to leave the unchanged
+    /// prototypes as they are.
+    pub(crate) fingerprint: Option<u64>,
+}
+
+impl DynamicPrototype {
+    /// Returns the name of the prototype.
+    pub fn name(&self) -> &ErasedPrototypeName {
+        &self.name
+    }
+
+    /// Returns the tags of the prototype, as written in the file.
+    pub fn tags(&self) -> &[String] {
+        &self.tags
+    }
+
+    /// Returns where the prototype was loaded from.
+    pub fn source(&self) -> &PrototypeSource {
+        &self.source
+    }
+
+    /// Returns the data of the prototype, before the `defaults_from` fields and the merged lists
+    /// are applied.
+    pub fn data(&self) -> &dyn PartialReflect {
+        &*self.proto
+    }
 }
 
-#[derive(Asset, TypePath, Deref)]
-pub(crate) struct PrototypesAsset(Box<[(TypeId, DynamicPrototype)]>);
+impl OnDiskPrototype<'_> {
+    fn fingerprint(&self) -> u64 {
+        let mut hasher = FingerprintHasher::default();
+
+        self.ty.hash(&mut hasher);
+        self.name.hash(&mut hasher);
+        self.tags.hash(&mut hasher);
+        self.defaults_from.hash(&mut hasher);
+        self.experiment.hash(&mut hasher);
+        self.uuid.hash(&mut hasher);
+
+        // The JSON of the fields is hashed as written in the file.
+        hasher.write_u8(u8::from(self.metadata.is_some()));
+
+        if let Some(metadata) = &self.metadata {
+            hash_json(metadata.get(), &mut hasher);
+        }
+
+        for (field, value) in &self.proto {
+            field.hash(&mut hasher);
+            hash_json(value.get(), &mut hasher);
+        }
+
+        hasher.finish()
+    }
+}
+
+/// FNV-1a hasher of the [`OnDiskPrototype::fingerprint`]s.
+struct FingerprintHasher(u64);
+
+impl Default for FingerprintHasher {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for FingerprintHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 = (self.0 ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
+
+/// A prototype of a [`PrototypesAsset`], as parsed from its file.
+pub struct ParsedPrototype<'a> {
+    /// Type id of the prototype data.
+    pub type_id: TypeId,
+    /// Name of the prototype.
+    pub name: &'a ErasedPrototypeName,
+    /// Tags of the prototype, as written in the file.
+    pub tags: &'a [String],
+    /// Where the prototype was loaded from.
+    pub source: &'a PrototypeSource,
+    /// Data of the entry, before the `defaults_from` fields and the merged lists are applied.
+    pub data: &'a dyn PartialReflect,
+}
+
+/// The prototypes parsed from a prototypes file, kept in `Assets<PrototypesAsset>` once applied
+/// when the [`PrototypesAssetRetention`](crate::PrototypesAssetRetention) is `Retain`.
+#[derive(Asset, TypePath)]
+pub struct PrototypesAsset {
+    prototypes: Box<[(TypeId, DynamicPrototype)]>,
+    /// Entries of the file that failed to convert to dynamic prototypes.
+    pub skipped: Vec<SkippedPrototype>,
+    /// Path of the prototypes file.
+    pub path: AssetPath<'static>,
+    /// BLAKE3 hash of the prototypes file, checked against the manifest of its folder.
+    #[cfg(feature = "integrity")]
+    pub hash: blake3::Hash,
+}
+
+impl PrototypesAsset {
+    pub(crate) fn new(
+        prototypes: Vec<(TypeId, DynamicPrototype)>,
+        skipped: Vec<SkippedPrototype>,
+        load_context: &LoadContext,
+        #[cfg_attr(not(feature = "integrity"), allow(unused_variables))] bytes: &[u8],
+    ) -> Self {
+        Self {
+            prototypes: prototypes.into_boxed_slice(),
+            skipped,
+            path: load_context.asset_path().clone_owned(),
+            #[cfg(feature = "integrity")]
+            hash: blake3::hash(bytes),
+        }
+    }
+
+    /// Returns the prototypes of the file, in the file order.
+    pub fn prototypes(&self) -> impl Iterator<Item = ParsedPrototype<'_>> {
+        self.prototypes
+            .iter()
+            .map(|(type_id, prototype)| ParsedPrototype {
+                type_id: *type_id,
+                name: &prototype.name,
+                tags: &prototype.tags,
+                source: &prototype.source,
+                data: &*prototype.proto,
+            })
+    }
+
+    pub(crate) fn iter(&self) -> core::slice::Iter<'_, (TypeId, DynamicPrototype)> {
+        self.prototypes.iter()
+    }
+}
 
 pub(crate) struct PrototypesAssetLoader {
     pub type_registry: TypeRegistryArc,
     pub prototype_type_registry: PrototypeTypeRegistry,
+    pub profiles: AppPrototypeProfiles,
+    /// Whether the assets referenced by the handle fields are loaded, see
+    /// [`ServerPrototypesPlugin`](crate::ServerPrototypesPlugin).
+    pub load_handles: bool,
 }
 
 impl AssetLoader for PrototypesAssetLoader {
@@ -103,190 +674,778 @@ impl AssetLoader for PrototypesAssetLoader {
         let mut bytes = Vec::new();
         reader.read_to_end(&mut bytes).await?;
 
-        let on_disk_prototypes: OnDiskPrototypes = serde_json::from_slice(&bytes)?;
+        let profile_overrides = read_profile_overrides(&self.profiles, load_context).await?;
 
-        // Helper for processing asset handles during deserialization
-        struct HandleProcessor<'a, 'b> {
-            load_context: &'a mut LoadContext<'b>,
+        let registry = self.type_registry.read();
+
+        let mut on_disk_prototypes = parse_on_disk_prototypes(
+            load_context.path(),
+            &bytes,
+            &registry,
+            &self.prototype_type_registry,
+        )?;
+
+        for (file, overrides) in profile_overrides {
+            apply_profile_override(&mut on_disk_prototypes, &file, overrides);
         }
 
-        impl ReflectDeserializerProcessor for HandleProcessor<'_, '_> {
-            fn try_deserialize<'de, D>(
-                &mut self,
-                registration: &TypeRegistration,
-                _registry: &TypeRegistry,
-                deserializer: D,
-            ) -> Result<Result<Box<dyn PartialReflect>, D>, D::Error>
-            where
-                D: serde::Deserializer<'de>,
+        let path = load_context.asset_path().clone_owned();
+        let (prototypes, skipped) = reflect_on_disk_prototypes(
+            on_disk_prototypes,
+            &path,
+            &registry,
+            &self.prototype_type_registry.read(),
+            self.load_handles.then_some(&mut *load_context),
+        );
+
+        Ok(PrototypesAsset::new(
+            prototypes,
+            skipped,
+            load_context,
+            &bytes,
+        ))
+    }
+
+    fn extensions(&self) -> &[&str] {
+        PROTOTYPE_LOADER_EXTENSIONS
+    }
+}
+
+/// Parses the entries of a prototypes file, like the prototypes loader does before deserializing
+/// their data, see [`parse_prototypes`].
+pub(crate) fn parse_on_disk_prototypes<'de>(
+    path: &Path,
+    bytes: &'de [u8],
+    registry: &TypeRegistry,
+    prototype_types: &PrototypeTypeRegistry,
+) -> std::io::Result<OnDiskPrototypes<'de>> {
+    let prototype_type_registry = prototype_types.read();
+
+    let (mut on_disk_prototypes, positions) = if is_prototype_table_file(path) {
+        let text = std::str::from_utf8(bytes)
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))?;
+
+        // Cells are converted with the type of their field, e.g. to keep a number written in
+        // a string field as a string.
+        let entries = table_to_entries(
+            text,
+            TableFormat::from_path(path),
+            table_type(path),
+            |ty, field, cell| match prototype_type_registry
+                .get(&*prototype_types.canonical_or(ty))
+                .and_then(|type_id| registry.get(*type_id))
             {
-                struct AssetPathVisitor<'a, 'b> {
-                    load_context: &'a mut LoadContext<'b>,
+                Some(registration) => typed_cell_value(registration, field, cell),
+                None => untyped_cell_value(cell),
+            },
+        )
+        .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))?;
+
+        let positions = entries
+            .iter()
+            .map(|(line, _)| (*line, 1))
+            .collect::<Vec<_>>();
+        let entries = entries.into_iter().map(|(_, entry)| entry).collect();
+
+        (
+            OnDiskPrototypes::from_file_value(JsonValue::Array(entries))?,
+            positions,
+        )
+    } else if let Some(entries) = read_kdl_file(path, bytes)? {
+        let positions = entries
+            .iter()
+            .map(|(offset, _)| line_column(bytes, *offset))
+            .collect::<Vec<_>>();
+        let entries = entries.into_iter().map(|(_, entry)| entry).collect();
+
+        (
+            OnDiskPrototypes::from_file_value(JsonValue::Array(entries))?,
+            positions,
+        )
+    } else {
+        let positions = entry_offsets(bytes)
+            .into_iter()
+            .map(|offset| line_column(bytes, offset))
+            .collect::<Vec<_>>();
+
+        (OnDiskPrototypes::from_json_file(bytes, path)?, positions)
+    };
+
+    // Files written before a prototype type was renamed keep loading, each file still using
+    // the former name is reported once per alias.
+    let mut aliases = Vec::new();
+
+    for prototype in on_disk_prototypes.iter_mut() {
+        if let Some(canonical) = prototype_types.canonical(&prototype.ty) {
+            let alias = core::mem::replace(&mut prototype.ty, Cow::Owned(canonical.into()));
+
+            if !aliases.iter().any(|(other, _)| *other == alias) {
+                aliases.push((alias, prototype.ty.clone()));
+            }
+        }
+    }
+
+    for (alias, canonical) in aliases {
+        warn!(
+            "Prototypes file {} uses the former prototype type name {alias}, rename it to {canonical}",
+            path.display()
+        );
+    }
+
+    on_disk_prototypes.positions = positions;
+
+    Ok(on_disk_prototypes)
+}
+
+/// Deserializes the data of the parsed prototypes into their prototype type, like the prototypes
+/// loader does, see [`reflect_prototypes`]. The assets of the handle fields are loaded with
+/// `load_context`, the fields keep their default handle without it.
+pub(crate) fn reflect_on_disk_prototypes(
+    on_disk_prototypes: OnDiskPrototypes<'_>,
+    path: &AssetPath<'static>,
+    registry: &TypeRegistry,
+    prototype_types: &HashMap<Box<str>, TypeId>,
+    mut load_context: Option<&mut LoadContext>,
+) -> (Vec<(TypeId, DynamicPrototype)>, Vec<SkippedPrototype>) {
+    let OnDiskPrototypes {
+        prototypes,
+        is_list,
+        positions,
+    } = on_disk_prototypes;
+    let mut skipped = Vec::new();
+
+    // Convert each on-disk prototype to a dynamic prototype
+    let prototypes = prototypes
+        .into_vec()
+        .into_iter()
+        .filter_map(|prototype| {
+            let index = prototype.entry;
+            let source = PrototypeSource {
+                path: path.clone(),
+                index,
+                position: positions.get(index).copied(),
+                pointer: if is_list {
+                    format!("/{index}")
+                } else {
+                    String::new()
+                },
+            };
+
+            // Look up the type ID for this prototype
+            let Some(type_id) = prototype_types.get(&*prototype.ty) else {
+                skipped.push(SkippedPrototype::logged(
+                    &prototype.name,
+                    Some(&source),
+                    format!(
+                        "Unknown prototype type {} of prototype {} at {source}",
+                        prototype.ty, prototype.name
+                    ),
+                ));
+                return None;
+            };
+
+            let Some(type_registration) = registry.get(*type_id) else {
+                skipped.push(SkippedPrototype::logged(
+                    &prototype.name,
+                    Some(&source),
+                    format!(
+                        "Unknown prototype type id {:?} of prototype {} at {source}",
+                        type_id.type_id(),
+                        prototype.name
+                    ),
+                ));
+                return None;
+            };
+
+            let mut handle_processor = HandleProcessor {
+                load_context: load_context.as_deref_mut(),
+            };
+
+            // Numbers out of the range of their field fail the deserialization below, the
+            // error is given their field path.
+            let lossy_numbers = lossy_numbers(&prototype.proto, *type_id, registry);
+            let lossy_context = if lossy_numbers.is_empty() {
+                String::new()
+            } else {
+                format!(" ({})", lossy_numbers.join(", "))
+            };
+
+            // Lists merged into the inherited ones are deserialized apart from the other
+            // fields, see `ListMergeStrategy`.
+            let list_merges = match read_list_merges(
+                &prototype.proto,
+                type_registration,
+                registry,
+                &mut handle_processor,
+            ) {
+                Ok(list_merges) => list_merges,
+                Err(err) => {
+                    skipped.push(SkippedPrototype::logged(
+                        &prototype.name,
+                        Some(&source),
+                        format!(
+                            "Failed to deserialize prototype {} at {source}: {err}{lossy_context}",
+                            prototype.name
+                        ),
+                    ));
+                    return None;
                 }
+            };
+
+            let reflect_deserializer = TypedReflectDeserializer::with_processor(
+                type_registration,
+                registry,
+                &mut handle_processor,
+            );
+
+            // The fields are deserialized from their JSON in the file.
+            let data = prototype
+                .data()
+                .filter(|(field, _)| !is_list_merge_key(field));
 
-                impl serde::de::Visitor<'_> for AssetPathVisitor<'_, '_> {
-                    type Value = AssetPath<'static>;
-
-                    fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
-                        formatter.write_str("asset path")
-                    }
-
-                    fn visit_str<E>(self, relative_path: &str) -> Result<Self::Value, E>
-                    where
-                        E: serde::de::Error,
-                    {
-                        Ok(self
-                            .load_context
-                            .asset_path()
-                            .parent()
-                            .unwrap()
-                            .resolve(relative_path)
-                            .map_err(|err| serde::de::Error::custom(err.to_string()))?
-                            .into_owned())
-                    }
+            let proto = match reflect_deserializer.deserialize(RawFieldsDeserializer::new(data)) {
+                Ok(proto) => proto,
+                Err(err) => {
+                    skipped.push(SkippedPrototype::logged(
+                        &prototype.name,
+                        Some(&source),
+                        format!(
+                            "Failed to deserialize prototype {} at {source}: {err}{lossy_context}",
+                            prototype.name
+                        ),
+                    ));
+                    return None;
                 }
+            };
 
-                let type_info = registration.type_info();
-                let type_path = type_info.type_path_table();
+            let fingerprint = prototype.fingerprint();
 
-                if type_path.module_path() != Some("bevy_asset::handle")
-                    || type_path.ident() != Some("Handle")
-                {
-                    return Ok(Err(deserializer));
+            Some((
+                *type_id,
+                DynamicPrototype {
+                    source,
+                    name: prototype.name,
+                    tags: prototype.tags.into_iter().map(Cow::into_owned).collect(),
+                    defaults_from: prototype.defaults_from,
+                    metadata: prototype.metadata.as_deref().map(from_raw),
+                    experiment: prototype.experiment,
+                    uuid: prototype.uuid,
+                    proto,
+                    list_merges,
+                    lossy_numbers,
+                    fingerprint: Some(fingerprint),
+                },
+            ))
+        })
+        .collect::<Vec<_>>();
+
+    (prototypes, skipped)
+}
+
+/// Parses the entries of the prototypes file at `path`, like the prototypes asset loader, e.g.
+/// for tools and custom asset systems reading the prototypes files themselves.
+///
+/// The format is told from the extension of `path`, the fragments, generators and `names` of
+/// the entries are expanded and the former prototype type names are replaced. The profile
+/// override files aren't read. Pair it with [`reflect_prototypes`] to deserialize the data of the
+/// entries, neither needs the world so both can run from any task:
+///
+/// ```no_run
+/// # use bevy::{asset::AssetPath, prelude::*};
+/// # use bevy_histrion_proto::prelude::*;
+/// # fn f(
+/// #     type_registry: &AppTypeRegistry,
+/// #     prototype_types: &AppPrototypeTypeRegistry,
+/// # ) -> std::io::Result<()> {
+/// let bytes = std::fs::read("assets/prototypes/swords.proto.json")?;
+/// let path = AssetPath::from("prototypes/swords.proto.json");
+/// let entries = parse_prototypes(&path, &bytes, &type_registry.read(), &prototype_types)?;
+/// let (prototypes, skipped) =
+///     reflect_prototypes(entries, &path, &type_registry.read(), &prototype_types);
+/// # Ok(())
+/// # }
+/// ```
+pub fn parse_prototypes<'de>(
+    path: &AssetPath,
+    bytes: &'de [u8],
+    type_registry: &TypeRegistry,
+    prototype_types: &AppPrototypeTypeRegistry,
+) -> std::io::Result<OnDiskPrototypes<'de>> {
+    parse_on_disk_prototypes(path.path(), bytes, type_registry, &prototype_types.0)
+}
+
+/// Deserializes the data of the entries parsed by [`parse_prototypes`] into their prototype type,
+/// like the prototypes asset loader, returning the prototypes and the skipped entries.
+///
+/// The assets of the handle fields aren't loaded, the fields keep their default handle.
+pub fn reflect_prototypes(
+    prototypes: OnDiskPrototypes<'_>,
+    path: &AssetPath,
+    type_registry: &TypeRegistry,
+    prototype_types: &AppPrototypeTypeRegistry,
+) -> (Vec<(TypeId, DynamicPrototype)>, Vec<SkippedPrototype>) {
+    reflect_on_disk_prototypes(
+        prototypes,
+        &path.clone_owned(),
+        type_registry,
+        &prototype_types.0.read(),
+        None,
+    )
+}
+
+/// Returns the byte offsets of the entries of a prototypes file, the elements of its top-level
+/// array or the top-level objects of single prototype and newline-delimited files.
+pub(crate) fn entry_offsets(bytes: &[u8]) -> Vec<usize> {
+    let mut offsets = Vec::new();
+    let (mut depth, mut in_string, mut escaped) = (0usize, false, false);
+    let (mut expecting_entry, mut in_list) = (false, false);
+
+    for (offset, byte) in bytes.iter().enumerate() {
+        if in_string {
+            match byte {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+
+        if byte.is_ascii_whitespace() {
+            continue;
+        }
+
+        if (depth == 0 && *byte == b'{') || (expecting_entry && *byte != b']') {
+            offsets.push(offset);
+        }
+
+        expecting_entry = false;
+
+        match byte {
+            b'"' => in_string = true,
+            b'[' | b'{' => {
+                depth += 1;
+
+                if depth == 1 {
+                    in_list = *byte == b'[';
+                    expecting_entry = in_list;
                 }
+            }
+            b']' | b'}' => depth = depth.saturating_sub(1),
+            b',' => expecting_entry = depth == 1 && in_list,
+            _ => {}
+        }
+    }
+
+    offsets
+}
 
-                let Some(reflect_default) = registration.data::<ReflectDefault>() else {
-                    error!("Handle didn't have a ReflectDefault");
-                    return Ok(Err(deserializer));
+/// Loads the asset handles of the prototypes from their path, relative to the loaded file.
+pub(crate) struct HandleProcessor<'a, 'b> {
+    /// Loads the assets of the handle fields, they are left to their default value without it.
+    pub load_context: Option<&'a mut LoadContext<'b>>,
+}
+
+impl ReflectDeserializerProcessor for HandleProcessor<'_, '_> {
+    fn try_deserialize<'de, D>(
+        &mut self,
+        registration: &TypeRegistration,
+        _registry: &TypeRegistry,
+        deserializer: D,
+    ) -> Result<Result<Box<dyn PartialReflect>, D>, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct AssetPathVisitor<'a, 'b> {
+            load_context: &'a mut LoadContext<'b>,
+        }
+
+        impl serde::de::Visitor<'_> for AssetPathVisitor<'_, '_> {
+            type Value = AssetPath<'static>;
+
+            fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+                formatter.write_str("asset path")
+            }
+
+            fn visit_str<E>(self, relative_path: &str) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                let folder = self.load_context.asset_path().parent().unwrap();
+
+                // Synchronized prototypes reference the assets of the default source.
+                #[cfg(feature = "sync")]
+                let folder = if folder.source()
+                    == &bevy::asset::io::AssetSourceId::from(crate::SYNC_SOURCE)
+                {
+                    folder.with_source(bevy::asset::io::AssetSourceId::Default)
+                } else {
+                    folder
                 };
 
-                let generics = type_info.generics();
-                let GenericInfo::Type(asset_type) = &generics[0] else {
-                    error!("Handle didn't have a generic type parameter, why?");
-                    return Ok(Err(deserializer));
+                // So do the remote tables, their asset paths are relative to the assets folder.
+                #[cfg(feature = "remote_tables")]
+                let folder = if folder.source()
+                    == &bevy::asset::io::AssetSourceId::from(crate::REMOTE_TABLE_SOURCE)
+                {
+                    folder.with_source(bevy::asset::io::AssetSourceId::Default)
+                } else {
+                    folder
                 };
 
-                let asset_path = deserializer.deserialize_str(AssetPathVisitor {
-                    load_context: self.load_context,
-                })?;
-
-                // Load the asset and return an handle to it
-                let handle = self
-                    .load_context
-                    .loader()
-                    .with_dynamic_type(asset_type.type_id())
-                    .load(asset_path);
-
-                let mut dyn_handle = DynamicEnum::default();
-
-                match handle {
-                    UntypedHandle::Strong(strong_handle) => {
-                        dyn_handle.set_variant("Strong", {
-                            let mut dyn_tuple = DynamicTuple::default();
-                            dyn_tuple.insert_boxed(strong_handle.to_dynamic());
-                            dyn_tuple
-                        });
-                    }
-                    UntypedHandle::Weak(untyped_asset_id) => {
-                        dyn_handle.set_variant("Weak", {
-                            let mut dyn_tuple = DynamicTuple::default();
-                            dyn_tuple.insert_boxed({
-                                let mut dyn_enum = DynamicEnum::default();
-
-                                match untyped_asset_id {
-                                    bevy::asset::UntypedAssetId::Index { index, .. } => {
-                                        dyn_enum.set_variant("Index", {
-                                            let mut dyn_struct = DynamicStruct::default();
-                                            dyn_struct.insert_boxed("index", index.to_dynamic());
-                                            dyn_struct
-                                        });
-                                    }
-                                    bevy::asset::UntypedAssetId::Uuid { uuid, .. } => {
-                                        dyn_enum.set_variant("Uuid", {
-                                            let mut dyn_struct = DynamicStruct::default();
-                                            dyn_struct.insert_boxed("uuid", uuid.to_dynamic());
-                                            dyn_struct
-                                        });
-                                    }
-                                }
-
-                                dyn_enum.to_dynamic()
-                            });
-                            dyn_tuple
-                        });
-                    }
-                }
+                Ok(folder
+                    .resolve(relative_path)
+                    .map_err(|err| serde::de::Error::custom(err.to_string()))?
+                    .into_owned())
+            }
+        }
+
+        let type_info = registration.type_info();
+        let type_path = type_info.type_path_table();
+
+        if !is_handle_type(type_path) {
+            return Ok(Err(deserializer));
+        }
+
+        let Some(reflect_default) = registration.data::<ReflectDefault>() else {
+            error!("Handle didn't have a ReflectDefault");
+            return Ok(Err(deserializer));
+        };
+
+        let generics = type_info.generics();
+        let GenericInfo::Type(asset_type) = &generics[0] else {
+            error!("Handle didn't have a generic type parameter, why?");
+            return Ok(Err(deserializer));
+        };
+
+        let Some(load_context) = self.load_context.as_deref_mut() else {
+            serde::de::IgnoredAny::deserialize(deserializer)?;
+            return Ok(Ok(reflect_default.default().into_partial_reflect()));
+        };
 
-                let mut typed_handle = reflect_default.default();
-                typed_handle.apply(&dyn_handle);
+        let asset_path = deserializer.deserialize_str(AssetPathVisitor {
+            load_context: &mut *load_context,
+        })?;
 
-                Ok(Ok(typed_handle.into_partial_reflect()))
+        // Load the asset and return an handle to it
+        let handle = load_context
+            .loader()
+            .with_dynamic_type(asset_type.type_id())
+            .load(asset_path);
+
+        let mut dyn_handle = DynamicEnum::default();
+
+        match handle {
+            UntypedHandle::Strong(strong_handle) => {
+                dyn_handle.set_variant("Strong", {
+                    let mut dyn_tuple = DynamicTuple::default();
+                    dyn_tuple.insert_boxed(strong_handle.to_dynamic());
+                    dyn_tuple
+                });
+            }
+            UntypedHandle::Weak(untyped_asset_id) => {
+                dyn_handle.set_variant("Weak", {
+                    let mut dyn_tuple = DynamicTuple::default();
+                    dyn_tuple.insert_boxed({
+                        let mut dyn_enum = DynamicEnum::default();
+
+                        match untyped_asset_id {
+                            bevy::asset::UntypedAssetId::Index { index, .. } => {
+                                dyn_enum.set_variant("Index", {
+                                    let mut dyn_struct = DynamicStruct::default();
+                                    dyn_struct.insert_boxed("index", index.to_dynamic());
+                                    dyn_struct
+                                });
+                            }
+                            bevy::asset::UntypedAssetId::Uuid { uuid, .. } => {
+                                dyn_enum.set_variant("Uuid", {
+                                    let mut dyn_struct = DynamicStruct::default();
+                                    dyn_struct.insert_boxed("uuid", uuid.to_dynamic());
+                                    dyn_struct
+                                });
+                            }
+                        }
+
+                        dyn_enum.to_dynamic()
+                    });
+                    dyn_tuple
+                });
             }
         }
 
-        let registry = self.type_registry.read();
-        let prototype_type_registry = self.prototype_type_registry.read();
+        let mut typed_handle = reflect_default.default();
+        typed_handle.apply(&dyn_handle);
 
-        // Convert each on-disk prototype to a dynamic prototype
-        let prototypes = (*on_disk_prototypes)
-            .iter()
-            .filter_map(|prototype| {
-                // Look up the type ID for this prototype
-                let Some(type_id) = prototype_type_registry.get(&prototype.ty) else {
-                    error!("Unknown prototype type {}", prototype.ty);
-                    return None;
-                };
+        Ok(Ok(typed_handle.into_partial_reflect()))
+    }
+}
 
-                let Some(type_registration) = registry.get(*type_id) else {
-                    error!("Unknown prototype type id {:?}", type_id.type_id());
-                    return None;
-                };
+/// Returns `true` if the given type path is the one of an asset [`Handle`].
+pub(crate) fn is_handle_type(type_path: &TypePathTable) -> bool {
+    type_path.module_path() == Some("bevy_asset::handle") && type_path.ident() == Some("Handle")
+}
 
-                let mut handle_processor = HandleProcessor { load_context };
-                let reflect_deserializer = TypedReflectDeserializer::with_processor(
-                    type_registration,
-                    &registry,
-                    &mut handle_processor,
-                );
-
-                let proto = match reflect_deserializer.deserialize(&prototype.proto) {
-                    Ok(proto) => proto,
-                    Err(err) => {
-                        error!("Failed to deserialize prototype: {}", err);
-                        return None;
-                    }
-                };
+/// Expands the generator entries and the entries with `names` into one entry per prototype.
+pub(crate) fn expand_entry(entry: JsonValue) -> Result<Vec<JsonValue>, String> {
+    let mut entries = Vec::new();
 
-                Some((
-                    *type_id,
-                    DynamicPrototype {
-                        name: prototype.name.clone(),
-                        tags: prototype.tags.clone(),
-                        proto,
-                    },
-                ))
-            })
-            .collect::<Vec<_>>();
+    for entry in expand_generator(entry)? {
+        entries.extend(expand_names(entry)?);
+    }
+
+    Ok(entries)
+}
+
+/// Expands an entry naming a family of prototypes with `names` into one entry per name, e.g.
+/// `{ "type": "sword", "names": ["iron_sword", { "name": "steel_sword", "damage": 12 }], "damage": 8 }`.
+///
+/// The objects of `names` override the fields of the entry for their prototype, objects being
+/// merged field by field. Entries without `names` are returned as they are.
+pub(crate) fn expand_names(entry: JsonValue) -> Result<Vec<JsonValue>, String> {
+    let JsonValue::Object(mut fields) = entry else {
+        return Ok(vec![entry]);
+    };
 
-        Ok(PrototypesAsset(prototypes.into_boxed_slice()))
+    let Some(names) = fields.remove("names") else {
+        return Ok(vec![JsonValue::Object(fields)]);
+    };
+
+    if fields.contains_key("name") {
+        return Err("entry has both `name` and `names`".to_string());
     }
 
-    fn extensions(&self) -> &[&str] {
-        PROTOTYPE_ASSET_EXTENSIONS
+    let JsonValue::Array(names) = names else {
+        return Err("`names` isn't a list".to_string());
+    };
+
+    if names.is_empty() {
+        return Err("`names` is empty".to_string());
+    }
+
+    names
+        .into_iter()
+        .map(|name| {
+            let mut entry = JsonValue::Object(fields.clone());
+
+            match name {
+                JsonValue::String(name) => {
+                    merge_json(&mut entry, serde_json::json!({ "name": name }));
+                }
+                JsonValue::Object(overrides)
+                    if overrides.get("name").is_some_and(JsonValue::is_string) =>
+                {
+                    merge_json(&mut entry, JsonValue::Object(overrides));
+                }
+                _ => {
+                    return Err(
+                        "`names` holds a value that isn't a name or an object with a `name`"
+                            .to_string(),
+                    );
+                }
+            }
+
+            Ok(entry)
+        })
+        .collect()
+}
+
+/// Sets the `type` and `name` a single-prototype file doesn't write from its file name, e.g.
+/// `wooden_stick.sword.proto.json` holds the data of the `wooden_stick` prototype of type `sword`
+/// and `wooden_stick.proto.json` only names its prototype.
+pub(crate) fn named_from_file(entry: JsonValue, path: &Path) -> JsonValue {
+    let JsonValue::Object(fields) = entry else {
+        return entry;
+    };
+
+    let Some((name, ty)) = file_prototype_name(path) else {
+        return JsonValue::Object(fields);
+    };
+
+    let mut entry = JsonMap::new();
+
+    if !fields.contains_key("type")
+        && let Some(ty) = ty
+    {
+        entry.insert("type".to_string(), ty.into());
+    }
+
+    if !["name", "names", "generate"]
+        .iter()
+        .any(|field| fields.contains_key(*field))
+    {
+        entry.insert("name".to_string(), name.into());
     }
+
+    entry.extend(fields);
+    JsonValue::Object(entry)
 }
 
-pub(crate) const PROTOTYPE_ASSET_EXTENSIONS: &[&str] = &["proto", "proto.json"];
+/// Returns the name and the type, if any, a single-prototype file is named after, see
+/// [`named_from_file`].
+fn file_prototype_name(path: &Path) -> Option<(&str, Option<&str>)> {
+    let stem = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .and_then(|name| {
+            PROTOTYPE_ASSET_EXTENSIONS
+                .iter()
+                .find_map(|extension| name.strip_suffix(&format!(".{extension}")))
+        })?;
+
+    Some(match stem.split_once('.') {
+        Some((name, ty)) => (name, Some(ty)),
+        None => (stem, None),
+    })
+}
+
+/// Extensions of the prototypes files and tables loaded by the prototypes asset loader.
+#[cfg(not(feature = "kdl"))]
+pub(crate) const PROTOTYPE_LOADER_EXTENSIONS: &[&str] = &[
+    "proto",
+    "proto.json",
+    "proto.ndjson",
+    "proto.csv",
+    "proto.tsv",
+];
+
+/// Extensions of the prototypes files and tables loaded by the prototypes asset loader.
+#[cfg(feature = "kdl")]
+pub(crate) const PROTOTYPE_LOADER_EXTENSIONS: &[&str] = &[
+    "proto",
+    "proto.json",
+    "proto.ndjson",
+    "proto.csv",
+    "proto.tsv",
+    "proto.kdl",
+];
+
+/// Extensions of the KDL prototypes files, none without the `kdl` feature.
+#[cfg(not(feature = "kdl"))]
+pub(crate) const PROTOTYPE_KDL_EXTENSIONS: &[&str] = &[];
 
-pub trait PrototypeData: Default + Clone + Reflectable + FromReflect + JsonSchema {
+/// KDL prototypes files need the `kdl` feature.
+#[cfg(not(feature = "kdl"))]
+pub(crate) fn read_kdl_file(
+    _path: &Path,
+    _bytes: &[u8],
+) -> std::io::Result<Option<Vec<(usize, JsonValue)>>> {
+    Ok(None)
+}
+
+pub trait PrototypeData: Default + Clone + Reflectable + FromReflect + PrototypeDataSchema {
+    /// Returns the `type` of the prototypes of this type in the prototypes files.
+    ///
+    /// Set with `#[proto(name = "sword")]`, the name of generic prototypes includes their
+    /// parameters, e.g. `stat<strength>` for `Stat<Strength>`, or places them with placeholders,
+    /// e.g. `#[proto(name = "{T}_stat")]` for `strength_stat`.
     fn prototype_name() -> &'static str;
+
+    /// Returns the tags the prototypes of this type may have, any tag when `None`.
+    ///
+    /// Set with `#[proto(allowed_tags("weapon", "consumable"))]`, prototypes with other tags are
+    /// rejected at load.
+    fn allowed_tags() -> Option<&'static [&'static str]> {
+        None
+    }
+}
+
+/// The schema bound of [`PrototypeData`], [`JsonSchema`] with the `schema` feature and none
+/// without it, implemented for every type meeting it.
+#[cfg(feature = "schema")]
+pub trait PrototypeDataSchema: JsonSchema {}
+#[cfg(not(feature = "schema"))]
+pub trait PrototypeDataSchema {}
+
+#[cfg(feature = "schema")]
+impl<T: JsonSchema> PrototypeDataSchema for T {}
+#[cfg(not(feature = "schema"))]
+impl<T> PrototypeDataSchema for T {}
+
+/// Where a prototype was loaded from, see [`Prototype::source`].
+#[derive(Debug, Clone, PartialEq, Eq, Reflect)]
+#[reflect(Clone, Debug, PartialEq)]
+pub struct PrototypeSource {
+    pub(crate) path: AssetPath<'static>,
+    pub(crate) index: usize,
+    /// Line and column of the entry, starting at 1, when known.
+    pub(crate) position: Option<(usize, usize)>,
+    /// JSON pointer of the entry in the file, e.g. `/3`, empty for single prototype files.
+    pub(crate) pointer: String,
 }
 
+impl PrototypeSource {
+    /// Returns the asset path of the prototypes file.
+    #[inline(always)]
+    pub fn path(&self) -> &AssetPath<'static> {
+        &self.path
+    }
+
+    /// Returns the index of the entry in the prototypes file.
+    #[inline(always)]
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Returns the line of the entry in the prototypes file, starting at 1.
+    #[inline(always)]
+    pub fn line(&self) -> Option<usize> {
+        self.position.map(|(line, _)| line)
+    }
+
+    /// Returns the column of the entry in the prototypes file, starting at 1.
+    #[inline(always)]
+    pub fn column(&self) -> Option<usize> {
+        self.position.map(|(_, column)| column)
+    }
+
+    /// Returns the JSON pointer of the entry in the prototypes file, e.g. `/3`.
+    #[inline(always)]
+    pub fn pointer(&self) -> &str {
+        &self.pointer
+    }
+}
+
+/// Formats the source as `path:line:column (pointer)`, e.g. `items.proto.json:12:5 (/2)`.
+impl core::fmt::Display for PrototypeSource {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.path)?;
+
+        if let Some((line, column)) = self.position {
+            write!(f, ":{line}:{column}")?;
+        }
+
+        if !self.pointer.is_empty() {
+            write!(f, " ({})", self.pointer)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the uuid of the prototype of type `ty` with the `id` id without `"uuid"` in its entry,
+/// see [`Prototype::uuid`].
+pub fn derived_prototype_uuid(ty: &str, id: ErasedPrototypeId) -> Uuid {
+    let mut bytes = [0; 16];
+    bytes[..8].copy_from_slice(&fnv1a_hash_str_64(ty).to_be_bytes());
+    bytes[8..].copy_from_slice(&id.to_raw().to_be_bytes());
+
+    uuid::Builder::from_custom_bytes(bytes).into_uuid()
+}
+
+/// Editor metadata of a prototype, see [`Prototype::metadata`].
+#[derive(Debug, Clone, Default, PartialEq, Reflect, Serialize, Deserialize)]
+#[reflect(opaque, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub(crate) struct PrototypeMetadata(pub Option<serde_json::Value>);
+
 #[derive(Debug, Clone, Reflect, Deref, DerefMut)]
 #[reflect(Clone, Default)]
 pub struct Prototype<P: PrototypeData> {
-    name: PrototypeName<P>,
+    pub(crate) name: PrototypeName<P>,
     tags: Vec<String>,
+    metadata: PrototypeMetadata,
+    pub(crate) uuid: Option<Uuid>,
+    pub(crate) source: Option<PrototypeSource>,
     #[deref]
     data: P,
 }
@@ -299,6 +1458,7 @@ impl<P: PrototypeData> Prototype<P> {
     }
 
     /// Returns the string name of this prototype instance.
+    #[cfg(not(feature = "strip_names"))]
     #[inline(always)]
     pub fn name(&self) -> &str {
         self.name.name()
@@ -310,6 +1470,41 @@ impl<P: PrototypeData> Prototype<P> {
         &self.tags
     }
 
+    /// Returns where this prototype instance was loaded from, `None` for the prototypes added at
+    /// runtime.
+    #[inline(always)]
+    pub fn source(&self) -> Option<&PrototypeSource> {
+        self.source.as_ref()
+    }
+
+    /// Returns the editor metadata of this prototype instance, the `"$meta"` (or `"$editor"`)
+    /// object of its entry, e.g. comments or the position of its node in a node editor.
+    #[inline(always)]
+    pub fn metadata(&self) -> Option<&serde_json::Value> {
+        self.metadata.0.as_ref()
+    }
+
+    /// Returns the stable id of this prototype instance, for the external databases and
+    /// analytics needing ids immune to renames.
+    ///
+    /// It's the `"uuid"` of its entry, or when the entry has none the one derived from its type
+    /// and name by [`derived_prototype_uuid`], which changes when the prototype is renamed. Write
+    /// the derived uuids into the entries before renaming them, e.g. with `pack assign-uuids`.
+    #[inline(always)]
+    pub fn uuid(&self) -> Uuid {
+        self.uuid
+            .unwrap_or_else(|| derived_prototype_uuid(P::prototype_name(), (*self.id()).into()))
+    }
+
+    /// Returns a random seed of this prototype instance derived from its [`uuid`](Self::uuid),
+    /// e.g. for procedural content that must stay the same across sessions.
+    #[inline(always)]
+    pub fn seed(&self) -> u64 {
+        let uuid = self.uuid().as_u128();
+
+        (uuid >> 64) as u64 ^ uuid as u64
+    }
+
     /// Returns a reference tothe [`PrototypeData`] of this prototype instance.
     #[inline(always)]
     pub fn data(&self) -> &P {
@@ -329,43 +1524,185 @@ impl<P: PrototypeData> Prototype<P> {
     }
 }
 
+#[cfg(test)]
+impl<P: PrototypeData> Prototype<P> {
+    /// Builds a prototype for the unit tests.
+    pub(crate) fn for_test(name: &str, tags: &[&str], data: P) -> Self {
+        Self {
+            name: PrototypeName::from_name(name),
+            tags: tags.iter().map(ToString::to_string).collect(),
+            data,
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn tags_mut(&mut self) -> &mut Vec<String> {
+        &mut self.tags
+    }
+}
+
 impl<P: PrototypeData> Default for Prototype<P> {
     fn default() -> Self {
         Self {
             name: PrototypeName::from_name(""),
             tags: Default::default(),
+            metadata: Default::default(),
+            uuid: None,
+            source: None,
             data: Default::default(),
         }
     }
 }
 
-impl<P: PrototypeData> JsonSchema for Prototype<P> {
-    fn json_schema(refs: &mut serde_json::Map<String, serde_json::Value>) -> serde_json::Value {
-        let ty_title = <PrototypeName<P> as JsonSchema>::schema_title();
-        if !refs.contains_key(&ty_title) {
-            let ty_schema = <PrototypeName<P> as JsonSchema>::json_schema(refs);
-            refs.insert(ty_title, ty_schema);
-        }
-        let ty_title = <Vec<String> as JsonSchema>::schema_title();
-        if !refs.contains_key(&ty_title) {
-            let ty_schema = <Vec<String> as JsonSchema>::json_schema(refs);
-            refs.insert(ty_title, ty_schema);
+/// Prototypes are compared by id, the registries hold a single prototype per id.
+impl<P: PrototypeData> PartialEq for Prototype<P> {
+    fn eq(&self, other: &Self) -> bool {
+        self.id() == other.id()
+    }
+}
+
+impl<P: PrototypeData> Eq for Prototype<P> {}
+
+impl<P: PrototypeData> core::hash::Hash for Prototype<P> {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.id().hash(state);
+    }
+}
+
+impl<P: PrototypeData> AsRef<P> for Prototype<P> {
+    fn as_ref(&self) -> &P {
+        &self.data
+    }
+}
+
+impl<P: PrototypeData> AsMut<P> for Prototype<P> {
+    fn as_mut(&mut self) -> &mut P {
+        &mut self.data
+    }
+}
+
+/// Serialized like an entry of a prototypes file, the fields of the data next to its `type`,
+/// `name`, `tags` and `$meta`.
+impl<P: PrototypeData + Serialize> Serialize for Prototype<P> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        #[derive(Serialize)]
+        struct Entry<'a, P> {
+            #[serde(rename = "type")]
+            ty: &'static str,
+            name: &'a PrototypeName<P>,
+            #[serde(skip_serializing_if = "<[String]>::is_empty")]
+            tags: &'a [String],
+            #[serde(rename = "$meta", skip_serializing_if = "Option::is_none")]
+            metadata: Option<&'a JsonValue>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            uuid: Option<Uuid>,
+            #[serde(flatten)]
+            data: &'a P,
         }
-        let ty_title = <P as JsonSchema>::schema_title();
-        if !refs.contains_key(&ty_title) {
-            let ty_schema = <P as JsonSchema>::json_schema(refs);
-            refs.insert(ty_title, ty_schema);
+
+        Entry {
+            ty: P::prototype_name(),
+            name: &self.name,
+            tags: &self.tags,
+            metadata: self.metadata(),
+            uuid: self.uuid,
+            data: &self.data,
         }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "schema")]
+impl<P: PrototypeData> JsonSchema for Prototype<P> {
+    fn json_schema(refs: &mut serde_json::Map<String, serde_json::Value>) -> serde_json::Value {
+        <PrototypeName<P> as JsonSchema>::register_schema(refs);
+        <Vec<String> as JsonSchema>::register_schema(refs);
+        <P as JsonSchema>::register_schema(refs);
 
         serde_json::json!({
             "type":"object",
-            "required": ["name"],
+            "anyOf": [
+                { "required": ["name"] },
+                { "required": ["names"] },
+                { "required": ["generate"] },
+            ],
             "properties":{
                 "name":{
                     "$ref": <PrototypeName<P>as JsonSchema> ::schema_ref()
                 },
-                "tags":{
-                    "$ref": <Vec<String>as JsonSchema> ::schema_ref()
+                "names": {
+                    "type": "array",
+                    "description": "Names of a family of prototypes sharing the fields of this entry, objects override them for their prototype",
+                    "minItems": 1,
+                    "items": {
+                        "anyOf": [
+                            { "$ref": <PrototypeName<P>as JsonSchema> ::schema_ref() },
+                            {
+                                "type": "object",
+                                "required": ["name"],
+                                "properties": {
+                                    "name": { "$ref": <PrototypeName<P>as JsonSchema> ::schema_ref() },
+                                },
+                            },
+                        ],
+                    },
+                },
+                "generate": {
+                    "type": "object",
+                    "description": "Generates a prototype per value i of the inclusive range, {i} is replaced by i in the name and the strings of data, strings of data starting with = are formulas of i",
+                    "required": ["name", "range"],
+                    "properties": {
+                        "name": { "type": "string" },
+                        "range": {
+                            "type": "array",
+                            "items": { "type": "integer" },
+                            "minItems": 2,
+                            "maxItems": 2,
+                        },
+                        "data": { "type": "object" },
+                    },
+                },
+                "uuid": {
+                    "type": "string",
+                    "format": "uuid",
+                    "description": "Stable id of the prototype kept across renames, derived from its type and name when not set",
+                },
+                "$fragments": {
+                    "type": "object",
+                    "description": "Values shared by the entries of this file, referenced as \"$fragments.<name>\" strings",
+                },
+                "$meta": {
+                    "type": "object",
+                    "description": "Editor metadata, ignored by the loader but kept with the prototype",
+                },
+                "$editor": {
+                    "type": "object",
+                    "description": "Editor metadata, ignored by the loader but kept with the prototype",
+                },
+                "experiment": {
+                    "type": "object",
+                    "description": "Experiment group this prototype is only applied for, replacing the prototype of the same name",
+                    "required": ["name", "group"],
+                    "properties": {
+                        "name": { "type": "string" },
+                        "group": { "type": "string" },
+                    },
+                },
+                "defaults_from": {
+                    "type": "string",
+                    "description": "Prototype of the same type whose data is used for the fields this prototype doesn't set",
+                },
+                "tags": match P::allowed_tags() {
+                    Some(allowed_tags) => serde_json::json!({
+                        "type": "array",
+                        "items": { "enum": allowed_tags },
+                    }),
+                    None => serde_json::json!({
+                        "$ref": <Vec<String>as JsonSchema> ::schema_ref()
+                    }),
                 }
             },
             "allOf": [{
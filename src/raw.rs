@@ -0,0 +1,311 @@
+use core::hash::Hasher;
+use std::borrow::Cow;
+
+use serde::{
+    Deserialize,
+    de::{self, DeserializeSeed, MapAccess, Visitor, value::MapAccessDeserializer},
+};
+use serde_json::{Number, Value as JsonValue, value::RawValue};
+
+/// The fields of a prototype entry in the file order, borrowed from the bytes of the file unless
+/// the loader rewrote them, e.g. the entries of a generator or the fields of a profile override.
+pub(crate) type RawFields<'de> = Vec<(Cow<'de, str>, Cow<'de, RawValue>)>;
+
+/// A JSON string, borrowed from the JSON unless it has escape sequences.
+#[derive(Deserialize)]
+pub(crate) struct RawStr<'a>(#[serde(borrow)] pub Cow<'a, str>);
+
+/// The fields of a JSON object, borrowed from the JSON.
+struct RawObject<'a>(Vec<(Cow<'a, str>, &'a RawValue)>);
+
+impl<'de> Deserialize<'de> for RawObject<'de> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct RawObjectVisitor;
+
+        impl<'de> Visitor<'de> for RawObjectVisitor {
+            type Value = RawObject<'de>;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(formatter, "a map")
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let mut fields = Vec::with_capacity(map.size_hint().unwrap_or_default());
+
+                while let Some((RawStr(key), value)) = map.next_entry()? {
+                    fields.push((key, value));
+                }
+
+                Ok(RawObject(fields))
+            }
+        }
+
+        deserializer.deserialize_map(RawObjectVisitor)
+    }
+}
+
+/// Returns the fields of `raw` if it's an object.
+pub(crate) fn raw_object(raw: &RawValue) -> Option<Vec<(Cow<'_, str>, &RawValue)>> {
+    raw.get()
+        .starts_with('{')
+        .then(|| serde_json::from_str::<RawObject>(raw.get()).ok())
+        .flatten()
+        .map(|object| object.0)
+}
+
+/// Returns the items of `raw` if it's an array.
+pub(crate) fn raw_array(raw: &RawValue) -> Option<Vec<&RawValue>> {
+    raw.get()
+        .starts_with('[')
+        .then(|| serde_json::from_str(raw.get()).ok())
+        .flatten()
+}
+
+/// Returns `raw` if it's a number.
+pub(crate) fn raw_number(raw: &RawValue) -> Option<Number> {
+    raw.get()
+        .starts_with(|c: char| c == '-' || c.is_ascii_digit())
+        .then(|| serde_json::from_str(raw.get()).ok())
+        .flatten()
+}
+
+/// Returns the kind of a JSON value for the error messages, e.g. `string`.
+pub(crate) fn raw_kind(raw: &RawValue) -> &'static str {
+    match raw.get().as_bytes().first() {
+        Some(b'n') => "null",
+        Some(b't' | b'f') => "boolean",
+        Some(b'"') => "string",
+        Some(b'[') => "sequence",
+        Some(b'{') => "map",
+        _ => "number",
+    }
+}
+
+/// Converts a JSON value written by the loader into a field of an entry.
+pub(crate) fn to_raw(value: &JsonValue) -> Box<RawValue> {
+    // JSON values always serialize.
+    serde_json::value::to_raw_value(value).unwrap_or_default()
+}
+
+/// Parses a field of an entry into a JSON value, `null` if it's invalid, the JSON being checked
+/// when the file is read.
+pub(crate) fn from_raw(raw: &RawValue) -> JsonValue {
+    serde_json::from_str(raw.get()).unwrap_or_default()
+}
+
+/// Writes `json` to `hasher` without the whitespace between its tokens, the hash of an entry
+/// doesn't depend on its formatting as long as `hasher` hashes its writes as a single stream of
+/// bytes, like FNV-1a.
+pub(crate) fn hash_json(json: &str, hasher: &mut impl Hasher) {
+    let bytes = json.as_bytes();
+    let (mut start, mut in_string, mut escaped) = (0, false, false);
+
+    for (offset, byte) in bytes.iter().enumerate() {
+        if in_string {
+            match byte {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+
+        match byte {
+            b'"' => in_string = true,
+            _ if byte.is_ascii_whitespace() => {
+                hasher.write(&bytes[start..offset]);
+                start = offset + 1;
+            }
+            _ => {}
+        }
+    }
+
+    hasher.write(&bytes[start..]);
+}
+
+/// Removes the position of the errors of a field, relative to the JSON of the field rather than
+/// to the file.
+pub(crate) fn without_position(err: serde_json::Error) -> serde_json::Error {
+    if err.line() == 0 {
+        return err;
+    }
+
+    let message = err.to_string();
+    let position = format!(" at line {} column {}", err.line(), err.column());
+
+    de::Error::custom(message.strip_suffix(&position).unwrap_or(&message))
+}
+
+/// Deserializes the fields of an entry as a JSON object, each field being deserialized from its
+/// JSON borrowed from the file.
+pub(crate) struct RawFieldsDeserializer<'de, I> {
+    fields: I,
+    value: Option<&'de RawValue>,
+}
+
+impl<'de, I> RawFieldsDeserializer<'de, I>
+where
+    I: Iterator<Item = (&'de str, &'de RawValue)>,
+{
+    pub(crate) fn new(fields: I) -> Self {
+        Self {
+            fields,
+            value: None,
+        }
+    }
+}
+
+impl<'de, I> de::Deserializer<'de> for RawFieldsDeserializer<'de, I>
+where
+    I: Iterator<Item = (&'de str, &'de RawValue)>,
+{
+    type Error = serde_json::Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_map(self)
+    }
+
+    // Enums are written with their variant as only field, like they're read from JSON values.
+    fn deserialize_enum<V>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        de::Deserializer::deserialize_enum(
+            MapAccessDeserializer::new(self),
+            name,
+            variants,
+            visitor,
+        )
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf
+        option unit unit_struct newtype_struct seq tuple tuple_struct map struct identifier
+        ignored_any
+    }
+}
+
+impl<'de, I> MapAccess<'de> for RawFieldsDeserializer<'de, I>
+where
+    I: Iterator<Item = (&'de str, &'de RawValue)>,
+{
+    type Error = serde_json::Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        let Some((key, value)) = self.fields.next() else {
+            return Ok(None);
+        };
+
+        self.value = Some(value);
+
+        seed.deserialize(de::value::BorrowedStrDeserializer::new(key))
+            .map(Some)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let value = self
+            .value
+            .take()
+            .ok_or_else(|| de::Error::custom("value is missing"))?;
+
+        seed.deserialize(value).map_err(without_position)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.fields.size_hint().1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::*;
+    use crate::PrototypeIdHasher;
+
+    fn hash(json: &str) -> u64 {
+        let mut hasher = PrototypeIdHasher::default();
+        hash_json(json, &mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn formatting_doesn_t_change_the_hash() {
+        assert_eq!(
+            hash(r#"{"name":"stick","tags":["a","b"]}"#),
+            hash("{\n  \"name\": \"stick\",\n  \"tags\": [ \"a\", \"b\" ]\n}")
+        );
+        assert_ne!(hash(r#"{"name":"a stick"}"#), hash(r#"{"name":"astick"}"#));
+        assert_ne!(hash(r#"{"name":"a\" b"}"#), hash(r#"{"name":"a\"b"}"#));
+    }
+
+    #[test]
+    fn raw_values_are_read_by_kind() {
+        let raw =
+            serde_json::from_str::<Box<RawValue>>(r#"{"a": 1, "b\n": [true, null]}"#).unwrap();
+
+        let fields = raw_object(&raw).unwrap();
+        assert_eq!(fields[0].0, "a");
+        assert_eq!(fields[1].0, "b\n");
+        assert_eq!(raw_number(fields[0].1), Some(Number::from(1)));
+        assert_eq!(raw_kind(fields[1].1), "sequence");
+        assert_eq!(
+            raw_array(fields[1].1)
+                .unwrap()
+                .into_iter()
+                .map(raw_kind)
+                .collect::<Vec<_>>(),
+            ["boolean", "null"]
+        );
+        assert!(raw_array(&raw).is_none());
+        assert!(raw_object(fields[0].1).is_none());
+    }
+
+    #[test]
+    fn fields_deserialize_as_an_object() {
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Sword {
+            damage: u32,
+            name: String,
+        }
+
+        let damage = RawValue::from_string("3".to_string()).unwrap();
+        let name = RawValue::from_string(r#""stick""#.to_string()).unwrap();
+        let fields = [("damage", &*damage), ("name", &*name)];
+
+        let sword = Sword::deserialize(RawFieldsDeserializer::new(fields.into_iter())).unwrap();
+        assert_eq!(
+            sword,
+            Sword {
+                damage: 3,
+                name: "stick".to_string()
+            }
+        );
+
+        let err = u32::deserialize(&*name)
+            .map_err(without_position)
+            .unwrap_err();
+        assert!(!err.to_string().contains(" at line "), "{err}");
+    }
+}
@@ -1,59 +1,1488 @@
 use core::any::TypeId;
 
 use bevy::prelude::*;
-use bevy::{ecs::system::SystemParam, platform::collections::HashMap};
+use bevy::{
+    ecs::{component::Tick, system::SystemParam},
+    platform::collections::HashMap,
+    reflect::{
+        ApplyError, FromType, GetTypeRegistration, Map, MapInfo, MapIter, PartialReflect,
+        ReflectCloneError, ReflectFromReflect, ReflectKind, ReflectMut, ReflectOwned, ReflectRef,
+        TypeInfo, TypeRegistration, TypeRegistry, Typed,
+        utility::{GenericTypeInfoCell, NonGenericTypeInfoCell},
+    },
+};
 
-use crate::{ErasedPrototypeId, Prototype, PrototypeData, PrototypeId};
+use crate::{
+    ErasedPrototypeId, Prototype, PrototypeData, PrototypeId, PrototypeIdMap, PrototypeName,
+    PrototypeState, TagQuery,
+    index::PrototypeChange,
+    lazy::LazyPrototypes,
+    lookup::PrototypeLookupError,
+    storage::{PrototypeArena, PrototypeStorage},
+    telemetry::{PrototypeTelemetry, PrototypeTelemetryEvent},
+};
 
-#[derive(Default, Debug, Resource)]
+/// Where and how many times a prototype was inserted in its registry.
+///
+/// Systems caching data derived from a prototype can compare versions to know if that
+/// prototype changed, e.g. after a hot-reload, without comparing its content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PrototypeVersion {
+    /// Insertion order of the prototype in its registry, kept when the prototype is updated.
+    pub index: u32,
+    /// Number of times the prototype was updated since its first insertion.
+    pub generation: u32,
+}
+
+/// The registries of the prototypes, by type of prototype data.
+///
+/// Registered in the type registry, reflection-based tools can read them as a map of registries
+/// by prototype type name, each registry being a map of [`Prototype`] by [`ErasedPrototypeId`].
+#[derive(Default, Debug, Resource, TypePath)]
 pub(crate) struct PrototypeRegistries {
-    registries: HashMap<TypeId, HashMap<ErasedPrototypeId, Box<dyn Reflect>>>,
+    registries: HashMap<TypeId, Registry>,
+    /// Prototype type name of each registry, the keys of the registries when reflected.
+    names: HashMap<TypeId, String>,
+    /// Whether the registries were frozen, see [`freeze_prototypes`].
+    frozen: bool,
+}
+
+#[derive(Debug, TypePath)]
+struct Registry {
+    /// Slot of each prototype in `storage` and `entries`.
+    slots: PrototypeIdMap<ErasedPrototypeId, usize>,
+    /// The prototypes, contiguous in their storage order, see [`PrototypeStorage`].
+    storage: Box<dyn PrototypeStorage>,
+    /// The id and version of the prototype of each slot.
+    entries: Vec<RegistryEntry>,
+    /// Slots of the prototypes of each tag, sorted.
+    tags: HashMap<Box<str>, Vec<usize>>,
+    /// Whether prototypes were accessed mutably through reflection since `tags` was built,
+    /// their tags may have changed.
+    tags_dirty: bool,
+    next_index: u32,
+    /// Prototypes replaced by an insert since the last [`send_reloaded_events`].
+    reloaded: Vec<ErasedPrototypeId>,
+    /// Prototypes inserted or removed since the last [`send_reloaded_events`], in their order.
+    changes: Vec<RegistryChange>,
+    /// Prototype type name of the registry.
+    name: &'static str,
+    /// Whether the prototypes can't be changed anymore, see [`freeze_prototypes`].
+    frozen: bool,
+    /// Prototypes whose change was rejected since the last [`send_rejected_writes`].
+    rejected: Vec<ErasedPrototypeId>,
+    /// Type info of the registry when reflected, with the [`Prototype`] type of its values.
+    type_info: fn() -> &'static TypeInfo,
+    /// Builds a [`Prototype`] of the registry from a reflected value.
+    from_reflect: fn(&dyn PartialReflect) -> Option<Box<dyn Reflect>>,
+    /// Unique among all the registries, changed with each write so the [`SharedPrototypes`]
+    /// only publish the registries that changed.
+    ///
+    /// [`SharedPrototypes`]: crate::SharedPrototypes
+    #[cfg(feature = "lock_free")]
+    revision: u64,
+}
+
+#[derive(Debug)]
+enum RegistryChange {
+    Inserted(ErasedPrototypeId),
+    Removed(ErasedPrototypeId),
+}
+
+#[derive(Debug, Clone, Copy)]
+struct RegistryEntry {
+    id: ErasedPrototypeId,
+    version: PrototypeVersion,
+}
+
+impl Registry {
+    fn new<P: PrototypeData>() -> Self {
+        Self {
+            slots: PrototypeIdMap::default(),
+            storage: Box::new(PrototypeArena::<P>::new()),
+            entries: Vec::new(),
+            tags: HashMap::default(),
+            tags_dirty: false,
+            next_index: 0,
+            reloaded: Vec::new(),
+            changes: Vec::new(),
+            name: P::prototype_name(),
+            frozen: false,
+            rejected: Vec::new(),
+            type_info: registry_type_info::<P>,
+            from_reflect: |value| {
+                <Prototype<P> as FromReflect>::from_reflect(value)
+                    .map(|proto| Box::new(proto) as Box<dyn Reflect>)
+            },
+            #[cfg(feature = "lock_free")]
+            revision: next_revision(),
+        }
+    }
+
+    fn reflect_clone(&self) -> Self {
+        Self {
+            slots: self.slots.clone(),
+            storage: self.storage.clone_storage(),
+            entries: self.entries.clone(),
+            tags: self.tags.clone(),
+            tags_dirty: self.tags_dirty,
+            next_index: self.next_index,
+            reloaded: Vec::new(),
+            changes: Vec::new(),
+            name: self.name,
+            frozen: self.frozen,
+            rejected: Vec::new(),
+            type_info: self.type_info,
+            from_reflect: self.from_reflect,
+            #[cfg(feature = "lock_free")]
+            revision: self.revision,
+        }
+    }
+
+    /// Records a write to the registry, see [`Registry::revision`].
+    fn touch(&mut self) {
+        #[cfg(feature = "lock_free")]
+        {
+            self.revision = next_revision();
+        }
+    }
+
+    /// Returns the prototypes of the `P` registry, `None` if this isn't the `P` registry.
+    fn as_slice<P: PrototypeData>(&self) -> Option<&[Prototype<P>]> {
+        self.storage
+            .as_any()
+            .downcast_ref::<PrototypeArena<P>>()
+            .map(PrototypeArena::as_slice)
+    }
+
+    fn as_mut_slice<P: PrototypeData>(&mut self) -> Option<&mut [Prototype<P>]> {
+        self.storage
+            .as_any_mut()
+            .downcast_mut::<PrototypeArena<P>>()
+            .map(PrototypeArena::as_mut_slice)
+    }
+
+    fn get(&self, id: &ErasedPrototypeId) -> Option<&dyn Reflect> {
+        self.storage.get(*self.slots.get(id)?)
+    }
+
+    /// Returns the slot of a prototype about to be changed, `None` if it doesn't exist or if
+    /// the registry is frozen.
+    fn slot_mut(&mut self, id: &ErasedPrototypeId) -> Option<usize> {
+        let slot = *self.slots.get(id)?;
+
+        if self.reject_frozen(*id) {
+            return None;
+        }
+
+        self.touch();
+        Some(slot)
+    }
+
+    /// Iterates over the prototypes tagged with `tag` in their storage order.
+    fn iter_with_tag<'a>(&'a self, tag: &str) -> impl Iterator<Item = &'a dyn Reflect> + use<'a> {
+        self.tags
+            .get(tag)
+            .into_iter()
+            .flatten()
+            .filter_map(|slot| self.storage.get(*slot))
+    }
+
+    /// Returns the sorted slots of the prototypes matching `query`, resolved against the tag
+    /// index: the slots of the tags are intersected, unioned and subtracted without going over
+    /// the other prototypes, only a query made of exclusions goes over every slot.
+    fn query_slots(&self, query: &TagQuery) -> Vec<usize> {
+        match query {
+            TagQuery::Tag(tag) => self.tags.get(tag.as_str()).cloned().unwrap_or_default(),
+            TagQuery::All(queries) => {
+                let (excluded, included): (Vec<_>, Vec<_>) = queries
+                    .iter()
+                    .partition(|query| matches!(query, TagQuery::Not(_)));
+
+                let mut slots = match included.split_first() {
+                    Some((first, others)) => {
+                        others.iter().fold(self.query_slots(first), |slots, query| {
+                            if slots.is_empty() {
+                                return slots;
+                            }
+
+                            intersect_slots(&slots, &self.query_slots(query))
+                        })
+                    }
+                    None => (0..self.storage.len()).collect(),
+                };
+
+                for query in excluded {
+                    if let TagQuery::Not(query) = query
+                        && !slots.is_empty()
+                    {
+                        slots = subtract_slots(&slots, &self.query_slots(query));
+                    }
+                }
+
+                slots
+            }
+            TagQuery::Any(queries) => queries.iter().fold(Vec::new(), |slots, query| {
+                union_slots(&slots, &self.query_slots(query))
+            }),
+            TagQuery::Not(query) => subtract_slots(
+                &(0..self.storage.len()).collect::<Vec<_>>(),
+                &self.query_slots(query),
+            ),
+        }
+    }
+
+    /// Adds the prototype of `slot` to the slots of its tags.
+    fn index_tags(&mut self, slot: usize) {
+        for tag in self.storage.tags(slot) {
+            let Some(slots) = self.tags.get_mut(tag.as_str()) else {
+                self.tags.insert(tag.as_str().into(), vec![slot]);
+                continue;
+            };
+
+            if let Err(index) = slots.binary_search(&slot) {
+                slots.insert(index, slot);
+            }
+        }
+    }
+
+    /// Removes the prototype of `slot` from the slots of its tags.
+    fn unindex_tags(&mut self, slot: usize) {
+        for tag in self.storage.tags(slot) {
+            let Some(slots) = self.tags.get_mut(tag.as_str()) else {
+                continue;
+            };
+
+            if let Ok(index) = slots.binary_search(&slot) {
+                slots.remove(index);
+            }
+
+            if slots.is_empty() {
+                self.tags.remove(tag.as_str());
+            }
+        }
+    }
+
+    /// Rebuilds the tag index if prototypes were accessed mutably through reflection.
+    fn refresh_tags(&mut self) {
+        if !core::mem::take(&mut self.tags_dirty) {
+            return;
+        }
+
+        self.tags.clear();
+
+        for slot in 0..self.storage.len() {
+            self.index_tags(slot);
+        }
+    }
+
+    /// Bumps the generation of the prototype of `slot` modified in place.
+    fn mark_modified(&mut self, slot: usize) {
+        self.touch();
+
+        let entry = &mut self.entries[slot];
+        entry.version.generation = entry.version.generation.wrapping_add(1);
+        self.reloaded.push(entry.id);
+    }
+
+    /// Returns `true` if the registry is frozen, the change of the `id` prototype is then
+    /// rejected, in debug builds it panics instead.
+    fn reject_frozen(&mut self, id: ErasedPrototypeId) -> bool {
+        if !self.frozen {
+            return false;
+        }
+
+        let message = format!(
+            "Attempted to change prototype {id:?} of registry {} after the prototypes were frozen",
+            self.name
+        );
+
+        if cfg!(debug_assertions) {
+            panic!("{message}");
+        }
+
+        error!("{message}");
+        self.rejected.push(id);
+
+        true
+    }
+
+    /// Inserts or replaces a prototype, returns `false` if the registry is frozen or if it
+    /// isn't a prototype of the registry.
+    fn insert(&mut self, id: ErasedPrototypeId, proto: Box<dyn Reflect>) -> bool {
+        self.insert_with(id, |storage, slot| {
+            match slot {
+                Some(slot) => storage.replace(slot, proto),
+                None => storage.push(proto),
+            }
+            .is_ok()
+        })
+    }
+
+    /// Inserts or replaces a prototype of the `P` registry without boxing it.
+    fn insert_typed<P: PrototypeData>(&mut self, proto: Prototype<P>) -> bool {
+        self.insert_with((*proto.id()).into(), |storage, slot| {
+            let Some(storage) = storage.as_any_mut().downcast_mut::<PrototypeArena<P>>() else {
+                return false;
+            };
+
+            match slot {
+                Some(slot) => storage[slot] = proto,
+                None => storage.push(proto),
+            }
+
+            true
+        })
+    }
+
+    /// Writes a prototype in the storage with `write`, in its slot or in a new slot, and
+    /// records the insertion or the replacement.
+    fn insert_with(
+        &mut self,
+        id: ErasedPrototypeId,
+        write: impl FnOnce(&mut dyn PrototypeStorage, Option<usize>) -> bool,
+    ) -> bool {
+        if self.reject_frozen(id) {
+            return false;
+        }
+
+        let slot = self.slots.get(&id).copied();
+
+        if let Some(slot) = slot {
+            self.unindex_tags(slot);
+        }
+
+        let written = write(self.storage.as_mut(), slot);
+
+        // The replaced prototype is still in its slot if it wasn't written.
+        if let Some(slot) = slot {
+            self.index_tags(slot);
+        }
+
+        if !written {
+            error!(
+                "Attempted to insert prototype {id:?} of another type into registry {}",
+                self.name
+            );
+            return false;
+        }
+
+        if let Some(slot) = slot {
+            self.mark_modified(slot);
+            return true;
+        }
+
+        let version = PrototypeVersion {
+            index: self.next_index,
+            generation: 0,
+        };
+        self.next_index += 1;
+
+        self.touch();
+        self.slots.insert(id, self.entries.len());
+        self.entries.push(RegistryEntry { id, version });
+        self.index_tags(self.entries.len() - 1);
+        self.changes.push(RegistryChange::Inserted(id));
+
+        true
+    }
+
+    /// Removes a prototype and returns it.
+    fn remove(&mut self, id: &ErasedPrototypeId) -> Option<Box<dyn Reflect>> {
+        let slot = self.slot_mut(id)?;
+
+        // The indices never see a prototype inserted and removed between two runs of
+        // `send_reloaded_events`.
+        let inserted = self.changes.iter().position(
+            |change| matches!(change, RegistryChange::Inserted(inserted) if inserted == id),
+        );
+
+        match inserted {
+            Some(index) => {
+                self.changes.remove(index);
+            }
+            None => self.changes.push(RegistryChange::Removed(*id)),
+        }
+
+        // Nor is it modified once removed.
+        self.reloaded.retain(|reloaded| reloaded != id);
+
+        // The last prototype moves into the slot, the other slots are left as they are.
+        let last = self.entries.len() - 1;
+
+        self.unindex_tags(slot);
+        if slot != last {
+            self.unindex_tags(last);
+        }
+
+        self.slots.remove(id);
+        self.entries.swap_remove(slot);
+        let proto = self.storage.swap_remove(slot);
+
+        if slot != last {
+            self.slots.insert(self.entries[slot].id, slot);
+            self.index_tags(slot);
+        }
+
+        Some(proto)
+    }
+}
+
+/// Returns a revision no registry had yet, see [`Registry::revision`].
+#[cfg(feature = "lock_free")]
+fn next_revision() -> u64 {
+    use core::sync::atomic::{AtomicU64, Ordering};
+
+    static NEXT_REVISION: AtomicU64 = AtomicU64::new(0);
+
+    NEXT_REVISION.fetch_add(1, Ordering::Relaxed)
+}
+
+/// Returns the slots of both sorted `a` and `b`.
+fn intersect_slots(a: &[usize], b: &[usize]) -> Vec<usize> {
+    let mut slots = Vec::with_capacity(a.len().min(b.len()));
+    let (mut i, mut j) = (0, 0);
+
+    while let (Some(x), Some(y)) = (a.get(i), b.get(j)) {
+        match x.cmp(y) {
+            core::cmp::Ordering::Less => i += 1,
+            core::cmp::Ordering::Greater => j += 1,
+            core::cmp::Ordering::Equal => {
+                slots.push(*x);
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+
+    slots
+}
+
+/// Returns the slots of either sorted `a` or `b`.
+fn union_slots(a: &[usize], b: &[usize]) -> Vec<usize> {
+    let mut slots = Vec::with_capacity(a.len() + b.len());
+    let (mut i, mut j) = (0, 0);
+
+    loop {
+        match (a.get(i), b.get(j)) {
+            (Some(x), Some(y)) if x < y => {
+                slots.push(*x);
+                i += 1;
+            }
+            (Some(x), Some(y)) if x > y => {
+                slots.push(*y);
+                j += 1;
+            }
+            (Some(x), Some(_)) => {
+                slots.push(*x);
+                i += 1;
+                j += 1;
+            }
+            (Some(x), None) => {
+                slots.push(*x);
+                i += 1;
+            }
+            (None, Some(y)) => {
+                slots.push(*y);
+                j += 1;
+            }
+            (None, None) => return slots,
+        }
+    }
+}
+
+/// Returns the slots of sorted `a` not in sorted `b`.
+fn subtract_slots(a: &[usize], b: &[usize]) -> Vec<usize> {
+    let mut j = 0;
+
+    a.iter()
+        .copied()
+        .filter(|x| {
+            while b.get(j).is_some_and(|y| y < x) {
+                j += 1;
+            }
+
+            b.get(j) != Some(x)
+        })
+        .collect()
+}
+
+impl PrototypeRegistries {
+    /// Deep copies the registries.
+    pub fn reflect_clone(&self) -> Self {
+        let registries = self
+            .registries
+            .iter()
+            .map(|(type_id, registry)| (*type_id, registry.reflect_clone()))
+            .collect();
+
+        Self {
+            registries,
+            names: self.names.clone(),
+            frozen: self.frozen,
+        }
+    }
+
+    /// Returns the revision of each registry, changed with each write to the registry.
+    #[cfg(feature = "lock_free")]
+    pub(crate) fn revisions(&self) -> impl Iterator<Item = (TypeId, u64)> + '_ {
+        self.registries
+            .iter()
+            .map(|(type_id, registry)| (*type_id, registry.revision))
+    }
+
+    /// Deep copies the `type_id` registry alone, the registries are empty if it isn't registered.
+    #[cfg(feature = "lock_free")]
+    pub(crate) fn clone_registry(&self, type_id: &TypeId) -> Self {
+        let mut registries = Self {
+            registries: HashMap::default(),
+            names: HashMap::default(),
+            frozen: self.frozen,
+        };
+
+        if let (Some(registry), Some(name)) =
+            (self.registries.get(type_id), self.names.get(type_id))
+        {
+            registries
+                .registries
+                .insert(*type_id, registry.reflect_clone());
+            registries.names.insert(*type_id, name.clone());
+        }
+
+        registries
+    }
+
+    /// Deep copies the `P` registry of `other` into these registries.
+    pub fn clone_registry_from<P: PrototypeData>(&mut self, other: &Self) {
+        let type_id = TypeId::of::<P>();
+
+        match other.registries.get(&type_id) {
+            Some(registry) => {
+                self.registries.insert(type_id, registry.reflect_clone());
+                self.names.insert(type_id, P::prototype_name().to_string());
+            }
+            None => error!(
+                "Attempted to clone unregistered registry {}",
+                P::prototype_name()
+            ),
+        }
+    }
+
+    pub fn new_registry<P: PrototypeData>(&mut self) {
+        let mut registry = Registry::new::<P>();
+        registry.frozen = self.frozen;
+
+        self.registries.insert(TypeId::of::<P>(), registry);
+        self.names
+            .insert(TypeId::of::<P>(), P::prototype_name().to_string());
+    }
+
+    /// Inserts or replaces a prototype, returns `false` if it wasn't inserted, e.g. because the
+    /// registries are frozen.
+    pub fn insert<P: PrototypeData>(&mut self, proto: Prototype<P>) -> bool {
+        let Some(registry) = self.registries.get_mut(&TypeId::of::<P>()) else {
+            error!(
+                "Attempted to insert prototype into unregistered registry {}",
+                P::prototype_name()
+            );
+            return false;
+        };
+
+        registry.insert_typed(proto)
+    }
+
+    pub fn insert_dyn(
+        &mut self,
+        type_id: &TypeId,
+        id: ErasedPrototypeId,
+        proto: Box<dyn Reflect>,
+    ) -> bool {
+        let Some(registry) = self.registries.get_mut(type_id) else {
+            error!("Attempted to insert prototype into unregistered registry");
+            return false;
+        };
+
+        registry.insert(id, proto)
+    }
+
+    /// Removes a prototype and returns it, returns `None` if it doesn't exist or if the
+    /// registries are frozen.
+    pub fn remove<P: PrototypeData>(&mut self, id: &PrototypeId<P>) -> Option<Prototype<P>> {
+        let proto = self.remove_dyn(&TypeId::of::<P>(), &(*id).into())?;
+
+        proto.downcast().ok().map(|proto| *proto)
+    }
+
+    /// Removes a prototype of the `type_id` registry and returns it, e.g. to unload the
+    /// prototypes of a mod without knowing their types.
+    pub fn remove_dyn(
+        &mut self,
+        type_id: &TypeId,
+        id: &ErasedPrototypeId,
+    ) -> Option<Box<dyn Reflect>> {
+        let Some(registry) = self.registries.get_mut(type_id) else {
+            error!("Attempted to remove prototype from unregistered registry");
+            return None;
+        };
+
+        registry.remove(id)
+    }
+
+    /// Rejects the changes of the prototypes from now on, see [`freeze_prototypes`].
+    pub fn freeze(&mut self) {
+        self.frozen = true;
+
+        for registry in self.registries.values_mut() {
+            registry.frozen = true;
+        }
+    }
+
+    pub fn is_frozen(&self) -> bool {
+        self.frozen
+    }
+
+    /// Takes the prototypes whose change was rejected since the last take, by prototype type
+    /// name.
+    fn take_rejected(&mut self) -> Vec<(&'static str, ErasedPrototypeId)> {
+        self.registries
+            .values_mut()
+            .flat_map(|registry| {
+                let name = registry.name;
+                core::mem::take(&mut registry.rejected)
+                    .into_iter()
+                    .map(move |id| (name, id))
+            })
+            .collect()
+    }
+
+    pub(crate) fn get_dyn_mut(
+        &mut self,
+        type_id: &TypeId,
+        id: &ErasedPrototypeId,
+    ) -> Option<&mut dyn Reflect> {
+        let registry = self.registries.get_mut(type_id)?;
+        let slot = registry.slot_mut(id)?;
+        registry.tags_dirty = true;
+
+        registry.storage.get_mut(slot)
+    }
+
+    /// Bumps the generation of a prototype modified in place and sends a
+    /// [`RegistryEvent::Modified`] for it, like a replaced prototype.
+    pub(crate) fn mark_modified(&mut self, type_id: &TypeId, id: ErasedPrototypeId) {
+        let Some(registry) = self.registries.get_mut(type_id) else {
+            return;
+        };
+
+        if let Some(slot) = registry.slots.get(&id).copied() {
+            registry.mark_modified(slot);
+        }
+    }
+
+    /// Takes the prototypes of the `P` registry replaced by an insert, e.g. by a reload.
+    fn take_reloaded<P: PrototypeData>(&mut self) -> Vec<ErasedPrototypeId> {
+        self.registries
+            .get_mut(&TypeId::of::<P>())
+            .map(|registry| core::mem::take(&mut registry.reloaded))
+            .unwrap_or_default()
+    }
+
+    /// Takes the prototypes of the `P` registry inserted or removed since the last take.
+    fn take_changes<P: PrototypeData>(&mut self) -> Vec<RegistryChange> {
+        self.registries
+            .get_mut(&TypeId::of::<P>())
+            .map(|registry| core::mem::take(&mut registry.changes))
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn is_registered(&self, type_id: &TypeId) -> bool {
+        self.registries.contains_key(type_id)
+    }
+
+    /// Returns the prototype type name of the registries, other than `type_id`, holding a
+    /// prototype with the `id` id, with the name of that prototype.
+    pub(crate) fn find_in_other_registries(
+        &self,
+        type_id: &TypeId,
+        id: &ErasedPrototypeId,
+    ) -> Vec<(&'static str, Option<String>)> {
+        let mut found = self
+            .registries
+            .iter()
+            .filter(|(other, _)| *other != type_id)
+            .filter_map(|(_, registry)| {
+                let name = registry
+                    .get(id)?
+                    .path::<&'static str>("name.name")
+                    .ok()
+                    .map(ToString::to_string);
+
+                Some((registry.name, name))
+            })
+            .collect::<Vec<_>>();
+        found.sort();
+
+        found
+    }
+
+    /// Iterates over the prototypes of a registry in their storage order.
+    pub fn iter_dyn<'a>(
+        &'a self,
+        type_id: &TypeId,
+    ) -> impl Iterator<Item = &'a dyn Reflect> + use<'a> {
+        self.registries
+            .get(type_id)
+            .into_iter()
+            .flat_map(|registry| (0..registry.storage.len()).map(|slot| registry.storage.get(slot)))
+            .flatten()
+    }
+
+    pub fn get_dyn(&self, type_id: &TypeId, id: &ErasedPrototypeId) -> Option<&dyn Reflect> {
+        self.registries
+            .get(type_id)
+            .and_then(|registry| registry.get(id))
+    }
+
+    /// Returns the prototypes of the `P` registry, contiguous in their storage order: their
+    /// insertion order, except that removing a prototype moves the last one into its slot.
+    pub fn as_slice<P: PrototypeData>(&self) -> &[Prototype<P>] {
+        self.registries
+            .get(&TypeId::of::<P>())
+            .and_then(Registry::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Iterates over the prototypes of the `P` registry in their storage order.
+    pub fn iter<P: PrototypeData>(&self) -> impl Iterator<Item = &Prototype<P>> {
+        self.as_slice().iter()
+    }
+
+    /// Iterates over the prototypes of the `P` registry tagged with `tag` in their storage
+    /// order.
+    pub fn iter_with_tag<'a, P: PrototypeData>(
+        &'a self,
+        tag: &str,
+    ) -> impl Iterator<Item = &'a Prototype<P>> + use<'a, P> {
+        let registry = self.registries.get(&TypeId::of::<P>());
+        let prototypes = registry.and_then(Registry::as_slice).unwrap_or_default();
+
+        registry
+            .and_then(|registry| registry.tags.get(tag))
+            .into_iter()
+            .flatten()
+            .filter_map(|slot| prototypes.get(*slot))
+    }
+
+    /// Iterates over the prototypes of the `P` registry whose tags match the [`TagQuery`] in
+    /// their storage order, resolved against the tag index.
+    pub fn iter_with_tags<'a, P: PrototypeData>(
+        &'a self,
+        query: &TagQuery,
+    ) -> impl Iterator<Item = &'a Prototype<P>> + use<'a, P> {
+        let registry = self.registries.get(&TypeId::of::<P>());
+        let prototypes = registry.and_then(Registry::as_slice).unwrap_or_default();
+        let slots = registry.map(|registry| registry.query_slots(query));
+
+        slots
+            .into_iter()
+            .flatten()
+            .filter_map(|slot| prototypes.get(slot))
+    }
+
+    /// Iterates over the prototypes of all the registries tagged with `tag`, with their
+    /// prototype type name, by type name then in their storage order.
+    pub fn iter_tagged<'a>(
+        &'a self,
+        tag: &'a str,
+    ) -> impl Iterator<Item = (&'static str, &'a dyn Reflect)> + use<'a> {
+        let mut registries = self.registries.values().collect::<Vec<_>>();
+        registries.sort_by_key(|registry| registry.name);
+
+        registries.into_iter().flat_map(move |registry| {
+            registry
+                .iter_with_tag(tag)
+                .map(move |proto| (registry.name, proto))
+        })
+    }
+
+    /// Rebuilds the tag index of the `P` registry if its prototypes were accessed mutably
+    /// through reflection since.
+    fn refresh_tags<P: PrototypeData>(&mut self) {
+        if let Some(registry) = self.registries.get_mut(&TypeId::of::<P>()) {
+            registry.refresh_tags();
+        }
+    }
+
+    /// Rebuilds the arena of the `P` registry, see [`PrototypeArena::rebuild`].
+    fn rebuild_storage<P: PrototypeData>(&mut self) {
+        if let Some(registry) = self.registries.get_mut(&TypeId::of::<P>()) {
+            registry.storage.rebuild();
+        }
+    }
+
+    pub fn get<P: PrototypeData>(&self, id: &PrototypeId<P>) -> Option<&Prototype<P>> {
+        let registry = self.registries.get(&TypeId::of::<P>())?;
+
+        registry
+            .as_slice()?
+            .get(*registry.slots.get(&ErasedPrototypeId::from(*id))?)
+    }
+
+    pub fn version<P: PrototypeData>(&self, id: &PrototypeId<P>) -> Option<PrototypeVersion> {
+        let registry = self.registries.get(&TypeId::of::<P>())?;
+        let slot = *registry.slots.get(&ErasedPrototypeId::from(*id))?;
+
+        Some(registry.entries[slot].version)
+    }
+
+    /// Returns the slot of a `P` prototype about to be changed, `None` if it doesn't exist or
+    /// if the registries are frozen.
+    fn slot_mut<P: PrototypeData>(&mut self, id: &PrototypeId<P>) -> Option<usize> {
+        self.registries
+            .get_mut(&TypeId::of::<P>())?
+            .slot_mut(&ErasedPrototypeId::from(*id))
+    }
+
+    fn get_at_mut<P: PrototypeData>(&mut self, slot: usize) -> Option<&mut Prototype<P>> {
+        self.registries
+            .get_mut(&TypeId::of::<P>())?
+            .as_mut_slice()?
+            .get_mut(slot)
+    }
+
+    /// Removes the `P` prototype of `slot` from the tag index, before its tags are changed.
+    fn unindex_tags_at<P: PrototypeData>(&mut self, slot: usize) {
+        if let Some(registry) = self.registries.get_mut(&TypeId::of::<P>()) {
+            registry.unindex_tags(slot);
+        }
+    }
+
+    /// Adds the `P` prototype of `slot` back to the tag index, once its tags were changed.
+    fn index_tags_at<P: PrototypeData>(&mut self, slot: usize) {
+        if let Some(registry) = self.registries.get_mut(&TypeId::of::<P>()) {
+            registry.index_tags(slot);
+        }
+    }
+
+    fn bump_generation_at<P: PrototypeData>(&mut self, slot: usize) {
+        if let Some(entry) = self
+            .registries
+            .get_mut(&TypeId::of::<P>())
+            .and_then(|registry| registry.entries.get_mut(slot))
+        {
+            entry.version.generation = entry.version.generation.wrapping_add(1);
+        }
+    }
+}
+
+fn registry_type_info<P: PrototypeData>() -> &'static TypeInfo {
+    static CELL: GenericTypeInfoCell = GenericTypeInfoCell::new();
+    CELL.get_or_insert::<Prototype<P>, _>(|| {
+        TypeInfo::Map(MapInfo::new::<Registry, ErasedPrototypeId, Prototype<P>>())
+    })
+}
+
+/// Converts a reflected key of a registry, an [`ErasedPrototypeId`] or a prototype name.
+fn prototype_key(key: &dyn PartialReflect) -> Option<ErasedPrototypeId> {
+    <ErasedPrototypeId as FromReflect>::from_reflect(key).or_else(|| {
+        <String as FromReflect>::from_reflect(key).map(|name| ErasedPrototypeId::from_name(&name))
+    })
+}
+
+impl PrototypeRegistries {
+    fn registry_type_id(&self, key: &dyn PartialReflect) -> Option<TypeId> {
+        let name = <String as FromReflect>::from_reflect(key)?;
+
+        self.names
+            .iter()
+            .find_map(|(type_id, registry)| (*registry == name).then_some(*type_id))
+    }
+}
+
+impl Typed for PrototypeRegistries {
+    fn type_info() -> &'static TypeInfo {
+        static CELL: NonGenericTypeInfoCell = NonGenericTypeInfoCell::new();
+        CELL.get_or_set(|| TypeInfo::Map(MapInfo::new::<Self, String, Registry>()))
+    }
+}
+
+impl GetTypeRegistration for PrototypeRegistries {
+    fn get_type_registration() -> TypeRegistration {
+        let mut registration = TypeRegistration::of::<Self>();
+        registration.insert::<ReflectResource>(FromType::<Self>::from_type());
+        registration.insert::<ReflectFromReflect>(FromType::<Self>::from_type());
+        registration
+    }
+
+    fn register_type_dependencies(registry: &mut TypeRegistry) {
+        registry.register::<String>();
+        registry.register::<ErasedPrototypeId>();
+    }
+}
+
+impl FromReflect for PrototypeRegistries {
+    fn from_reflect(reflect: &dyn PartialReflect) -> Option<Self> {
+        reflect
+            .try_downcast_ref::<Self>()
+            .map(PrototypeRegistries::reflect_clone)
+    }
+}
+
+impl PartialReflect for PrototypeRegistries {
+    fn get_represented_type_info(&self) -> Option<&'static TypeInfo> {
+        Some(<Self as Typed>::type_info())
+    }
+
+    fn into_partial_reflect(self: Box<Self>) -> Box<dyn PartialReflect> {
+        self
+    }
+
+    fn as_partial_reflect(&self) -> &dyn PartialReflect {
+        self
+    }
+
+    fn as_partial_reflect_mut(&mut self) -> &mut dyn PartialReflect {
+        self
+    }
+
+    fn try_into_reflect(self: Box<Self>) -> Result<Box<dyn Reflect>, Box<dyn PartialReflect>> {
+        Ok(self)
+    }
+
+    fn try_as_reflect(&self) -> Option<&dyn Reflect> {
+        Some(self)
+    }
+
+    fn try_as_reflect_mut(&mut self) -> Option<&mut dyn Reflect> {
+        Some(self)
+    }
+
+    fn try_apply(&mut self, value: &dyn PartialReflect) -> Result<(), ApplyError> {
+        bevy::reflect::map_try_apply(self, value)
+    }
+
+    fn reflect_kind(&self) -> ReflectKind {
+        ReflectKind::Map
+    }
+
+    fn reflect_ref(&self) -> ReflectRef<'_> {
+        ReflectRef::Map(self)
+    }
+
+    fn reflect_mut(&mut self) -> ReflectMut<'_> {
+        ReflectMut::Map(self)
+    }
+
+    fn reflect_owned(self: Box<Self>) -> ReflectOwned {
+        ReflectOwned::Map(self)
+    }
+
+    fn reflect_clone(&self) -> Result<Box<dyn Reflect>, ReflectCloneError> {
+        Ok(Box::new(PrototypeRegistries::reflect_clone(self)))
+    }
+
+    fn reflect_partial_eq(&self, value: &dyn PartialReflect) -> Option<bool> {
+        bevy::reflect::map_partial_eq(self, value)
+    }
+}
+
+impl Reflect for PrototypeRegistries {
+    fn into_any(self: Box<Self>) -> Box<dyn core::any::Any> {
+        self
+    }
+
+    fn as_any(&self) -> &dyn core::any::Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn core::any::Any {
+        self
+    }
+
+    fn into_reflect(self: Box<Self>) -> Box<dyn Reflect> {
+        self
+    }
+
+    fn as_reflect(&self) -> &dyn Reflect {
+        self
+    }
+
+    fn as_reflect_mut(&mut self) -> &mut dyn Reflect {
+        self
+    }
+
+    fn set(&mut self, value: Box<dyn Reflect>) -> Result<(), Box<dyn Reflect>> {
+        *self = *value.downcast()?;
+        Ok(())
+    }
+}
+
+impl Map for PrototypeRegistries {
+    fn get(&self, key: &dyn PartialReflect) -> Option<&dyn PartialReflect> {
+        self.registries
+            .get(&self.registry_type_id(key)?)
+            .map(PartialReflect::as_partial_reflect)
+    }
+
+    fn get_mut(&mut self, key: &dyn PartialReflect) -> Option<&mut dyn PartialReflect> {
+        let type_id = self.registry_type_id(key)?;
+
+        self.registries
+            .get_mut(&type_id)
+            .map(PartialReflect::as_partial_reflect_mut)
+    }
+
+    fn get_at(&self, index: usize) -> Option<(&dyn PartialReflect, &dyn PartialReflect)> {
+        let (type_id, registry) = self.registries.iter().nth(index)?;
+
+        Some((self.names.get(type_id)?, registry))
+    }
+
+    fn get_at_mut(
+        &mut self,
+        index: usize,
+    ) -> Option<(&dyn PartialReflect, &mut dyn PartialReflect)> {
+        let (type_id, registry) = self.registries.iter_mut().nth(index)?;
+
+        Some((self.names.get(type_id)?, registry))
+    }
+
+    fn len(&self) -> usize {
+        self.registries.len()
+    }
+
+    fn iter(&self) -> MapIter<'_> {
+        MapIter::new(self)
+    }
+
+    fn drain(&mut self) -> Vec<(Box<dyn PartialReflect>, Box<dyn PartialReflect>)> {
+        error!("Attempted to drain the prototype registries through reflection");
+        Vec::new()
+    }
+
+    fn insert_boxed(
+        &mut self,
+        key: Box<dyn PartialReflect>,
+        _value: Box<dyn PartialReflect>,
+    ) -> Option<Box<dyn PartialReflect>> {
+        error!("Attempted to insert unregistered registry {key:?} through reflection");
+        None
+    }
+
+    fn remove(&mut self, key: &dyn PartialReflect) -> Option<Box<dyn PartialReflect>> {
+        error!("Attempted to remove registry {key:?} through reflection");
+        None
+    }
+}
+
+impl bevy::reflect::MaybeTyped for Registry {}
+
+impl bevy::reflect::DynamicTyped for Registry {
+    fn reflect_type_info(&self) -> &'static TypeInfo {
+        (self.type_info)()
+    }
+}
+
+impl PartialReflect for Registry {
+    fn get_represented_type_info(&self) -> Option<&'static TypeInfo> {
+        Some((self.type_info)())
+    }
+
+    fn into_partial_reflect(self: Box<Self>) -> Box<dyn PartialReflect> {
+        self
+    }
+
+    fn as_partial_reflect(&self) -> &dyn PartialReflect {
+        self
+    }
+
+    fn as_partial_reflect_mut(&mut self) -> &mut dyn PartialReflect {
+        self
+    }
+
+    fn try_into_reflect(self: Box<Self>) -> Result<Box<dyn Reflect>, Box<dyn PartialReflect>> {
+        Ok(self)
+    }
+
+    fn try_as_reflect(&self) -> Option<&dyn Reflect> {
+        Some(self)
+    }
+
+    fn try_as_reflect_mut(&mut self) -> Option<&mut dyn Reflect> {
+        Some(self)
+    }
+
+    fn try_apply(&mut self, value: &dyn PartialReflect) -> Result<(), ApplyError> {
+        bevy::reflect::map_try_apply(self, value)
+    }
+
+    fn reflect_kind(&self) -> ReflectKind {
+        ReflectKind::Map
+    }
+
+    fn reflect_ref(&self) -> ReflectRef<'_> {
+        ReflectRef::Map(self)
+    }
+
+    fn reflect_mut(&mut self) -> ReflectMut<'_> {
+        ReflectMut::Map(self)
+    }
+
+    fn reflect_owned(self: Box<Self>) -> ReflectOwned {
+        ReflectOwned::Map(self)
+    }
+
+    fn reflect_clone(&self) -> Result<Box<dyn Reflect>, ReflectCloneError> {
+        Ok(Box::new(Registry::reflect_clone(self)))
+    }
+
+    fn reflect_partial_eq(&self, value: &dyn PartialReflect) -> Option<bool> {
+        bevy::reflect::map_partial_eq(self, value)
+    }
 }
 
-impl PrototypeRegistries {
-    pub fn new_registry<P: PrototypeData>(&mut self) {
-        self.registries.insert(TypeId::of::<P>(), HashMap::new());
+impl Reflect for Registry {
+    fn into_any(self: Box<Self>) -> Box<dyn core::any::Any> {
+        self
     }
 
-    pub fn insert<P: PrototypeData>(&mut self, proto: Prototype<P>) {
-        let Some(registry) = self.registries.get_mut(&TypeId::of::<P>()) else {
-            error!(
-                "Attempted to insert prototype into unregistered registry {}",
-                P::prototype_name()
-            );
-            return;
-        };
+    fn as_any(&self) -> &dyn core::any::Any {
+        self
+    }
 
-        registry.insert(ErasedPrototypeId::from(*proto.id()), Box::new(proto));
+    fn as_any_mut(&mut self) -> &mut dyn core::any::Any {
+        self
     }
 
-    pub fn insert_dyn(&mut self, type_id: &TypeId, id: ErasedPrototypeId, proto: Box<dyn Reflect>) {
-        let Some(registry) = self.registries.get_mut(type_id) else {
-            error!("Attempted to insert prototype into unregistered registry");
-            return;
+    fn into_reflect(self: Box<Self>) -> Box<dyn Reflect> {
+        self
+    }
+
+    fn as_reflect(&self) -> &dyn Reflect {
+        self
+    }
+
+    fn as_reflect_mut(&mut self) -> &mut dyn Reflect {
+        self
+    }
+
+    fn set(&mut self, value: Box<dyn Reflect>) -> Result<(), Box<dyn Reflect>> {
+        *self = *value.downcast()?;
+        Ok(())
+    }
+}
+
+/// Prototypes accessed mutably through reflection are considered modified, like with
+/// [`RegMut::get_mut`].
+impl Map for Registry {
+    fn get(&self, key: &dyn PartialReflect) -> Option<&dyn PartialReflect> {
+        Registry::get(self, &prototype_key(key)?).map(PartialReflect::as_partial_reflect)
+    }
+
+    fn get_mut(&mut self, key: &dyn PartialReflect) -> Option<&mut dyn PartialReflect> {
+        let slot = self.slot_mut(&prototype_key(key)?)?;
+
+        self.mark_modified(slot);
+        self.tags_dirty = true;
+
+        self.storage
+            .get_mut(slot)
+            .map(PartialReflect::as_partial_reflect_mut)
+    }
+
+    fn get_at(&self, index: usize) -> Option<(&dyn PartialReflect, &dyn PartialReflect)> {
+        let entry = self.entries.get(index)?;
+
+        self.storage
+            .get(index)
+            .map(|proto| (entry.id.as_partial_reflect(), proto.as_partial_reflect()))
+    }
+
+    fn get_at_mut(
+        &mut self,
+        index: usize,
+    ) -> Option<(&dyn PartialReflect, &mut dyn PartialReflect)> {
+        let id = self.entries.get(index)?.id;
+
+        if self.reject_frozen(id) {
+            return None;
+        }
+
+        self.mark_modified(index);
+        self.tags_dirty = true;
+
+        let id = &self.entries[index].id;
+        let proto = self.storage.get_mut(index)?;
+
+        Some((id.as_partial_reflect(), proto.as_partial_reflect_mut()))
+    }
+
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    fn iter(&self) -> MapIter<'_> {
+        MapIter::new(self)
+    }
+
+    fn drain(&mut self) -> Vec<(Box<dyn PartialReflect>, Box<dyn PartialReflect>)> {
+        let ids = self
+            .entries
+            .iter()
+            .map(|entry| entry.id)
+            .collect::<Vec<_>>();
+
+        ids.into_iter()
+            .filter_map(|id| {
+                let proto = self.remove(&id)?;
+                Some((
+                    Box::new(id) as Box<dyn PartialReflect>,
+                    proto.into_partial_reflect(),
+                ))
+            })
+            .collect()
+    }
+
+    fn insert_boxed(
+        &mut self,
+        key: Box<dyn PartialReflect>,
+        value: Box<dyn PartialReflect>,
+    ) -> Option<Box<dyn PartialReflect>> {
+        let Some(id) = prototype_key(key.as_ref()) else {
+            error!("Attempted to insert prototype with invalid id {key:?} through reflection");
+            return None;
         };
 
-        registry.insert(id, proto);
+        let Some(proto) = (self.from_reflect)(value.as_ref()) else {
+            error!("Attempted to insert invalid prototype {id:?} through reflection");
+            return None;
+        };
+
+        let previous = Registry::get(self, &id).map(PartialReflect::to_dynamic);
+
+        if !self.insert(id, proto) {
+            return None;
+        }
+
+        previous
     }
 
-    pub fn get<P: PrototypeData>(&self, id: &PrototypeId<P>) -> Option<&Prototype<P>> {
-        self.registries
-            .get(&TypeId::of::<P>())
-            .and_then(|registry| registry.get(&(ErasedPrototypeId::from(*id))))
-            .and_then(|proto| proto.downcast_ref::<Prototype<P>>())
+    fn remove(&mut self, key: &dyn PartialReflect) -> Option<Box<dyn PartialReflect>> {
+        Registry::remove(self, &prototype_key(key)?).map(PartialReflect::into_partial_reflect)
     }
 }
 
 #[derive(SystemParam)]
 pub struct Reg<'w, P: PrototypeData> {
     registries: Res<'w, PrototypeRegistries>,
+    lazy: Option<Res<'w, LazyPrototypes>>,
+    #[cfg(feature = "access_stats")]
+    pub(crate) access_stats: Option<Res<'w, crate::PrototypeAccessStats>>,
+    telemetry: Option<Res<'w, PrototypeTelemetry>>,
     _marker: core::marker::PhantomData<P>,
 }
 
 impl<P: PrototypeData> Reg<'_, P> {
     /// Get a prototype instance with it's [`PrototypeId`]
+    ///
+    /// Missing a prototype indexed with
+    /// [`PrototypeServer::index_prototypes`](crate::PrototypeServer::index_prototypes) requests
+    /// the load of its file, see [`Reg::state`].
     pub fn get(&self, id: impl Into<PrototypeId<P>>) -> Option<&Prototype<P>> {
-        self.registries.get(&id.into())
+        let id = id.into();
+        let proto = self.registries.get(&id);
+
+        #[cfg(feature = "access_stats")]
+        if proto.is_some()
+            && let Some(stats) = &self.access_stats
+        {
+            stats.record(TypeId::of::<P>(), id.into());
+        }
+
+        if proto.is_none()
+            && let Some(lazy) = &self.lazy
+        {
+            lazy.request(TypeId::of::<P>(), id.into());
+        }
+
+        if proto.is_none()
+            && let Some(telemetry) = &self.telemetry
+        {
+            telemetry.send(PrototypeTelemetryEvent::LookupMissed {
+                ty: P::prototype_name(),
+                id: id.into(),
+            });
+        }
+
+        proto
+    }
+
+    /// Returns whether a prototype is in the registry or waiting for its file to be loaded on
+    /// demand.
+    pub fn state(&self, id: impl Into<PrototypeId<P>>) -> PrototypeState {
+        let id = id.into();
+
+        if self.registries.get(&id).is_some() {
+            PrototypeState::Loaded
+        } else if self
+            .lazy
+            .as_ref()
+            .is_some_and(|lazy| lazy.is_pending(TypeId::of::<P>(), id.into()))
+        {
+            PrototypeState::Pending
+        } else {
+            PrototypeState::Missing
+        }
+    }
+
+    /// Get a prototype instance with it's [`PrototypeId`], like [`Reg::get`], or why it isn't
+    /// there, e.g. for `?`-based gameplay code:
+    ///
+    /// ```no_run
+    /// # use bevy::prelude::*;
+    /// # use bevy_histrion_proto::prelude::*;
+    /// # #[derive(Debug, Clone, Default, Reflect, JsonSchema, Prototype)]
+    /// # #[proto(name = "sword")]
+    /// # struct Sword { damage: f32 }
+    /// # fn f(swords: Reg<Sword>, id: PrototypeId<Sword>) -> Result {
+    /// let sword = swords.try_get(id)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn try_get(
+        &self,
+        id: impl Into<PrototypeId<P>>,
+    ) -> Result<&Prototype<P>, PrototypeLookupError> {
+        let id = id.into();
+
+        if !self.registries.is_registered(&TypeId::of::<P>()) {
+            return Err(PrototypeLookupError::Unregistered {
+                ty: P::prototype_name(),
+            });
+        }
+
+        self.get(id).ok_or_else(|| {
+            #[cfg(not(feature = "strip_names"))]
+            use crate::lookup::similar_names;
+
+            let found = self
+                .registries
+                .find_in_other_registries(&TypeId::of::<P>(), &id.into());
+            let name = found.iter().find_map(|(_, name)| name.clone());
+            #[cfg(not(feature = "strip_names"))]
+            let suggestions = name
+                .as_deref()
+                .map(|name| similar_names(name, self.iter().map(Prototype::name)))
+                .unwrap_or_default();
+            #[cfg(feature = "strip_names")]
+            let suggestions = Vec::new();
+
+            PrototypeLookupError::Missing {
+                ty: P::prototype_name(),
+                id: id.into(),
+                pending: self.state(id) == PrototypeState::Pending,
+                other_types: found.into_iter().map(|(ty, _)| ty).collect(),
+                name,
+                suggestions,
+            }
+        })
+    }
+
+    /// Get the [`PrototypeVersion`] of a prototype with it's [`PrototypeId`]
+    pub fn version(&self, id: impl Into<PrototypeId<P>>) -> Option<PrototypeVersion> {
+        self.registries.version(&id.into())
+    }
+
+    /// Iterates over the [`Prototype`] instances in their storage order
+    pub fn iter(&self) -> impl Iterator<Item = &Prototype<P>> {
+        self.registries.iter()
+    }
+
+    /// Returns the [`Prototype`] instances in their storage order, stored contiguously, e.g.
+    /// for the systems going over all the prototypes each frame
+    ///
+    /// The storage order is the insertion order, except that removing a prototype moves the
+    /// last one into its slot.
+    pub fn as_slice(&self) -> &[Prototype<P>] {
+        self.registries.as_slice()
+    }
+
+    /// Iterates over the [`Prototype`] instances tagged with `tag` in their storage order,
+    /// without going over the other prototypes
+    pub fn iter_with_tag<'a>(&'a self, tag: &str) -> impl Iterator<Item = &'a Prototype<P>> {
+        self.registries.iter_with_tag(tag)
+    }
+
+    /// Iterates over the [`Prototype`] instances whose tags match the [`TagQuery`], resolved
+    /// against the tag index without going over the other prototypes
+    pub fn iter_with_tags<'a>(
+        &'a self,
+        query: &'a TagQuery,
+    ) -> impl Iterator<Item = &'a Prototype<P>> {
+        self.registries.iter_with_tags(query)
+    }
+
+    /// Returns `true` once the prototypes are frozen, see [`freeze_prototypes`].
+    pub fn is_frozen(&self) -> bool {
+        self.registries.is_frozen()
+    }
+
+    /// Returns the change tick of the last time the registries were changed.
+    pub(crate) fn last_changed(&self) -> Tick {
+        self.registries.last_changed()
+    }
+}
+
+/// Mutable access to a [`Prototype`] of a registry, see [`RegMut::get_mut`].
+pub struct PrototypeMut<'a, 'w, P: PrototypeData> {
+    id: PrototypeId<P>,
+    /// Slot of the prototype in the `P` registry.
+    slot: usize,
+    registries: Mut<'a, PrototypeRegistries>,
+    events: &'a mut EventWriter<'w, RegistryEvent<P>>,
+    modified: bool,
+}
+
+impl<P: PrototypeData> core::ops::Deref for PrototypeMut<'_, '_, P> {
+    type Target = Prototype<P>;
+
+    fn deref(&self) -> &Self::Target {
+        // Checked by `RegMut::get_mut`.
+        &self.registries.as_slice()[self.slot]
+    }
+}
+
+impl<P: PrototypeData> core::ops::DerefMut for PrototypeMut<'_, '_, P> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        // The tags may change, the prototype is indexed again once the guard is dropped.
+        if !core::mem::replace(&mut self.modified, true) {
+            self.registries.unindex_tags_at::<P>(self.slot);
+        }
+
+        self.registries.get_at_mut(self.slot).unwrap()
+    }
+}
+
+impl<P: PrototypeData> Drop for PrototypeMut<'_, '_, P> {
+    fn drop(&mut self) {
+        if !self.modified {
+            return;
+        }
+
+        let registries = self.registries.bypass_change_detection();
+        registries.index_tags_at::<P>(self.slot);
+        registries.bump_generation_at::<P>(self.slot);
+
+        self.events.write(RegistryEvent::Modified(self.id));
+    }
+}
+
+impl<P: PrototypeData> core::fmt::Debug for PrototypeMut<'_, '_, P> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("PrototypeMut")
+            .field("id", &self.id)
+            .field("modified", &self.modified)
+            .finish()
     }
 }
 
@@ -63,20 +1492,536 @@ impl<P: PrototypeData> core::fmt::Debug for Reg<'_, P> {
     }
 }
 
+/// A [`PrototypeId`] remembering the [`PrototypeVersion`] of the prototype it was resolved to,
+/// for references cached across hot-reloads to detect that their prototype changed or
+/// disappeared.
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_histrion_proto::prelude::*;
+/// # #[derive(Debug, Clone, Default, Reflect, JsonSchema, Prototype)]
+/// # #[proto(name = "sword")]
+/// # struct Sword { damage: f32 }
+/// # fn f(swords: Reg<Sword>, mut weapon: PrototypeWeakRef<Sword>) {
+/// if weapon.is_stale(&swords) {
+///     let damage = weapon.resolve(&swords).map(|sword| sword.damage);
+/// }
+/// # }
+/// ```
+pub struct PrototypeWeakRef<P: PrototypeData> {
+    id: PrototypeId<P>,
+    version: Option<PrototypeVersion>,
+}
+
+impl<P: PrototypeData> PrototypeWeakRef<P> {
+    /// Returns a reference to the `id` prototype, stale until it's resolved.
+    pub fn new(id: impl Into<PrototypeId<P>>) -> Self {
+        Self {
+            id: id.into(),
+            version: None,
+        }
+    }
+
+    pub fn id(&self) -> PrototypeId<P> {
+        self.id
+    }
+
+    /// Returns the version of the prototype when it was last resolved, `None` if it wasn't
+    /// resolved or didn't exist.
+    pub fn version(&self) -> Option<PrototypeVersion> {
+        self.version
+    }
+
+    /// Returns the prototype and remembers its current version, `None` if it doesn't exist.
+    pub fn resolve<'a>(&mut self, reg: &'a Reg<'_, P>) -> Option<&'a Prototype<P>> {
+        self.version = reg.version(self.id);
+
+        reg.get(self.id)
+    }
+
+    /// Returns `true` if the prototype was modified, reloaded or removed since it was resolved,
+    /// or if it wasn't resolved to an existing prototype yet.
+    pub fn is_stale(&self, reg: &Reg<P>) -> bool {
+        self.version.is_none() || reg.version(self.id) != self.version
+    }
+}
+
+impl<P: PrototypeData> Clone for PrototypeWeakRef<P> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<P: PrototypeData> Copy for PrototypeWeakRef<P> {}
+
+impl<P: PrototypeData> PartialEq for PrototypeWeakRef<P> {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id && self.version == other.version
+    }
+}
+
+impl<P: PrototypeData> Eq for PrototypeWeakRef<P> {}
+
+impl<P: PrototypeData> core::fmt::Debug for PrototypeWeakRef<P> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("PrototypeWeakRef")
+            .field("id", &self.id)
+            .field("version", &self.version)
+            .finish()
+    }
+}
+
+impl<P: PrototypeData> From<PrototypeId<P>> for PrototypeWeakRef<P> {
+    fn from(id: PrototypeId<P>) -> Self {
+        Self::new(id)
+    }
+}
+
+/// Change ticks of the main world when its registries were last extracted into a sub-app, by
+/// extracted registry (`PrototypeRegistries` itself when they were all extracted).
+#[derive(Default, Resource)]
+struct ExtractedPrototypesTicks(HashMap<TypeId, Tick>);
+
+/// Copies the registries of `main_world` into `sub_world` when they changed since the last
+/// extraction, so [`Reg`] can be used in the systems of the sub-app.
+///
+/// [`PrototypeAppExt::share_prototypes_with`](crate::PrototypeAppExt::share_prototypes_with)
+/// calls it after the extract function of the sub-app, call it from that function instead to
+/// run it in a different order.
+pub fn extract_prototype_registries(main_world: &mut World, sub_world: &mut World) {
+    if !needs_extraction(main_world, sub_world, TypeId::of::<PrototypeRegistries>()) {
+        return;
+    }
+
+    let registries = main_world.resource::<PrototypeRegistries>().reflect_clone();
+
+    sub_world.insert_resource(registries);
+}
+
+/// Copies only the `P` registry of `main_world` into `sub_world` when the registries changed
+/// since the last extraction, e.g. to read the prototypes of a material from the render world.
+///
+/// See [`PrototypeAppExt::share_prototype_registry_with`](crate::PrototypeAppExt::share_prototype_registry_with).
+pub fn extract_prototype_registry<P: PrototypeData>(main_world: &mut World, sub_world: &mut World) {
+    if !needs_extraction(main_world, sub_world, TypeId::of::<P>()) {
+        return;
+    }
+
+    let registries = main_world.resource::<PrototypeRegistries>();
+
+    sub_world
+        .get_resource_or_init::<PrototypeRegistries>()
+        .clone_registry_from::<P>(registries);
+}
+
+/// Returns `true` if the registries of `main_world` changed since they were last extracted
+/// under `key`, and records the extraction.
+fn needs_extraction(main_world: &World, sub_world: &mut World, key: TypeId) -> bool {
+    let Some(ticks) = main_world.get_resource_change_ticks::<PrototypeRegistries>() else {
+        return false;
+    };
+
+    let this_run = main_world.read_change_tick();
+    let mut extracted = sub_world.get_resource_or_init::<ExtractedPrototypesTicks>();
+
+    if let Some(last_run) = extracted.0.get(&key)
+        && !ticks.is_changed(*last_run, this_run)
+    {
+        return false;
+    }
+
+    extracted.0.insert(key, this_run);
+
+    true
+}
+
+/// Sent when the prototypes of a registry change.
+#[derive(Event, Debug)]
+pub enum RegistryEvent<P: PrototypeData> {
+    /// The prototype was inserted, e.g. when its prototypes file is loaded or with
+    /// [`RegMut::insert`].
+    Added(PrototypeId<P>),
+    /// The prototype was modified through [`RegMut::get_mut`] or replaced, e.g. when its
+    /// prototypes file is reloaded.
+    Modified(PrototypeId<P>),
+    /// The prototype was removed with [`RegMut::remove`] or
+    /// [`PrototypeRegistries::remove_dyn`], e.g. when unloading the content of a mod.
+    Removed(PrototypeId<P>),
+}
+
+impl<P: PrototypeData> Clone for RegistryEvent<P> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<P: PrototypeData> Copy for RegistryEvent<P> {}
+
+impl<P: PrototypeData> PartialEq for RegistryEvent<P> {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Added(id), Self::Added(other))
+            | (Self::Modified(id), Self::Modified(other))
+            | (Self::Removed(id), Self::Removed(other)) => id == other,
+            _ => false,
+        }
+    }
+}
+
+impl<P: PrototypeData> Eq for RegistryEvent<P> {}
+
+/// Sends the [`RegistryEvent`]s of the `P` prototypes inserted, replaced and removed since the
+/// last run, and the [`PrototypeChange`]s of the prototypes inserted or removed for the indices.
+pub(crate) fn send_reloaded_events<P: PrototypeData>(
+    mut registries: ResMut<PrototypeRegistries>,
+    mut events: EventWriter<RegistryEvent<P>>,
+    mut changes: EventWriter<PrototypeChange<P>>,
+) {
+    let registries = registries.bypass_change_detection();
+    registries.refresh_tags::<P>();
+    registries.rebuild_storage::<P>();
+
+    for change in registries.take_changes::<P>() {
+        match change {
+            RegistryChange::Inserted(id) => {
+                events.write(RegistryEvent::Added(id.into()));
+                changes.write(PrototypeChange::Inserted(id.into()));
+            }
+            RegistryChange::Removed(id) => {
+                events.write(RegistryEvent::Removed(id.into()));
+                changes.write(PrototypeChange::Removed(id.into()));
+            }
+        }
+    }
+
+    let reloaded = registries.take_reloaded::<P>();
+
+    events.write_batch(
+        reloaded
+            .into_iter()
+            .map(|id| RegistryEvent::Modified(PrototypeId::from(id))),
+    );
+}
+
+/// Freezes the prototypes for the rest of the session, systems can then treat the prototype
+/// data as immutable, e.g. to keep a
+/// [`RegistriesSnapshot`](crate::RegistriesSnapshot) of the `lock_free` feature forever.
+///
+/// Any later insertion, modification or removal of a prototype, including the reloads, the
+/// locale overrides and the variant layers, panics in debug builds and is rejected with a
+/// [`PrototypeWriteRejected`] event in release builds.
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_histrion_proto::prelude::*;
+/// # fn f(app: &mut App) {
+/// app.add_systems(
+///     Update,
+///     freeze_prototypes.run_if(all_prototypes_loaded().and(run_once)),
+/// );
+/// # }
+/// ```
+pub fn freeze_prototypes(world: &mut World) {
+    match world.get_resource_mut::<PrototypeRegistries>() {
+        Some(mut registries) => registries.freeze(),
+        None => error!("PrototypeRegistries resource not found"),
+    }
+}
+
+/// Sent in release builds for each change of a prototype rejected because the prototypes were
+/// frozen with [`freeze_prototypes`].
+#[derive(Event, Debug, Clone, PartialEq, Eq)]
+pub struct PrototypeWriteRejected {
+    /// Prototype type name of the prototype.
+    pub ty: &'static str,
+    pub id: ErasedPrototypeId,
+}
+
+/// Sends a [`PrototypeWriteRejected`] for the changes rejected since the last run.
+pub(crate) fn send_rejected_writes(
+    mut registries: ResMut<PrototypeRegistries>,
+    mut events: EventWriter<PrototypeWriteRejected>,
+) {
+    let rejected = registries.bypass_change_detection().take_rejected();
+
+    events.write_batch(
+        rejected
+            .into_iter()
+            .map(|(ty, id)| PrototypeWriteRejected { ty, id }),
+    );
+}
+
 #[derive(SystemParam)]
 pub struct RegMut<'w, P: PrototypeData> {
     registries: ResMut<'w, PrototypeRegistries>,
-    _marker: core::marker::PhantomData<P>,
+    events: EventWriter<'w, RegistryEvent<P>>,
 }
 
-impl<P: PrototypeData> RegMut<'_, P> {
+impl<'w, P: PrototypeData> RegMut<'w, P> {
     /// Get a [`Prototype`] instance with it's [`PrototypeId`]
     pub fn get(&self, id: &PrototypeId<P>) -> Option<&Prototype<P>> {
         self.registries.get(id)
     }
 
+    /// Get the [`PrototypeVersion`] of a prototype with it's [`PrototypeId`]
+    pub fn version(&self, id: &PrototypeId<P>) -> Option<PrototypeVersion> {
+        self.registries.version(id)
+    }
+
+    /// Iterates over the [`Prototype`] instances in their storage order
+    pub fn iter(&self) -> impl Iterator<Item = &Prototype<P>> {
+        self.registries.iter()
+    }
+
+    /// Returns the [`Prototype`] instances in their storage order, stored contiguously, e.g.
+    /// for the systems going over all the prototypes each frame
+    pub fn as_slice(&self) -> &[Prototype<P>] {
+        self.registries.as_slice()
+    }
+
+    /// Iterates over the [`Prototype`] instances tagged with `tag` in their storage order,
+    /// without going over the other prototypes
+    pub fn iter_with_tag<'a>(&'a self, tag: &str) -> impl Iterator<Item = &'a Prototype<P>> {
+        self.registries.iter_with_tag(tag)
+    }
+
+    /// Iterates over the [`Prototype`] instances whose tags match the [`TagQuery`], resolved
+    /// against the tag index without going over the other prototypes
+    pub fn iter_with_tags<'a>(
+        &'a self,
+        query: &'a TagQuery,
+    ) -> impl Iterator<Item = &'a Prototype<P>> {
+        self.registries.iter_with_tags(query)
+    }
+
+    /// Returns `true` once the prototypes are frozen, see [`freeze_prototypes`].
+    pub fn is_frozen(&self) -> bool {
+        self.registries.is_frozen()
+    }
+
+    /// Get a mutable [`Prototype`] instance with it's [`PrototypeId`]
+    ///
+    /// Writing through the returned guard marks the registries as changed, bumps the
+    /// [`PrototypeVersion::generation`] of the prototype and sends a [`RegistryEvent::Modified`]
+    /// once the guard is dropped.
+    pub fn get_mut(&mut self, id: &PrototypeId<P>) -> Option<PrototypeMut<'_, 'w, P>> {
+        let slot = self.registries.bypass_change_detection().slot_mut(id)?;
+
+        Some(PrototypeMut {
+            id: *id,
+            slot,
+            registries: self.registries.reborrow(),
+            events: &mut self.events,
+            modified: false,
+        })
+    }
+
+    /// Insert a copy of the `source` prototype named `name`, e.g. to generate variants of a
+    /// prototype at runtime, and returns the [`PrototypeId`] of the copy.
+    ///
+    /// The copy doesn't keep the `"uuid"` of its source, its [`Prototype::uuid`] is derived from
+    /// its own name.
+    ///
+    /// Returns `None` if `source` doesn't exist or if a prototype is already named `name`.
+    pub fn duplicate(
+        &mut self,
+        source: &PrototypeId<P>,
+        name: impl Into<PrototypeName<P>>,
+    ) -> Option<PrototypeId<P>> {
+        let name = name.into();
+        let id = *name.id();
+
+        if self.registries.get(&id).is_some() {
+            error!(
+                "Attempted to duplicate prototype {source:?} into existing prototype {name} of registry {}",
+                P::prototype_name()
+            );
+            return None;
+        }
+
+        let mut prototype = self.registries.get(source)?.clone();
+        prototype.name = name;
+        prototype.uuid = None;
+        prototype.source = None;
+
+        self.registries.insert(prototype).then_some(id)
+    }
+
     /// Insert a [`Prototype`] instance into the registry
+    ///
+    /// Once the prototypes are frozen, see [`freeze_prototypes`], the prototype isn't inserted.
     pub fn insert(&mut self, prototype: Prototype<P>) {
         self.registries.insert(prototype);
     }
+
+    /// Remove a [`Prototype`] instance from the registry and returns it, e.g. to unload the
+    /// content of a DLC or a mod at runtime
+    ///
+    /// A [`RegistryEvent::Removed`] is sent and the [`PrototypeIndex`](crate::PrototypeIndex)es
+    /// are updated with the other registry events, the pending [`RegistryEvent::Modified`] of
+    /// the prototype are dropped. Once the prototypes are frozen, see
+    /// [`freeze_prototypes`], the prototype isn't removed.
+    pub fn remove(&mut self, id: &PrototypeId<P>) -> Option<Prototype<P>> {
+        self.registries.remove(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{asset::uuid::Uuid, ecs::system::RunSystemOnce};
+
+    use super::*;
+    use crate::{JsonSchema, derived_prototype_uuid};
+
+    #[derive(Debug, Clone, Default, Reflect, crate::Prototype, JsonSchema)]
+    #[proto(name = "card")]
+    struct Card {
+        cost: u32,
+    }
+
+    fn id(name: &str) -> PrototypeId<Card> {
+        PrototypeId::from_name(name)
+    }
+
+    fn world(cards: &[(&str, &[&str])]) -> World {
+        let mut world = World::new();
+        let mut registries = PrototypeRegistries::default();
+        registries.new_registry::<Card>();
+
+        for (name, tags) in cards {
+            registries.insert(Prototype::for_test(name, tags, Card::default()));
+        }
+
+        world.insert_resource(registries);
+        world.init_resource::<Events<RegistryEvent<Card>>>();
+        world
+    }
+
+    fn tagged(world: &World, tag: &str) -> Vec<PrototypeId<Card>> {
+        let registries = world.resource::<PrototypeRegistries>();
+
+        registries
+            .iter_with_tag::<Card>(tag)
+            .map(|card| *card.id())
+            .collect()
+    }
+
+    #[test]
+    fn tags_changed_through_get_mut_are_indexed() {
+        let mut world = world(&[("fireball", &["spell"]), ("goblin", &["creature"])]);
+
+        world
+            .run_system_once(|mut cards: RegMut<Card>| {
+                let mut fireball = cards.get_mut(&id("fireball")).unwrap();
+                fireball.tags_mut().clear();
+                fireball.tags_mut().push("creature".to_string());
+            })
+            .unwrap();
+
+        assert!(tagged(&world, "spell").is_empty());
+        assert_eq!(tagged(&world, "creature"), [id("fireball"), id("goblin")]);
+
+        let registries = world.resource::<PrototypeRegistries>();
+        let tagged = registries
+            .iter_tagged("creature")
+            .map(|(ty, _)| ty)
+            .collect::<Vec<_>>();
+        assert_eq!(tagged, ["card", "card"]);
+    }
+
+    #[test]
+    fn reading_through_get_mut_keeps_the_tags() {
+        let mut world = world(&[("fireball", &["spell"])]);
+
+        world
+            .run_system_once(|mut cards: RegMut<Card>| {
+                let fireball = cards.get_mut(&id("fireball")).unwrap();
+                assert_eq!(fireball.cost, 0);
+            })
+            .unwrap();
+
+        assert_eq!(tagged(&world, "spell"), [id("fireball")]);
+    }
+
+    #[test]
+    fn removing_a_prototype_keeps_the_other_slots() {
+        let mut world = world(&[("a", &["x"]), ("b", &["x"]), ("c", &["y"])]);
+        let mut registries = world.resource_mut::<PrototypeRegistries>();
+
+        assert!(registries.remove::<Card>(&id("a")).is_some());
+
+        let ids = registries
+            .iter::<Card>()
+            .map(|card| *card.id())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, [id("c"), id("b")]);
+        assert_eq!(registries.version(&id("b")).unwrap().index, 1);
+        assert_eq!(registries.version(&id("c")).unwrap().index, 2);
+        assert_eq!(tagged(&world, "x"), [id("b")]);
+        assert_eq!(tagged(&world, "y"), [id("c")]);
+    }
+
+    #[test]
+    fn duplicated_prototypes_derive_their_uuid_from_their_name() {
+        let mut world = World::new();
+        let mut registries = PrototypeRegistries::default();
+        registries.new_registry::<Card>();
+
+        let mut goblin = Prototype::for_test("goblin", &[], Card::default());
+        goblin.uuid = Some(Uuid::from_u128(1));
+        registries.insert(goblin);
+
+        world.insert_resource(registries);
+        world.init_resource::<Events<RegistryEvent<Card>>>();
+
+        let copy = world
+            .run_system_once(|mut cards: RegMut<Card>| {
+                cards.duplicate(&id("goblin"), "goblin_elite")
+            })
+            .unwrap()
+            .unwrap();
+
+        let registries = world.resource::<PrototypeRegistries>();
+        let copy = registries.get(&copy).unwrap();
+        assert_eq!(
+            registries.get(&id("goblin")).unwrap().uuid(),
+            Uuid::from_u128(1)
+        );
+        assert_eq!(
+            copy.uuid(),
+            derived_prototype_uuid(Card::prototype_name(), id("goblin_elite").into())
+        );
+    }
+
+    #[test]
+    fn tag_queries_resolve_like_matching_each_prototype() {
+        let world = world(&[
+            ("sword", &["weapon", "melee"]),
+            ("bow", &["weapon", "ranged"]),
+            ("excalibur", &["weapon", "melee", "legendary"]),
+            ("potion", &[]),
+        ]);
+        let registries = world.resource::<PrototypeRegistries>();
+
+        for query in [
+            TagQuery::tag("melee"),
+            TagQuery::all(["weapon", "melee"]).and(TagQuery::not("legendary")),
+            TagQuery::any(["ranged", "legendary"]),
+            TagQuery::not("weapon"),
+            TagQuery::All(Vec::new()),
+            TagQuery::Any(Vec::new()),
+        ] {
+            let indexed = registries
+                .iter_with_tags::<Card>(&query)
+                .map(|card| *card.id())
+                .collect::<Vec<_>>();
+            let matching = registries
+                .iter::<Card>()
+                .filter(|card| query.matches(card.tags()))
+                .map(|card| *card.id())
+                .collect::<Vec<_>>();
+
+            assert_eq!(indexed, matching, "{query:?}");
+        }
+    }
 }
@@ -0,0 +1,109 @@
+use std::path::{Path, PathBuf};
+
+use bevy::{
+    asset::io::{
+        AssetReader, AssetReaderError, AssetSource, AssetSourceId, PathStream, Reader, VecReader,
+    },
+    platform::sync::Arc,
+    prelude::*,
+};
+
+use crate::PrototypeServer;
+
+/// Registers the `http` and `https` asset sources, so prototypes files can be fetched from a
+/// web server or a CDN with [`PrototypeServer::load_prototypes_url`].
+///
+/// Like any asset source, it must be added before the `AssetPlugin`.
+pub struct HttpPrototypesSourcePlugin;
+
+impl Plugin for HttpPrototypesSourcePlugin {
+    fn build(&self, app: &mut App) {
+        for scheme in ["http", "https"] {
+            app.register_asset_source(
+                AssetSourceId::from(scheme),
+                AssetSource::build().with_reader(move || Box::new(HttpAssetReader { scheme })),
+            );
+        }
+    }
+}
+
+/// Reads assets over HTTP, the asset path being the URL without its scheme.
+///
+/// Web servers can't list directories, [`PrototypeServer::load_prototypes_folder`] loads the
+/// folders of this source from their manifest.
+pub struct HttpAssetReader {
+    scheme: &'static str,
+}
+
+impl HttpAssetReader {
+    async fn get(&self, path: &Path) -> Result<VecReader, AssetReaderError> {
+        let url = format!(
+            "{}://{}",
+            self.scheme,
+            path.to_string_lossy().replace('\\', "/")
+        );
+
+        fetch(url, path.to_path_buf()).await
+    }
+}
+
+/// Fetches the body of `url`, `path` being the asset path reported when it isn't found.
+pub(crate) async fn fetch(url: String, path: PathBuf) -> Result<VecReader, AssetReaderError> {
+    // `ureq` is blocking, run the request on a thread of its own.
+    blocking::unblock(move || match ureq::get(&url).call() {
+        Ok(mut response) => response
+            .body_mut()
+            .read_to_vec()
+            .map(VecReader::new)
+            .map_err(|err| AssetReaderError::Io(Arc::new(std::io::Error::other(err)))),
+        Err(ureq::Error::StatusCode(404)) => Err(AssetReaderError::NotFound(path)),
+        Err(ureq::Error::StatusCode(status)) => Err(AssetReaderError::HttpError(status)),
+        Err(err) => Err(AssetReaderError::Io(Arc::new(std::io::Error::other(err)))),
+    })
+    .await
+}
+
+impl AssetReader for HttpAssetReader {
+    async fn read<'a>(&'a self, path: &'a Path) -> Result<impl Reader + 'a, AssetReaderError> {
+        self.get(path).await
+    }
+
+    async fn read_meta<'a>(&'a self, path: &'a Path) -> Result<impl Reader + 'a, AssetReaderError> {
+        // Prototypes files don't use meta files, don't double the number of requests for them.
+        Err::<VecReader, _>(AssetReaderError::NotFound(meta_path(path)))
+    }
+
+    async fn read_directory<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> Result<Box<PathStream>, AssetReaderError> {
+        Err(AssetReaderError::NotFound(path.to_path_buf()))
+    }
+
+    async fn is_directory<'a>(&'a self, _path: &'a Path) -> Result<bool, AssetReaderError> {
+        Ok(false)
+    }
+}
+
+pub(crate) fn meta_path(path: &Path) -> PathBuf {
+    let mut meta_path = path.as_os_str().to_owned();
+    meta_path.push(".meta");
+    meta_path.into()
+}
+
+impl PrototypeServer<'_> {
+    /// Loads a prototypes file from an `http://` or `https://` URL, see
+    /// [`HttpPrototypesSourcePlugin`].
+    pub fn load_prototypes_url(&mut self, url: &str) {
+        let is_http = ["http://", "https://"]
+            .iter()
+            .any(|scheme| url.starts_with(scheme));
+
+        if !is_http {
+            error!("Invalid prototypes URL {url}, expected an http:// or https:// URL");
+            return;
+        }
+
+        self.load_prototypes(url);
+    }
+}
@@ -0,0 +1,157 @@
+use std::path::{Path, PathBuf};
+
+use bevy::{
+    asset::io::{
+        AssetReader, AssetReaderError, AssetSource, AssetSourceId, PathStream, Reader, VecReader,
+    },
+    platform::{collections::HashMap, sync::Arc},
+    prelude::*,
+};
+
+use crate::{
+    PrototypeServer, TableFormat,
+    remote::{fetch, meta_path},
+};
+
+/// Name of the asset source the remote tables are loaded from.
+pub(crate) const REMOTE_TABLE_SOURCE: &str = "remote-table";
+
+/// Loads prototype tables (see [`PrototypeTables`](crate::PrototypeTables)) published on the web,
+/// e.g. the CSV export of a Google Sheet or an Airtable view, so balance changes don't need to be
+/// exported to files by hand.
+///
+/// Tables are loaded at startup like the `.proto.csv` files, the rows without a `type` cell have
+/// the type the table is named after. Send a [`ReloadRemoteTables`] event to fetch them again,
+/// e.g. from a debug key binding.
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_histrion_proto::{RemoteTablesPlugin, TableFormat};
+/// # fn f(app: &mut App) {
+/// app.add_plugins(RemoteTablesPlugin::default().with_table(
+///     "sword",
+///     "https://docs.google.com/spreadsheets/d/e/<id>/pub?output=csv",
+///     TableFormat::Csv,
+/// ))
+/// .add_plugins(DefaultPlugins);
+/// # }
+/// ```
+///
+/// Like any asset source, it must be added before the `AssetPlugin`.
+#[derive(Default)]
+pub struct RemoteTablesPlugin {
+    tables: HashMap<String, (String, TableFormat)>,
+}
+
+impl RemoteTablesPlugin {
+    /// Adds the table at `url`, named after the default type of its rows.
+    #[must_use]
+    pub fn with_table(
+        mut self,
+        name: impl Into<String>,
+        url: impl Into<String>,
+        format: TableFormat,
+    ) -> Self {
+        self.tables.insert(name.into(), (url.into(), format));
+        self
+    }
+}
+
+impl Plugin for RemoteTablesPlugin {
+    fn build(&self, app: &mut App) {
+        let urls = Arc::new(
+            self.tables
+                .iter()
+                .map(|(name, (url, format))| (table_path(name, *format), url.clone()))
+                .collect::<HashMap<_, _>>(),
+        );
+
+        let reader_urls = urls.clone();
+
+        app.register_asset_source(
+            AssetSourceId::from(REMOTE_TABLE_SOURCE),
+            AssetSource::build().with_reader(move || {
+                Box::new(RemoteTableReader {
+                    urls: reader_urls.clone(),
+                })
+            }),
+        )
+        .insert_resource(RemoteTables(urls))
+        .add_event::<ReloadRemoteTables>()
+        .add_systems(Startup, load_remote_tables)
+        .add_systems(PreUpdate, reload_remote_tables);
+    }
+}
+
+/// Send to fetch the remote tables of the [`RemoteTablesPlugin`] again.
+#[derive(Event, Debug, Clone, Copy, Default)]
+pub struct ReloadRemoteTables;
+
+/// The URLs of the remote tables, by asset path.
+#[derive(Resource)]
+struct RemoteTables(Arc<HashMap<PathBuf, String>>);
+
+/// Reads the remote tables from their URL, the asset path being the table file.
+struct RemoteTableReader {
+    urls: Arc<HashMap<PathBuf, String>>,
+}
+
+impl AssetReader for RemoteTableReader {
+    async fn read<'a>(&'a self, path: &'a Path) -> Result<impl Reader + 'a, AssetReaderError> {
+        let Some(url) = self.urls.get(path) else {
+            return Err(AssetReaderError::NotFound(path.to_path_buf()));
+        };
+
+        fetch(url.clone(), path.to_path_buf()).await
+    }
+
+    async fn read_meta<'a>(&'a self, path: &'a Path) -> Result<impl Reader + 'a, AssetReaderError> {
+        Err::<VecReader, _>(AssetReaderError::NotFound(meta_path(path)))
+    }
+
+    async fn read_directory<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> Result<Box<PathStream>, AssetReaderError> {
+        Err(AssetReaderError::NotFound(path.to_path_buf()))
+    }
+
+    async fn is_directory<'a>(&'a self, _path: &'a Path) -> Result<bool, AssetReaderError> {
+        Ok(false)
+    }
+}
+
+fn table_path(name: &str, format: TableFormat) -> PathBuf {
+    PathBuf::from(format!("{name}.proto.{}", format.extension()))
+}
+
+/// Returns the asset paths of the remote tables, sorted to load them in a stable order.
+fn table_paths(tables: &RemoteTables) -> Vec<String> {
+    let mut paths = tables
+        .0
+        .keys()
+        .map(|path| format!("{REMOTE_TABLE_SOURCE}://{}", path.to_string_lossy()))
+        .collect::<Vec<_>>();
+    paths.sort();
+    paths
+}
+
+fn load_remote_tables(tables: Res<RemoteTables>, mut server: PrototypeServer) {
+    for path in table_paths(&tables) {
+        server.load_prototypes(&path);
+    }
+}
+
+fn reload_remote_tables(
+    mut events_rx: EventReader<ReloadRemoteTables>,
+    tables: Res<RemoteTables>,
+    mut server: PrototypeServer,
+) {
+    if events_rx.read().count() == 0 {
+        return;
+    }
+
+    for path in table_paths(&tables) {
+        server.reload(&path);
+    }
+}
@@ -0,0 +1,186 @@
+use core::time::Duration;
+
+use bevy::{
+    asset::AssetPath,
+    platform::{collections::HashMap, time::Instant},
+    prelude::*,
+};
+
+use crate::{LintIssue, PrototypeSource};
+
+/// A prototype that was not added to its registry, e.g. because its data failed to deserialize.
+#[derive(Debug, Clone)]
+pub struct SkippedPrototype {
+    /// Name of the prototype.
+    pub name: String,
+    /// Where the prototype was loaded from, if known.
+    pub source: Option<PrototypeSource>,
+    /// Why the prototype was skipped, as logged.
+    pub reason: String,
+}
+
+impl SkippedPrototype {
+    /// Logs `reason` as an error.
+    pub(crate) fn logged(
+        name: impl ToString,
+        source: Option<&PrototypeSource>,
+        reason: String,
+    ) -> Self {
+        error!("{reason}");
+
+        Self {
+            name: name.to_string(),
+            source: source.cloned(),
+            reason,
+        }
+    }
+}
+
+/// Summary of the prototypes loaded from a prototypes file, or from every file of a batch.
+///
+/// A report is sent as an event after each file is applied, with [`PrototypeLoadReport::file`]
+/// set, and once every requested file, including the ones of folders and manifests, is applied,
+/// with the totals of the batch. The last batch report is kept by [`PrototypeLoadReports`].
+#[derive(Event, Debug, Clone, Default)]
+pub struct PrototypeLoadReport {
+    /// The prototypes file, `None` for the report of a whole batch.
+    pub file: Option<AssetPath<'static>>,
+    /// Number of prototypes added to the registries, by prototype type.
+    pub loaded: HashMap<String, usize>,
+    /// Number of prototypes left as they were by a reload, their entry being the same as in the
+    /// retained file, see [`PrototypesAssetRetention`](crate::PrototypesAssetRetention).
+    pub unchanged: usize,
+    /// Prototypes that were not added to their registry.
+    pub skipped: Vec<SkippedPrototype>,
+    /// Files that failed to load, with the load error.
+    pub failed_files: Vec<(AssetPath<'static>, String)>,
+    /// Warnings logged while applying the prototypes, e.g. deprecated tag aliases.
+    pub warnings: Vec<String>,
+    /// Violations of the enabled [`LintConfig`](crate::LintConfig) rules, only checked for the report of a batch.
+    pub lints: Vec<LintIssue>,
+    /// Wall-clock time from the load request to the loaded asset, for a batch from its first
+    /// request to its completion.
+    pub load_duration: Duration,
+    /// Wall-clock time spent applying the prototypes into the registries.
+    pub apply_duration: Duration,
+}
+
+impl PrototypeLoadReport {
+    /// Returns the number of prototypes added to the registries.
+    pub fn loaded_count(&self) -> usize {
+        self.loaded.values().sum()
+    }
+
+    /// Returns `true` if no prototype was skipped, no file failed and nothing was warned about.
+    pub fn is_clean(&self) -> bool {
+        self.skipped.is_empty()
+            && self.failed_files.is_empty()
+            && self.warnings.is_empty()
+            && self.lints.is_empty()
+    }
+
+    pub(crate) fn skip(
+        &mut self,
+        name: impl ToString,
+        source: Option<&PrototypeSource>,
+        reason: String,
+    ) {
+        self.skipped
+            .push(SkippedPrototype::logged(name, source, reason));
+    }
+
+    /// Panics with the skipped prototypes and the failed files of the report, if any, see
+    /// [`PanicOnPrototypeErrors`].
+    pub(crate) fn panic_on_errors(&self) {
+        if self.skipped.is_empty() && self.failed_files.is_empty() {
+            return;
+        }
+
+        let mut message = match &self.file {
+            Some(file) => format!("Invalid prototypes in {file}:"),
+            None => "Invalid prototypes:".to_string(),
+        };
+
+        for (file, err) in &self.failed_files {
+            message.push_str(&format!("\n  failed to load {file}: {err}"));
+        }
+
+        for skipped in &self.skipped {
+            message.push_str(&format!("\n  {}", skipped.reason));
+        }
+
+        panic!("{message}");
+    }
+
+    pub(crate) fn warn(&mut self, warning: String) {
+        warn!("{warning}");
+        self.warnings.push(warning);
+    }
+
+    fn merge(&mut self, other: &Self) {
+        for (ty, count) in &other.loaded {
+            *self.loaded.entry(ty.clone()).or_default() += count;
+        }
+
+        self.unchanged += other.unchanged;
+        self.skipped.extend(other.skipped.iter().cloned());
+        self.failed_files.extend(other.failed_files.iter().cloned());
+        self.warnings.extend(other.warnings.iter().cloned());
+        self.apply_duration += other.apply_duration;
+    }
+}
+
+/// Panics as soon as a prototypes file fails to load or skips some of its prototypes, e.g. for
+/// an unknown prototype type or data that failed to deserialize, instead of logging the errors
+/// and leaving the prototypes out of their registry.
+///
+/// Meant for development builds, so data mistakes surface right away rather than as content
+/// missing later on, the panic message lists every error of the file:
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_histrion_proto::PanicOnPrototypeErrors;
+/// # fn f(app: &mut App) {
+/// app.insert_resource(PanicOnPrototypeErrors(cfg!(debug_assertions)));
+/// # }
+/// ```
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Eq, Deref, DerefMut)]
+pub struct PanicOnPrototypeErrors(pub bool);
+
+/// The reports of the prototypes loads, see [`PrototypeLoadReport`].
+#[derive(Resource, Debug, Default)]
+pub struct PrototypeLoadReports {
+    latest: Option<PrototypeLoadReport>,
+    batch: Option<(Instant, PrototypeLoadReport)>,
+}
+
+impl PrototypeLoadReports {
+    /// Returns the report of the last completed batch of loads.
+    pub fn latest(&self) -> Option<&PrototypeLoadReport> {
+        self.latest.as_ref()
+    }
+
+    /// Adds the report of a file to the current batch, started at the earliest request.
+    pub(crate) fn add_file(&mut self, requested: Instant, report: &PrototypeLoadReport) {
+        let (started, batch) = self
+            .batch
+            .get_or_insert_with(|| (requested, PrototypeLoadReport::default()));
+
+        *started = (*started).min(requested);
+        batch.merge(report);
+    }
+
+    /// Completes the current batch, if any, and returns its report with the `lints`.
+    pub(crate) fn complete_batch(
+        &mut self,
+        lints: impl FnOnce() -> Vec<LintIssue>,
+    ) -> Option<PrototypeLoadReport> {
+        let (started, mut batch) = self.batch.take()?;
+        batch.lints = lints();
+        batch.load_duration = started.elapsed();
+
+        self.latest = Some(batch.clone());
+
+        Some(batch)
+    }
+}
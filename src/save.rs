@@ -0,0 +1,387 @@
+use std::path::Path;
+
+use bevy::{
+    asset::{AssetPath, StrongHandle, UntypedHandle},
+    platform::sync::Arc,
+    prelude::*,
+    reflect::{
+        PartialReflect, ReflectRef, TypeRegistry,
+        serde::{ReflectSerializerProcessor, TypedReflectSerializer},
+    },
+};
+use serde_json::{Map as JsonMap, Value as JsonValue};
+
+#[cfg(not(feature = "strip_names"))]
+use crate::{Prototype, PrototypeData};
+use crate::{
+    declared_fragments, entry_offsets, fragment_reference, is_handle_type, is_ndjson_file,
+    named_from_file, read_ndjson,
+};
+
+/// Serializes asset handles as their asset path.
+///
+/// Paths inside the folder of `file` are written relative to it, the others are written as full
+/// paths (`/path/to/asset`) so they resolve the same way when loaded back from `file`.
+pub(crate) struct HandlePathProcessor<'a> {
+    pub file: Option<&'a AssetPath<'a>>,
+}
+
+impl HandlePathProcessor<'_> {
+    fn asset_path_string(&self, path: &AssetPath) -> String {
+        let label = path
+            .label()
+            .map(|label| format!("#{label}"))
+            .unwrap_or_default();
+        let full_path = path.path().to_string_lossy().replace('\\', "/");
+
+        let Some(dir) = self.file.and_then(AssetPath::parent) else {
+            return format!("/{full_path}{label}");
+        };
+
+        if path.source() != dir.source() {
+            return path.to_string();
+        }
+
+        match path.path().strip_prefix(dir.path()) {
+            Ok(relative) => format!("{}{label}", relative.to_string_lossy().replace('\\', "/")),
+            Err(_) => format!("/{full_path}{label}"),
+        }
+    }
+}
+
+impl ReflectSerializerProcessor for HandlePathProcessor<'_> {
+    fn try_serialize<S>(
+        &self,
+        value: &dyn PartialReflect,
+        _registry: &TypeRegistry,
+        serializer: S,
+    ) -> Result<Result<S::Ok, S>, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        if !value
+            .get_represented_type_info()
+            .is_some_and(|type_info| is_handle_type(type_info.type_path_table()))
+        {
+            return Ok(Err(serializer));
+        }
+
+        let path = match value.reflect_ref() {
+            ReflectRef::Enum(handle) => handle
+                .field_at(0)
+                .and_then(|strong| strong.try_downcast_ref::<Arc<StrongHandle>>())
+                .and_then(|strong| UntypedHandle::Strong(strong.clone()).path().cloned()),
+            _ => None,
+        };
+
+        match path {
+            Some(path) => Ok(Ok(serializer.serialize_str(&self.asset_path_string(&path))?)),
+            // Weak handles and handles to assets added at runtime have no path to write.
+            None => Ok(Ok(serializer.serialize_none()?)),
+        }
+    }
+}
+
+/// Serializes reflected prototype data into JSON, using the same layout as the prototypes files.
+///
+/// Asset handles are written as asset paths relative to the folder of `file` when given. Struct
+/// fields that can't be serialized are skipped with a warning.
+pub fn prototype_data_to_json(
+    data: &dyn PartialReflect,
+    registry: &TypeRegistry,
+    file: Option<&AssetPath>,
+) -> JsonValue {
+    let processor = HandlePathProcessor { file };
+    let to_json = |value: &dyn PartialReflect| {
+        serde_json::to_value(TypedReflectSerializer::with_processor(
+            value, registry, &processor,
+        ))
+    };
+
+    let ReflectRef::Struct(data) = data.reflect_ref() else {
+        return to_json(data).unwrap_or_else(|err| {
+            warn!("Failed to serialize prototype data: {err}");
+            JsonValue::Null
+        });
+    };
+
+    let mut fields = serde_json::Map::new();
+
+    for (i, field) in data.iter_fields().enumerate() {
+        let Some(name) = data.name_at(i) else {
+            continue;
+        };
+
+        match to_json(field) {
+            Ok(value) => {
+                fields.insert(name.to_string(), value);
+            }
+            Err(err) => warn!("Failed to serialize prototype field {name}: {err}"),
+        }
+    }
+
+    JsonValue::Object(fields)
+}
+
+/// Replaces the fields of the `name` entry of type `ty` in the prototypes file at `path`.
+///
+/// Only the keys present in `data` are written, the other keys of the entry and the other
+/// entries of the file are left untouched. Values equivalent to the ones already in the file
+/// keep their original representation, e.g. a prototype id written as a name isn't replaced by
+/// its hash and a `"$fragments.<name>"` reference isn't replaced by the fragment.
+///
+/// Only the text of the entry is rewritten, with the indentation it had, so the rest of the file
+/// keeps its formatting and the file isn't written at all when the entry didn't change.
+pub fn write_back_prototype(
+    path: impl AsRef<Path>,
+    ty: &str,
+    name: &str,
+    data: &JsonValue,
+) -> std::io::Result<()> {
+    use std::io::{Error, ErrorKind};
+
+    let path = path.as_ref();
+    let bytes = std::fs::read(path)?;
+    let is_ndjson = is_ndjson_file(path);
+    let mut file = if is_ndjson {
+        read_ndjson(&bytes).map(JsonValue::Array)
+    } else {
+        serde_json::from_slice(&bytes)
+    }
+    .map_err(|err| Error::new(ErrorKind::InvalidData, err))?;
+    let fragments = declared_fragments(&file);
+
+    let is_entry = |entry: &JsonValue| {
+        entry.get("type").and_then(JsonValue::as_str) == Some(ty)
+            && entry.get("name").and_then(JsonValue::as_str) == Some(name)
+    };
+
+    let (index, entry) = match &mut file {
+        JsonValue::Array(entries) => entries
+            .iter_mut()
+            .enumerate()
+            .find(|(_, entry)| is_entry(entry))
+            .unzip(),
+        entry if is_entry(&named_from_file(entry.clone(), path)) => (Some(0), Some(entry)),
+        _ => (None, None),
+    };
+
+    let (Some(index), Some(entry)) = (index, entry.and_then(JsonValue::as_object_mut)) else {
+        return Err(Error::new(
+            ErrorKind::NotFound,
+            format!("no {ty} prototype named {name} in {}", path.display()),
+        ));
+    };
+
+    let JsonValue::Object(fields) = data else {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "prototype data must be a JSON object",
+        ));
+    };
+
+    let original = entry.clone();
+
+    for (key, value) in fields {
+        let value = match entry.get(key) {
+            Some(current) => merge_preserving(current, value, &fragments),
+            None => value.clone(),
+        };
+
+        entry.insert(key.clone(), value);
+    }
+
+    if *entry == original {
+        return Ok(());
+    }
+
+    let entry = JsonValue::Object(entry.clone());
+
+    let output = match entry_span(&bytes, index, file.as_array().map_or(1, Vec::len)) {
+        Some((start, end)) => {
+            let text = if is_ndjson {
+                serde_json::to_vec(&entry).map_err(Error::other)?
+            } else {
+                entry_to_json(&entry, &bytes, start, end)?
+            };
+
+            [&bytes[..start], &text, &bytes[end..]].concat()
+        }
+        // The entries couldn't be located in the text, rewrite the whole file.
+        None => {
+            let mut output = match &file {
+                // Newline-delimited files keep one compact entry per line.
+                JsonValue::Array(entries) if is_ndjson => entries
+                    .iter()
+                    .map(serde_json::to_vec)
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(Error::other)?
+                    .join(&b'\n'),
+                file => to_json_pretty(file)?,
+            };
+            if bytes.ends_with(b"\n") {
+                output.push(b'\n');
+            }
+            output
+        }
+    };
+
+    std::fs::write(path, output)
+}
+
+/// Returns the byte range of the `index` entry of a prototypes file holding `len` entries.
+fn entry_span(bytes: &[u8], index: usize, len: usize) -> Option<(usize, usize)> {
+    let offsets = entry_offsets(bytes);
+
+    if offsets.len() != len {
+        return None;
+    }
+
+    let start = *offsets.get(index)?;
+    let mut entries =
+        serde_json::Deserializer::from_slice(&bytes[start..]).into_iter::<serde::de::IgnoredAny>();
+    entries.next()?.ok()?;
+
+    Some((start, start + entries.byte_offset()))
+}
+
+/// Serializes an entry to replace the `start..end` text of a prototypes file, with the same
+/// indentation, entries written on a single line stay on a single line.
+fn entry_to_json(
+    entry: &JsonValue,
+    bytes: &[u8],
+    start: usize,
+    end: usize,
+) -> std::io::Result<Vec<u8>> {
+    let text = &bytes[start..end];
+
+    let Some(newline) = text.iter().position(|byte| *byte == b'\n') else {
+        return serde_json::to_vec(entry).map_err(std::io::Error::other);
+    };
+
+    let is_indent = |byte: &u8| *byte == b' ' || *byte == b'\t';
+
+    // Indentation of the line the entry starts on, when it only has whitespace before the entry.
+    let line_start = bytes[..start]
+        .iter()
+        .rposition(|byte| *byte == b'\n')
+        .map_or(0, |newline| newline + 1);
+    let base = if bytes[line_start..start].iter().all(is_indent) {
+        &bytes[line_start..start]
+    } else {
+        &[]
+    };
+
+    let inner = text[newline + 1..]
+        .iter()
+        .take_while(|byte| is_indent(byte))
+        .count();
+    let inner = &text[newline + 1..newline + 1 + inner];
+    let indent = match inner.strip_prefix(base) {
+        Some(indent) if !indent.is_empty() => indent,
+        _ => b"    ",
+    };
+
+    let mut json = Vec::new();
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(indent);
+    let mut serializer = serde_json::Serializer::with_formatter(&mut json, formatter);
+    serde::Serialize::serialize(entry, &mut serializer).map_err(std::io::Error::other)?;
+
+    let mut output = Vec::with_capacity(json.len());
+
+    for (i, line) in json.split(|byte| *byte == b'\n').enumerate() {
+        if i > 0 {
+            output.push(b'\n');
+            output.extend_from_slice(base);
+        }
+
+        output.extend_from_slice(line);
+    }
+
+    Ok(output)
+}
+
+/// Merges `new` into `current`, keeping the parts of `current` that are equivalent to `new`.
+fn merge_preserving(
+    current: &JsonValue,
+    new: &JsonValue,
+    fragments: &JsonMap<String, JsonValue>,
+) -> JsonValue {
+    // References to fragments equivalent to the new value stay references, a fragment only
+    // referencing another fragment isn't followed in case they reference each other.
+    if let Some(fragment) = fragment_reference(current, fragments)
+        && fragment_reference(fragment, fragments).is_none()
+        && merge_preserving(fragment, new, fragments) == *fragment
+    {
+        return current.clone();
+    }
+
+    match (current, new) {
+        (JsonValue::Array(current), JsonValue::Array(new)) => JsonValue::Array(
+            new.iter()
+                .enumerate()
+                .map(|(i, new)| match current.get(i) {
+                    Some(current) => merge_preserving(current, new, fragments),
+                    None => new.clone(),
+                })
+                .collect(),
+        ),
+        (JsonValue::Object(current), JsonValue::Object(new)) => JsonValue::Object(
+            new.iter()
+                .map(|(key, new)| {
+                    let value = match current.get(key) {
+                        Some(current) => merge_preserving(current, new, fragments),
+                        None => new.clone(),
+                    };
+
+                    (key.clone(), value)
+                })
+                .collect(),
+        ),
+        (JsonValue::String(name), JsonValue::Number(hash))
+            if hash.as_u64() == Some(const_fnv1a_hash::fnv1a_hash_str_64(name)) =>
+        {
+            JsonValue::String(name.clone())
+        }
+        // Reflected `f32` fields come back widened to `f64`, compare them at the `f32` precision.
+        (JsonValue::Number(a), JsonValue::Number(b))
+            if a.as_f64().map(|a| a as f32) == b.as_f64().map(|b| b as f32) =>
+        {
+            JsonValue::Number(a.clone())
+        }
+        _ => new.clone(),
+    }
+}
+
+/// Serializes a JSON value with the four spaces indentation used by the prototypes files.
+pub(crate) fn to_json_pretty(value: &impl serde::Serialize) -> std::io::Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(b"    ");
+    let mut serializer = serde_json::Serializer::with_formatter(&mut bytes, formatter);
+    value
+        .serialize(&mut serializer)
+        .map_err(std::io::Error::other)?;
+
+    Ok(bytes)
+}
+
+#[cfg(not(feature = "strip_names"))]
+impl<P: PrototypeData> Prototype<P> {
+    /// Writes the data of this prototype back into its entry of the prototypes `file`, located in
+    /// the `asset_root` folder (usually `assets`).
+    pub fn write_back(
+        &self,
+        asset_root: impl AsRef<Path>,
+        file: &AssetPath,
+        registry: &TypeRegistry,
+    ) -> std::io::Result<()> {
+        let data = prototype_data_to_json(self.data().as_partial_reflect(), registry, Some(file));
+
+        write_back_prototype(
+            asset_root.as_ref().join(file.path()),
+            P::prototype_name(),
+            self.name(),
+            &data,
+        )
+    }
+}
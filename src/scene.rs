@@ -0,0 +1,421 @@
+use core::any::TypeId;
+use std::path::Path;
+
+use bevy::{
+    asset::{AssetLoader, LoadContext, io::Reader as AssetReader, uuid::Uuid},
+    ecs::system::SystemParam,
+    platform::collections::HashMap,
+    prelude::*,
+    reflect::{
+        ReflectRef, TypeRegistration, TypeRegistry, TypeRegistryArc,
+        serde::{TypedReflectDeserializer, TypedReflectSerializer},
+    },
+};
+use serde::{
+    Deserialize, Serialize,
+    de::{DeserializeSeed, IgnoredAny, MapAccess, SeqAccess, Visitor},
+    ser::{SerializeMap, SerializeStruct},
+};
+
+use crate::{
+    AppPrototypeTypeRegistry, DynamicPrototype, ErasedPrototypeName, HandlePathProcessor,
+    HandleProcessor, PrototypeMetadata, PrototypeRegistries, PrototypeSource,
+    PrototypeTypeRegistry, PrototypesAsset,
+};
+
+/// Extension of the registry scenes, loaded like prototypes files with
+/// [`PrototypeServer::load_prototypes`](crate::PrototypeServer::load_prototypes).
+pub const REGISTRY_SCENE_EXTENSION: &str = "registry.ron";
+
+/// Captures the registries into a registry scene, e.g. to restore the prototypes added at
+/// runtime.
+///
+/// Prototypes are grouped by prototype type and serialized with the reflect serializer, asset
+/// handles are written as full asset paths:
+///
+/// ```ron
+/// {
+///     "sword": [
+///         (
+///             name: "wooden_stick",
+///             tags: [],
+///             data: (level: 1, damage: 1.0, icon: "/prototypes/wooden_stick.icon"),
+///         ),
+///     ],
+/// }
+/// ```
+#[derive(SystemParam)]
+pub struct RegistryScene<'w> {
+    registries: Res<'w, PrototypeRegistries>,
+    prototype_types: Res<'w, AppPrototypeTypeRegistry>,
+    type_registry: Res<'w, AppTypeRegistry>,
+}
+
+impl RegistryScene<'_> {
+    /// Serializes every registered prototype into a registry scene.
+    pub fn serialize(&self) -> Result<String, ron::Error> {
+        let type_registry = self.type_registry.read();
+        let prototype_types = self.prototype_types.0.read();
+
+        let mut types = prototype_types.iter().collect::<Vec<_>>();
+        types.sort_by_key(|(ty, _)| *ty);
+
+        let scene = SceneSerializer {
+            types: types
+                .into_iter()
+                .map(|(ty, type_id)| (&**ty, self.registries.iter_dyn(type_id).collect()))
+                .collect(),
+            registry: &type_registry,
+        };
+
+        ron::ser::to_string_pretty(&scene, ron::ser::PrettyConfig::default())
+    }
+
+    /// Writes the registry scene into `path`, usually under the assets folder with the
+    /// [`REGISTRY_SCENE_EXTENSION`] extension.
+    pub fn save(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        let scene = self.serialize().map_err(std::io::Error::other)?;
+
+        std::fs::write(path, scene)
+    }
+}
+
+struct SceneSerializer<'a> {
+    types: Vec<(&'a str, Vec<&'a dyn Reflect>)>,
+    registry: &'a TypeRegistry,
+}
+
+impl Serialize for SceneSerializer<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut map = serializer.serialize_map(Some(self.types.len()))?;
+
+        for (ty, prototypes) in &self.types {
+            let entries = prototypes
+                .iter()
+                .map(|proto| EntrySerializer {
+                    proto: *proto,
+                    registry: self.registry,
+                })
+                .collect::<Vec<_>>();
+
+            map.serialize_entry(ty, &entries)?;
+        }
+
+        map.end()
+    }
+}
+
+struct EntrySerializer<'a> {
+    proto: &'a dyn Reflect,
+    registry: &'a TypeRegistry,
+}
+
+impl Serialize for EntrySerializer<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let ReflectRef::Struct(proto) = self.proto.reflect_ref() else {
+            return Err(serde::ser::Error::custom("prototype isn't a struct"));
+        };
+
+        let processor = HandlePathProcessor { file: None };
+        let metadata = proto
+            .field("metadata")
+            .and_then(<dyn PartialReflect>::try_downcast_ref::<PrototypeMetadata>)
+            .and_then(|metadata| metadata.0.as_ref());
+        let uuid = proto
+            .field("uuid")
+            .and_then(<dyn PartialReflect>::try_downcast_ref::<Option<Uuid>>)
+            .copied()
+            .flatten();
+        let mut entry = serializer.serialize_struct(
+            "Prototype",
+            3 + usize::from(metadata.is_some()) + usize::from(uuid.is_some()),
+        )?;
+
+        for field in ["name", "tags", "data"] {
+            let Some(value) = proto.field(field) else {
+                return Err(serde::ser::Error::custom(format!(
+                    "prototype has no {field} field"
+                )));
+            };
+
+            // Editor metadata is written before the data, like in the prototypes files.
+            if field == "data"
+                && let Some(metadata) = metadata
+            {
+                entry.serialize_field("metadata", metadata)?;
+            }
+
+            if field == "data"
+                && let Some(uuid) = uuid
+            {
+                entry.serialize_field("uuid", &uuid)?;
+            }
+
+            entry.serialize_field(
+                field,
+                &TypedReflectSerializer::with_processor(value, self.registry, &processor),
+            )?;
+        }
+
+        entry.end()
+    }
+}
+
+pub(crate) struct RegistrySceneLoader {
+    pub type_registry: TypeRegistryArc,
+    pub prototype_type_registry: PrototypeTypeRegistry,
+    pub load_handles: bool,
+}
+
+impl AssetLoader for RegistrySceneLoader {
+    type Asset = PrototypesAsset;
+    type Settings = ();
+    type Error = std::io::Error;
+
+    async fn load(
+        &self,
+        reader: &mut dyn AssetReader,
+        _settings: &Self::Settings,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        let registry = self.type_registry.read();
+        let prototype_types = self.prototype_type_registry.read();
+
+        let mut deserializer = ron::de::Deserializer::from_bytes(&bytes)
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))?;
+
+        let prototypes = SceneSeed {
+            registry: &registry,
+            prototype_types: &prototype_types,
+            load_context,
+            load_handles: self.load_handles,
+        }
+        .deserialize(&mut deserializer)
+        .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))?;
+
+        Ok(PrototypesAsset::new(
+            prototypes,
+            Vec::new(),
+            load_context,
+            &bytes,
+        ))
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &[REGISTRY_SCENE_EXTENSION]
+    }
+}
+
+struct SceneSeed<'a, 'b> {
+    registry: &'a TypeRegistry,
+    prototype_types: &'a HashMap<Box<str>, TypeId>,
+    load_context: &'a mut LoadContext<'b>,
+    load_handles: bool,
+}
+
+impl<'de> DeserializeSeed<'de> for SceneSeed<'_, '_> {
+    type Value = Vec<(TypeId, DynamicPrototype)>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de> Visitor<'de> for SceneSeed<'_, '_> {
+    type Value = Vec<(TypeId, DynamicPrototype)>;
+
+    fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter.write_str("a map of prototypes by prototype type")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut prototypes = Vec::new();
+
+        while let Some(ty) = map.next_key::<String>()? {
+            let Some(type_id) = self.prototype_types.get(ty.as_str()) else {
+                error!("Unknown prototype type {ty}");
+                map.next_value::<IgnoredAny>()?;
+                continue;
+            };
+
+            let Some(registration) = self.registry.get(*type_id) else {
+                error!("Unknown prototype type id {type_id:?}");
+                map.next_value::<IgnoredAny>()?;
+                continue;
+            };
+
+            map.next_value_seed(EntriesSeed {
+                ty: &ty,
+                type_id: *type_id,
+                registration,
+                registry: self.registry,
+                load_context: &mut *self.load_context,
+                load_handles: self.load_handles,
+                prototypes: &mut prototypes,
+            })?;
+        }
+
+        Ok(prototypes)
+    }
+}
+
+struct EntriesSeed<'a, 'b> {
+    ty: &'a str,
+    type_id: TypeId,
+    registration: &'a TypeRegistration,
+    registry: &'a TypeRegistry,
+    load_context: &'a mut LoadContext<'b>,
+    load_handles: bool,
+    prototypes: &'a mut Vec<(TypeId, DynamicPrototype)>,
+}
+
+impl<'de> DeserializeSeed<'de> for EntriesSeed<'_, '_> {
+    type Value = ();
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de> Visitor<'de> for EntriesSeed<'_, '_> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter.write_str("a list of prototypes")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut index = 0;
+
+        while let Some(prototype) = seq.next_element_seed(EntrySeed {
+            ty: self.ty,
+            index,
+            registration: self.registration,
+            registry: self.registry,
+            load_context: &mut *self.load_context,
+            load_handles: self.load_handles,
+        })? {
+            self.prototypes.push((self.type_id, prototype));
+            index += 1;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(field_identifier, rename_all = "lowercase")]
+enum EntryField {
+    Name,
+    Tags,
+    Metadata,
+    Uuid,
+    Data,
+    #[serde(other)]
+    Unknown,
+}
+
+struct EntrySeed<'a, 'b> {
+    ty: &'a str,
+    index: usize,
+    registration: &'a TypeRegistration,
+    registry: &'a TypeRegistry,
+    load_context: &'a mut LoadContext<'b>,
+    load_handles: bool,
+}
+
+impl<'de> DeserializeSeed<'de> for EntrySeed<'_, '_> {
+    type Value = DynamicPrototype;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_struct(
+            "Prototype",
+            &["name", "tags", "metadata", "uuid", "data"],
+            self,
+        )
+    }
+}
+
+impl<'de> Visitor<'de> for EntrySeed<'_, '_> {
+    type Value = DynamicPrototype;
+
+    fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter.write_str("a prototype")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let (mut name, mut tags, mut metadata, mut uuid, mut proto) =
+            (None, Vec::new(), None, None, None);
+
+        while let Some(field) = map.next_key::<EntryField>()? {
+            match field {
+                EntryField::Name => name = Some(map.next_value::<ErasedPrototypeName>()?),
+                EntryField::Tags => tags = map.next_value()?,
+                EntryField::Metadata => metadata = Some(map.next_value()?),
+                EntryField::Uuid => uuid = Some(map.next_value()?),
+                EntryField::Data => {
+                    let mut processor = HandleProcessor {
+                        load_context: self.load_handles.then_some(&mut *self.load_context),
+                    };
+
+                    proto = Some(
+                        map.next_value_seed(TypedReflectDeserializer::with_processor(
+                            self.registration,
+                            self.registry,
+                            &mut processor,
+                        ))?,
+                    );
+                }
+                EntryField::Unknown => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+
+        Ok(DynamicPrototype {
+            source: PrototypeSource {
+                path: self.load_context.asset_path().clone_owned(),
+                index: self.index,
+                position: None,
+                pointer: format!("/{}/{}", self.ty, self.index),
+            },
+            name: name.ok_or_else(|| serde::de::Error::missing_field("name"))?,
+            tags,
+            defaults_from: None,
+            metadata,
+            experiment: None,
+            uuid,
+            proto: proto.ok_or_else(|| serde::de::Error::missing_field("data"))?,
+            list_merges: Vec::new(),
+            lossy_numbers: Vec::new(),
+            fingerprint: None,
+        })
+    }
+}
@@ -1,7 +1,15 @@
-use bevy::reflect::TypePath;
+use core::any::TypeId;
+use std::path::PathBuf;
+
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use bevy::reflect::{
+    GetTypeRegistration, PartialReflect, ReflectRef, TypeInfo, TypePath, TypeRegistry, VariantInfo,
+    serde::TypedReflectSerializer,
+};
 use serde_json::{Map as JsonMap, Value as JsonValue, json};
 
-use crate::PrototypeData;
+use crate::{PrototypeData, is_handle_type};
 
 pub trait JsonSchema: TypePath {
     fn json_schema(refs: &mut JsonMap<String, JsonValue>) -> JsonValue;
@@ -13,6 +21,606 @@ pub trait JsonSchema: TypePath {
     fn schema_ref() -> String {
         format!("#/definitions/{}", Self::schema_title())
     }
+
+    /// Schema of the keys of the JSON objects storing maps keyed by this type, JSON object keys
+    /// are always strings.
+    fn property_names_schema(refs: &mut JsonMap<String, JsonValue>) -> JsonValue {
+        Self::register_schema(refs);
+
+        json!({ "$ref": Self::schema_ref() })
+    }
+
+    /// Inserts the schema of this type into `refs` if it isn't already there.
+    fn register_schema(refs: &mut JsonMap<String, JsonValue>) {
+        let title = Self::schema_title();
+
+        if !refs.contains_key(&title) {
+            // Reserve the title first so that self-referential types end up as a `$ref` cycle
+            // instead of recursing forever.
+            refs.insert(title.clone(), json!({}));
+            let schema = Self::json_schema(refs);
+            refs.insert(title, schema);
+        }
+    }
+}
+
+/// Development plugin keeping the prototypes schema file up to date.
+///
+/// The schema is rewritten whenever the registered prototypes change, and the app exits right
+/// after writing it when started with the `--dump-schema` argument.
+pub struct PrototypesSchemaPlugin {
+    /// Path of the generated schema file.
+    pub path: PathBuf,
+}
+
+impl Default for PrototypesSchemaPlugin {
+    fn default() -> Self {
+        Self {
+            path: PathBuf::from(".vscode/prototypes.schema.json"),
+        }
+    }
+}
+
+impl Plugin for PrototypesSchemaPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(PrototypesSchemaPath(self.path.clone()))
+            .add_systems(
+                PreStartup,
+                write_prototypes_schema.run_if(resource_changed::<PrototypesSchemas>),
+            )
+            .add_systems(
+                Last,
+                write_prototypes_schema.run_if(resource_changed::<PrototypesSchemas>),
+            );
+
+        if std::env::args().any(|arg| arg == "--dump-schema") {
+            app.add_systems(
+                PreStartup,
+                exit_after_schema_dump.after(write_prototypes_schema),
+            );
+        }
+    }
+}
+
+#[derive(Resource)]
+struct PrototypesSchemaPath(PathBuf);
+
+fn write_prototypes_schema(
+    schemas: Res<PrototypesSchemas>,
+    type_registry: Res<AppTypeRegistry>,
+    path: Res<PrototypesSchemaPath>,
+) {
+    let schema = schemas.to_json_string(&type_registry.read());
+
+    // Avoid touching the file when nothing changed, editors and watchers would reload it.
+    if std::fs::read_to_string(&path.0).is_ok_and(|current| current == schema) {
+        return;
+    }
+
+    if let Some(parent) = path.0.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+
+    match std::fs::write(&path.0, schema) {
+        Ok(()) => info!("Prototypes schema written to {}", path.0.display()),
+        Err(err) => error!(
+            "Failed to write prototypes schema to {}: {err}",
+            path.0.display()
+        ),
+    }
+}
+
+fn exit_after_schema_dump(mut exit: EventWriter<AppExit>) {
+    exit.write(AppExit::Success);
+}
+
+#[derive(Default, Resource)]
+pub(crate) struct PrototypesSchemas {
+    pub prototypes: HashMap<String, String>,
+    pub refs: JsonMap<String, JsonValue>,
+}
+
+/// Name of the definitions file shared by the schemas of
+/// [`PrototypesSchemas::to_split_json_strings`].
+pub(crate) const SHARED_DEFINITIONS_FILE: &str = "prototypes.definitions.schema.json";
+
+impl PrototypesSchemas {
+    /// Builds the combined schema of all the registered prototypes.
+    pub fn to_json_string(&self, type_registry: &TypeRegistry) -> String {
+        let refs = self.definitions(type_registry);
+
+        serde_json::to_string_pretty(&json!({
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "title": "Prototype",
+            "type": ["object", "array"],
+            "oneOf": [
+                {
+                    "$ref": "#/definitions/PrototypeAny"
+                },
+                {
+                    "type": "array",
+                    "items": {
+                        "anyOf": [
+                            { "$ref": "#/definitions/PrototypeAny" },
+                            { "$ref": "#/definitions/PrototypeFragments" },
+                        ],
+                    },
+                }
+            ],
+            "definitions": refs,
+        }))
+        .unwrap()
+    }
+
+    /// Builds one small schema per registered prototype type, by file name, referencing the
+    /// definitions of the [`SHARED_DEFINITIONS_FILE`] also returned, so editors only load the
+    /// schema of the files they edit instead of the combined schema.
+    pub fn to_split_json_strings(&self, type_registry: &TypeRegistry) -> Vec<(String, String)> {
+        let refs = self.definitions(type_registry);
+        let shared =
+            |reference: &str| json!({ "$ref": format!("{SHARED_DEFINITIONS_FILE}{reference}") });
+
+        let mut types = self.prototypes.iter().collect::<Vec<_>>();
+        types.sort();
+
+        let mut files = vec![(
+            SHARED_DEFINITIONS_FILE.to_string(),
+            serde_json::to_string_pretty(&json!({
+                "$schema": "http://json-schema.org/draft-07/schema#",
+                "title": "Prototype definitions",
+                "definitions": refs,
+            }))
+            .unwrap(),
+        )];
+
+        for (ty, reference) in types {
+            let entry = json!({
+                "allOf": [
+                    shared(reference),
+                    {
+                        "properties": {
+                            "type": {
+                                "const": ty,
+                            },
+                        },
+                    },
+                ],
+            });
+
+            files.push((
+                split_schema_file_name(ty),
+                serde_json::to_string_pretty(&json!({
+                    "$schema": "http://json-schema.org/draft-07/schema#",
+                    "title": ty,
+                    "type": ["object", "array"],
+                    "oneOf": [
+                        entry,
+                        {
+                            "type": "array",
+                            "items": {
+                                "anyOf": [entry, shared("#/definitions/PrototypeFragments")],
+                            },
+                        }
+                    ],
+                }))
+                .unwrap(),
+            ));
+        }
+
+        files
+    }
+
+    /// Returns the definitions of the schemas, with the `PrototypeAny` and
+    /// `PrototypeFragments` entry schemas.
+    fn definitions(&self, type_registry: &TypeRegistry) -> JsonMap<String, JsonValue> {
+        let PrototypesSchemas { prototypes, refs } = self;
+        let mut refs = refs.clone();
+
+        apply_reflect_schemas(&mut refs, type_registry);
+        apply_reflect_defaults(&mut refs, type_registry);
+
+        let mut types = prototypes.keys().collect::<Vec<_>>();
+        types.sort();
+
+        // Each prototype type is selected by its `type` discriminator, this lets editors know
+        // which properties belong to which type instead of trying to match every one of them.
+        refs.insert(
+            "PrototypeAny".to_string(),
+            json!({
+                "type": "object",
+                "required": ["type"],
+                "properties": {
+                    "type": {
+                        "enum": types,
+                    },
+                },
+                "allOf": types.iter().map(|ty| json!({
+                    "if": {
+                        "required": ["type"],
+                        "properties": {
+                            "type": {
+                                "const": ty,
+                            },
+                        },
+                    },
+                    "then": {
+                        "$ref": prototypes[*ty]
+                    }
+                })).collect::<Vec<_>>(),
+            }),
+        );
+
+        // Entries of a list can also only declare the fragments of the file.
+        refs.insert(
+            "PrototypeFragments".to_string(),
+            json!({
+                "type": "object",
+                "required": ["$fragments"],
+                "properties": {
+                    "$fragments": {
+                        "type": "object",
+                        "description": "Values shared by the entries of this file, referenced as \"$fragments.<name>\" strings",
+                    },
+                },
+            }),
+        );
+
+        refs
+    }
+}
+
+/// Returns the name of the split schema file of the `ty` prototype type, e.g.
+/// `sword.schema.json`, the characters of generic types not allowed in file names are replaced.
+pub(crate) fn split_schema_file_name(ty: &str) -> String {
+    let name = ty
+        .chars()
+        .map(|char| {
+            if char.is_alphanumeric() || char == '_' || char == '-' {
+                char
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>();
+
+    format!("{name}.schema.json")
+}
+
+/// Marks the placeholder schemas registered for the `#[schema(reflect)]` fields.
+const REFLECT_SCHEMA_KEY: &str = "x-reflect-schema";
+
+/// Reference to the schema of a `#[schema(reflect)]` field for the derived schemas.
+#[doc(hidden)]
+pub fn reflect_schema_ref<T: TypePath>() -> String {
+    format!("#/definitions/{}", T::type_path())
+}
+
+/// Registers a placeholder schema for a `#[schema(reflect)]` field, it is synthesized from the
+/// reflected type info of `T` by [`apply_reflect_schemas`] once the type registry is available.
+#[doc(hidden)]
+pub fn register_reflect_schema<T: TypePath>(refs: &mut JsonMap<String, JsonValue>) {
+    refs.entry(T::type_path())
+        .or_insert_with(|| json!({ REFLECT_SCHEMA_KEY: true }));
+}
+
+/// Replaces the placeholder schemas of the `#[schema(reflect)]` fields with schemas synthesized
+/// from the [`TypeInfo`] of their types, for third-party types without [`JsonSchema`] impl.
+pub(crate) fn apply_reflect_schemas(
+    refs: &mut JsonMap<String, JsonValue>,
+    registry: &TypeRegistry,
+) {
+    let placeholders = refs
+        .iter()
+        .filter(|(_, schema)| schema.get(REFLECT_SCHEMA_KEY).is_some())
+        .map(|(title, _)| title.clone())
+        .collect::<Vec<_>>();
+
+    for title in placeholders {
+        let Some(registration) = registry.get_with_type_path(&title) else {
+            warn!("Type {title} isn't registered, its schema can't be synthesized");
+            refs.insert(title, json!({}));
+            continue;
+        };
+
+        // Reserve the title first, like `JsonSchema::register_schema`, for self-referential
+        // types.
+        refs.insert(title.clone(), json!({}));
+        let schema = reflect_type_schema(registration.type_info(), refs, registry);
+        refs.insert(title, schema);
+    }
+}
+
+/// Returns the schema of a field of a reflected type, structs and enums are registered into
+/// `refs` and referenced.
+fn reflect_field_schema(
+    type_id: TypeId,
+    refs: &mut JsonMap<String, JsonValue>,
+    registry: &TypeRegistry,
+) -> JsonValue {
+    let Some(type_info) = registry.get_type_info(type_id) else {
+        return json!({});
+    };
+
+    let type_path = type_info.type_path();
+
+    if is_handle_type(type_info.type_path_table()) {
+        return json!({ "type": "string", "$comment": "an asset path" });
+    }
+
+    if refs.contains_key(type_path) {
+        return json!({ "$ref": format!("#/definitions/{type_path}") });
+    }
+
+    match type_info {
+        TypeInfo::Struct(_) | TypeInfo::TupleStruct(_) | TypeInfo::Enum(_)
+            if !is_option(type_info) =>
+        {
+            // Reserve the title first, like `JsonSchema::register_schema`, for self-referential
+            // types.
+            refs.insert(type_path.to_string(), json!({}));
+            let schema = reflect_type_schema(type_info, refs, registry);
+            refs.insert(type_path.to_string(), schema);
+
+            json!({ "$ref": format!("#/definitions/{type_path}") })
+        }
+        _ => reflect_type_schema(type_info, refs, registry),
+    }
+}
+
+fn reflect_type_schema(
+    type_info: &TypeInfo,
+    refs: &mut JsonMap<String, JsonValue>,
+    registry: &TypeRegistry,
+) -> JsonValue {
+    match type_info {
+        TypeInfo::Struct(info) => {
+            let mut properties = JsonMap::new();
+            let mut required = Vec::new();
+
+            for field in info.iter() {
+                if !registry
+                    .get_type_info(field.type_id())
+                    .is_some_and(is_option)
+                {
+                    required.push(field.name());
+                }
+
+                properties.insert(
+                    field.name().to_string(),
+                    reflect_field_schema(field.type_id(), refs, registry),
+                );
+            }
+
+            json!({
+                "type": "object",
+                "required": required,
+                "properties": properties,
+            })
+        }
+        TypeInfo::TupleStruct(info) if info.field_len() == 1 => {
+            reflect_field_schema(info.field_at(0).unwrap().type_id(), refs, registry)
+        }
+        TypeInfo::TupleStruct(info) => {
+            let items = info
+                .iter()
+                .map(|field| reflect_field_schema(field.type_id(), refs, registry))
+                .collect::<Vec<_>>();
+
+            json!({
+                "type": "array",
+                "items": items,
+                "minItems": items.len(),
+                "maxItems": items.len(),
+            })
+        }
+        TypeInfo::Tuple(info) => {
+            let items = info
+                .iter()
+                .map(|field| reflect_field_schema(field.type_id(), refs, registry))
+                .collect::<Vec<_>>();
+
+            json!({
+                "type": "array",
+                "items": items,
+                "minItems": items.len(),
+                "maxItems": items.len(),
+            })
+        }
+        TypeInfo::List(info) => json!({
+            "type": "array",
+            "items": reflect_field_schema(info.item_ty().id(), refs, registry),
+        }),
+        TypeInfo::Array(info) => json!({
+            "type": "array",
+            "items": reflect_field_schema(info.item_ty().id(), refs, registry),
+            "minItems": info.capacity(),
+            "maxItems": info.capacity(),
+        }),
+        TypeInfo::Set(info) => json!({
+            "type": "array",
+            "items": reflect_field_schema(info.value_ty().id(), refs, registry),
+            "uniqueItems": true,
+        }),
+        TypeInfo::Map(info) => json!({
+            "type": "object",
+            "additionalProperties": reflect_field_schema(info.value_ty().id(), refs, registry),
+        }),
+        TypeInfo::Enum(info) if is_option(type_info) => {
+            let some = info
+                .variant("Some")
+                .and_then(|variant| variant.as_tuple_variant().ok())
+                .and_then(|variant| variant.field_at(0))
+                .map_or_else(
+                    || json!({}),
+                    |field| reflect_field_schema(field.type_id(), refs, registry),
+                );
+
+            json!({
+                "anyOf": [
+                    { "type": "null" },
+                    some
+                ],
+                "$comment": "optional value"
+            })
+        }
+        TypeInfo::Enum(info) => {
+            let mut one_of = Vec::new();
+            let mut unit_variants = Vec::new();
+
+            for variant in info.iter() {
+                let value = match variant {
+                    VariantInfo::Unit(variant) => {
+                        unit_variants.push(variant.name());
+                        continue;
+                    }
+                    VariantInfo::Tuple(variant) if variant.field_len() == 1 => {
+                        reflect_field_schema(variant.field_at(0).unwrap().type_id(), refs, registry)
+                    }
+                    VariantInfo::Tuple(variant) => {
+                        let items = variant
+                            .iter()
+                            .map(|field| reflect_field_schema(field.type_id(), refs, registry))
+                            .collect::<Vec<_>>();
+
+                        json!({
+                            "type": "array",
+                            "items": items,
+                            "minItems": items.len(),
+                            "maxItems": items.len(),
+                        })
+                    }
+                    VariantInfo::Struct(variant) => {
+                        let mut properties = JsonMap::new();
+
+                        for field in variant.iter() {
+                            properties.insert(
+                                field.name().to_string(),
+                                reflect_field_schema(field.type_id(), refs, registry),
+                            );
+                        }
+
+                        json!({
+                            "type": "object",
+                            "properties": properties,
+                        })
+                    }
+                };
+
+                one_of.push(json!({
+                    "type": "object",
+                    "required": [variant.name()],
+                    "properties": { variant.name(): value },
+                    "additionalProperties": false,
+                }));
+            }
+
+            if !unit_variants.is_empty() {
+                one_of.push(json!({ "type": "string", "enum": unit_variants }));
+            }
+
+            json!({ "oneOf": one_of })
+        }
+        TypeInfo::Opaque(info) => primitive_schema(info.type_id(), refs)
+            .unwrap_or_else(|| json!({ "$comment": format!("opaque type {}", info.type_path()) })),
+    }
+}
+
+fn is_option(type_info: &TypeInfo) -> bool {
+    matches!(type_info, TypeInfo::Enum(_))
+        && type_info.type_path_table().module_path() == Some("core::option")
+        && type_info.type_path_table().ident() == Some("Option")
+}
+
+/// Returns the reference to the [`JsonSchema`] of a primitive type.
+fn primitive_schema(type_id: TypeId, refs: &mut JsonMap<String, JsonValue>) -> Option<JsonValue> {
+    macro_rules! primitive_schema {
+        ($($t:ty),+) => {
+            $(
+                if type_id == TypeId::of::<$t>() {
+                    <$t as JsonSchema>::register_schema(refs);
+                    return Some(json!({ "$ref": <$t as JsonSchema>::schema_ref() }));
+                }
+            )+
+        };
+    }
+
+    primitive_schema!(
+        bool, char, u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32, f64, String
+    );
+
+    None
+}
+
+/// Fills the `"default"` values of the registered schemas from the types reflected [`Default`].
+///
+/// Struct schemas get one default per property, other schemas get a top-level default if they
+/// don't already declare one.
+pub(crate) fn apply_reflect_defaults(
+    refs: &mut JsonMap<String, JsonValue>,
+    registry: &TypeRegistry,
+) {
+    for (title, schema) in refs.iter_mut() {
+        let Some(reflect_default) = registry
+            .get_with_type_path(title)
+            .and_then(|registration| registration.data::<ReflectDefault>())
+        else {
+            continue;
+        };
+
+        let default = reflect_default.default();
+
+        if let ReflectRef::Struct(default) = default.reflect_ref() {
+            let Some(properties) = schema
+                .get_mut("properties")
+                .and_then(JsonValue::as_object_mut)
+            else {
+                continue;
+            };
+
+            for (i, field) in default.iter_fields().enumerate() {
+                let Some(property) = default
+                    .name_at(i)
+                    .and_then(|name| properties.get_mut(name))
+                    .and_then(JsonValue::as_object_mut)
+                else {
+                    continue;
+                };
+
+                // Defaults of `#[serde(default = "path")]` fields are set by the derive.
+                if !property.contains_key("default")
+                    && let Some(value) = reflect_default_value(field, registry)
+                {
+                    property.insert("default".to_string(), value);
+                }
+            }
+        } else if let Some(schema) = schema.as_object_mut()
+            && !schema.contains_key("default")
+            && let Some(value) = reflect_default_value(default.as_partial_reflect(), registry)
+        {
+            schema.insert("default".to_string(), value);
+        }
+    }
+}
+
+fn reflect_default_value(value: &dyn PartialReflect, registry: &TypeRegistry) -> Option<JsonValue> {
+    // A default handle doesn't point to any asset path, so there is nothing meaningful to emit.
+    if value
+        .get_represented_type_info()
+        .is_some_and(|type_info| is_handle_type(type_info.type_path_table()))
+    {
+        return None;
+    }
+
+    serde_json::to_value(TypedReflectSerializer::new(value, registry)).ok()
+}
+
+/// Serializes the value of a `#[serde(default = "path")]` field for the derived schemas.
+#[doc(hidden)]
+pub fn schema_default_value<T: Reflect + GetTypeRegistration>(value: T) -> Option<JsonValue> {
+    let mut registry = TypeRegistry::new();
+    registry.register::<T>();
+
+    reflect_default_value(value.as_partial_reflect(), &registry)
 }
 
 macro_rules! impl_schema_for_int {
@@ -28,6 +636,14 @@ macro_rules! impl_schema_for_int {
                     "format": stringify!($t)
                 })
             }
+
+            fn property_names_schema(_refs: &mut JsonMap<String, JsonValue>) -> JsonValue {
+                json!({
+                    "type": "string",
+                    "pattern": "^-?[0-9]+$",
+                    "$comment": $comment,
+                })
+            }
         }
     };
     ($({$t:ty, $comment:literal}),+) => {
@@ -177,12 +793,7 @@ macro_rules! impl_schema_for_vec {
     ($ty:ty, $scalar:ty, $arity:literal, $name:literal, $comment:literal) => {
         impl JsonSchema for $ty {
             fn json_schema(refs: &mut JsonMap<String, JsonValue>) -> JsonValue {
-                let scalar_title = <$scalar as JsonSchema>::schema_title();
-
-                if !refs.contains_key(&scalar_title) {
-                    let scalar_schema = <$scalar as JsonSchema>::json_schema(refs);
-                    refs.insert(scalar_title, scalar_schema);
-                }
+                <$scalar as JsonSchema>::register_schema(refs);
 
                 json!({
                     "type": "array",
@@ -249,24 +860,58 @@ impl_schema_for_vec!(
     {::bevy::math::Dir3A, f32, 3, "Direction3d", "3D direction vector of f32"}
 );
 
+macro_rules! impl_schema_for_input {
+    ($ty:ty, $name:literal, $comment:literal) => {
+        impl JsonSchema for $ty {
+            fn json_schema(_refs: &mut JsonMap<String, JsonValue>) -> JsonValue {
+                // Only the unit variants are listed, e.g. `"KeyA"`, the variants holding a
+                // platform specific code are still accepted by the deserializer.
+                let variants = match <$ty as bevy::reflect::Typed>::type_info() {
+                    bevy::reflect::TypeInfo::Enum(info) => info
+                        .iter()
+                        .filter(|variant| matches!(variant, bevy::reflect::VariantInfo::Unit(_)))
+                        .map(bevy::reflect::VariantInfo::name)
+                        .collect::<Vec<_>>(),
+                    _ => Vec::new(),
+                };
+
+                json!({
+                    "type": "string",
+                    "enum": variants,
+                    "$comment": $comment,
+                })
+            }
+
+            fn schema_title() -> String {
+                $name.to_string()
+            }
+        }
+    };
+    ($({$ty:ty, $name:literal, $comment:literal}),+) => {
+        $(
+            impl_schema_for_input!($ty, $name, $comment);
+        )+
+    }
+}
+
+impl_schema_for_input!(
+    {::bevy::input::keyboard::KeyCode, "KeyCode", "physical key of a keyboard"},
+    {::bevy::input::mouse::MouseButton, "MouseButton", "button of a mouse"},
+    {::bevy::input::gamepad::GamepadButton, "GamepadButton", "button of a gamepad"}
+);
+
 impl<T: JsonSchema> JsonSchema for Option<T>
 where
     Option<T>: TypePath,
 {
     fn json_schema(refs: &mut JsonMap<String, JsonValue>) -> JsonValue {
-        let t_title = <T as JsonSchema>::schema_title();
-
-        if !refs.contains_key(&t_title) {
-            let t_schema = <T as JsonSchema>::json_schema(refs);
-            refs.insert(t_title, t_schema);
-        }
+        <T as JsonSchema>::register_schema(refs);
 
         json!({
-            "type": [
-                "object",
-                "null"
+            "anyOf": [
+                { "type": "null" },
+                { "$ref": <T as JsonSchema>::schema_ref() }
             ],
-            "$ref": <T as JsonSchema>::schema_ref(),
             "$comment": "optional value"
         })
     }
@@ -281,12 +926,7 @@ where
     Vec<T>: TypePath,
 {
     fn json_schema(refs: &mut JsonMap<String, JsonValue>) -> JsonValue {
-        let t_title = <T as JsonSchema>::schema_title();
-
-        if !refs.contains_key(&t_title) {
-            let t_schema = <T as JsonSchema>::json_schema(refs);
-            refs.insert(t_title, t_schema);
-        }
+        <T as JsonSchema>::register_schema(refs);
 
         json!({
             "type": "array",
@@ -299,17 +939,65 @@ where
     }
 }
 
+macro_rules! impl_schema_for_map {
+    ($($map:ident)::+) => {
+        impl<K: JsonSchema, V: JsonSchema, S> JsonSchema for $($map)::+<K, V, S>
+        where
+            $($map)::+<K, V, S>: TypePath,
+        {
+            fn json_schema(refs: &mut JsonMap<String, JsonValue>) -> JsonValue {
+                <V as JsonSchema>::register_schema(refs);
+
+                json!({
+                    "type": "object",
+                    "propertyNames": <K as JsonSchema>::property_names_schema(refs),
+                    "additionalProperties": { "$ref": <V as JsonSchema>::schema_ref() },
+                })
+            }
+
+            fn schema_title() -> String {
+                format!(
+                    "HashMap<{}, {}>",
+                    <K as JsonSchema>::schema_title(),
+                    <V as JsonSchema>::schema_title()
+                )
+            }
+        }
+    };
+}
+
+impl_schema_for_map!(std::collections::HashMap);
+impl_schema_for_map!(bevy::platform::collections::HashMap);
+
+impl<K: JsonSchema, V: JsonSchema> JsonSchema for std::collections::BTreeMap<K, V>
+where
+    std::collections::BTreeMap<K, V>: TypePath,
+{
+    fn json_schema(refs: &mut JsonMap<String, JsonValue>) -> JsonValue {
+        <V as JsonSchema>::register_schema(refs);
+
+        json!({
+            "type": "object",
+            "propertyNames": <K as JsonSchema>::property_names_schema(refs),
+            "additionalProperties": { "$ref": <V as JsonSchema>::schema_ref() },
+        })
+    }
+
+    fn schema_title() -> String {
+        format!(
+            "BTreeMap<{}, {}>",
+            <K as JsonSchema>::schema_title(),
+            <V as JsonSchema>::schema_title()
+        )
+    }
+}
+
 impl<T: JsonSchema, const N: usize> JsonSchema for [T; N]
 where
     [T; N]: TypePath,
 {
     fn json_schema(refs: &mut JsonMap<String, JsonValue>) -> JsonValue {
-        let t_title = <T as JsonSchema>::schema_title();
-
-        if !refs.contains_key(&t_title) {
-            let t_schema = <T as JsonSchema>::json_schema(refs);
-            refs.insert(t_title, t_schema);
-        }
+        <T as JsonSchema>::register_schema(refs);
 
         json!({
             "type": "array",
@@ -325,12 +1013,7 @@ macro_rules! impl_schema_for_tuple {
         impl<$($T: JsonSchema),*> JsonSchema for ($($T,)*) where ($($T,)*): TypePath {
             fn json_schema(refs: &mut JsonMap<String, JsonValue>) -> JsonValue {
                 $(
-                    let t_title = <$T as JsonSchema>::schema_title();
-
-                    if !refs.contains_key(&t_title) {
-                        let t_schema = <$T as JsonSchema>::json_schema(refs);
-                        refs.insert(t_title, t_schema);
-                    }
+                    <$T as JsonSchema>::register_schema(refs);
                 )*
 
                 json!({
@@ -0,0 +1,241 @@
+use core::any::TypeId;
+use std::sync::Arc;
+
+use arc_swap::ArcSwap;
+use bevy::{platform::collections::HashMap, prelude::*};
+
+use crate::{
+    Prototype, PrototypeData, PrototypeId, PrototypeRegistries, PrototypeVersion, TagQuery,
+};
+
+/// Lock-free read access to the registries.
+///
+/// Readers take a [`RegistriesSnapshot`] without borrowing the registries, so they
+/// never wait on the systems writing prototypes with [`RegMut`](crate::RegMut). A new snapshot
+/// is published at the end of each frame the registries changed, in [`Last`], it copies the
+/// registries written to during the frame and shares the others with the previous snapshot.
+///
+/// The snapshots are one frame behind the registries: the prototypes loaded, reloaded or
+/// written during a frame are only in the snapshots taken from the next one, read the
+/// prototypes with [`Reg`](crate::Reg) where they must be up to date.
+///
+/// Only the snapshots are lock-free, [`Reg`](crate::Reg) and [`RegMut`](crate::RegMut) still
+/// borrow the registries resource, use the snapshots in the systems that only read prototypes,
+/// they have the same read methods as [`Reg`](crate::Reg).
+///
+/// The resource can be cloned to read the prototypes from other threads.
+#[derive(Resource, Clone, Default)]
+pub struct SharedPrototypes(Arc<ArcSwap<SharedRegistries>>);
+
+/// The published registries, each shared by the snapshots published since it last changed.
+#[derive(Default)]
+struct SharedRegistries {
+    /// The revision and a copy of each registry, alone in its [`PrototypeRegistries`].
+    registries: HashMap<TypeId, (u64, Arc<PrototypeRegistries>)>,
+    frozen: bool,
+}
+
+impl SharedPrototypes {
+    /// Returns the latest published snapshot of the registries.
+    pub fn snapshot(&self) -> RegistriesSnapshot {
+        RegistriesSnapshot(self.0.load_full())
+    }
+}
+
+/// The registries as published at the end of a frame, see [`SharedPrototypes`].
+#[derive(Clone)]
+pub struct RegistriesSnapshot(Arc<SharedRegistries>);
+
+impl RegistriesSnapshot {
+    fn registry<P: PrototypeData>(&self) -> Option<&PrototypeRegistries> {
+        self.0
+            .registries
+            .get(&TypeId::of::<P>())
+            .map(|(_, registry)| &**registry)
+    }
+
+    /// Get a prototype instance with it's [`PrototypeId`]
+    pub fn get<P: PrototypeData>(&self, id: impl Into<PrototypeId<P>>) -> Option<&Prototype<P>> {
+        self.registry::<P>()?.get(&id.into())
+    }
+
+    /// Get the [`PrototypeVersion`] of a prototype with it's [`PrototypeId`]
+    pub fn version<P: PrototypeData>(
+        &self,
+        id: impl Into<PrototypeId<P>>,
+    ) -> Option<PrototypeVersion> {
+        self.registry::<P>()?.version(&id.into())
+    }
+
+    /// Iterates over the [`Prototype`] instances in their storage order
+    pub fn iter<P: PrototypeData>(&self) -> impl Iterator<Item = &Prototype<P>> {
+        self.as_slice().iter()
+    }
+
+    /// Returns the [`Prototype`] instances in their storage order, stored contiguously, see
+    /// [`Reg::as_slice`](crate::Reg::as_slice)
+    pub fn as_slice<P: PrototypeData>(&self) -> &[Prototype<P>] {
+        self.registry::<P>()
+            .map(PrototypeRegistries::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Iterates over the [`Prototype`] instances tagged with `tag` in their storage order,
+    /// without going over the other prototypes
+    pub fn iter_with_tag<'a, P: PrototypeData>(
+        &'a self,
+        tag: &str,
+    ) -> impl Iterator<Item = &'a Prototype<P>> + use<'a, P> {
+        self.registry::<P>()
+            .map(|registry| registry.iter_with_tag(tag))
+            .into_iter()
+            .flatten()
+    }
+
+    /// Iterates over the [`Prototype`] instances whose tags match the [`TagQuery`], resolved
+    /// against the tag index without going over the other prototypes
+    pub fn iter_with_tags<'a, P: PrototypeData>(
+        &'a self,
+        query: &TagQuery,
+    ) -> impl Iterator<Item = &'a Prototype<P>> + use<'a, P> {
+        self.registry::<P>()
+            .map(|registry| registry.iter_with_tags(query))
+            .into_iter()
+            .flatten()
+    }
+
+    /// Iterates over the names of the prototypes in their storage order
+    #[cfg(not(feature = "strip_names"))]
+    pub fn names<P: PrototypeData>(&self) -> impl Iterator<Item = &str> {
+        self.iter::<P>().map(Prototype::name)
+    }
+
+    /// Returns `true` if the snapshot was taken once the prototypes were frozen, see
+    /// [`freeze_prototypes`](crate::freeze_prototypes), it's then the last snapshot published
+    /// and readers can keep it instead of taking new ones.
+    pub fn is_frozen(&self) -> bool {
+        self.0.frozen
+    }
+}
+
+impl core::fmt::Debug for RegistriesSnapshot {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("RegistriesSnapshot").finish()
+    }
+}
+
+pub(crate) fn publish_shared_prototypes(
+    registries: Res<PrototypeRegistries>,
+    shared: Res<SharedPrototypes>,
+) {
+    if !registries.is_changed() {
+        return;
+    }
+
+    let published = shared.0.load();
+
+    let registries = SharedRegistries {
+        registries: registries
+            .revisions()
+            .map(|(type_id, revision)| {
+                let registry = match published.registries.get(&type_id) {
+                    Some((published, registry)) if *published == revision => registry.clone(),
+                    _ => Arc::new(registries.clone_registry(&type_id)),
+                };
+
+                (type_id, (revision, registry))
+            })
+            .collect(),
+        frozen: registries.is_frozen(),
+    };
+
+    shared.0.store(Arc::new(registries));
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+    use crate::JsonSchema;
+
+    #[derive(Debug, Clone, Default, Reflect, crate::Prototype, JsonSchema)]
+    #[proto(name = "card")]
+    struct Card {
+        cost: u32,
+    }
+
+    #[derive(Debug, Clone, Default, Reflect, crate::Prototype, JsonSchema)]
+    #[proto(name = "relic")]
+    struct Relic {
+        weight: u32,
+    }
+
+    fn publish(world: &mut World) -> RegistriesSnapshot {
+        world.run_system_once(publish_shared_prototypes).unwrap();
+        world.resource::<SharedPrototypes>().snapshot()
+    }
+
+    fn world() -> World {
+        let mut world = World::new();
+        let mut registries = PrototypeRegistries::default();
+        registries.new_registry::<Card>();
+        registries.new_registry::<Relic>();
+        registries.insert(Prototype::for_test(
+            "goblin",
+            &["creature"],
+            Card::default(),
+        ));
+        registries.insert(Prototype::for_test("fireball", &["spell"], Card::default()));
+        registries.insert(Prototype::for_test("amulet", &[], Relic::default()));
+
+        world.insert_resource(registries);
+        world.init_resource::<SharedPrototypes>();
+        world
+    }
+
+    #[test]
+    fn snapshots_have_the_published_prototypes() {
+        let mut world = world();
+        let snapshot = publish(&mut world);
+
+        let goblin = PrototypeId::<Card>::from_name("goblin");
+        assert!(snapshot.get(goblin).is_some());
+        assert_eq!(
+            snapshot.version(goblin),
+            world.resource::<PrototypeRegistries>().version(&goblin)
+        );
+        assert_eq!(snapshot.iter::<Card>().count(), 2);
+        assert_eq!(snapshot.as_slice::<Relic>().len(), 1);
+
+        let spells = snapshot
+            .iter_with_tag::<Card>("spell")
+            .map(|card| *card.id())
+            .collect::<Vec<_>>();
+        assert_eq!(spells, [PrototypeId::from_name("fireball")]);
+    }
+
+    #[test]
+    fn snapshots_only_copy_the_changed_registries() {
+        let mut world = world();
+        let before = publish(&mut world);
+
+        world
+            .resource_mut::<PrototypeRegistries>()
+            .insert(Prototype::for_test("troll", &["creature"], Card::default()));
+        let after = publish(&mut world);
+
+        let troll = PrototypeId::<Card>::from_name("troll");
+        assert!(before.get(troll).is_none());
+        assert!(after.get(troll).is_some());
+        assert_eq!(after.iter_with_tag::<Card>("creature").count(), 2);
+        assert!(core::ptr::eq(
+            before.as_slice::<Relic>(),
+            after.as_slice::<Relic>()
+        ));
+        assert!(!core::ptr::eq(
+            before.as_slice::<Card>(),
+            after.as_slice::<Card>()
+        ));
+    }
+}
@@ -0,0 +1,296 @@
+use core::marker::PhantomData;
+
+use bevy::{
+    ecs::{
+        entity_disabling::Disabled,
+        system::{EntityCommands, SystemParam},
+    },
+    platform::collections::{HashMap, HashSet},
+    prelude::*,
+};
+
+use crate::{
+    ErasedPrototypeId, Prototype, PrototypeData, PrototypeId, PrototypeIdMap, RegistryEvent,
+    registry::PrototypeRegistries,
+};
+
+/// Prototypes whose instances can be spawned as entities, see [`PrototypeCommandsExt`].
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_histrion_proto::prelude::*;
+/// # #[derive(Debug, Clone, Default, Reflect, JsonSchema, Prototype)]
+/// # #[proto(name = "sword")]
+/// # struct Sword { damage: f32 }
+/// # #[derive(Component)]
+/// # struct Damage(f32);
+/// impl SpawnablePrototype for Sword {
+///     fn apply_to(prototype: &Prototype<Self>, entity: &mut EntityWorldMut) {
+///         entity.insert(Damage(prototype.damage));
+///     }
+/// }
+/// ```
+pub trait SpawnablePrototype: PrototypeData {
+    /// Inserts the components of `prototype` into `entity`.
+    ///
+    /// Also called again on the spawned entities when the prototype is modified or reloaded,
+    /// components that only hold the state of the entity shouldn't be reset here.
+    fn apply_to(prototype: &Prototype<Self>, entity: &mut EntityWorldMut);
+
+    /// Resets `entity`, taken back from the [`PrototypePool`] of `prototype` to be spawned again.
+    ///
+    /// Defaults to [`apply_to`](Self::apply_to), override it to also reset the components
+    /// holding the state of the entity, e.g. the lifetime of a projectile.
+    fn reset(prototype: &Prototype<Self>, entity: &mut EntityWorldMut) {
+        Self::apply_to(prototype, entity);
+    }
+}
+
+/// Prototypes that are components themselves, e.g. stat blocks, inserted as is on the entities
+/// spawned from them.
+///
+/// Implemented with `#[derive(PrototypeComponent)]`, which also implements [`SpawnablePrototype`]
+/// by cloning the data of the prototype, see [`PrototypeEntityCommandsExt::insert_prototype_data`].
+pub trait PrototypeComponent: SpawnablePrototype + Component {}
+
+/// The prototype an entity was spawned from, inserted by [`PrototypeCommandsExt::spawn_prototype`]
+/// and [`PrototypeEntityCommandsExt::apply_prototype`].
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SpawnedFromPrototype(pub ErasedPrototypeId);
+
+/// The type of the prototype an entity was spawned from, prototypes of different types may have
+/// the same id.
+#[derive(Component)]
+pub(crate) struct SpawnedPrototypeType<P: PrototypeData>(PhantomData<P>);
+
+pub trait PrototypeCommandsExt {
+    /// Spawns an entity with the components of the `id` prototype, see [`SpawnablePrototype`].
+    fn spawn_prototype<P: SpawnablePrototype>(
+        &mut self,
+        id: impl Into<PrototypeId<P>>,
+    ) -> EntityCommands<'_>;
+}
+
+impl PrototypeCommandsExt for Commands<'_, '_> {
+    fn spawn_prototype<P: SpawnablePrototype>(
+        &mut self,
+        id: impl Into<PrototypeId<P>>,
+    ) -> EntityCommands<'_> {
+        let mut entity = self.spawn_empty();
+        entity.apply_prototype(id);
+        entity
+    }
+}
+
+pub trait PrototypeEntityCommandsExt {
+    /// Inserts the components of the `id` prototype into the entity, see [`SpawnablePrototype`].
+    fn apply_prototype<P: SpawnablePrototype>(
+        &mut self,
+        id: impl Into<PrototypeId<P>>,
+    ) -> &mut Self;
+
+    /// Inserts a clone of the data of the `id` prototype into the entity, see
+    /// [`PrototypeComponent`].
+    fn insert_prototype_data<P: PrototypeComponent>(
+        &mut self,
+        id: impl Into<PrototypeId<P>>,
+    ) -> &mut Self;
+}
+
+impl PrototypeEntityCommandsExt for EntityCommands<'_> {
+    fn apply_prototype<P: SpawnablePrototype>(
+        &mut self,
+        id: impl Into<PrototypeId<P>>,
+    ) -> &mut Self {
+        let id = id.into();
+
+        self.queue(move |mut entity: EntityWorldMut| apply_prototype(id, &mut entity))
+    }
+
+    fn insert_prototype_data<P: PrototypeComponent>(
+        &mut self,
+        id: impl Into<PrototypeId<P>>,
+    ) -> &mut Self {
+        self.apply_prototype(id)
+    }
+}
+
+fn apply_prototype<P: SpawnablePrototype>(id: PrototypeId<P>, entity: &mut EntityWorldMut) {
+    apply_prototype_with(id, entity, P::apply_to);
+}
+
+fn apply_prototype_with<P: SpawnablePrototype>(
+    id: PrototypeId<P>,
+    entity: &mut EntityWorldMut,
+    apply: fn(&Prototype<P>, &mut EntityWorldMut),
+) {
+    // Cloned so the applier can read the other prototypes it references.
+    let Some(prototype) = entity
+        .world()
+        .resource::<PrototypeRegistries>()
+        .get(&id)
+        .cloned()
+    else {
+        error!(
+            "Attempted to apply unknown prototype {id:?} of registry {}",
+            P::prototype_name()
+        );
+        return;
+    };
+
+    entity.insert((
+        SpawnedFromPrototype(id.into()),
+        SpawnedPrototypeType::<P>(PhantomData),
+    ));
+
+    apply(&prototype, entity);
+}
+
+/// The entities released to the [`PrototypePool`] of the `P` prototypes, by prototype.
+#[derive(Resource)]
+pub(crate) struct PooledEntities<P: SpawnablePrototype> {
+    free: PrototypeIdMap<ErasedPrototypeId, Vec<Entity>>,
+    _marker: PhantomData<P>,
+}
+
+impl<P: SpawnablePrototype> Default for PooledEntities<P> {
+    fn default() -> Self {
+        Self {
+            free: HashMap::default(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// Recycles the entities spawned from the `P` prototypes instead of spawning and despawning
+/// them, e.g. for projectiles spawned every frame.
+///
+/// Released entities are [`Disabled`] until they are spawned again from the same prototype,
+/// they are then reset with [`SpawnablePrototype::reset`].
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_histrion_proto::{PrototypePool, prelude::*};
+/// # #[derive(Debug, Clone, Default, Reflect, JsonSchema, Prototype)]
+/// # #[proto(name = "projectile")]
+/// # struct Projectile { speed: f32 }
+/// # impl SpawnablePrototype for Projectile {
+/// #     fn apply_to(_: &Prototype<Self>, _: &mut EntityWorldMut) {}
+/// # }
+/// # #[derive(Component)]
+/// # struct Hit;
+/// fn fire(mut pool: PrototypePool<Projectile>) {
+///     pool.spawn("arrow").insert(Transform::from_xyz(0.0, 1.0, 0.0));
+/// }
+///
+/// fn expire(mut pool: PrototypePool<Projectile>, hits: Query<Entity, With<Hit>>) {
+///     for entity in &hits {
+///         pool.release(entity);
+///     }
+/// }
+/// ```
+#[derive(SystemParam)]
+pub struct PrototypePool<'w, 's, P: SpawnablePrototype> {
+    commands: Commands<'w, 's>,
+    pooled: ResMut<'w, PooledEntities<P>>,
+}
+
+impl<P: SpawnablePrototype> PrototypePool<'_, '_, P> {
+    /// Spawns an entity with the components of the `id` prototype, reusing an entity released
+    /// from the same prototype if any, see [`PrototypeCommandsExt::spawn_prototype`].
+    pub fn spawn(&mut self, id: impl Into<PrototypeId<P>>) -> EntityCommands<'_> {
+        let id = id.into();
+
+        // Released entities may have been despawned since.
+        while let Some(entity) = self
+            .pooled
+            .free
+            .get_mut(&ErasedPrototypeId::from(id))
+            .and_then(Vec::pop)
+        {
+            if self.commands.get_entity(entity).is_err() {
+                continue;
+            }
+
+            let mut entity = self.commands.entity(entity);
+            entity.queue(move |mut entity: EntityWorldMut| {
+                entity.remove::<Disabled>();
+                apply_prototype_with(id, &mut entity, P::reset);
+            });
+
+            return entity;
+        }
+
+        self.commands.spawn_prototype(id)
+    }
+
+    /// Releases an entity spawned from a `P` prototype to the pool, it's disabled until it's
+    /// reused by [`spawn`](Self::spawn).
+    pub fn release(&mut self, entity: Entity) {
+        self.commands
+            .entity(entity)
+            .queue(|mut entity: EntityWorldMut| {
+                let Some(&SpawnedFromPrototype(id)) = entity
+                    .get::<SpawnedFromPrototype>()
+                    .filter(|_| entity.contains::<SpawnedPrototypeType<P>>())
+                else {
+                    error!(
+                        "Attempted to release entity {} to the pool of registry {}, it wasn't spawned from one of its prototypes",
+                        entity.id(),
+                        P::prototype_name()
+                    );
+                    return;
+                };
+
+                if entity.contains::<Disabled>() {
+                    return;
+                }
+
+                let entity_id = entity.id();
+                entity.insert(Disabled);
+                entity.world_scope(|world| {
+                    world
+                        .resource_mut::<PooledEntities<P>>()
+                        .free
+                        .entry(id)
+                        .or_default()
+                        .push(entity_id);
+                });
+            });
+    }
+
+    /// Returns the number of released entities of the `id` prototype waiting to be reused.
+    pub fn available(&self, id: impl Into<PrototypeId<P>>) -> usize {
+        self.pooled
+            .free
+            .get(&ErasedPrototypeId::from(id.into()))
+            .map_or(0, Vec::len)
+    }
+}
+
+/// Applies the modified `P` prototypes again on the entities spawned from them.
+pub(crate) fn refresh_spawned_prototypes<P: SpawnablePrototype>(
+    mut events_rx: EventReader<RegistryEvent<P>>,
+    spawned: Query<(Entity, &SpawnedFromPrototype), With<SpawnedPrototypeType<P>>>,
+    mut commands: Commands,
+) {
+    let modified = events_rx
+        .read()
+        .filter_map(|event| match event {
+            RegistryEvent::Modified(id) => Some(ErasedPrototypeId::from(*id)),
+            RegistryEvent::Added(_) | RegistryEvent::Removed(_) => None,
+        })
+        .collect::<HashSet<_>>();
+
+    if modified.is_empty() {
+        return;
+    }
+
+    for (entity, spawned_from) in &spawned {
+        if modified.contains(&spawned_from.0) {
+            commands
+                .entity(entity)
+                .apply_prototype(PrototypeId::<P>::from(spawned_from.0));
+        }
+    }
+}
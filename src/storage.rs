@@ -0,0 +1,338 @@
+use core::{alloc::Layout, any::Any, ptr::NonNull};
+
+use bevy::prelude::*;
+use bumpalo::Bump;
+
+use crate::{Prototype, PrototypeData};
+
+/// The prototypes of a registry, stored contiguously by slot in a bump arena instead of boxed
+/// one by one, the storage of the `P` registry is a [`PrototypeArena<P>`].
+///
+/// Iterating over the slots visits the prototypes in memory order, their storage order: the
+/// insertion order, except that a removal moves the last prototype into the removed slot. The
+/// slots of the other prototypes are stable, a removal is `O(1)`.
+pub(crate) trait PrototypeStorage: Send + Sync + 'static {
+    fn len(&self) -> usize;
+
+    fn get(&self, slot: usize) -> Option<&dyn Reflect>;
+
+    fn get_mut(&mut self, slot: usize) -> Option<&mut dyn Reflect>;
+
+    /// Returns the tags of the prototype of `slot`, none if the slot is empty.
+    fn tags(&self, slot: usize) -> &[String];
+
+    /// Appends a prototype in a new slot, returns it back if it isn't a prototype of the
+    /// registry.
+    fn push(&mut self, proto: Box<dyn Reflect>) -> Result<(), Box<dyn Reflect>>;
+
+    /// Replaces the prototype of `slot` in place, returns it back if it isn't a prototype of
+    /// the registry.
+    fn replace(&mut self, slot: usize, proto: Box<dyn Reflect>) -> Result<(), Box<dyn Reflect>>;
+
+    /// Removes the prototype of `slot`, moving the last prototype into it.
+    fn swap_remove(&mut self, slot: usize) -> Box<dyn Reflect>;
+
+    /// Rebuilds the arena if growing it left previous copies of the prototypes behind, see
+    /// [`PrototypeArena::rebuild`].
+    fn rebuild(&mut self);
+
+    fn clone_storage(&self) -> Box<dyn PrototypeStorage>;
+
+    fn as_any(&self) -> &dyn Any;
+
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+/// The prototypes of the `P` registry, allocated contiguously in a [`Bump`] arena.
+///
+/// The prototypes grow like a [`Vec`], each growth moving them into a larger block of the
+/// arena, the previous blocks staying allocated until the arena is rebuilt. The registries
+/// rebuild it once the prototypes of a load or a reload are written, the prototypes are then
+/// moved into a new arena sized for them and the previous one is freed at once.
+pub(crate) struct PrototypeArena<P: PrototypeData> {
+    arena: Bump,
+    /// The `capacity` slots allocated in `arena`, the first `len` of them initialized.
+    slots: NonNull<Prototype<P>>,
+    len: usize,
+    capacity: usize,
+    /// Slots of the blocks left behind by the growths since the arena was rebuilt.
+    abandoned: usize,
+}
+
+// The arena is only allocated from through `&mut self`, it's never shared between threads.
+unsafe impl<P: PrototypeData> Send for PrototypeArena<P> {}
+unsafe impl<P: PrototypeData> Sync for PrototypeArena<P> {}
+
+impl<P: PrototypeData> PrototypeArena<P> {
+    pub(crate) fn new() -> Self {
+        Self {
+            arena: Bump::new(),
+            slots: NonNull::dangling(),
+            len: 0,
+            capacity: 0,
+            abandoned: 0,
+        }
+    }
+
+    pub(crate) fn as_slice(&self) -> &[Prototype<P>] {
+        // The first `len` slots are initialized.
+        unsafe { core::slice::from_raw_parts(self.slots.as_ptr(), self.len) }
+    }
+
+    pub(crate) fn as_mut_slice(&mut self) -> &mut [Prototype<P>] {
+        // The first `len` slots are initialized.
+        unsafe { core::slice::from_raw_parts_mut(self.slots.as_ptr(), self.len) }
+    }
+
+    pub(crate) fn push(&mut self, proto: Prototype<P>) {
+        if self.len == self.capacity {
+            self.abandoned += self.capacity;
+            self.move_into((self.capacity * 2).max(4));
+        }
+
+        // The slot is allocated and not initialized.
+        unsafe { self.slots.as_ptr().add(self.len).write(proto) };
+        self.len += 1;
+    }
+
+    pub(crate) fn swap_remove(&mut self, slot: usize) -> Prototype<P> {
+        assert!(
+            slot < self.len,
+            "slot {slot} out of {} prototypes",
+            self.len
+        );
+
+        let last = self.len - 1;
+        self.as_mut_slice().swap(slot, last);
+        self.len = last;
+
+        // The last slot isn't part of the initialized slots anymore, its prototype is moved out.
+        unsafe { self.slots.as_ptr().add(self.len).read() }
+    }
+
+    /// Returns the number of bytes allocated by the arena, the prototypes and the blocks they
+    /// were moved out of.
+    #[cfg(test)]
+    pub(crate) fn allocated_bytes(&self) -> usize {
+        self.arena.allocated_bytes()
+    }
+
+    /// Moves the prototypes into a new arena sized for them and frees the previous one, if
+    /// growing the arena left blocks behind since it was last rebuilt.
+    pub(crate) fn rebuild(&mut self) {
+        if self.abandoned == 0 {
+            return;
+        }
+
+        let previous = core::mem::replace(
+            &mut self.arena,
+            Bump::with_capacity(size_of::<Prototype<P>>() * self.len),
+        );
+        self.move_into(self.len);
+        self.abandoned = 0;
+
+        // The prototypes were moved out of the previous arena, freeing it doesn't drop them.
+        drop(previous);
+    }
+
+    /// Allocates `capacity` slots in the arena and moves the prototypes into them.
+    fn move_into(&mut self, capacity: usize) {
+        let layout = Layout::array::<Prototype<P>>(capacity).expect("too many prototypes");
+        let slots = self.arena.alloc_layout(layout).cast::<Prototype<P>>();
+
+        // The new slots don't overlap the previous ones, which aren't read once moved.
+        unsafe { core::ptr::copy_nonoverlapping(self.slots.as_ptr(), slots.as_ptr(), self.len) };
+
+        self.slots = slots;
+        self.capacity = capacity;
+    }
+}
+
+impl<P: PrototypeData> Default for PrototypeArena<P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<P: PrototypeData> Clone for PrototypeArena<P> {
+    fn clone(&self) -> Self {
+        self.as_slice().iter().cloned().collect()
+    }
+}
+
+impl<P: PrototypeData> FromIterator<Prototype<P>> for PrototypeArena<P> {
+    fn from_iter<I: IntoIterator<Item = Prototype<P>>>(iter: I) -> Self {
+        let mut arena = Self::new();
+
+        for proto in iter {
+            arena.push(proto);
+        }
+
+        arena.rebuild();
+        arena
+    }
+}
+
+impl<P: PrototypeData> Drop for PrototypeArena<P> {
+    fn drop(&mut self) {
+        // The arena frees the slots without dropping the prototypes.
+        unsafe { core::ptr::drop_in_place(self.as_mut_slice()) };
+    }
+}
+
+impl<P: PrototypeData> core::ops::Index<usize> for PrototypeArena<P> {
+    type Output = Prototype<P>;
+
+    fn index(&self, slot: usize) -> &Self::Output {
+        &self.as_slice()[slot]
+    }
+}
+
+impl<P: PrototypeData> core::ops::IndexMut<usize> for PrototypeArena<P> {
+    fn index_mut(&mut self, slot: usize) -> &mut Self::Output {
+        &mut self.as_mut_slice()[slot]
+    }
+}
+
+impl<P: PrototypeData> PrototypeStorage for PrototypeArena<P> {
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn get(&self, slot: usize) -> Option<&dyn Reflect> {
+        self.as_slice().get(slot).map(|proto| proto as &dyn Reflect)
+    }
+
+    fn get_mut(&mut self, slot: usize) -> Option<&mut dyn Reflect> {
+        self.as_mut_slice()
+            .get_mut(slot)
+            .map(|proto| proto as &mut dyn Reflect)
+    }
+
+    fn tags(&self, slot: usize) -> &[String] {
+        self.as_slice().get(slot).map_or(&[], Prototype::tags)
+    }
+
+    fn push(&mut self, proto: Box<dyn Reflect>) -> Result<(), Box<dyn Reflect>> {
+        PrototypeArena::push(self, *proto.downcast::<Prototype<P>>()?);
+        Ok(())
+    }
+
+    fn replace(&mut self, slot: usize, proto: Box<dyn Reflect>) -> Result<(), Box<dyn Reflect>> {
+        self[slot] = *proto.downcast::<Prototype<P>>()?;
+        Ok(())
+    }
+
+    fn swap_remove(&mut self, slot: usize) -> Box<dyn Reflect> {
+        Box::new(PrototypeArena::swap_remove(self, slot))
+    }
+
+    fn rebuild(&mut self) {
+        PrototypeArena::rebuild(self);
+    }
+
+    fn clone_storage(&self) -> Box<dyn PrototypeStorage> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+impl core::fmt::Debug for dyn PrototypeStorage {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("PrototypeStorage")
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+    use crate::{JsonSchema, PrototypeId};
+
+    static DROPPED: AtomicUsize = AtomicUsize::new(0);
+
+    #[derive(Debug, Clone, Default, Reflect, crate::Prototype, JsonSchema)]
+    #[proto(name = "card")]
+    struct Card {
+        cost: u32,
+    }
+
+    #[derive(Debug, Clone, Default, Reflect, crate::Prototype, JsonSchema)]
+    #[proto(name = "token")]
+    struct Token {
+        cost: u32,
+    }
+
+    impl Drop for Token {
+        fn drop(&mut self) {
+            DROPPED.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn card(cost: u32) -> Prototype<Card> {
+        Prototype::for_test(&format!("card_{cost}"), &[], Card { cost })
+    }
+
+    fn costs(arena: &PrototypeArena<Card>) -> Vec<u32> {
+        arena.as_slice().iter().map(|card| card.cost).collect()
+    }
+
+    #[test]
+    fn growing_keeps_the_prototypes_until_rebuilt() {
+        let mut arena = (0..100).map(card).collect::<PrototypeArena<Card>>();
+        let rebuilt = arena.allocated_bytes();
+
+        for cost in 100..200 {
+            arena.push(card(cost));
+        }
+
+        let grown = arena.allocated_bytes();
+        assert_eq!(costs(&arena), (0..200).collect::<Vec<_>>());
+
+        arena.rebuild();
+
+        assert_eq!(costs(&arena), (0..200).collect::<Vec<_>>());
+        assert!(arena.allocated_bytes() < grown);
+        assert!(arena.allocated_bytes() >= rebuilt);
+        assert_eq!(*arena[150].id(), PrototypeId::from_name("card_150"));
+    }
+
+    #[test]
+    fn swap_remove_moves_the_last_prototype_into_the_slot() {
+        let mut arena = (0..4).map(card).collect::<PrototypeArena<Card>>();
+
+        assert_eq!(arena.swap_remove(1).cost, 1);
+        assert_eq!(costs(&arena), [0, 3, 2]);
+        assert_eq!(arena.swap_remove(2).cost, 2);
+        assert_eq!(costs(&arena), [0, 3]);
+    }
+
+    #[test]
+    fn prototypes_are_dropped_once() {
+        let mut arena = PrototypeArena::new();
+
+        for cost in 0..10 {
+            arena.push(Prototype::for_test(&cost.to_string(), &[], Token { cost }));
+        }
+
+        // The defaults `Prototype::for_test` builds the prototypes from are dropped too.
+        let dropped = DROPPED.load(Ordering::Relaxed);
+
+        drop(arena.swap_remove(3));
+        arena.rebuild();
+        assert_eq!(DROPPED.load(Ordering::Relaxed) - dropped, 1);
+
+        drop(arena);
+        assert_eq!(DROPPED.load(Ordering::Relaxed) - dropped, 10);
+    }
+}
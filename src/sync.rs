@@ -0,0 +1,273 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
+use bevy::{
+    asset::{
+        io::{
+            AssetSource, AssetSourceId,
+            memory::{Dir, MemoryAssetReader},
+        },
+        uuid::Uuid,
+    },
+    ecs::system::SystemParam,
+    platform::time::Instant,
+    prelude::*,
+    reflect::{GetPath, TypeRegistry},
+};
+use serde_json::{Map as JsonMap, Value as JsonValue};
+
+use crate::{
+    AppPrototypeTypeRegistry, LoadingPrototypesHandles, PrototypeData, PrototypeId,
+    PrototypeLoadPriority, PrototypeMetadata, PrototypeRegistries, PrototypesAsset,
+    prototype_data_to_json,
+};
+
+/// Name of the in memory asset source the synchronized prototypes are loaded from.
+pub(crate) const SYNC_SOURCE: &str = "prototypes-sync";
+
+/// Synchronizes the prototypes of a server with its clients.
+///
+/// The server encodes its prototypes with [`PrototypesSnapshot`] and sends the bytes with the
+/// transport of its choice, clients send a [`PrototypesSyncReceived`] event with the received
+/// bytes to apply them into their registries. Prototypes are applied through the prototypes
+/// loader so asset handles are loaded from the client's own assets.
+///
+/// Like any asset source, it must be added before the `AssetPlugin`.
+pub struct PrototypesSyncPlugin;
+
+impl Plugin for PrototypesSyncPlugin {
+    fn build(&self, app: &mut App) {
+        let dir = Dir::default();
+        let reader_dir = dir.clone();
+
+        app.register_asset_source(
+            AssetSourceId::from(SYNC_SOURCE),
+            AssetSource::build().with_reader(move || {
+                Box::new(MemoryAssetReader {
+                    root: reader_dir.clone(),
+                })
+            }),
+        )
+        .insert_resource(SyncedPrototypesFiles {
+            dir,
+            loading: Vec::new(),
+            next_index: 0,
+        })
+        .add_event::<PrototypesSyncReceived>()
+        .add_event::<RemotePrototypesHashes>()
+        .add_event::<DataMismatch>()
+        .add_systems(
+            PreUpdate,
+            (apply_received_prototypes, check_remote_prototypes_hashes),
+        );
+    }
+}
+
+/// Sent on the client with the bytes of a [`PrototypesSnapshot`] received from the server.
+#[derive(Event, Debug, Clone)]
+pub struct PrototypesSyncReceived(pub Vec<u8>);
+
+#[derive(Resource)]
+struct SyncedPrototypesFiles {
+    dir: Dir,
+    loading: Vec<(AssetId<PrototypesAsset>, PathBuf)>,
+    next_index: u64,
+}
+
+fn apply_received_prototypes(
+    mut events_rx: EventReader<PrototypesSyncReceived>,
+    mut files: ResMut<SyncedPrototypesFiles>,
+    mut loading_prototypes_handles: ResMut<LoadingPrototypesHandles>,
+    asset_server: Res<AssetServer>,
+) {
+    let SyncedPrototypesFiles {
+        dir,
+        loading,
+        next_index,
+    } = &mut *files;
+
+    // Applied messages are removed from `LoadingPrototypesHandles`, their files can be dropped.
+    loading.retain(|(id, path)| {
+        let is_loading = loading_prototypes_handles.contains_key(id);
+
+        if !is_loading {
+            dir.remove_asset(path);
+        }
+
+        is_loading
+    });
+
+    for PrototypesSyncReceived(bytes) in events_rx.read() {
+        let path = PathBuf::from(format!("sync_{next_index}.proto.json"));
+        *next_index += 1;
+
+        dir.insert_asset(&path, bytes.clone());
+
+        let handle: Handle<PrototypesAsset> =
+            asset_server.load(bevy::asset::AssetPath::from_path(&path).with_source(SYNC_SOURCE));
+        loading.push((handle.id(), path));
+        loading_prototypes_handles.insert(
+            handle.id(),
+            (handle, Instant::now(), PrototypeLoadPriority::Normal),
+        );
+    }
+}
+
+/// Sent with the [`PrototypesSnapshot::type_hashes`] of a remote peer, e.g. during a connection
+/// handshake, to compare them with the local ones.
+#[derive(Event, Debug, Clone)]
+pub struct RemotePrototypesHashes(pub BTreeMap<String, u64>);
+
+/// Sent when the prototypes of a type differ from the ones of a remote peer.
+///
+/// A `None` hash means the prototype type isn't registered on that side.
+#[derive(Event, Debug, Clone, PartialEq, Eq)]
+pub struct DataMismatch {
+    pub prototype_type: String,
+    pub local_hash: Option<u64>,
+    pub remote_hash: Option<u64>,
+}
+
+fn check_remote_prototypes_hashes(
+    mut events_rx: EventReader<RemotePrototypesHashes>,
+    mut mismatches: EventWriter<DataMismatch>,
+    snapshot: PrototypesSnapshot,
+) {
+    for RemotePrototypesHashes(remote) in events_rx.read() {
+        mismatches.write_batch(snapshot.compare_type_hashes(remote));
+    }
+}
+
+/// Encodes the prototypes of the registries into messages for [`PrototypesSyncPlugin`].
+///
+/// Messages use the layout of the prototypes files, with asset handles written as full asset
+/// paths.
+#[derive(SystemParam)]
+pub struct PrototypesSnapshot<'w> {
+    registries: Res<'w, PrototypeRegistries>,
+    prototype_types: Res<'w, AppPrototypeTypeRegistry>,
+    type_registry: Res<'w, AppTypeRegistry>,
+}
+
+impl PrototypesSnapshot<'_> {
+    /// Encodes every registered prototype.
+    pub fn snapshot(&self) -> Vec<u8> {
+        let type_registry = self.type_registry.read();
+        let prototype_types = self.prototype_types.0.read();
+
+        let entries = prototype_types
+            .iter()
+            .flat_map(|(ty, type_id)| {
+                self.registries
+                    .iter_dyn(type_id)
+                    .filter_map(|proto| prototype_entry(ty, proto, &type_registry))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        serde_json::to_vec(&entries).unwrap_or_default()
+    }
+
+    /// Hashes the prototypes of each registered type, keyed by prototype type name.
+    ///
+    /// Two peers with the same hash for a type have the same prototypes of that type.
+    pub fn type_hashes(&self) -> BTreeMap<String, u64> {
+        let type_registry = self.type_registry.read();
+        let prototype_types = self.prototype_types.0.read();
+
+        prototype_types
+            .iter()
+            .map(|(ty, type_id)| {
+                let mut entries = self
+                    .registries
+                    .iter_dyn(type_id)
+                    .filter_map(|proto| prototype_entry(ty, proto, &type_registry))
+                    .map(|entry| serde_json::to_string(&entry).unwrap_or_default())
+                    .collect::<Vec<_>>();
+                // Registries aren't ordered, hash the entries in a stable order.
+                entries.sort();
+
+                let hash = const_fnv1a_hash::fnv1a_hash_str_64(&entries.join("\n"));
+
+                (ty.to_string(), hash)
+            })
+            .collect()
+    }
+
+    /// Compares the [`type_hashes`](Self::type_hashes) of a remote peer with the local ones.
+    pub fn compare_type_hashes(&self, remote: &BTreeMap<String, u64>) -> Vec<DataMismatch> {
+        let local = self.type_hashes();
+
+        local
+            .keys()
+            .chain(remote.keys().filter(|ty| !local.contains_key(*ty)))
+            .filter_map(|ty| {
+                let local_hash = local.get(ty).copied();
+                let remote_hash = remote.get(ty).copied();
+
+                (local_hash != remote_hash).then(|| DataMismatch {
+                    prototype_type: ty.clone(),
+                    local_hash,
+                    remote_hash,
+                })
+            })
+            .collect()
+    }
+
+    /// Encodes only the given prototypes, e.g. the ones modified since the last snapshot.
+    pub fn delta<P: PrototypeData>(
+        &self,
+        ids: impl IntoIterator<Item = PrototypeId<P>>,
+    ) -> Vec<u8> {
+        let type_registry = self.type_registry.read();
+
+        let entries = ids
+            .into_iter()
+            .filter_map(|id| self.registries.get(&id))
+            .filter_map(|proto| {
+                prototype_entry(P::prototype_name(), proto.as_reflect(), &type_registry)
+            })
+            .collect::<Vec<_>>();
+
+        serde_json::to_vec(&entries).unwrap_or_default()
+    }
+}
+
+/// Builds the prototypes file entry of a reflected [`Prototype`](crate::Prototype), with its
+/// metadata and uuid so the clients' prototypes are the same as the server's.
+fn prototype_entry(ty: &str, proto: &dyn Reflect, registry: &TypeRegistry) -> Option<JsonValue> {
+    // Stripped names are sent as their id, which the loader accepts in place of the name.
+    #[cfg(feature = "strip_names")]
+    let name = JsonValue::from(*proto.path::<u64>("name.id.hash").ok()?);
+    #[cfg(not(feature = "strip_names"))]
+    let name = JsonValue::from(*proto.path::<&'static str>("name.name").ok()?);
+    let tags = proto.path::<Vec<String>>("tags").ok()?;
+    let metadata = proto.path::<PrototypeMetadata>("metadata").ok()?;
+    let uuid = proto.path::<Option<Uuid>>("uuid").ok()?;
+    let data = proto.reflect_path("data").ok()?;
+
+    let mut entry = JsonMap::new();
+    entry.insert("type".to_string(), JsonValue::from(ty));
+    entry.insert("name".to_string(), name.clone());
+
+    if !tags.is_empty() {
+        entry.insert("tags".to_string(), JsonValue::from(tags.clone()));
+    }
+
+    if let Some(metadata) = &metadata.0 {
+        entry.insert("$meta".to_string(), metadata.clone());
+    }
+
+    if let Some(uuid) = uuid {
+        entry.insert("uuid".to_string(), JsonValue::from(uuid.to_string()));
+    }
+
+    match prototype_data_to_json(data.as_partial_reflect(), registry, None) {
+        JsonValue::Object(fields) => entry.extend(fields),
+        _ => {
+            warn!("Prototype {ty} {name} data isn't a struct and can't be synchronized");
+            return None;
+        }
+    }
+
+    Some(JsonValue::Object(entry))
+}
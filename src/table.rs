@@ -0,0 +1,362 @@
+use std::path::Path;
+
+use bevy::{
+    ecs::system::SystemParam,
+    platform::collections::HashMap,
+    prelude::*,
+    reflect::{
+        NamedField, ReflectRef, TypeInfo, TypeRegistration, TypeRegistry, UnnamedField,
+        VariantInfo,
+        serde::{ReflectSerializerProcessor, TypedReflectSerializer},
+    },
+};
+use serde_json::Value as JsonValue;
+
+use crate::{
+    AppPrototypeTypeRegistry, ErasedPrototypeId, HandlePathProcessor, PackError, PrototypeData,
+    PrototypeRegistries, TableFormat, is_handle_type, table_to_entries, table_type, to_json_pretty,
+    untyped_cell_value,
+};
+
+/// Exports the prototypes of a type as a table, e.g. for designers balancing numbers in a
+/// spreadsheet.
+///
+/// Each prototype is a row and each field a column named after its reflect path, the fields of
+/// nested structs without their own serde implementation get their own columns:
+///
+/// ```csv
+/// type,name,tags,level,damage,stats.speed,effects,icon
+/// sword,wooden_stick,,1,1.0,1.5,[],/prototypes/wooden_stick.icon
+/// sword,mighty_sword,"weapon,legendary",10,25.0,1.0,"[""bleeding""]",/prototypes/mighty_sword.icon
+/// ```
+///
+/// Strings, numbers and booleans are written as is, `None` as an empty cell and the other values
+/// (lists, tuples, enums, maps) as JSON snippets, tags are separated by commas. Prototype ids are
+/// written as the name of the prototype when it's registered.
+#[derive(SystemParam)]
+pub struct PrototypeTables<'w> {
+    registries: Res<'w, PrototypeRegistries>,
+    prototype_types: Res<'w, AppPrototypeTypeRegistry>,
+    type_registry: Res<'w, AppTypeRegistry>,
+}
+
+impl PrototypeTables<'_> {
+    /// Exports every `P` prototype as a table, in their storage order.
+    pub fn export<P: PrototypeData>(&self, format: TableFormat) -> String {
+        let type_registry = self.type_registry.read();
+        let processor = TableProcessor {
+            handles: HandlePathProcessor { file: None },
+            names: self.prototype_names(&type_registry),
+        };
+        let mut columns = Vec::<String>::new();
+        let mut rows = Vec::new();
+
+        for proto in self.registries.iter::<P>() {
+            // Stripped names are serialized as their id.
+            let name = match serde_json::to_value(&proto.name) {
+                Ok(JsonValue::String(name)) => name,
+                Ok(name) => name.to_string(),
+                Err(err) => {
+                    warn!("Failed to serialize prototype name: {err}");
+                    continue;
+                }
+            };
+
+            let mut cells = Vec::new();
+            flatten_cells(
+                (**proto).as_partial_reflect(),
+                String::new(),
+                &type_registry,
+                &processor,
+                &mut cells,
+            );
+
+            for (column, _) in &cells {
+                if !columns.contains(column) {
+                    columns.push(column.clone());
+                }
+            }
+
+            rows.push((name, proto.tags().join(","), cells));
+        }
+
+        let delimiter = format.delimiter();
+        let mut table = String::new();
+
+        let header = ["type", "name", "tags"]
+            .into_iter()
+            .map(str::to_string)
+            .chain(columns.iter().cloned())
+            .collect::<Vec<_>>();
+        write_row(&mut table, &header, delimiter);
+
+        for (name, tags, cells) in rows {
+            let mut row = vec![P::prototype_name().to_string(), name, tags];
+            row.extend(columns.iter().map(|column| {
+                cells
+                    .iter()
+                    .find(|(path, _)| path == column)
+                    .map(|(_, cell)| cell.clone())
+                    .unwrap_or_default()
+            }));
+
+            write_row(&mut table, &row, delimiter);
+        }
+
+        table
+    }
+
+    /// Writes the table of the `P` prototypes into `path`, see [`PrototypeTables::export`].
+    pub fn save<P: PrototypeData>(
+        &self,
+        path: impl AsRef<Path>,
+        format: TableFormat,
+    ) -> std::io::Result<()> {
+        std::fs::write(path, self.export::<P>(format))
+    }
+
+    /// Returns the names of the registered prototypes, by id.
+    fn prototype_names(&self, registry: &TypeRegistry) -> HashMap<ErasedPrototypeId, String> {
+        let prototype_types = self.prototype_types.0.read();
+        let mut names = HashMap::default();
+
+        for type_id in prototype_types.values() {
+            for proto in self.registries.iter_dyn(type_id) {
+                let ReflectRef::Struct(proto) = proto.reflect_ref() else {
+                    continue;
+                };
+
+                // Stripped names are serialized as their id and stay ids.
+                if let Some(JsonValue::String(name)) = proto.field("name").and_then(|name| {
+                    serde_json::to_value(TypedReflectSerializer::new(name, registry)).ok()
+                }) {
+                    names.insert(ErasedPrototypeId::from_name(&name), name);
+                }
+            }
+        }
+
+        names
+    }
+}
+
+impl core::fmt::Debug for PrototypeTables<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("PrototypeTables").finish()
+    }
+}
+
+/// Collects the cells of `value`, one per field of its nested structs, with their reflect path.
+fn flatten_cells(
+    value: &dyn PartialReflect,
+    path: String,
+    registry: &TypeRegistry,
+    processor: &TableProcessor,
+    cells: &mut Vec<(String, String)>,
+) {
+    // Structs with their own serde implementation, e.g. `Vec2`, are written as one cell.
+    if let ReflectRef::Struct(fields) = value.reflect_ref()
+        && (path.is_empty() || !has_serde(value, registry))
+    {
+        for (i, field) in fields.iter_fields().enumerate() {
+            let Some(name) = fields.name_at(i) else {
+                continue;
+            };
+
+            let path = if path.is_empty() {
+                name.to_string()
+            } else {
+                format!("{path}.{name}")
+            };
+
+            flatten_cells(field, path, registry, processor, cells);
+        }
+
+        return;
+    }
+
+    let cell = match serde_json::to_value(TypedReflectSerializer::with_processor(
+        value, registry, processor,
+    )) {
+        Ok(JsonValue::Null) => String::new(),
+        Ok(JsonValue::String(value)) => value,
+        Ok(value) => value.to_string(),
+        Err(err) => {
+            warn!("Failed to serialize prototype field {path}: {err}");
+            return;
+        }
+    };
+
+    cells.push((path, cell));
+}
+
+/// Serializes the asset handles as their asset path and the prototype ids as their name.
+struct TableProcessor<'a> {
+    handles: HandlePathProcessor<'a>,
+    names: HashMap<ErasedPrototypeId, String>,
+}
+
+impl ReflectSerializerProcessor for TableProcessor<'_> {
+    fn try_serialize<S>(
+        &self,
+        value: &dyn PartialReflect,
+        registry: &TypeRegistry,
+        serializer: S,
+    ) -> Result<Result<S::Ok, S>, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let name = match value.reflect_ref() {
+            ReflectRef::Struct(id) if is_prototype_id(value) => id
+                .field("hash")
+                .and_then(<dyn PartialReflect>::try_downcast_ref::<u64>)
+                .and_then(|hash| self.names.get(&ErasedPrototypeId::from_raw(*hash))),
+            _ => None,
+        };
+
+        match name {
+            Some(name) => Ok(Ok(serializer.serialize_str(name)?)),
+            None => self.handles.try_serialize(value, registry, serializer),
+        }
+    }
+}
+
+fn has_serde(value: &dyn PartialReflect, registry: &TypeRegistry) -> bool {
+    value
+        .get_represented_type_info()
+        .and_then(|type_info| registry.get(type_info.type_id()))
+        .is_some_and(|registration| {
+            registration.data::<ReflectSerialize>().is_some()
+                || registration.data::<ReflectDeserialize>().is_some()
+        })
+}
+
+fn is_prototype_id(value: &dyn PartialReflect) -> bool {
+    value.get_represented_type_info().is_some_and(|type_info| {
+        let type_path = type_info.type_path_table();
+
+        type_path.module_path() == Some("bevy_histrion_proto::identifier")
+            && matches!(type_path.ident(), Some("PrototypeId" | "ErasedPrototypeId"))
+    })
+}
+
+/// Appends a row to `table`, quoting the cells containing the delimiter, quotes or line breaks.
+fn write_row(table: &mut String, cells: &[String], delimiter: char) {
+    for (i, cell) in cells.iter().enumerate() {
+        if i > 0 {
+            table.push(delimiter);
+        }
+
+        if cell.contains([delimiter, '"', '\n', '\r']) {
+            table.push('"');
+            table.push_str(&cell.replace('"', "\"\""));
+            table.push('"');
+        } else {
+            table.push_str(cell);
+        }
+    }
+
+    table.push('\n');
+}
+
+/// Converts a cell of the field at the reflect `path` of `registration` into JSON.
+///
+/// Strings, asset paths and their options are kept as is, prototype ids are names unless they
+/// are numbers and the other values are JSON snippets, or strings when they aren't valid JSON,
+/// e.g. the unit variants of enums.
+pub(crate) fn typed_cell_value(
+    registration: &TypeRegistration,
+    path: &str,
+    cell: &str,
+) -> JsonValue {
+    let mut type_info = Some(registration.type_info());
+
+    for field in path.split('.') {
+        type_info = match type_info {
+            Some(TypeInfo::Struct(info)) => info.field(field).and_then(NamedField::type_info),
+            _ => None,
+        };
+    }
+
+    match type_info.map(cell_kind) {
+        Some(CellKind::String) => JsonValue::String(cell.to_string()),
+        Some(CellKind::PrototypeId) => cell
+            .parse::<u64>()
+            .map_or_else(|_| JsonValue::String(cell.to_string()), JsonValue::from),
+        _ => untyped_cell_value(cell),
+    }
+}
+
+enum CellKind {
+    String,
+    PrototypeId,
+    Json,
+}
+
+fn cell_kind(type_info: &TypeInfo) -> CellKind {
+    let type_path = type_info.type_path_table();
+
+    if is_handle_type(type_path)
+        || matches!(
+            type_path.path(),
+            "alloc::string::String" | "alloc::borrow::Cow<str>" | "std::path::PathBuf" | "char"
+        )
+    {
+        return CellKind::String;
+    }
+
+    if type_path.module_path() == Some("bevy_histrion_proto::identifier")
+        && matches!(type_path.ident(), Some("PrototypeId" | "ErasedPrototypeId"))
+    {
+        return CellKind::PrototypeId;
+    }
+
+    // Options are written as their value.
+    if let TypeInfo::Enum(info) = type_info
+        && type_path.module_path() == Some("core::option")
+        && let Some(VariantInfo::Tuple(some)) = info.variant("Some")
+        && let Some(value) = some.field_at(0).and_then(UnnamedField::type_info)
+    {
+        return cell_kind(value);
+    }
+
+    CellKind::Json
+}
+
+/// Converts the table file `table`, see [`PrototypeTables`], into the prototypes file `output`
+/// and returns the number of prototypes written.
+///
+/// Without the prototype types at hand the cells that are valid JSON are written as JSON, e.g.
+/// a number in a string field is written as a number, loading the table itself with
+/// [`PrototypeServer::load_prototypes`](crate::PrototypeServer::load_prototypes) doesn't have
+/// this issue. Rows without a `type` cell are of the type the table file is named after, e.g.
+/// `sword.proto.csv`.
+pub fn import_table(table: impl AsRef<Path>, output: impl AsRef<Path>) -> Result<usize, PackError> {
+    let (table, output) = (table.as_ref(), output.as_ref());
+    let io_error = |path: &Path| {
+        let path = path.to_path_buf();
+        move |source| PackError::Io { path, source }
+    };
+
+    let text = std::fs::read_to_string(table).map_err(io_error(table))?;
+    let entries = table_to_entries(
+        &text,
+        TableFormat::from_path(table),
+        table_type(table),
+        |_, _, cell| untyped_cell_value(cell),
+    )
+    .map_err(|reason| PackError::InvalidTable {
+        path: table.to_path_buf(),
+        reason,
+    })?;
+
+    let entries = entries
+        .into_iter()
+        .map(|(_, entry)| entry)
+        .collect::<Vec<_>>();
+
+    let mut bytes = to_json_pretty(&entries).map_err(io_error(output))?;
+    bytes.push(b'\n');
+    std::fs::write(output, bytes).map_err(io_error(output))?;
+
+    Ok(entries.len())
+}
@@ -0,0 +1,197 @@
+use core::any::TypeId;
+
+use bevy::{platform::collections::HashMap, prelude::*};
+
+/// A boolean combination of tags, see [`Reg::iter_with_tags`](crate::Reg::iter_with_tags).
+///
+/// ```no_run
+/// # use bevy_histrion_proto::TagQuery;
+/// // Melee weapons that aren't legendary.
+/// let query = TagQuery::all(["weapon", "melee"]).and(TagQuery::not("legendary"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TagQuery {
+    /// Matches the prototypes with this tag.
+    Tag(String),
+    /// Matches the prototypes matching every query, or every prototype when empty.
+    All(Vec<TagQuery>),
+    /// Matches the prototypes matching at least one query, or no prototype when empty.
+    Any(Vec<TagQuery>),
+    /// Matches the prototypes not matching the query.
+    Not(Box<TagQuery>),
+}
+
+impl TagQuery {
+    /// Matches the prototypes with the `tag` tag.
+    pub fn tag(tag: impl Into<String>) -> Self {
+        Self::Tag(tag.into())
+    }
+
+    /// Matches the prototypes matching every query.
+    pub fn all(queries: impl IntoIterator<Item = impl Into<TagQuery>>) -> Self {
+        Self::All(queries.into_iter().map(Into::into).collect())
+    }
+
+    /// Matches the prototypes matching at least one query.
+    pub fn any(queries: impl IntoIterator<Item = impl Into<TagQuery>>) -> Self {
+        Self::Any(queries.into_iter().map(Into::into).collect())
+    }
+
+    /// Matches the prototypes not matching `query`.
+    pub fn not(query: impl Into<TagQuery>) -> Self {
+        Self::Not(Box::new(query.into()))
+    }
+
+    /// Matches the prototypes matching both this query and `other`.
+    #[must_use]
+    pub fn and(self, other: impl Into<TagQuery>) -> Self {
+        match self {
+            Self::All(mut queries) => {
+                queries.push(other.into());
+                Self::All(queries)
+            }
+            query => Self::All(vec![query, other.into()]),
+        }
+    }
+
+    /// Matches the prototypes matching this query, `other` or both.
+    #[must_use]
+    pub fn or(self, other: impl Into<TagQuery>) -> Self {
+        match self {
+            Self::Any(mut queries) => {
+                queries.push(other.into());
+                Self::Any(queries)
+            }
+            query => Self::Any(vec![query, other.into()]),
+        }
+    }
+
+    /// Returns `true` if a prototype with the given `tags` matches the query.
+    pub fn matches(&self, tags: &[String]) -> bool {
+        match self {
+            Self::Tag(tag) => tags.contains(tag),
+            Self::All(queries) => queries.iter().all(|query| query.matches(tags)),
+            Self::Any(queries) => queries.iter().any(|query| query.matches(tags)),
+            Self::Not(query) => !query.matches(tags),
+        }
+    }
+}
+
+impl From<&str> for TagQuery {
+    fn from(tag: &str) -> Self {
+        Self::tag(tag)
+    }
+}
+
+impl From<String> for TagQuery {
+    fn from(tag: String) -> Self {
+        Self::Tag(tag)
+    }
+}
+
+/// Tag aliases and their canonical tag, see
+/// [`PrototypeAppExt::add_tag_alias`](crate::PrototypeAppExt::add_tag_alias).
+#[derive(Default, Resource, Deref, DerefMut)]
+pub(crate) struct TagAliases(HashMap<String, String>);
+
+impl TagAliases {
+    /// Replaces the aliases of `tags` by their canonical tag, calling `on_alias` with each
+    /// replaced alias and its canonical tag.
+    pub fn canonicalize(&self, tags: &mut Vec<String>, mut on_alias: impl FnMut(&str, &str)) {
+        if self.is_empty() {
+            return;
+        }
+
+        let mut canonical_tags = Vec::with_capacity(tags.len());
+
+        for tag in tags.drain(..) {
+            let tag = match self.get(&tag) {
+                Some(canonical) => {
+                    on_alias(&tag, canonical);
+                    canonical.clone()
+                }
+                None => tag,
+            };
+
+            // An alias and its canonical tag may both be listed.
+            if !canonical_tags.contains(&tag) {
+                canonical_tags.push(tag);
+            }
+        }
+
+        *tags = canonical_tags;
+    }
+}
+
+/// The [`PrototypeData::allowed_tags`](crate::PrototypeData::allowed_tags) of the registered
+/// prototype types restricting their tags.
+#[derive(Default, Resource, Deref, DerefMut)]
+pub(crate) struct AllowedTags(HashMap<TypeId, &'static [&'static str]>);
+
+impl AllowedTags {
+    /// Returns the `tags` not allowed for the `type_id` prototype type.
+    pub fn disallowed<'a>(&self, type_id: &TypeId, tags: &'a [String]) -> Vec<&'a str> {
+        let Some(allowed_tags) = self.get(type_id) else {
+            return Vec::new();
+        };
+
+        tags.iter()
+            .map(String::as_str)
+            .filter(|tag| !allowed_tags.contains(tag))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tags(tags: &[&str]) -> Vec<String> {
+        tags.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn queries_combine_the_tags() {
+        let query = TagQuery::all(["weapon", "melee"]).and(TagQuery::not("legendary"));
+
+        assert!(query.matches(&tags(&["melee", "weapon"])));
+        assert!(!query.matches(&tags(&["melee", "weapon", "legendary"])));
+        assert!(!query.matches(&tags(&["weapon"])));
+
+        let query = TagQuery::tag("fire").or("ice").or("poison");
+        assert_eq!(query, TagQuery::any(["fire", "ice", "poison"]));
+        assert!(query.matches(&tags(&["poison"])));
+        assert!(!query.matches(&[]));
+    }
+
+    #[test]
+    fn empty_queries_match_every_or_no_prototype() {
+        assert!(TagQuery::All(Vec::new()).matches(&[]));
+        assert!(!TagQuery::Any(Vec::new()).matches(&tags(&["weapon"])));
+    }
+
+    #[test]
+    fn aliases_are_replaced_by_their_canonical_tag_once() {
+        let mut aliases = TagAliases::default();
+        aliases.insert("sword".to_string(), "blade".to_string());
+
+        let mut replaced = Vec::new();
+        let mut tags = tags(&["sword", "melee", "blade"]);
+        aliases.canonicalize(&mut tags, |alias, canonical| {
+            replaced.push((alias.to_string(), canonical.to_string()));
+        });
+
+        assert_eq!(tags, ["blade", "melee"]);
+        assert_eq!(replaced, [("sword".to_string(), "blade".to_string())]);
+    }
+
+    #[test]
+    fn only_the_restricted_types_have_disallowed_tags() {
+        let mut allowed = AllowedTags::default();
+        allowed.insert(TypeId::of::<u8>(), &["melee", "ranged"]);
+
+        let tags = tags(&["melee", "magic"]);
+        assert_eq!(allowed.disallowed(&TypeId::of::<u8>(), &tags), ["magic"]);
+        assert!(allowed.disallowed(&TypeId::of::<u16>(), &tags).is_empty());
+    }
+}
@@ -0,0 +1,70 @@
+use bevy::{asset::AssetPath, prelude::*};
+
+use crate::{ErasedPrototypeId, ErasedPrototypeName};
+
+/// A structured event about the prototypes data, received by the sinks added with
+/// [`PrototypeAppExt::add_prototype_telemetry_sink`](crate::PrototypeAppExt::add_prototype_telemetry_sink).
+#[derive(Debug, Clone, Copy)]
+pub enum PrototypeTelemetryEvent<'a> {
+    /// A prototype of `file` was loaded or reloaded.
+    Loaded {
+        ty: &'a str,
+        name: &'a ErasedPrototypeName,
+        file: &'a AssetPath<'static>,
+    },
+    /// [`Reg::get`](crate::Reg::get) didn't find the `id` prototype.
+    LookupMissed { ty: &'a str, id: ErasedPrototypeId },
+    /// The fields of the `id` prototype were patched, or restored, by the overrides of the
+    /// current locale or the layers of the selected variants.
+    OverrideApplied {
+        ty: &'a str,
+        id: ErasedPrototypeId,
+        kind: PrototypeOverrideKind,
+    },
+}
+
+/// The overrides patching the fields of the loaded prototypes, see
+/// [`PrototypeTelemetryEvent::OverrideApplied`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PrototypeOverrideKind {
+    /// The overrides of the [`CurrentLocale`](crate::CurrentLocale).
+    Locale,
+    /// The layers of the selected [`PrototypeVariants`](crate::PrototypeVariants).
+    Variant,
+}
+
+/// Receives the [`PrototypeTelemetryEvent`]s, e.g. to forward data health metrics to the
+/// telemetry of a studio.
+///
+/// Events are sent from the systems loading and looking up the prototypes, sinks should only
+/// record or queue them. Implemented for the `Fn(&PrototypeTelemetryEvent)` closures.
+pub trait PrototypeTelemetrySink: Send + Sync + 'static {
+    fn send(&self, event: &PrototypeTelemetryEvent);
+}
+
+impl<F> PrototypeTelemetrySink for F
+where
+    F: Fn(&PrototypeTelemetryEvent) + Send + Sync + 'static,
+{
+    fn send(&self, event: &PrototypeTelemetryEvent) {
+        self(event);
+    }
+}
+
+/// The sinks added with
+/// [`PrototypeAppExt::add_prototype_telemetry_sink`](crate::PrototypeAppExt::add_prototype_telemetry_sink),
+/// only inserted once a sink is added.
+#[derive(Resource, Default)]
+pub(crate) struct PrototypeTelemetry(Vec<Box<dyn PrototypeTelemetrySink>>);
+
+impl PrototypeTelemetry {
+    pub(crate) fn add(&mut self, sink: impl PrototypeTelemetrySink) {
+        self.0.push(Box::new(sink));
+    }
+
+    pub(crate) fn send(&self, event: PrototypeTelemetryEvent) {
+        for sink in &self.0 {
+            sink.send(&event);
+        }
+    }
+}
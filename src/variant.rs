@@ -0,0 +1,345 @@
+use std::path::Path;
+
+use bevy::{
+    asset::{AssetLoader, LoadContext, io::Reader as AssetReader},
+    platform::collections::HashMap,
+    prelude::*,
+    reflect::{GetPath, PartialReflect, ReflectFromReflect, ReflectRef, TypeRegistry},
+};
+use serde::de::DeserializeSeed;
+use serde_json::Value as JsonValue;
+
+use crate::{
+    AppPrototypeTypeRegistry, ErasedPrototypeId,
+    overlay::PrototypeOverlay,
+    pack::join_field_path,
+    profile::override_suffix,
+    registry::PrototypeRegistries,
+    telemetry::{PrototypeOverrideKind, PrototypeTelemetry, PrototypeTelemetryEvent},
+};
+
+/// The selected value of each variant declared with
+/// [`PrototypeAppExt::add_prototype_variant`](crate::PrototypeAppExt::add_prototype_variant), e.g.
+/// `hard` for `difficulty`.
+///
+/// The layer files of the selected values, e.g. `stats.proto.hard.json`, patch the fields of the
+/// prototypes, variants without a selected value keep the fields of the base prototypes.
+/// Changing the selection applies the new layers and sends a [`PrototypeVariantChanged`] event.
+#[derive(Resource, Debug, Clone, Default, PartialEq, Eq, Deref, DerefMut)]
+pub struct PrototypeVariants(pub HashMap<String, String>);
+
+/// Sent once the layers of a new value of `variant` are applied, along with a
+/// [`RegistryEvent::Modified`](crate::RegistryEvent::Modified) for each patched prototype.
+#[derive(Event, Debug, Clone, PartialEq, Eq)]
+pub struct PrototypeVariantChanged {
+    pub variant: String,
+    pub previous: Option<String>,
+    pub current: Option<String>,
+}
+
+/// The fields of the prototypes set by a variant layer file.
+#[derive(Asset, TypePath, Debug)]
+pub(crate) struct PrototypeVariantLayer {
+    value: String,
+    entries: Vec<VariantLayerEntry>,
+}
+
+#[derive(Debug)]
+struct VariantLayerEntry {
+    ty: String,
+    name: String,
+    /// The fields set by the entry, as an object.
+    fields: JsonValue,
+}
+
+/// Loads the variant layer files, like the locale override files they are loaded as typed
+/// assets.
+#[derive(Default)]
+pub(crate) struct PrototypeVariantLayerLoader;
+
+impl AssetLoader for PrototypeVariantLayerLoader {
+    type Asset = PrototypeVariantLayer;
+    type Settings = ();
+    type Error = std::io::Error;
+
+    async fn load(
+        &self,
+        reader: &mut dyn AssetReader,
+        _settings: &Self::Settings,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        use std::io::{Error, ErrorKind};
+
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        let path = load_context.path();
+        let Some(value) = override_suffix(path) else {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("{} isn't a variant layer file", path.display()),
+            ));
+        };
+
+        let entries = match serde_json::from_slice(&bytes)? {
+            JsonValue::Array(entries) => entries,
+            entry => vec![entry],
+        };
+
+        let entries = entries
+            .into_iter()
+            .enumerate()
+            .filter_map(|(index, entry)| {
+                let JsonValue::Object(mut fields) = entry else {
+                    warn!(
+                        "Variant layer {index} of {} isn't an object",
+                        path.display()
+                    );
+                    return None;
+                };
+
+                let (Some(JsonValue::String(ty)), Some(JsonValue::String(name))) =
+                    (fields.remove("type"), fields.remove("name"))
+                else {
+                    warn!(
+                        "Variant layer {index} of {} has no type or name",
+                        path.display()
+                    );
+                    return None;
+                };
+
+                for field in [
+                    "tags",
+                    "defaults_from",
+                    "experiment",
+                    "uuid",
+                    "$meta",
+                    "$editor",
+                ] {
+                    fields.remove(field);
+                }
+
+                Some(VariantLayerEntry {
+                    ty,
+                    name,
+                    fields: JsonValue::Object(fields),
+                })
+            })
+            .collect();
+
+        Ok(PrototypeVariantLayer {
+            value: value.to_string(),
+            entries,
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &[]
+    }
+}
+
+/// The declared variants, the loaded layer files and the fields they patched, see
+/// [`PrototypeVariants`].
+#[derive(Resource, Default)]
+pub(crate) struct VariantLayers {
+    /// The variant of each declared value.
+    declared: HashMap<String, String>,
+    /// Layer files in their load order, later files take precedence.
+    files: Vec<Handle<PrototypeVariantLayer>>,
+    overlay: PrototypeOverlay,
+    /// The values whose layers are applied.
+    selected: HashMap<String, String>,
+}
+
+impl VariantLayers {
+    pub(crate) fn declare(&mut self, variant: String, values: Vec<String>) {
+        for value in values {
+            match self.declared.get(&value) {
+                Some(other) if *other != variant => warn!(
+                    "Value {value} of variant {variant} is already a value of variant {other}, it's ignored"
+                ),
+                _ => {
+                    self.declared.insert(value, variant.clone());
+                }
+            }
+        }
+    }
+
+    /// Returns `true` for the layer files of the declared values, e.g. `stats.proto.hard.json`.
+    pub(crate) fn is_layer_file(&self, path: &Path) -> bool {
+        override_suffix(path).is_some_and(|value| self.declared.contains_key(value))
+    }
+
+    pub(crate) fn add(&mut self, handle: Handle<PrototypeVariantLayer>) {
+        if !self.files.contains(&handle) {
+            self.files.push(handle);
+        }
+    }
+}
+
+/// Splits the fields set by a layer into the fields to patch, objects set on struct fields only
+/// patch the fields they set.
+fn collect_fields<'a>(
+    field: &dyn PartialReflect,
+    path: String,
+    value: &'a JsonValue,
+    fields: &mut Vec<(String, &'a JsonValue)>,
+) {
+    if let (JsonValue::Object(values), ReflectRef::Struct(data)) = (value, field.reflect_ref()) {
+        for (name, value) in values {
+            let path = join_field_path(&path, name);
+
+            match data.field(name) {
+                Some(field) => collect_fields(field, path, value, fields),
+                // Reported as unknown when applied.
+                None => fields.push((path, value)),
+            }
+        }
+    } else {
+        fields.push((path, value));
+    }
+}
+
+/// Deserializes a value set by a layer as a value of the type of `field`.
+fn deserialize_field(
+    field: &dyn PartialReflect,
+    value: &JsonValue,
+    registry: &TypeRegistry,
+) -> Result<Box<dyn Reflect>, String> {
+    let Some(registration) = field
+        .get_represented_type_info()
+        .and_then(|type_info| registry.get(type_info.type_id()))
+    else {
+        return Err("the type of the field isn't registered".to_string());
+    };
+
+    let value = bevy::reflect::serde::TypedReflectDeserializer::new(registration, registry)
+        .deserialize(value)
+        .map_err(|err| err.to_string())?;
+
+    match value.try_into_reflect() {
+        Ok(value) => Ok(value),
+        Err(value) => registration
+            .data::<ReflectFromReflect>()
+            .and_then(|from_reflect| from_reflect.from_reflect(value.as_ref()))
+            .ok_or_else(|| {
+                format!(
+                    "{} can't be built from reflection",
+                    registration.type_info().type_path()
+                )
+            }),
+    }
+}
+
+/// Patches the fields of the prototypes with the layers of the selected [`PrototypeVariants`],
+/// when they change, when layer files are loaded and when base prototypes are reloaded.
+pub(crate) fn apply_variant_layers(
+    mut events_rx: EventReader<AssetEvent<PrototypeVariantLayer>>,
+    assets: Res<Assets<PrototypeVariantLayer>>,
+    variants: Res<PrototypeVariants>,
+    mut layers: ResMut<VariantLayers>,
+    mut registries: ResMut<PrototypeRegistries>,
+    type_registry: Res<AppTypeRegistry>,
+    prototype_types: Res<AppPrototypeTypeRegistry>,
+    mut variant_changed_tx: EventWriter<PrototypeVariantChanged>,
+    telemetry: Option<Res<PrototypeTelemetry>>,
+) {
+    let files_changed = events_rx.read().count() > 0;
+
+    if !files_changed && !variants.is_changed() && !registries.is_changed() {
+        return;
+    }
+
+    let layers = layers.bypass_change_detection();
+    let type_registry = type_registry.read();
+    let prototype_types = prototype_types.0.read();
+
+    let selected = variants
+        .iter()
+        .filter(|(variant, value)| {
+            let declared = layers.declared.get(*value) == Some(*variant);
+
+            if !declared && variants.is_changed() {
+                warn!("Value {value} isn't a declared value of variant {variant}");
+            }
+
+            declared
+        })
+        .map(|(variant, value)| (variant.clone(), value.clone()))
+        .collect::<HashMap<_, _>>();
+
+    let mut wanted = HashMap::new();
+
+    for file in layers.files.iter().filter_map(|handle| assets.get(handle)) {
+        if !selected.values().any(|value| *value == file.value) {
+            continue;
+        }
+
+        for entry in &file.entries {
+            let Some(type_id) = prototype_types.get(entry.ty.as_str()) else {
+                continue;
+            };
+
+            let id = ErasedPrototypeId::from_name(&entry.name);
+
+            let Some(data) = registries
+                .get_dyn(type_id, &id)
+                .and_then(|proto| proto.reflect_path("data").ok())
+            else {
+                continue;
+            };
+
+            let mut fields = Vec::new();
+            collect_fields(data, "data".to_string(), &entry.fields, &mut fields);
+
+            for (path, value) in fields {
+                wanted.insert((*type_id, id, path), (entry.name.as_str(), value));
+            }
+        }
+    }
+
+    let modified = layers.overlay.apply(
+        registries.bypass_change_detection(),
+        wanted,
+        |field, value: &&JsonValue| deserialize_field(field, value, &type_registry),
+    );
+
+    for (type_id, id) in modified {
+        registries.mark_modified(&type_id, id);
+
+        if let Some(telemetry) = &telemetry
+            && let Some(ty) = prototype_types
+                .iter()
+                .find_map(|(name, ty)| (*ty == type_id).then_some(&**name))
+        {
+            telemetry.send(PrototypeTelemetryEvent::OverrideApplied {
+                ty,
+                id,
+                kind: PrototypeOverrideKind::Variant,
+            });
+        }
+    }
+
+    if selected != layers.selected {
+        let previous = core::mem::replace(&mut layers.selected, selected);
+
+        let mut variants = previous
+            .keys()
+            .chain(layers.selected.keys())
+            .collect::<Vec<_>>();
+        variants.sort();
+        variants.dedup();
+
+        for variant in variants {
+            let (previous, current) = (previous.get(variant), layers.selected.get(variant));
+
+            if previous != current {
+                variant_changed_tx.write(PrototypeVariantChanged {
+                    variant: variant.clone(),
+                    previous: previous.cloned(),
+                    current: current.cloned(),
+                });
+            }
+        }
+    }
+}